@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use wowie_jam_4::bench::{BenchTemplates, Scenario, ScenarioKind};
+
+/// One second of the game.
+const TICKS: usize = 60;
+
+fn scenarios(c: &mut Criterion) {
+    let templates = BenchTemplates::parse(include_str!("templates.json")).unwrap();
+
+    let mut group = c.benchmark_group("scenarios");
+    group.sample_size(10);
+    for kind in ScenarioKind::ALL {
+        group.bench_function(kind.name(), |b| {
+            b.iter_batched_ref(
+                || Scenario::new(&templates, kind),
+                |scenario| {
+                    for _ in 0..TICKS {
+                        scenario.tick();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scenarios);
+criterion_main!(benches);
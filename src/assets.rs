@@ -1,18 +1,70 @@
 use super::*;
 
+mod library;
 mod pixel_texture;
 
 use geng::Sound;
+pub use library::*;
 pub use pixel_texture::*;
 
-#[derive(geng::Assets)]
+/// Put together by the [Library] from the groups listed in `static/manifest.json`,
+/// each field is the directory of `static` with its name.
 pub struct Assets {
+    /// Loaded before the rest, shared with the menu shown while the rest is loading.
+    pub menu: Rc<MenuAssets>,
     pub background: BackgroundAssets,
+    pub cutscenes: CutsceneAssets,
+    pub scripts: ScriptAssets,
     pub effects: EffectAssets,
     pub enemies: EnemyAssets,
     pub mech: MechAssets,
     pub ui: UIAssets,
     pub sound_design: SoundAssets,
+    pub shaders: ShaderAssets,
+    pub mods: mods::ModAssets,
+}
+
+/// What the main menu needs to be shown, the first stage of the asset manifest.
+pub struct MenuAssets {
+    pub locale: LocaleAssets,
+    pub fonts: FontAssets,
+}
+
+#[derive(geng::Assets)]
+pub struct ShaderAssets {
+    /// Draws the team-colored outline around the units.
+    pub outline: ugli::Program,
+}
+
+/// String tables, see [crate::Locale] for the format.
+#[derive(geng::Assets)]
+pub struct LocaleAssets {
+    #[asset(path = "en.txt")]
+    pub en: String,
+    #[asset(path = "ru.txt")]
+    pub ru: String,
+}
+
+#[derive(geng::Assets)]
+pub struct FontAssets {
+    /// Has the Cyrillic glyphs missing in the default font.
+    #[asset(path = "DejaVuSans.ttf")]
+    pub cyrillic: Rc<geng::Font>,
+}
+
+/// Cutscene scripts, see [crate::model::CutsceneCommand] for the format.
+#[derive(geng::Assets)]
+pub struct CutsceneAssets {
+    #[asset(path = "intro.txt")]
+    pub intro: String,
+    #[asset(path = "boss.txt")]
+    pub boss: String,
+}
+
+#[derive(geng::Assets)]
+pub struct ScriptAssets {
+    #[asset(path = "effects.rhai")]
+    pub effects: String,
 }
 
 #[derive(geng::Assets)]
@@ -22,6 +74,52 @@ pub struct SoundAssets {
     pub enemies: EnemySounds,
 }
 
+/// Names a sound of the [SoundAssets], so that the effects refer to it without holding the audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SoundId {
+    Lava,
+    EnemyDeath,
+    BlighterShoot,
+    BlighterWalk,
+    RavagerRoar,
+    RavagerCharge,
+    RavagerBite,
+    RavagerWalk,
+    MechDeath,
+    MechHit,
+    MechWalk,
+    SanityZero,
+    ArtilleryShoot,
+    RocketExplode,
+    TankShoot,
+    HealEffect,
+}
+
+impl SoundAssets {
+    pub fn get(&self, id: SoundId) -> &Rc<Sound> {
+        let enemies = &self.enemies;
+        let mechs = &self.mechs;
+        match id {
+            SoundId::Lava => &self.lava,
+            SoundId::EnemyDeath => &enemies.death,
+            SoundId::BlighterShoot => &enemies.blighter.shoot,
+            SoundId::BlighterWalk => &enemies.blighter.walk,
+            SoundId::RavagerRoar => &enemies.ravager.roar,
+            SoundId::RavagerCharge => &enemies.ravager.charge,
+            SoundId::RavagerBite => &enemies.ravager.bite,
+            SoundId::RavagerWalk => &enemies.ravager.walk,
+            SoundId::MechDeath => &mechs.death,
+            SoundId::MechHit => &mechs.hit,
+            SoundId::MechWalk => &mechs.walk,
+            SoundId::SanityZero => &mechs.sanity_zero,
+            SoundId::ArtilleryShoot => &mechs.artillery.artillery_shoot,
+            SoundId::RocketExplode => &mechs.artillery.rocket_explode,
+            SoundId::TankShoot => &mechs.tank.shoot,
+            SoundId::HealEffect => &mechs.healer.heal_effect,
+        }
+    }
+}
+
 #[derive(geng::Assets)]
 pub struct EnemySounds {
     pub death: Rc<Sound>,
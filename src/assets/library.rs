@@ -0,0 +1,320 @@
+use super::*;
+
+use futures::future::LocalBoxFuture;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::task::{Context, Poll};
+
+/// The loading order of the asset groups, read from `static/manifest.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetManifest {
+    /// The first stage is loaded before the menu is shown, it must have the locale and the fonts.
+    pub stages: Vec<ManifestStage>,
+}
+
+/// Asset groups loaded together, each one a directory of `static`, e.g. `enemies`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestStage {
+    pub name: String,
+    pub groups: Vec<String>,
+    /// Loaded once a screen needs the whole [Assets], instead of in the background of the menu.
+    /// Only on the web, where the files are fetched, the native build reads them right away.
+    #[serde(default)]
+    pub lazy: bool,
+}
+
+/// The groups loaded so far, moved into the [Assets] once every stage is done.
+#[derive(Default)]
+struct Groups {
+    locale: Option<LocaleAssets>,
+    fonts: Option<FontAssets>,
+    background: Option<BackgroundAssets>,
+    cutscenes: Option<CutsceneAssets>,
+    scripts: Option<ScriptAssets>,
+    effects: Option<EffectAssets>,
+    enemies: Option<EnemyAssets>,
+    mech: Option<MechAssets>,
+    ui: Option<UIAssets>,
+    sound_design: Option<SoundAssets>,
+    shaders: Option<ShaderAssets>,
+}
+
+/// The result of the loading: the assets, or the problems to show instead of the game.
+pub type LoadResult = Result<Rc<Assets>, Vec<String>>;
+
+/// Loads the [Assets] in the stages of the [AssetManifest]: the menu is shown after the first one,
+/// the rest are streamed while it is open. Shared by the screens until the loading is done.
+#[derive(Clone)]
+pub struct Library(Rc<RefCell<LibraryState>>);
+
+struct LibraryState {
+    geng: Geng,
+    path: PathBuf,
+    menu: Rc<MenuAssets>,
+    groups: Rc<RefCell<Groups>>,
+    /// The stages not started yet.
+    stages: VecDeque<ManifestStage>,
+    /// The stage being loaded.
+    loading: Option<geng::AssetFuture<()>>,
+    /// Whether a screen waits for the lazy stages.
+    demanded: bool,
+    result: Option<LoadResult>,
+}
+
+impl Library {
+    /// Reads the manifest and loads its first stage, behind the progress screen.
+    pub fn load(geng: &Geng, path: &Path) -> geng::AssetFuture<Self> {
+        let geng = geng.clone();
+        let path = path.to_owned();
+        async move {
+            let manifest =
+                <String as geng::LoadAsset>::load(&geng, &path.join("manifest.json")).await?;
+            let manifest: AssetManifest = serde_json::from_str(&manifest)
+                .map_err(|error| anyhow::anyhow!("Invalid asset manifest: {}", error))?;
+            let mut stages: VecDeque<_> = manifest.stages.into();
+            let first = stages
+                .pop_front()
+                .ok_or_else(|| anyhow::anyhow!("The asset manifest has no stages"))?;
+            let groups = Rc::new(RefCell::new(Groups::default()));
+            load_stage(&geng, &path, &first, &groups)?.await?;
+            let menu = {
+                let mut loaded = groups.borrow_mut();
+                let missing = |group| {
+                    anyhow::anyhow!(
+                        "The first stage of the asset manifest must load the {}",
+                        group
+                    )
+                };
+                MenuAssets {
+                    locale: loaded.locale.take().ok_or_else(|| missing("locale"))?,
+                    fonts: loaded.fonts.take().ok_or_else(|| missing("fonts"))?,
+                }
+            };
+            Ok(Self(Rc::new(RefCell::new(LibraryState {
+                geng,
+                path,
+                menu: Rc::new(menu),
+                groups,
+                stages,
+                loading: None,
+                demanded: false,
+                result: None,
+            }))))
+        }
+        .boxed_local()
+    }
+
+    /// The assets are already loaded, e.g. when going back to the menu from a run.
+    pub fn loaded(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        Self(Rc::new(RefCell::new(LibraryState {
+            geng: geng.clone(),
+            path: PathBuf::new(),
+            menu: assets.menu.clone(),
+            groups: default(),
+            stages: VecDeque::new(),
+            loading: None,
+            demanded: false,
+            result: Some(Ok(assets.clone())),
+        })))
+    }
+
+    pub fn menu(&self) -> Rc<MenuAssets> {
+        self.0.borrow().menu.clone()
+    }
+
+    /// Keeps loading the stages up to the first lazy one, called every frame by the menu.
+    /// Returns the result once every stage is loaded.
+    pub fn poll(&self) -> Option<LoadResult> {
+        let waker = futures::task::noop_waker_ref();
+        match self.0.borrow_mut().poll(&mut Context::from_waker(waker)) {
+            Poll::Ready(result) => Some(result),
+            Poll::Pending => None,
+        }
+    }
+
+    /// Loads the lazy stages as well, for the loading screen of a screen that needs the assets.
+    pub fn demand(&self) -> LocalBoxFuture<'static, LoadResult> {
+        self.0.borrow_mut().demanded = true;
+        let library = self.clone();
+        futures::future::poll_fn(move |cx| library.0.borrow_mut().poll(cx)).boxed_local()
+    }
+}
+
+impl LibraryState {
+    fn poll(&mut self, cx: &mut Context) -> Poll<LoadResult> {
+        loop {
+            if let Some(result) = &self.result {
+                return Poll::Ready(result.clone());
+            }
+            if self.loading.is_none() {
+                match self.stages.front() {
+                    Some(stage) if stage.lazy && !self.demanded && cfg!(target_arch = "wasm32") => {
+                        return Poll::Pending
+                    }
+                    Some(_) => {
+                        let stage = self.stages.pop_front().unwrap();
+                        info!("Loading the {} assets", stage.name);
+                        match load_stage(&self.geng, &self.path, &stage, &self.groups) {
+                            Ok(loading) => self.loading = Some(loading),
+                            Err(error) => self.fail(error),
+                        }
+                    }
+                    None => self.result = Some(self.finish()),
+                }
+                continue;
+            }
+            match self.loading.as_mut().unwrap().as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(())) => self.loading = None,
+                Poll::Ready(Err(error)) => self.fail(error),
+            }
+        }
+    }
+
+    fn fail(&mut self, error: anyhow::Error) {
+        // E.g. a missing texture or sound file
+        let problem = format!("Failed to load the assets: {:#}", error);
+        error!("{}", problem);
+        self.loading = None;
+        self.result = Some(Err(vec![problem]));
+    }
+
+    /// Puts the groups together and applies the mods, fails if the manifest left a group out.
+    fn finish(&self) -> LoadResult {
+        let mut groups = self.groups.borrow_mut();
+        let loaded = [
+            ("background", groups.background.is_some()),
+            ("cutscenes", groups.cutscenes.is_some()),
+            ("scripts", groups.scripts.is_some()),
+            ("effects", groups.effects.is_some()),
+            ("enemies", groups.enemies.is_some()),
+            ("mech", groups.mech.is_some()),
+            ("ui", groups.ui.is_some()),
+            ("sound_design", groups.sound_design.is_some()),
+            ("shaders", groups.shaders.is_some()),
+        ];
+        let problems: Vec<String> = loaded
+            .into_iter()
+            .filter(|&(_, loaded)| !loaded)
+            .map(|(group, _)| format!("The asset manifest does not load the {}", group))
+            .collect();
+        if !problems.is_empty() {
+            for problem in &problems {
+                error!("{}", problem);
+            }
+            return Err(problems);
+        }
+        let mut assets = Assets {
+            menu: self.menu.clone(),
+            background: groups.background.take().unwrap(),
+            cutscenes: groups.cutscenes.take().unwrap(),
+            scripts: groups.scripts.take().unwrap(),
+            effects: groups.effects.take().unwrap(),
+            enemies: groups.enemies.take().unwrap(),
+            mech: groups.mech.take().unwrap(),
+            ui: groups.ui.take().unwrap(),
+            sound_design: groups.sound_design.take().unwrap(),
+            shaders: groups.shaders.take().unwrap(),
+            mods: default(),
+        };
+        assets.process(&self.geng);
+        mods::apply(&mut assets, mods::load(&self.geng));
+        let assets = Rc::new(assets);
+        let problems = validation::validate(&assets);
+        for problem in &problems {
+            error!("Invalid asset: {}", problem);
+        }
+        if problems.is_empty() {
+            Ok(assets)
+        } else {
+            // The game would panic on the broken data sooner or later
+            Err(problems)
+        }
+    }
+}
+
+/// Starts loading every group of the stage at once.
+fn load_stage(
+    geng: &Geng,
+    path: &Path,
+    stage: &ManifestStage,
+    groups: &Rc<RefCell<Groups>>,
+) -> anyhow::Result<geng::AssetFuture<()>> {
+    let loading = stage
+        .groups
+        .iter()
+        .map(|group| {
+            let path = &path.join(group);
+            Ok(match group.as_str() {
+                "locale" => load_group(geng, path, groups, |groups| &mut groups.locale),
+                "fonts" => load_group(geng, path, groups, |groups| &mut groups.fonts),
+                "background" => load_group(geng, path, groups, |groups| &mut groups.background),
+                "cutscenes" => load_group(geng, path, groups, |groups| &mut groups.cutscenes),
+                "scripts" => load_group(geng, path, groups, |groups| &mut groups.scripts),
+                "effects" => load_group(geng, path, groups, |groups| &mut groups.effects),
+                "enemies" => load_group(geng, path, groups, |groups| &mut groups.enemies),
+                "mech" => load_group(geng, path, groups, |groups| &mut groups.mech),
+                "ui" => load_group(geng, path, groups, |groups| &mut groups.ui),
+                "sound_design" => load_group(geng, path, groups, |groups| &mut groups.sound_design),
+                "shaders" => load_group(geng, path, groups, |groups| &mut groups.shaders),
+                _ => anyhow::bail!("Unknown asset group {:?} in the manifest", group),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(futures::future::try_join_all(loading)
+        .map_ok(|_| ())
+        .boxed_local())
+}
+
+fn load_group<T: geng::LoadAsset>(
+    geng: &Geng,
+    path: &Path,
+    groups: &Rc<RefCell<Groups>>,
+    slot: fn(&mut Groups) -> &mut Option<T>,
+) -> geng::AssetFuture<()> {
+    let loading = T::load(geng, path);
+    let groups = groups.clone();
+    async move {
+        let group = loading.await?;
+        *slot(&mut groups.borrow_mut()) = Some(group);
+        Ok(())
+    }
+    .boxed_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_manifest_loads_every_group_once() {
+        let manifest: AssetManifest =
+            serde_json::from_str(include_str!("../../static/manifest.json")).unwrap();
+        assert!(["locale", "fonts"]
+            .iter()
+            .all(|group| manifest.stages[0].groups.iter().any(|other| other == group)));
+        let mut groups: Vec<&str> = manifest
+            .stages
+            .iter()
+            .flat_map(|stage| stage.groups.iter().map(String::as_str))
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            [
+                "background",
+                "cutscenes",
+                "effects",
+                "enemies",
+                "fonts",
+                "locale",
+                "mech",
+                "scripts",
+                "shaders",
+                "sound_design",
+                "ui",
+            ]
+        );
+    }
+}
@@ -1,11 +1,92 @@
 use geng::prelude::*;
 
-#[derive(Clone, Deref, DerefMut)]
-pub struct PixelTexture(Rc<ugli::Texture>);
+use std::cell::RefCell;
+
+/// A texture drawn without smoothing, registered under its path for the [TextureId]s.
+#[derive(Clone)]
+pub struct PixelTexture {
+    texture: Rc<ugli::Texture>,
+    id: TextureId,
+}
+
+/// A handle to a texture, so that the sprites do not hold the texture itself.
+/// Serialized as the path of the texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureId(usize);
+
+thread_local! {
+    /// The paths of the handles and the loaded textures, the textures are not loaded
+    /// for the handles deserialized in a headless run.
+    static TEXTURES: RefCell<Vec<(String, Option<Rc<ugli::Texture>>)>> = default();
+}
 
 impl PixelTexture {
+    fn new(mut texture: ugli::Texture, path: String) -> Self {
+        texture.set_filter(ugli::Filter::Nearest);
+        let texture = Rc::new(texture);
+        Self {
+            id: TextureId::register(path, Some(texture.clone())),
+            texture,
+        }
+    }
+
     pub fn texture(&self) -> Rc<ugli::Texture> {
-        self.0.clone()
+        self.texture.clone()
+    }
+
+    pub fn id(&self) -> TextureId {
+        self.id
+    }
+}
+
+impl TextureId {
+    /// The handle of the texture at the path, the texture is missing until it is loaded.
+    pub fn from_path(path: &str) -> Self {
+        Self::register(path.to_owned(), None)
+    }
+
+    fn register(path: String, texture: Option<Rc<ugli::Texture>>) -> Self {
+        TEXTURES.with(|textures| {
+            let mut textures = textures.borrow_mut();
+            if let Some(index) = textures.iter().position(|(known, _)| *known == path) {
+                if texture.is_some() {
+                    textures[index].1 = texture;
+                }
+                return Self(index);
+            }
+            textures.push((path, texture));
+            Self(textures.len() - 1)
+        })
+    }
+
+    pub fn path(&self) -> String {
+        TEXTURES.with(|textures| textures.borrow()[self.0].0.clone())
+    }
+
+    /// The texture of the handle, if it is loaded.
+    pub fn texture(&self) -> Option<Rc<ugli::Texture>> {
+        TEXTURES.with(|textures| textures.borrow()[self.0].1.clone())
+    }
+}
+
+impl Serialize for TextureId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.path().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TextureId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Ok(Self::from_path(&path))
+    }
+}
+
+impl Deref for PixelTexture {
+    type Target = Rc<ugli::Texture>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.texture
     }
 }
 
@@ -17,27 +98,21 @@ impl ugli::Uniform for PixelTexture {
 
 impl std::borrow::Borrow<ugli::Texture> for PixelTexture {
     fn borrow(&self) -> &ugli::Texture {
-        &self.0
+        &self.texture
     }
 }
 
 impl std::borrow::Borrow<ugli::Texture> for &'_ PixelTexture {
     fn borrow(&self) -> &ugli::Texture {
-        &self.0
-    }
-}
-
-impl From<ugli::Texture> for PixelTexture {
-    fn from(mut texture: ugli::Texture) -> Self {
-        texture.set_filter(ugli::Filter::Nearest);
-        Self(Rc::new(texture))
+        &self.texture
     }
 }
 
 impl geng::LoadAsset for PixelTexture {
     fn load(geng: &Geng, path: &std::path::Path) -> geng::AssetFuture<Self> {
         let texture = ugli::Texture::load(geng, path);
-        async move { Ok(texture.await?.into()) }.boxed_local()
+        let path = path.to_string_lossy().into_owned();
+        async move { Ok(Self::new(texture.await?, path)) }.boxed_local()
     }
 
     const DEFAULT_EXT: Option<&'static str> = Some("png");
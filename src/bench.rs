@@ -0,0 +1,243 @@
+use super::*;
+
+use model::*;
+
+/// Number of enemies in the crowded scenarios.
+const CROWD: usize = 200;
+/// Enemies in a row of the crowd, the rows are stacked above each other.
+const CROWD_ROW: usize = 50;
+const PROJECTILES: usize = 500;
+/// Area effects resolved every tick in the mass AoE scenario.
+const AREAS_PER_TICK: usize = 50;
+/// Heals, each spawning a particle, and sparks thrown every tick in the particle storm.
+const HEALS_PER_TICK: usize = 100;
+const SPARKS_PER_TICK: usize = 500;
+
+/// The templates and their animations, deserialized without the textures,
+/// so that the scenarios run headless. The blighter and the artillery are the game's,
+/// the other templates only stand in for the fields the scenarios never use.
+#[derive(Deserialize)]
+pub struct BenchTemplates {
+    templates: UnitTemplates,
+    animations: Animations,
+}
+
+impl BenchTemplates {
+    pub fn parse(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// A headless model on the flat ground, with no waves coming.
+    pub fn model(&self, seed: u64) -> Model {
+        let config = RunConfig {
+            seed,
+            difficulty: default(),
+            mode: GameMode::Standard,
+            commander: default(),
+            perks: vec![],
+            language: default(),
+            aim_assist: 0.0,
+        };
+        let mut model = Model::headless(config, self.templates.clone(), self.animations.clone());
+        // A wave too far to ever start, so that none are generated
+        model.world.waves.push_back(Wave {
+            position: Coord::new(1e6),
+            units: vec![],
+        });
+        model
+    }
+}
+
+/// The model the tests of the simulation run in, with the templates of the benchmarks.
+#[cfg(test)]
+pub fn test_model() -> Model {
+    BenchTemplates::parse(include_str!("../benches/templates.json"))
+        .unwrap()
+        .model(0)
+}
+
+/// Spawns a unit of the template on the ground of the test model, returns its id.
+#[cfg(test)]
+pub fn test_spawn(model: &mut Model, template: &str, x: f32, faction: Faction) -> Id {
+    let template = model.world.templates.get(template).unwrap().clone();
+    let position = vec2(Coord::new(x), model.world.ground_level);
+    model.spawn_unit(template, position, faction);
+    // The ids of the test model are never freed before
+    model.world.units.iter().map(|unit| unit.id).max().unwrap()
+}
+
+/// Simulates the tick of the test model the effect on the target is processed in.
+#[cfg(test)]
+pub fn test_effect(model: &mut Model, effect: Effect, caster: Option<Id>, target: Id) {
+    model.world.delayed_effects.push(DelayedEffect {
+        time_left: Time::ZERO,
+        effect,
+        context: EffectContext {
+            caster,
+            target: Some(target),
+            position: None,
+            direction: None,
+            damage_multiplier: R32::ONE,
+            missing: MissingUnit::Skip,
+        },
+    });
+    model.update(Time::new(logic::TICK_TIME));
+}
+
+/// A canned situation for the benchmarks.
+#[derive(Debug, Clone, Copy)]
+pub enum ScenarioKind {
+    /// A volley of projectiles flying into a crowd of enemies.
+    ProjectileHail,
+    /// Overlapping explosions going off in a crowd every tick.
+    MassAoe,
+    /// Heal particles and sparks thrown all over a crowd every tick.
+    ParticleStorm,
+}
+
+impl ScenarioKind {
+    pub const ALL: [Self; 3] = [Self::ProjectileHail, Self::MassAoe, Self::ParticleStorm];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ProjectileHail => "projectile_hail",
+            Self::MassAoe => "mass_aoe",
+            Self::ParticleStorm => "particle_storm",
+        }
+    }
+}
+
+/// A scenario simulated without rendering, through the same ticks as the game.
+pub struct Scenario {
+    kind: ScenarioKind,
+    model: Model,
+}
+
+impl Scenario {
+    /// Sets the scenario up on the flat ground, with no waves coming.
+    pub fn new(templates: &BenchTemplates, kind: ScenarioKind) -> Self {
+        let model = templates.model(0);
+        let mut scenario = Self { kind, model };
+        scenario.spawn_crowd();
+        if let ScenarioKind::ProjectileHail = kind {
+            scenario.fire_volley();
+        }
+        scenario
+    }
+
+    /// Feeds the scenario for the tick and simulates it.
+    pub fn tick(&mut self) {
+        match self.kind {
+            ScenarioKind::ProjectileHail => {}
+            ScenarioKind::MassAoe => self.explode(),
+            ScenarioKind::ParticleStorm => self.storm(),
+        }
+        self.model.update(Time::new(logic::TICK_TIME));
+    }
+
+    fn spawn_crowd(&mut self) {
+        let model = &mut self.model;
+        for index in 0..CROWD {
+            let position = vec2(
+                Coord::new(10.0 + (index % CROWD_ROW) as f32),
+                model.world.ground_level + Coord::new((index / CROWD_ROW) as f32 * 2.0),
+            );
+            model.spawn_unit(
+                model.world.templates.blighter.clone(),
+                position,
+                Faction::Alien,
+            );
+        }
+    }
+
+    /// Queues the shots of the artillery at the crowd, fired on the first tick.
+    fn fire_volley(&mut self) {
+        let model = &mut self.model;
+        model.spawn_unit(
+            model.world.templates.artillery.clone(),
+            vec2(Coord::ZERO, model.world.ground_level),
+            Faction::Mech,
+        );
+        let (mech, effect) = match model.world.units.iter().find_map(|unit| {
+            let effect = model
+                .world
+                .animations
+                .get(unit.action.animation)
+                .keyframes
+                .iter()
+                .find_map(|frame| frame.start_effect.as_ref()?.projectile_subtree())?;
+            Some((unit.id, effect))
+        }) {
+            Some(shot) => shot,
+            None => return,
+        };
+        let targets = self.ids(Faction::Alien);
+        for &target in targets.iter().cycle().take(PROJECTILES) {
+            self.queue(effect.clone(), Some(mech), target);
+        }
+    }
+
+    fn explode(&mut self) {
+        let area = Effect::new(EffectNode::Area(AreaEffect {
+            center: Who::Target,
+            radius: Coord::new(1.0),
+            filter: FactionFilter::All,
+            hits_caster: false,
+            falloff: None,
+            // Small enough for the crowd to last through the benchmark
+            effect: Effect::new(EffectNode::Damage(DamageEffect {
+                damage_type: DamageType::Explosive,
+                value: Value::Flat(Hp::new(0.001)),
+                hits_caster: false,
+                impact: None,
+            })),
+        }));
+        for target in self.ids(Faction::Alien).into_iter().take(AREAS_PER_TICK) {
+            self.queue(area.clone(), None, target);
+        }
+    }
+
+    fn storm(&mut self) {
+        let heal = Effect::new(EffectNode::Heal(HealEffect {
+            value: Value::Flat(Hp::ZERO),
+            overheal_shield: false,
+        }));
+        let targets = self.ids(Faction::Alien);
+        for &target in targets.iter().take(HEALS_PER_TICK) {
+            self.queue(heal.clone(), None, target);
+        }
+        let mut impact = DamageType::Physical.impact();
+        impact.sparks = SPARKS_PER_TICK / targets.len().max(1);
+        for target in targets {
+            if let Some(&position) = self.model.world.bodies.positions.get(target) {
+                self.model.spawn_sparks(position, &impact);
+            }
+        }
+    }
+
+    fn ids(&self, faction: Faction) -> Vec<Id> {
+        self.model
+            .world
+            .units
+            .iter()
+            .filter(|unit| unit.faction == faction)
+            .map(|unit| unit.id)
+            .collect()
+    }
+
+    /// Queues the effect to be processed on the next tick.
+    fn queue(&mut self, effect: Effect, caster: Option<Id>, target: Id) {
+        self.model.world.delayed_effects.push(DelayedEffect {
+            time_left: Time::ZERO,
+            effect,
+            context: EffectContext {
+                caster,
+                target: Some(target),
+                position: None,
+                direction: None,
+                damage_multiplier: R32::ONE,
+                missing: MissingUnit::Skip,
+            },
+        });
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+use geng::Draw2d;
+
+/// Frames per second of the recorded clips.
+const CLIP_FPS: f64 = 10.0;
+/// Length of the recorded clips in seconds.
+const CLIP_LENGTH: f64 = 5.0;
+/// Height of the clip frames in pixels, the width keeps the aspect of the window.
+const CLIP_HEIGHT: usize = 270;
+
+/// Saves screenshots and keeps the last seconds as a clip. The game is drawn offscreen
+/// only on the frames that are read back, otherwise straight to the screen.
+pub struct Capture {
+    geng: Geng,
+    /// The texture the frames are drawn to, taken out while the next one is drawn.
+    frame: Option<ugli::Texture>,
+    /// The downscaled frame copied to the clip ring.
+    copy: Option<ugli::Texture>,
+    /// Whether the next frame is saved as a screenshot.
+    screenshot: bool,
+    /// The last frames of the clip, read back as they are copied so that saving the clip
+    /// does not stall. The oldest one is at `next` once the ring is full,
+    /// about 25 MB at the clip resolution.
+    ring: Vec<image::RgbaImage>,
+    next: usize,
+    timer: Timer,
+    since_copy: f64,
+}
+
+impl Capture {
+    pub fn new(geng: &Geng) -> Self {
+        Self {
+            geng: geng.clone(),
+            frame: None,
+            copy: None,
+            screenshot: false,
+            ring: vec![],
+            next: 0,
+            timer: Timer::new(),
+            since_copy: 0.0,
+        }
+    }
+
+    /// Whether the next frame has to be drawn offscreen, to be saved as a screenshot
+    /// or copied to the clip ring at the clip frame rate.
+    pub fn wants_frame(&mut self) -> bool {
+        self.since_copy += self.timer.tick();
+        // Neither is saved on the web
+        !cfg!(target_arch = "wasm32") && (self.screenshot || self.since_copy >= 1.0 / CLIP_FPS)
+    }
+
+    /// Takes the texture to draw the next frame to, sized as the window.
+    pub fn take_frame(&mut self, size: Vec2<usize>) -> ugli::Texture {
+        match self.frame.take() {
+            Some(frame) if frame.size() == size => frame,
+            _ => ugli::Texture::new_uninitialized(self.geng.ugli(), size),
+        }
+    }
+
+    /// Copies the drawn frame to the screen and to the clip ring, saves the screenshot.
+    pub fn present(&mut self, mut frame: ugli::Texture, framebuffer: &mut ugli::Framebuffer) {
+        blit(&self.geng, &frame, framebuffer);
+
+        if std::mem::take(&mut self.screenshot) {
+            let image = read_image(&self.geng, &mut frame);
+            match save_screenshot(image) {
+                Ok(path) => info!("Saved a screenshot to {}", path),
+                Err(error) => error!("{}", error),
+            }
+        }
+
+        if self.since_copy >= 1.0 / CLIP_FPS {
+            self.since_copy = 0.0;
+            let size = frame.size();
+            let size = vec2(CLIP_HEIGHT * size.x / size.y.max(1), CLIP_HEIGHT);
+            let copy = match self.copy.take() {
+                Some(copy) if copy.size() == size => copy,
+                _ => {
+                    // The old frames no longer match the aspect of the window
+                    self.ring.clear();
+                    self.next = 0;
+                    ugli::Texture::new_uninitialized(self.geng.ugli(), size)
+                }
+            };
+            let copy = self.copy.insert(copy);
+            blit(
+                &self.geng,
+                &frame,
+                &mut ugli::Framebuffer::new_color(
+                    self.geng.ugli(),
+                    ugli::ColorAttachment::Texture(copy),
+                ),
+            );
+            let image = read_image(&self.geng, copy);
+            let capacity = (CLIP_LENGTH * CLIP_FPS) as usize;
+            if self.ring.len() < capacity {
+                self.ring.push(image);
+            } else {
+                self.ring[self.next] = image;
+                self.next = (self.next + 1) % capacity;
+            }
+        }
+
+        self.frame = Some(frame);
+    }
+
+    /// Saves the next drawn frame as a png.
+    pub fn screenshot(&mut self) {
+        if cfg!(target_arch = "wasm32") {
+            error!("Screenshots are not supported on the web");
+            return;
+        }
+        self.screenshot = true;
+    }
+
+    /// Saves the frames of the last seconds as a gif, encoded in the background.
+    pub fn save_clip(&mut self) {
+        if cfg!(target_arch = "wasm32") {
+            error!("Clips are not supported on the web");
+            return;
+        }
+        let mut frames = std::mem::take(&mut self.ring);
+        frames.rotate_left(self.next);
+        self.next = 0;
+        if frames.is_empty() {
+            return;
+        }
+        save_clip(frames);
+    }
+}
+
+/// Draws the texture stretched over the whole framebuffer.
+fn blit(geng: &Geng, texture: &ugli::Texture, framebuffer: &mut ugli::Framebuffer) {
+    let size = framebuffer.size().map(|x| x as f32);
+    draw_2d::TexturedQuad::new(AABB::ZERO.extend_positive(size), texture).draw_2d(
+        geng,
+        framebuffer,
+        &geng::PixelPerfectCamera,
+    );
+}
+
+fn read_image(geng: &Geng, texture: &mut ugli::Texture) -> image::RgbaImage {
+    let size = texture.size();
+    let framebuffer =
+        ugli::Framebuffer::new_color(geng.ugli(), ugli::ColorAttachment::Texture(texture));
+    let data = framebuffer.read_color();
+    image::RgbaImage::from_fn(size.x as u32, size.y as u32, |x, y| {
+        // The rows of the texture go from the bottom up
+        let color = data.get(x as usize, size.y - 1 - y as usize);
+        // The translucent sprites lower the alpha of the frame
+        image::Rgba([color.r, color.g, color.b, 255])
+    })
+}
+
+/// Milliseconds since the epoch, used to name the saved files.
+#[cfg(not(target_arch = "wasm32"))]
+fn timestamp() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_screenshot(image: image::RgbaImage) -> Result<String, String> {
+    let path = run_dir().join(format!("screenshot-{}.png", timestamp()));
+    image
+        .save(&path)
+        .map_err(|error| format!("Failed to save the screenshot: {}", error))?;
+    Ok(path.display().to_string())
+}
+
+/// Encoding takes a while, so the clip is saved in the background.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_clip(frames: Vec<image::RgbaImage>) {
+    let path = run_dir().join(format!("clip-{}.gif", timestamp()));
+    std::thread::spawn(move || {
+        let result = std::fs::File::create(&path)
+            .map_err(image::ImageError::IoError)
+            .and_then(|file| {
+                let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(file, 10);
+                encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?;
+                let delay = image::Delay::from_numer_denom_ms((1000.0 / CLIP_FPS) as u32, 1);
+                encoder.encode_frames(
+                    frames
+                        .into_iter()
+                        .map(|frame| image::Frame::from_parts(frame, 0, 0, delay)),
+                )
+            });
+        match result {
+            Ok(()) => info!("Saved a clip to {}", path.display()),
+            Err(error) => error!("Failed to save the clip: {}", error),
+        }
+    });
+}
+
+/// There is no file system on the web.
+#[cfg(target_arch = "wasm32")]
+fn save_screenshot(_image: image::RgbaImage) -> Result<String, String> {
+    Err("Screenshots are not supported on the web".to_owned())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_clip(_frames: Vec<image::RgbaImage>) {
+    error!("Clips are not supported on the web");
+}
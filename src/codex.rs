@@ -0,0 +1,202 @@
+use super::*;
+
+use geng::Draw2d;
+use model::{Animations, Stat, UnitTemplate, UnitTemplates};
+
+const ENTRY_SPACING: f32 = 44.0;
+/// Size of the box the sprite of the selected enemy is fitted into.
+const SPRITE_BOX: f32 = 220.0;
+/// Maximum hp of the hypothetical target the abilities are previewed against.
+const PREVIEW_TARGET_HP: f32 = 100.0;
+const LOCKED_COLOR: Rgba<f32> = Rgba {
+    r: 0.4,
+    g: 0.4,
+    b: 0.45,
+    a: 1.0,
+};
+
+/// Lists the enemies with their stats and abilities, an enemy stays hidden until
+/// the player kills one.
+pub struct Codex {
+    geng: Geng,
+    assets: Rc<Assets>,
+    templates: UnitTemplates,
+    animations: Animations,
+    profile: Profile,
+    locale: Locale,
+    selected: usize,
+    transition: Option<geng::Transition>,
+}
+
+impl Codex {
+    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let mut animations = Animations::new();
+        let templates = UnitTemplates::new(assets, &mut animations);
+        Self {
+            geng: geng.clone(),
+            assets: assets.clone(),
+            templates,
+            animations,
+            profile: Profile::load(),
+            locale: Locale::new(&assets.menu, Language::load()),
+            selected: 0,
+            transition: None,
+        }
+    }
+
+    fn is_known(&self, name: &str) -> bool {
+        self.profile.codex.contains(name)
+    }
+
+    fn back_to_menu(&mut self) {
+        let menu = menu::MainMenu::new(&self.geng, &self.assets);
+        self.transition = Some(geng::Transition::Switch(Box::new(menu)));
+    }
+
+    /// The stats and abilities of the enemy, one line each.
+    fn describe(&self, template: &UnitTemplate) -> Vec<String> {
+        let locale = &self.locale;
+        let mut lines = vec![
+            locale.format(
+                "codex.health",
+                &[("value", &format!("{:.0}", template.health.max_hp))],
+            ),
+            locale.format(
+                "codex.speed",
+                &[("value", &format!("{:.1}", template.speed))],
+            ),
+            locale.format("codex.size", &[("value", &locale.get(template.size.key()))]),
+        ];
+        let defense = template.stats.value(Stat::Defense);
+        if defense > R32::ZERO {
+            let reduction = (1.0 - 1.0 / (1.0 + defense.as_f32())) * 100.0;
+            lines.push(locale.format("codex.armor", &[("value", &reduction.round())]));
+        }
+        let target = model::Health::new(model::Hp::new(PREVIEW_TARGET_HP));
+        let abilities = template.describe_abilities(&self.animations, &target);
+        if !abilities.is_empty() {
+            lines.push(locale.format("codex.abilities", &[("value", &locale.preview(&abilities))]));
+        }
+        lines
+    }
+}
+
+impl geng::State for Codex {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(geng);
+        let locale = &self.locale;
+        let center = screen.center();
+
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("codex.title"),
+            vec2(center.x, screen.y_max - 80.0),
+            geng::TextAlign::CENTER,
+            56.0,
+            Rgba::WHITE,
+        );
+
+        // The list of the enemies
+        let enemies = self.templates.named_enemies();
+        let list_x = screen.x_min + screen.width() * 0.2;
+        let top = screen.y_max - 180.0;
+        for (index, (name, _)) in enemies.iter().enumerate() {
+            let known = self.is_known(name);
+            let text = if known {
+                locale.get(&format!("unit.{}", name)).to_owned()
+            } else {
+                locale.get("codex.unknown").to_owned()
+            };
+            let color = if index == self.selected {
+                ui::SELECTED_COLOR
+            } else if known {
+                Rgba::WHITE
+            } else {
+                LOCKED_COLOR
+            };
+            font.draw(
+                framebuffer,
+                camera,
+                &text,
+                vec2(list_x, top - index as f32 * ENTRY_SPACING),
+                geng::TextAlign::CENTER,
+                32.0,
+                color,
+            );
+        }
+
+        // The selected enemy
+        let (name, template) = enemies[self.selected];
+        let panel_x = screen.x_min + screen.width() * 0.6;
+        if !self.is_known(name) {
+            font.draw(
+                framebuffer,
+                camera,
+                locale.get("codex.locked"),
+                vec2(panel_x, center.y),
+                geng::TextAlign::CENTER,
+                28.0,
+                LOCKED_COLOR,
+            );
+        } else {
+            if let Some((sprite, texture)) = self
+                .animations
+                .get(template.idle_animation)
+                .keyframes
+                .first()
+                .and_then(|frame| Some((&frame.sprite, frame.sprite.texture()?)))
+            {
+                let size = sprite.size * (SPRITE_BOX / sprite.size.x.max(sprite.size.y));
+                let aabb =
+                    AABB::point(vec2(panel_x, top - SPRITE_BOX / 2.0)).extend_symmetric(size / 2.0);
+                draw_2d::TexturedQuad::new(aabb, &*texture).draw_2d(geng, framebuffer, camera);
+            }
+            let lines_top = top - SPRITE_BOX - 40.0;
+            for (index, line) in self.describe(template).iter().enumerate() {
+                font.draw(
+                    framebuffer,
+                    camera,
+                    line,
+                    vec2(panel_x, lines_top - index as f32 * 32.0),
+                    geng::TextAlign::CENTER,
+                    24.0,
+                    Rgba::WHITE,
+                );
+            }
+        }
+
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("codex.help"),
+            vec2(center.x, screen.y_min + 40.0),
+            geng::TextAlign::CENTER,
+            24.0,
+            Rgba::GRAY,
+        );
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        let count = self.templates.named_enemies().len();
+        if let geng::Event::KeyDown { key } = event {
+            match key {
+                geng::Key::Escape => self.back_to_menu(),
+                key => match ui::UiInput::from_key(key) {
+                    Some(ui::UiInput::Up) => self.selected = (self.selected + count - 1) % count,
+                    Some(ui::UiInput::Down) => self.selected = (self.selected + 1) % count,
+                    Some(ui::UiInput::Confirm) => self.back_to_menu(),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+
+use model::{Id, Model};
+
+mod commands;
+
+use commands::COMMANDS;
+
+/// Maximum number of output lines kept in the console.
+const MAX_HISTORY: usize = 20;
+
+/// A drop-down console with debug commands, toggled with the backquote key.
+#[derive(Debug)]
+pub struct Console {
+    pub open: bool,
+    /// The command being typed.
+    pub input: String,
+    /// Entered commands and their output, the latest last.
+    pub history: Vec<String>,
+    /// Multiplier of the simulation speed.
+    pub time_scale: f64,
+    /// The unit selected in the debug overlay.
+    pub inspected: Option<Id>,
+}
+
+/// The game state available to the commands.
+pub struct CommandContext<'a> {
+    pub model: &'a mut Model,
+    pub time_scale: &'a mut f64,
+    pub inspected: Option<Id>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: vec![],
+            time_scale: 1.0,
+            inspected: None,
+        }
+    }
+}
+
+impl Console {
+    /// Handles the key press while the console is open.
+    pub fn handle_key(&mut self, key: geng::Key, shift: bool, model: &mut Model) {
+        match key {
+            geng::Key::Enter => {
+                let input = std::mem::take(&mut self.input);
+                self.execute(&input, model);
+            }
+            geng::Key::Backspace => {
+                self.input.pop();
+            }
+            geng::Key::Escape => self.open = false,
+            _ => {
+                if let Some(c) = key_char(key, shift) {
+                    self.input.push(c);
+                }
+            }
+        }
+    }
+
+    fn execute(&mut self, input: &str, model: &mut Model) {
+        let mut words = input.split_whitespace();
+        let name = match words.next() {
+            Some(name) => name,
+            None => return,
+        };
+        let args: Vec<&str> = words.collect();
+        self.log(format!("> {}", input));
+        let output = if name == "help" {
+            Ok(COMMANDS
+                .iter()
+                .map(|command| command.usage)
+                .collect::<Vec<_>>()
+                .join(", "))
+        } else {
+            match COMMANDS.iter().find(|command| command.name == name) {
+                Some(command) => (command.run)(
+                    &mut CommandContext {
+                        model,
+                        time_scale: &mut self.time_scale,
+                        inspected: self.inspected,
+                    },
+                    &args,
+                ),
+                None => Err(format!("Unknown command `{}`, try `help`", name)),
+            }
+        };
+        match output {
+            Ok(output) if !output.is_empty() => self.log(output),
+            Ok(_) => {}
+            Err(error) => self.log(format!("Error: {}", error)),
+        }
+    }
+
+    pub fn log(&mut self, line: String) {
+        self.history.push(line);
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
+
+/// The character the key types, also used by the address field of the lobby.
+pub fn key_char(key: geng::Key, shift: bool) -> Option<char> {
+    use geng::Key;
+    let c = match key {
+        Key::A => 'a',
+        Key::B => 'b',
+        Key::C => 'c',
+        Key::D => 'd',
+        Key::E => 'e',
+        Key::F => 'f',
+        Key::G => 'g',
+        Key::H => 'h',
+        Key::I => 'i',
+        Key::J => 'j',
+        Key::K => 'k',
+        Key::L => 'l',
+        Key::M => 'm',
+        Key::N => 'n',
+        Key::O => 'o',
+        Key::P => 'p',
+        Key::Q => 'q',
+        Key::R => 'r',
+        Key::S => 's',
+        Key::T => 't',
+        Key::U => 'u',
+        Key::V => 'v',
+        Key::W => 'w',
+        Key::X => 'x',
+        Key::Y => 'y',
+        Key::Z => 'z',
+        Key::Num0 => '0',
+        Key::Num1 => '1',
+        Key::Num2 => '2',
+        Key::Num3 => '3',
+        Key::Num4 => '4',
+        Key::Num5 => '5',
+        Key::Num6 => '6',
+        Key::Num7 => '7',
+        Key::Num8 => '8',
+        Key::Num9 => '9',
+        Key::Space => ' ',
+        Key::Period => '.',
+        Key::Minus if shift => '_',
+        Key::Minus => '-',
+        _ => return None,
+    };
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use game::{DebugCommand, PlayerEvent};
+
+    #[test]
+    fn commands_queue_their_changes() {
+        let mut model = bench::test_model();
+        let mut console = Console::default();
+        console.execute("spawn tank 2", &mut model);
+        assert_eq!(console.history.last().unwrap(), "Spawned 2 tank");
+        let spawned: Vec<&str> = model
+            .queued_events
+            .iter()
+            .filter_map(|event| match event {
+                PlayerEvent::Debug(DebugCommand::SpawnUnit { template, .. }) => {
+                    Some(template.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(spawned, ["tank", "tank"]);
+    }
+
+    #[test]
+    fn invalid_input_is_reported() {
+        let mut model = bench::test_model();
+        let mut console = Console::default();
+        console.execute("spawn nothing", &mut model);
+        assert_eq!(
+            console.history.last().unwrap(),
+            "Error: Unknown template `nothing`"
+        );
+        console.execute("set_hp lots", &mut model);
+        assert_eq!(
+            console.history.last().unwrap(),
+            "Error: Invalid argument `lots`"
+        );
+        console.execute("give_status", &mut model);
+        assert_eq!(console.history.last().unwrap(), "Error: Missing argument 1");
+        console.execute("fly", &mut model);
+        assert_eq!(
+            console.history.last().unwrap(),
+            "Error: Unknown command `fly`, try `help`"
+        );
+        assert!(model.queued_events.is_empty());
+    }
+
+    #[test]
+    fn the_time_scale_is_clamped() {
+        let mut model = bench::test_model();
+        let mut console = Console::default();
+        console.execute("timescale 100", &mut model);
+        assert_eq!(console.time_scale, 10.0);
+        console.execute("timescale -1", &mut model);
+        assert_eq!(console.time_scale, 0.0);
+    }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+use game::{debug_status, DebugCommand, PlayerEvent, UNIT_FIELDS};
+use model::{Coord, Faction, Hp, Time};
+use std::str::FromStr;
+
+/// A console command, looked up by the first word of the input.
+pub struct Command {
+    pub name: &'static str,
+    pub usage: &'static str,
+    /// Returns the output to show in the console.
+    pub run: fn(&mut CommandContext, &[&str]) -> Result<String, String>,
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "spawn",
+        usage: "spawn <template> [count]",
+        run: spawn,
+    },
+    Command {
+        name: "give_status",
+        usage: "give_status <status> [time]",
+        run: give_status,
+    },
+    Command {
+        name: "set_hp",
+        usage: "set_hp <hp>",
+        run: set_hp,
+    },
+    Command {
+        name: "timescale",
+        usage: "timescale <scale>",
+        run: timescale,
+    },
+    Command {
+        name: "edit",
+        usage: "edit <field> <value>",
+        run: edit,
+    },
+    Command {
+        name: "trace",
+        usage: "trace",
+        run: trace,
+    },
+    Command {
+        name: "kill_all",
+        usage: "kill_all",
+        run: kill_all,
+    },
+    Command {
+        name: "replay",
+        usage: "replay <crash tick>",
+        run: replay,
+    },
+];
+
+/// Queues the change of the model for the next tick.
+fn queue(context: &mut CommandContext, command: DebugCommand) {
+    context.model.handle_event(PlayerEvent::Debug(command));
+}
+
+/// Parses the argument at the index, falling back to the default if it is missing.
+fn arg<T: FromStr>(args: &[&str], index: usize, default: Option<T>) -> Result<T, String> {
+    match args.get(index) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| format!("Invalid argument `{}`", arg)),
+        None => default.ok_or_else(|| format!("Missing argument {}", index + 1)),
+    }
+}
+
+fn spawn(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let name: String = arg(args, 0, None)?;
+    let count: usize = arg(args, 1, Some(1))?;
+    if context.model.world.templates.get(&name).is_none() {
+        return Err(format!("Unknown template `{}`", name));
+    }
+    let (faction, distance) = match name.as_str() {
+        "artillery" | "tank" | "healer" => (Faction::Mech, 5.0),
+        _ => (Faction::Alien, 25.0),
+    };
+    for index in 0..count {
+        let position = vec2(
+            context.model.world.left_border + Coord::new(distance + index as f32),
+            context.model.world.ground_level + Coord::new(5.0),
+        );
+        queue(
+            context,
+            DebugCommand::SpawnUnit {
+                template: name.clone(),
+                position,
+                faction,
+            },
+        );
+    }
+    Ok(format!("Spawned {} {}", count, name))
+}
+
+fn give_status(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let name: String = arg(args, 0, None)?;
+    let time = Time::new(arg(args, 1, Some(5.0))?);
+    if debug_status(&name, time).is_none() {
+        return Err(format!("Unknown status `{}`", name));
+    }
+    let count = mech_count(context);
+    queue(
+        context,
+        DebugCommand::GiveStatus {
+            status: name.clone(),
+            time,
+        },
+    );
+    Ok(format!("Gave {} to {} mechs", name, count))
+}
+
+fn set_hp(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let hp = Hp::new(arg(args, 0, None)?);
+    queue(context, DebugCommand::SetHp(hp));
+    Ok(String::new())
+}
+
+fn timescale(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let scale: f64 = arg(args, 0, None)?;
+    *context.time_scale = scale.clamp(0.0, 10.0);
+    Ok(format!("Time scale is {}", context.time_scale))
+}
+
+/// Changes a field of the unit selected in the debug overlay.
+fn edit(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let unit = context
+        .inspected
+        .filter(|id| context.model.world.units.get(id).is_some())
+        .ok_or("No unit is selected, click one with the debug overlay on")?;
+    let field: String = arg(args, 0, None)?;
+    if field == "clear_statuses" {
+        queue(context, DebugCommand::ClearStatuses(unit));
+        return Ok(String::new());
+    }
+    let value = R32::new(arg(args, 1, None)?);
+    if !UNIT_FIELDS.contains(&field.as_str()) {
+        return Err(format!("Unknown field `{}`", field));
+    }
+    queue(context, DebugCommand::EditUnit { unit, field, value });
+    Ok(String::new())
+}
+
+/// Starts recording a chrome trace of the profiler, or stops and saves it.
+fn trace(context: &mut CommandContext, _args: &[&str]) -> Result<String, String> {
+    let profiler = &mut context.model.profiler;
+    if profiler.is_tracing() {
+        let path = profiler.stop_trace()?;
+        Ok(format!("Saved the trace to {}", path))
+    } else {
+        profiler.start_trace();
+        Ok("Recording the trace, run `trace` again to save it".to_owned())
+    }
+}
+
+fn kill_all(context: &mut CommandContext, _args: &[&str]) -> Result<String, String> {
+    let count = context.model.world.units.len() - mech_count(context);
+    queue(context, DebugCommand::KillAll);
+    Ok(format!("Killed {} enemies", count))
+}
+
+/// Replaces the run with the one from the crash file, replaying the inputs up to the crash.
+fn replay(context: &mut CommandContext, args: &[&str]) -> Result<String, String> {
+    let tick: u64 = arg(args, 0, None)?;
+    let crash = crash::load(tick)?;
+    let message = crash.message.clone();
+    let assets = context.model.assets().clone();
+    *context.model = Model::from_crash(&assets, crash);
+    Ok(format!("Replaying the crash: {}", message))
+}
+
+fn mech_count(context: &CommandContext) -> usize {
+    context
+        .model
+        .world
+        .units
+        .iter()
+        .filter(|unit| unit.faction == Faction::Mech)
+        .count()
+}
@@ -0,0 +1,208 @@
+use super::*;
+
+use std::collections::HashMap;
+
+const PREFERENCES_KEY: &str = "controls";
+
+/// An action that can be performed by the player and bound to keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ControlAction {
+    SpawnArtillery,
+    SpawnTank,
+    SpawnHealer,
+    /// Order the mechs to fire at the cursor while held.
+    Aim,
+    /// Reload the weapons of all mechs.
+    Reload,
+    /// Use the object next to a mech.
+    Interact,
+    /// Order the mechs to block while held.
+    Block,
+    /// Order the airborne mechs to glide while held.
+    Glide,
+    /// Place a ping at the cursor.
+    Ping,
+    Pause,
+    /// Show the debug overlay.
+    #[serde(alias = "ToggleHitboxes")]
+    ToggleDebug,
+    ToggleMinimap,
+    ToggleProfiler,
+    ToggleEffectTree,
+    /// Show the recent damage, heals and statuses.
+    ToggleCombatLog,
+    Screenshot,
+    SaveClip,
+    ToggleFullscreen,
+    /// Select the next unit of the sandbox palette.
+    NextSandboxUnit,
+    /// Spawn the selected unit of the sandbox at the cursor.
+    SpawnSandboxUnit,
+    /// Slow down the time in the sandbox.
+    SlowDown,
+    /// Speed up the time in the sandbox.
+    SpeedUp,
+    /// Rewind the simulation a few seconds and simulate it again, for debugging.
+    Rewind,
+}
+
+impl ControlAction {
+    pub const ALL: [Self; 23] = [
+        Self::SpawnArtillery,
+        Self::SpawnTank,
+        Self::SpawnHealer,
+        Self::Aim,
+        Self::Reload,
+        Self::Interact,
+        Self::Block,
+        Self::Glide,
+        Self::Ping,
+        Self::Pause,
+        Self::ToggleDebug,
+        Self::ToggleMinimap,
+        Self::ToggleProfiler,
+        Self::ToggleEffectTree,
+        Self::ToggleCombatLog,
+        Self::Screenshot,
+        Self::SaveClip,
+        Self::ToggleFullscreen,
+        Self::NextSandboxUnit,
+        Self::SpawnSandboxUnit,
+        Self::SlowDown,
+        Self::SpeedUp,
+        Self::Rewind,
+    ];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::SpawnArtillery => "control.spawn_artillery",
+            Self::SpawnTank => "control.spawn_tank",
+            Self::SpawnHealer => "control.spawn_healer",
+            Self::Aim => "control.aim",
+            Self::Reload => "control.reload",
+            Self::Interact => "control.interact",
+            Self::Block => "control.block",
+            Self::Glide => "control.glide",
+            Self::Ping => "control.ping",
+            Self::Pause => "control.pause",
+            Self::ToggleDebug => "control.toggle_debug",
+            Self::ToggleMinimap => "control.toggle_minimap",
+            Self::ToggleProfiler => "control.toggle_profiler",
+            Self::ToggleEffectTree => "control.toggle_effect_tree",
+            Self::ToggleCombatLog => "control.toggle_combat_log",
+            Self::Screenshot => "control.screenshot",
+            Self::SaveClip => "control.save_clip",
+            Self::ToggleFullscreen => "control.toggle_fullscreen",
+            Self::NextSandboxUnit => "control.next_sandbox_unit",
+            Self::SpawnSandboxUnit => "control.spawn_sandbox_unit",
+            Self::SlowDown => "control.slow_down",
+            Self::SpeedUp => "control.speed_up",
+            Self::Rewind => "control.rewind",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<geng::Key> {
+        match self {
+            Self::SpawnArtillery => vec![geng::Key::Q],
+            Self::SpawnTank => vec![geng::Key::W],
+            Self::SpawnHealer => vec![geng::Key::E],
+            Self::Aim => vec![geng::Key::Space],
+            Self::Reload => vec![geng::Key::R],
+            Self::Interact => vec![geng::Key::F],
+            Self::Block => vec![geng::Key::LShift],
+            Self::Glide => vec![geng::Key::G],
+            Self::Ping => vec![geng::Key::Z],
+            Self::Pause => vec![geng::Key::Escape, geng::Key::P],
+            Self::ToggleDebug => vec![geng::Key::F1],
+            Self::ToggleMinimap => vec![geng::Key::M],
+            Self::ToggleProfiler => vec![geng::Key::F2],
+            Self::ToggleEffectTree => vec![geng::Key::F3],
+            Self::ToggleCombatLog => vec![geng::Key::F4],
+            Self::Screenshot => vec![geng::Key::F12],
+            Self::SaveClip => vec![geng::Key::F11],
+            Self::ToggleFullscreen => vec![geng::Key::F10],
+            Self::NextSandboxUnit => vec![geng::Key::Tab],
+            Self::SpawnSandboxUnit => vec![geng::Key::X],
+            Self::SlowDown => vec![geng::Key::PageDown],
+            Self::SpeedUp => vec![geng::Key::PageUp],
+            Self::Rewind => vec![geng::Key::F5],
+        }
+    }
+}
+
+/// Key bindings for every [ControlAction] and the aim assist, persisted between sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Controls {
+    bindings: HashMap<ControlAction, Vec<geng::Key>>,
+    /// How strongly the aim is pulled towards the enemies, one of the [model::AIM_ASSIST_STRENGTHS].
+    #[serde(default)]
+    pub aim_assist: f32,
+}
+
+impl Controls {
+    /// Loads the saved bindings, falling back to the defaults for missing actions.
+    pub fn load() -> Self {
+        let mut controls: Self = storage::load(PREFERENCES_KEY).unwrap_or_default();
+        for action in ControlAction::ALL {
+            controls
+                .bindings
+                .entry(action)
+                .or_insert_with(|| action.default_keys());
+        }
+        controls
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    pub fn keys(&self, action: ControlAction) -> &[geng::Key] {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Whether any of the keys bound to the action are currently pressed.
+    pub fn is_pressed(&self, action: ControlAction, window: &geng::Window) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|&key| window.is_key_pressed(key))
+    }
+
+    /// Returns the action bound to the key, if any.
+    pub fn action(&self, key: geng::Key) -> Option<ControlAction> {
+        ControlAction::ALL
+            .into_iter()
+            .find(|&action| self.keys(action).contains(&key))
+    }
+
+    /// Binds the key to the action, removing it from any other action.
+    pub fn bind(&mut self, action: ControlAction, key: geng::Key) {
+        for keys in self.bindings.values_mut() {
+            keys.retain(|&other| other != key);
+        }
+        self.bindings.insert(action, vec![key]);
+    }
+
+    pub fn reset(&mut self, action: ControlAction) {
+        let keys = action.default_keys();
+        for other in self.bindings.values_mut() {
+            other.retain(|key| !keys.contains(key));
+        }
+        self.bindings.insert(action, keys);
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            bindings: ControlAction::ALL
+                .into_iter()
+                .map(|action| (action, action.default_keys()))
+                .collect(),
+            aim_assist: 0.0,
+        }
+    }
+}
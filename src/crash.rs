@@ -0,0 +1,166 @@
+use super::*;
+
+use model::{TickInput, World};
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+/// The last panic, filled in by the panic hook on whichever thread panicked.
+static LAST_PANIC: Mutex<Option<Panic>> = Mutex::new(None);
+
+thread_local! {
+    /// The latest snapshot of the running simulation and the inputs since then.
+    static RECORDING: RefCell<Option<Recording>> = RefCell::new(None);
+}
+
+struct Panic {
+    message: String,
+    /// Where the report was saved to, missing if the panic was not on the simulation's thread.
+    location: Option<Result<String, String>>,
+}
+
+struct Recording {
+    config: RunConfig,
+    world: Rc<World>,
+    inputs: Vec<TickInput>,
+}
+
+/// The contents of a crash file, enough to simulate the ticks up to the crash again,
+/// see [model::Model::from_crash].
+#[derive(Serialize, Deserialize)]
+pub struct CrashFile {
+    pub message: String,
+    pub config: RunConfig,
+    /// The state at the latest snapshot before the crash.
+    pub world: World,
+    /// Inputs of the ticks from the snapshot up to the crash.
+    pub inputs: Vec<TickInput>,
+}
+
+/// Saves the crash reports on top of the existing panic hook.
+///
+/// The report is written by the hook itself: on the web the panics abort,
+/// so nothing after the panic gets to run.
+pub fn install_hook() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = match info.location() {
+            Some(location) => format!("{} at {}", info, location),
+            None => info.to_string(),
+        };
+        // The worker threads of the parallel passes have no recording,
+        // their report is saved once the panic reaches the simulation's thread
+        let location = has_recording().then(|| save_recording(&message));
+        *LAST_PANIC.lock().unwrap() = Some(Panic { message, location });
+        hook(info);
+    }));
+}
+
+/// Starts the inputs over from the new snapshot of the simulation.
+pub fn record_snapshot(config: &RunConfig, world: &Rc<World>) {
+    RECORDING.with(|recording| {
+        *recording.borrow_mut() = Some(Recording {
+            config: config.clone(),
+            world: world.clone(),
+            inputs: vec![],
+        })
+    });
+}
+
+pub fn record_input(input: &TickInput) {
+    RECORDING.with(|recording| {
+        if let Some(recording) = &mut *recording.borrow_mut() {
+            recording.inputs.push(input.clone());
+        }
+    });
+}
+
+/// Forgets the simulation once the run is left, later panics are not its crashes.
+pub fn stop_recording() {
+    RECORDING.with(|recording| *recording.borrow_mut() = None);
+}
+
+fn has_recording() -> bool {
+    RECORDING.with(|recording| {
+        recording
+            .try_borrow()
+            .map_or(false, |recording| recording.is_some())
+    })
+}
+
+fn save_recording(message: &str) -> Result<String, String> {
+    // The hook may be called while the recording is borrowed, if recording panicked
+    let file = RECORDING.with(|recording| {
+        let recording = recording.try_borrow().ok()?;
+        let recording = recording.as_ref()?;
+        Some(CrashFile {
+            message: message.to_owned(),
+            config: recording.config.clone(),
+            world: (*recording.world).clone(),
+            inputs: recording.inputs.clone(),
+        })
+    });
+    let file = file.ok_or_else(|| "No simulation was running".to_owned())?;
+    let tick = file.world.current_tick;
+    let report = serde_json::to_string(&file)
+        .map_err(|error| format!("Failed to serialize the crash report: {}", error))?;
+    save(tick, &report)
+}
+
+/// A crash of the simulation, shown to the player instead of the game.
+#[derive(Debug)]
+pub struct CrashReport {
+    pub message: String,
+    /// Where the report was saved to.
+    pub location: Result<String, String>,
+}
+
+impl CrashReport {
+    /// The report of the last panic, saved to a crash file by the panic hook if it can.
+    pub fn take() -> Self {
+        let panic = LAST_PANIC.lock().unwrap().take();
+        let message = panic
+            .as_ref()
+            .map_or("Unknown panic", |panic| &panic.message)
+            .to_owned();
+        let location = match panic.and_then(|panic| panic.location) {
+            Some(location) => location,
+            None => save_recording(&message),
+        };
+        Self { message, location }
+    }
+}
+
+/// Reads the crash file saved at the tick, to replay the crash with [model::Model::from_crash].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(tick: u64) -> Result<CrashFile, String> {
+    let path = file_path(tick);
+    let report = std::fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    serde_json::from_str(&report).map_err(|error| format!("Invalid crash report: {}", error))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(_tick: u64) -> Result<CrashFile, String> {
+    Err("The crash reports can only be replayed in the native build".to_owned())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save(tick: u64, report: &str) -> Result<String, String> {
+    let path = file_path(tick);
+    std::fs::write(&path, report)
+        .map_err(|error| format!("Failed to save the crash report: {}", error))?;
+    Ok(path.display().to_string())
+}
+
+/// The crash files are named by the tick of their snapshot.
+#[cfg(not(target_arch = "wasm32"))]
+fn file_path(tick: u64) -> std::path::PathBuf {
+    run_dir().join(format!("crash-{}.json", tick))
+}
+
+/// There is no file system on the web, so the report is printed to the log instead.
+#[cfg(target_arch = "wasm32")]
+fn save(_tick: u64, report: &str) -> Result<String, String> {
+    error!("Crash report:\n{}", report);
+    Ok("the browser console".to_owned())
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+const PREFERENCES_KEY: &str = "display";
+/// Scales of the world's render resolution to pick from, lower is faster on weak GPUs.
+pub const RENDER_SCALES: [f32; 4] = [0.25, 0.5, 0.75, 1.0];
+/// Scales of the HUD to pick from, on top of the scaling for taller screens.
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+/// Margins of the safe area to pick from, as a fraction of the screen size on each side.
+pub const SAFE_AREAS: [f32; 4] = [0.0, 0.025, 0.05, 0.1];
+
+/// How the frame rate is limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameLimit {
+    /// Synchronized with the monitor, changes after a restart.
+    VSync,
+    Fps30,
+    Fps60,
+    Unlimited,
+}
+
+impl FrameLimit {
+    pub const ALL: [Self; 4] = [Self::VSync, Self::Fps30, Self::Fps60, Self::Unlimited];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::VSync => "display.vsync",
+            Self::Fps30 => "display.fps_30",
+            Self::Fps60 => "display.fps_60",
+            Self::Unlimited => "display.unlimited",
+        }
+    }
+
+    fn max_fps(&self) -> Option<f64> {
+        match self {
+            Self::Fps30 => Some(30.0),
+            Self::Fps60 => Some(60.0),
+            Self::VSync | Self::Unlimited => None,
+        }
+    }
+}
+
+/// The window settings chosen in the menu, saved between sessions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    pub fullscreen: bool,
+    /// The world is drawn at this fraction of the window's resolution, the interface is not scaled.
+    pub render_scale: f32,
+    pub frame_limit: FrameLimit,
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// The HUD is kept this far from the edges of the screen, for the displays that cut them off.
+    #[serde(default)]
+    pub safe_area: f32,
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            render_scale: 1.0,
+            frame_limit: FrameLimit::VSync,
+            ui_scale: default_ui_scale(),
+            safe_area: 0.0,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    pub fn apply_fullscreen(&self, geng: &Geng) {
+        if geng.window().is_fullscreen() != self.fullscreen {
+            geng.window().toggle_fullscreen();
+        }
+    }
+}
+
+/// Sleeps at the end of the frame to keep the frame rate under the limit.
+pub struct FrameLimiter {
+    timer: Timer,
+}
+
+impl Default for FrameLimiter {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(),
+        }
+    }
+}
+
+impl FrameLimiter {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn wait(&mut self, limit: FrameLimit) {
+        if let Some(max_fps) = limit.max_fps() {
+            let left = 1.0 / max_fps - self.timer.elapsed();
+            if left > 0.0 {
+                std::thread::sleep(std::time::Duration::from_secs_f64(left));
+            }
+        }
+        self.timer.tick();
+    }
+
+    /// The browser schedules the frames itself, so only the vsync is used on the web.
+    #[cfg(target_arch = "wasm32")]
+    pub fn wait(&mut self, _limit: FrameLimit) {}
+}
@@ -0,0 +1,347 @@
+use super::*;
+
+use geng::{Camera2d, Draw2d};
+use model::{
+    Level, LevelObject, LevelObjectKind, Weather, DOOR_SIZE, LEVEL_AREA_WIDTH, WATER_DEPTH,
+};
+
+mod simulation;
+mod waves;
+
+use waves::WavePanel;
+
+const FOV: f32 = 20.0;
+/// Camera speed in world units per second.
+const CAMERA_SPEED: f32 = 20.0;
+/// The objects are placed on a grid of this size.
+const GRID_SIZE: f32 = 0.5;
+/// Right click removes the closest object if it is that close to the cursor.
+const REMOVE_DISTANCE: f32 = 2.0;
+const SPAWN_MARKER_SIZE: f32 = 0.8;
+/// Opacity of the object previewed under the cursor.
+const PREVIEW_ALPHA: f32 = 0.5;
+/// The day lengths the editor cycles through, in seconds.
+const DAY_LENGTHS: [Option<f32>; 4] = [None, Some(120.0), Some(240.0), Some(480.0)];
+
+/// A state for building the level by placing objects with the mouse.
+pub struct Editor {
+    geng: Geng,
+    assets: Rc<Assets>,
+    camera: Camera2d,
+    framebuffer_size: Vec2<f32>,
+    level: Level,
+    /// Index of the kind placed on click in [LevelObjectKind::ALL].
+    selected: usize,
+    waves: WavePanel,
+    /// Whether the level has changes that are not saved yet.
+    unsaved: bool,
+    transition: Option<geng::Transition>,
+}
+
+impl Editor {
+    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        Self {
+            geng: geng.clone(),
+            assets: assets.clone(),
+            camera: Camera2d {
+                center: vec2(0.0, 0.0),
+                rotation: 0.0,
+                fov: FOV,
+            },
+            framebuffer_size: vec2(1.0, 1.0),
+            level: Level::load(),
+            selected: 0,
+            waves: default(),
+            unsaved: false,
+            transition: None,
+        }
+    }
+
+    /// The grid position under the cursor.
+    fn cursor_x(&self) -> f32 {
+        let position = self.camera.screen_to_world(
+            self.framebuffer_size,
+            self.geng.window().mouse_pos().map(|x| x as f32),
+        );
+        (position.x / GRID_SIZE).round() * GRID_SIZE
+    }
+
+    fn place(&mut self) {
+        self.level.objects.push(LevelObject {
+            kind: LevelObjectKind::ALL[self.selected],
+            x: self.cursor_x(),
+        });
+        self.unsaved = true;
+    }
+
+    fn remove(&mut self) {
+        let x = self.cursor_x();
+        let closest = self
+            .level
+            .objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| (index, (object_center(object) - x).abs()))
+            .filter(|&(_, distance)| distance <= REMOVE_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+        if let Some((index, _)) = closest {
+            self.level.objects.remove(index);
+            self.unsaved = true;
+        }
+    }
+
+    fn save(&mut self) {
+        self.level.save();
+        self.unsaved = false;
+    }
+
+    /// Saves the level and starts a run with it.
+    fn play(&mut self) {
+        self.save();
+        let profile = Profile::load();
+        let config = RunConfig {
+            seed: global_rng().gen(),
+            difficulty: default(),
+            mode: default(),
+            commander: default(),
+            perks: profile.available_perks(),
+            language: Language::load(),
+            aim_assist: Controls::load().aim_assist,
+        };
+        let game = game::Game::new(&self.geng, &self.assets, config, &self.level);
+        self.transition = Some(geng::Transition::Switch(Box::new(game)));
+    }
+
+    fn draw_object(
+        &self,
+        kind: LevelObjectKind,
+        x: f32,
+        alpha: f32,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &self.camera;
+        if let Some(sprite) = kind.sprite(&self.assets) {
+            let aabb =
+                AABB::point(vec2(x, sprite.size.y / 2.0)).extend_symmetric(sprite.size / 2.0);
+            if let Some(texture) = sprite.texture() {
+                draw_2d::TexturedQuad::colored(aabb, &*texture, Rgba::new(1.0, 1.0, 1.0, alpha))
+                    .draw_2d(geng, framebuffer, camera);
+            }
+            return;
+        }
+        let area =
+            |height: f32| AABB::point(vec2(x, 0.0)).extend_positive(vec2(LEVEL_AREA_WIDTH, height));
+        match kind {
+            LevelObjectKind::SpawnPoint => {
+                let marker = AABB::point(vec2(x, SPAWN_MARKER_SIZE / 2.0))
+                    .extend_uniform(SPAWN_MARKER_SIZE / 2.0);
+                draw_2d::Quad::new(marker, Rgba::new(1.0, 0.3, 0.3, alpha)).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+                let spawn_points = self.level.spawn_points();
+                let label = match spawn_points.iter().position(|&point| point == x) {
+                    Some(index) => format!("#{}", index + 1),
+                    None => "spawn".to_owned(),
+                };
+                geng.default_font().draw(
+                    framebuffer,
+                    camera,
+                    &label,
+                    vec2(x, SPAWN_MARKER_SIZE * 1.5),
+                    geng::TextAlign::CENTER,
+                    0.6,
+                    Rgba::new(1.0, 1.0, 1.0, alpha),
+                );
+            }
+            LevelObjectKind::Door => {
+                let door =
+                    AABB::point(vec2(x, DOOR_SIZE.y / 2.0)).extend_symmetric(DOOR_SIZE / 2.0);
+                draw_2d::Quad::new(door, Rgba::new(0.45, 0.35, 0.3, alpha)).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+            LevelObjectKind::Water => {
+                draw_2d::Quad::new(area(WATER_DEPTH), Rgba::new(0.2, 0.4, 0.9, 0.5 * alpha))
+                    .draw_2d(geng, framebuffer, camera);
+            }
+            _ => {
+                if let Some((_, height)) = kind.hazard() {
+                    let color = match kind {
+                        LevelObjectKind::Spikes => Rgba::new(0.6, 0.6, 0.6, alpha),
+                        LevelObjectKind::Lava => Rgba::new(1.0, 0.35, 0.1, 0.9 * alpha),
+                        _ => Rgba::new(0.7, 0.9, 1.0, 0.15 * alpha),
+                    };
+                    draw_2d::Quad::new(area(height), color).draw_2d(geng, framebuffer, camera);
+                }
+            }
+        }
+    }
+}
+
+/// The horizontal center of the object, the areas are placed by their left edge.
+fn object_center(object: &LevelObject) -> f32 {
+    match object.kind {
+        LevelObjectKind::Spikes
+        | LevelObjectKind::Lava
+        | LevelObjectKind::Wind
+        | LevelObjectKind::Water => object.x + LEVEL_AREA_WIDTH / 2.0,
+        _ => object.x,
+    }
+}
+
+impl geng::State for Editor {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.framebuffer_size = framebuffer.size().map(|x| x as f32);
+        ugli::clear(framebuffer, Some(Rgba::new(0.1, 0.1, 0.15, 1.0)), None);
+        let geng = &self.geng;
+
+        // Ground
+        let width = FOV * self.framebuffer_size.x / self.framebuffer_size.y;
+        let ground = AABB::point(vec2(self.camera.center.x - width / 2.0, -FOV / 2.0))
+            .extend_positive(vec2(width, FOV / 2.0));
+        draw_2d::Quad::new(ground, Rgba::new(0.3, 0.25, 0.2, 1.0)).draw_2d(
+            geng,
+            framebuffer,
+            &self.camera,
+        );
+
+        // Where the waves start
+        for (index, wave) in self.level.waves.iter().enumerate() {
+            let line = AABB::point(vec2(wave.position, 0.0)).extend_positive(vec2(0.05, FOV / 2.0));
+            draw_2d::Quad::new(line, Rgba::new(1.0, 0.3, 0.3, 0.5)).draw_2d(
+                geng,
+                framebuffer,
+                &self.camera,
+            );
+            geng.default_font().draw(
+                framebuffer,
+                &self.camera,
+                &format!("Wave {}", index + 1),
+                vec2(wave.position + 0.2, FOV / 2.0 - 2.0),
+                geng::TextAlign::LEFT,
+                0.6,
+                Rgba::new(1.0, 0.3, 0.3, 1.0),
+            );
+        }
+
+        for object in &self.level.objects {
+            self.draw_object(object.kind, object.x, 1.0, framebuffer);
+        }
+        self.draw_object(
+            LevelObjectKind::ALL[self.selected],
+            self.cursor_x(),
+            PREVIEW_ALPHA,
+            framebuffer,
+        );
+
+        let camera = &geng::PixelPerfectCamera;
+        let font = geng.default_font();
+        let top_left = vec2(20.0, self.framebuffer_size.y - 40.0);
+        let unsaved = if self.unsaved { " (unsaved)" } else { "" };
+        font.draw(
+            framebuffer,
+            camera,
+            &format!(
+                "Level editor{}  Tool: {}  Weather: {}  Day: {}",
+                unsaved,
+                LevelObjectKind::ALL[self.selected].name(),
+                self.level.weather.name(),
+                match self.level.day_length {
+                    Some(length) => format!("{}s", length),
+                    None => "default".to_owned(),
+                }
+            ),
+            top_left,
+            geng::TextAlign::LEFT,
+            30.0,
+            Rgba::WHITE,
+        );
+        font.draw(
+            framebuffer,
+            camera,
+            "LMB - place, RMB - remove, Q/E or wheel - tool, A/D - scroll, W - waves, R - weather, N - day length, S - save, Enter - play, Escape - menu",
+            top_left - vec2(0.0, 30.0),
+            geng::TextAlign::LEFT,
+            20.0,
+            Rgba::GRAY,
+        );
+        if self.waves.open {
+            self.waves.draw(geng, &self.level, framebuffer);
+        }
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        let tools = LevelObjectKind::ALL.len();
+        match event {
+            geng::Event::KeyDown { key } => match key {
+                geng::Key::W => self.waves.open = !self.waves.open,
+                geng::Key::N => {
+                    let index = DAY_LENGTHS
+                        .iter()
+                        .position(|&length| length == self.level.day_length)
+                        .unwrap_or(0);
+                    self.level.day_length = DAY_LENGTHS[(index + 1) % DAY_LENGTHS.len()];
+                    self.unsaved = true;
+                }
+                geng::Key::R => {
+                    let index = Weather::ALL
+                        .iter()
+                        .position(|&weather| weather == self.level.weather)
+                        .unwrap_or(0);
+                    self.level.weather = Weather::ALL[(index + 1) % Weather::ALL.len()];
+                    self.unsaved = true;
+                }
+                geng::Key::S => self.save(),
+                geng::Key::Enter => self.play(),
+                geng::Key::Escape => {
+                    let menu = menu::MainMenu::new(&self.geng, &self.assets);
+                    self.transition = Some(geng::Transition::Switch(Box::new(menu)));
+                }
+                _ if self.waves.open => {
+                    if self.waves.handle_key(key, &mut self.level, &self.assets) {
+                        self.unsaved = true;
+                    }
+                }
+                geng::Key::Q => self.selected = (self.selected + tools - 1) % tools,
+                geng::Key::E => self.selected = (self.selected + 1) % tools,
+                _ => {}
+            },
+            geng::Event::Wheel { delta } => {
+                let step = if delta > 0.0 { 1 } else { tools - 1 };
+                self.selected = (self.selected + step) % tools;
+            }
+            geng::Event::MouseDown { button, .. } => match button {
+                geng::MouseButton::Left => self.place(),
+                geng::MouseButton::Right => self.remove(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, delta_time: f64) {
+        self.waves.update();
+        if self.waves.open {
+            // The arrow keys edit the waves
+            return;
+        }
+        let window = self.geng.window();
+        let mut direction = 0.0;
+        if window.is_key_pressed(geng::Key::A) || window.is_key_pressed(geng::Key::Left) {
+            direction -= 1.0;
+        }
+        if window.is_key_pressed(geng::Key::D) || window.is_key_pressed(geng::Key::Right) {
+            direction += 1.0;
+        }
+        self.camera.center.x += direction * CAMERA_SPEED * delta_time as f32;
+    }
+
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
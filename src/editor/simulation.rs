@@ -0,0 +1,121 @@
+use super::*;
+
+use game::MechType;
+use model::{Coord, Faction, Model, Time, Wave};
+
+/// Simulated time after which the wave counts as not cleared.
+const MAX_SIMULATION_TIME: f32 = 180.0;
+/// Ticks simulated per frame, so that the editor stays responsive during the simulation.
+const TICKS_PER_FRAME: usize = 240;
+
+/// The outcome of a wave played by the default team of mechs.
+#[derive(Debug, Clone)]
+pub struct WaveReport {
+    pub cleared: bool,
+    /// Seconds until the wave was cleared or the team was lost.
+    pub time: f32,
+    pub enemies: usize,
+    pub mechs_lost: usize,
+    /// Share of the team's health lost.
+    pub damage_taken: f32,
+}
+
+/// Plays the wave of the level without rendering, with a mech of every type
+/// standing at the wave's position and no input from the player.
+/// Advanced a number of ticks per frame by [WaveSimulation::update].
+pub struct WaveSimulation {
+    model: Model,
+    enemies: usize,
+    /// Total health of the team at the start.
+    start_hp: f32,
+}
+
+impl WaveSimulation {
+    pub fn new(assets: &Rc<Assets>, level: &Level, index: usize) -> Self {
+        let config = RunConfig {
+            seed: 0,
+            difficulty: default(),
+            mode: default(),
+            commander: default(),
+            perks: vec![],
+            language: default(),
+            aim_assist: 0.0,
+        };
+        let mut model = Model::preview(assets, config);
+        model.load_level(level);
+        // Nobody is there to follow the prompts
+        model.world.tutorial.clear();
+        let wave = level.waves[index].build(&level.spawn_points(), &model.world.templates);
+        let enemies = wave.units.len();
+        model.world.left_border = wave.position;
+        model.world.waves.clear();
+        model.world.waves.push_back(wave);
+        // Keeps the waves from being generated after this one
+        model.world.waves.push_back(Wave {
+            position: Coord::new(f32::MAX),
+            units: vec![],
+        });
+        for (index, mech) in MechType::ALL.into_iter().enumerate() {
+            let template = model.world.templates.mech(mech).clone();
+            let position = vec2(
+                model.world.left_border + Coord::new(5.0 + index as f32 * 2.0),
+                model.world.ground_level,
+            );
+            model.spawn_unit(template, position, Faction::Mech);
+        }
+        let start_hp = mech_hp(&model).1;
+        Self {
+            model,
+            enemies,
+            start_hp,
+        }
+    }
+
+    /// Share of the longest simulation done so far, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        (self.model.time().as_f32() / MAX_SIMULATION_TIME).min(1.0)
+    }
+
+    /// Simulates the ticks of this frame, returns the report once the wave is over.
+    pub fn update(&mut self) -> Option<WaveReport> {
+        let model = &mut self.model;
+        let tick_time = Time::new(logic::TICK_TIME);
+        for _ in 0..TICKS_PER_FRAME {
+            model.world.cutscene = None;
+            model.update(tick_time);
+            if model.world.perk_choice.is_some() {
+                return Some(self.report(true));
+            }
+            if mech_hp(model).0 == 0 || model.time().as_f32() >= MAX_SIMULATION_TIME {
+                return Some(self.report(false));
+            }
+        }
+        None
+    }
+
+    fn report(&self, cleared: bool) -> WaveReport {
+        let (alive, hp) = mech_hp(&self.model);
+        WaveReport {
+            cleared,
+            time: self.model.time().as_f32(),
+            enemies: self.enemies,
+            mechs_lost: MechType::ALL.len() - alive,
+            damage_taken: 1.0 - hp / self.start_hp,
+        }
+    }
+}
+
+/// The number of mechs alive and their total health.
+fn mech_hp(model: &Model) -> (usize, f32) {
+    model
+        .world
+        .units
+        .iter()
+        .filter(|unit| unit.faction == Faction::Mech)
+        .fold((0, 0.0), |(count, hp), unit| {
+            (
+                count + 1,
+                hp + model.world.bodies.healths[unit.id].hp.as_f32(),
+            )
+        })
+}
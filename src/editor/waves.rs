@@ -0,0 +1,320 @@
+use super::*;
+
+use super::simulation::{WaveReport, WaveSimulation};
+use menu::cycle;
+use model::{LevelWave, UnitTemplates, WaveSpawn};
+
+/// Change of the wave position with a single key press.
+const POSITION_STEP: f32 = 5.0;
+/// Change of the spawn delay with a single key press.
+const DELAY_STEP: f32 = 0.5;
+/// Distance between the waves added in the panel, the same as between the generated ones.
+const WAVE_DISTANCE: f32 = 30.0;
+const LINE_HEIGHT: f32 = 26.0;
+
+/// The panel listing the waves of the level, edited with the keyboard.
+pub struct WavePanel {
+    pub open: bool,
+    /// Names of the enemy templates to pick from.
+    enemies: Vec<&'static str>,
+    /// Index of the selected wave.
+    wave: usize,
+    /// The selected spawn of the wave, the wave itself when not set.
+    spawn: Option<usize>,
+    /// The field of the selected spawn changed with Left/Right.
+    field: SpawnField,
+    /// The wave being simulated.
+    simulation: Option<(usize, WaveSimulation)>,
+    /// Outcome of the last simulated wave.
+    report: Option<(usize, WaveReport)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpawnField {
+    Template,
+    Count,
+    Delay,
+    SpawnPoint,
+}
+
+impl Default for SpawnField {
+    fn default() -> Self {
+        Self::Template
+    }
+}
+
+impl SpawnField {
+    const ALL: [Self; 4] = [Self::Template, Self::Count, Self::Delay, Self::SpawnPoint];
+}
+
+impl Default for WavePanel {
+    fn default() -> Self {
+        Self {
+            open: false,
+            enemies: UnitTemplates::ENEMY_NAMES.to_vec(),
+            wave: 0,
+            spawn: None,
+            field: default(),
+            simulation: None,
+            report: None,
+        }
+    }
+}
+
+impl WavePanel {
+    /// Handles the key press while the panel is open. Returns whether the level was changed.
+    pub fn handle_key(&mut self, key: geng::Key, level: &mut Level, assets: &Rc<Assets>) -> bool {
+        let spawn_points = level.spawn_points().len();
+        match key {
+            geng::Key::Up => self.select_previous(level),
+            geng::Key::Down => self.select_next(level),
+            geng::Key::Tab => {
+                self.field = cycle(&SpawnField::ALL, self.field, 1);
+            }
+            geng::Key::Left | geng::Key::Right => {
+                let delta = if key == geng::Key::Left { -1 } else { 1 };
+                return self.change_value(level, delta, spawn_points);
+            }
+            geng::Key::N => {
+                let position = level
+                    .waves
+                    .last()
+                    .map_or(0.0, |wave| wave.position + WAVE_DISTANCE);
+                level.waves.push(LevelWave {
+                    position,
+                    spawns: vec![],
+                });
+                self.wave = level.waves.len() - 1;
+                self.spawn = None;
+                return true;
+            }
+            geng::Key::A => {
+                let wave = match level.waves.get_mut(self.wave) {
+                    Some(wave) => wave,
+                    None => return false,
+                };
+                wave.spawns.push(WaveSpawn {
+                    template: self.enemies[0].to_owned(),
+                    count: 1,
+                    delay: 0.0,
+                    spawn_point: None,
+                });
+                self.spawn = Some(wave.spawns.len() - 1);
+                return true;
+            }
+            geng::Key::Backspace => return self.remove(level),
+            geng::Key::Space => {
+                if self.wave < level.waves.len() {
+                    let simulation = WaveSimulation::new(assets, level, self.wave);
+                    self.simulation = Some((self.wave, simulation));
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Advances the simulation of the wave, even while the panel is closed.
+    pub fn update(&mut self) {
+        if let Some((wave, simulation)) = &mut self.simulation {
+            if let Some(report) = simulation.update() {
+                self.report = Some((*wave, report));
+                self.simulation = None;
+            }
+        }
+    }
+
+    fn select_previous(&mut self, level: &Level) {
+        match self.spawn {
+            Some(0) => self.spawn = None,
+            Some(index) => self.spawn = Some(index - 1),
+            None if self.wave > 0 => {
+                self.wave -= 1;
+                self.spawn = level.waves[self.wave].spawns.len().checked_sub(1);
+            }
+            None => {}
+        }
+    }
+
+    fn select_next(&mut self, level: &Level) {
+        let wave = match level.waves.get(self.wave) {
+            Some(wave) => wave,
+            None => return,
+        };
+        let next = self.spawn.map_or(0, |index| index + 1);
+        if next < wave.spawns.len() {
+            self.spawn = Some(next);
+        } else if self.wave + 1 < level.waves.len() {
+            self.wave += 1;
+            self.spawn = None;
+        }
+    }
+
+    fn change_value(&mut self, level: &mut Level, delta: isize, spawn_points: usize) -> bool {
+        let wave = match level.waves.get_mut(self.wave) {
+            Some(wave) => wave,
+            None => return false,
+        };
+        let spawn = match self.spawn {
+            Some(index) => &mut wave.spawns[index],
+            None => {
+                wave.position += delta as f32 * POSITION_STEP;
+                return true;
+            }
+        };
+        match self.field {
+            SpawnField::Template => {
+                let current = self
+                    .enemies
+                    .iter()
+                    .copied()
+                    .find(|&name| name == spawn.template)
+                    .unwrap_or(self.enemies[0]);
+                spawn.template = cycle(&self.enemies, current, delta).to_owned();
+            }
+            SpawnField::Count => {
+                spawn.count = (spawn.count as isize + delta).max(1) as usize;
+            }
+            SpawnField::Delay => {
+                spawn.delay = (spawn.delay + delta as f32 * DELAY_STEP).max(0.0);
+            }
+            SpawnField::SpawnPoint => {
+                // `None` comes before the first spawn point and after the last one
+                let options: Vec<_> = std::iter::once(None)
+                    .chain((0..spawn_points).map(Some))
+                    .collect();
+                let current = spawn.spawn_point.filter(|&index| index < spawn_points);
+                spawn.spawn_point = cycle(&options, current, delta);
+            }
+        }
+        true
+    }
+
+    /// Removes the selected spawn, or the selected wave if it has no spawns left.
+    fn remove(&mut self, level: &mut Level) -> bool {
+        if self.wave >= level.waves.len() {
+            return false;
+        }
+        match self.spawn {
+            Some(index) => {
+                let spawns = &mut level.waves[self.wave].spawns;
+                spawns.remove(index);
+                self.spawn = spawns.len().checked_sub(1).map(|last| index.min(last));
+            }
+            None if level.waves[self.wave].spawns.is_empty() => {
+                level.waves.remove(self.wave);
+                self.wave = self.wave.min(level.waves.len().saturating_sub(1));
+            }
+            None => return false,
+        }
+        true
+    }
+
+    pub fn draw(&self, geng: &Geng, level: &Level, framebuffer: &mut ugli::Framebuffer) {
+        let camera = &geng::PixelPerfectCamera;
+        let font = geng.default_font();
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let panel = AABB {
+            x_min: screen.x_max - 520.0,
+            y_min: screen.y_min + 20.0,
+            y_max: screen.y_max - 100.0,
+            ..screen
+        };
+        draw_2d::Quad::new(panel, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(geng, framebuffer, camera);
+
+        let mut lines = vec![];
+        for (wave_index, wave) in level.waves.iter().enumerate() {
+            let selected = wave_index == self.wave && self.spawn.is_none();
+            lines.push((
+                format!("Wave {} at {}", wave_index + 1, wave.position),
+                selected,
+            ));
+            for (spawn_index, spawn) in wave.spawns.iter().enumerate() {
+                let selected = wave_index == self.wave && self.spawn == Some(spawn_index);
+                let fields = [
+                    (SpawnField::Template, spawn.template.clone()),
+                    (SpawnField::Count, format!("x{}", spawn.count)),
+                    (SpawnField::Delay, format!("after {}s", spawn.delay)),
+                    (
+                        SpawnField::SpawnPoint,
+                        match spawn.spawn_point {
+                            Some(index) => format!("at #{}", index + 1),
+                            None => "at random".to_owned(),
+                        },
+                    ),
+                ];
+                let text = fields
+                    .iter()
+                    .map(|(field, text)| {
+                        if selected && *field == self.field {
+                            format!("[{}]", text)
+                        } else {
+                            text.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                lines.push((format!("    {}", text), selected));
+            }
+        }
+        if level.waves.is_empty() {
+            lines.push(("No waves, press N to add one".to_owned(), false));
+        }
+        if let Some((wave, simulation)) = &self.simulation {
+            lines.push((String::new(), false));
+            lines.push((
+                format!(
+                    "Simulating wave {}: {:.0}%",
+                    wave + 1,
+                    simulation.progress() * 100.0
+                ),
+                false,
+            ));
+        } else if let Some((wave, report)) = &self.report {
+            lines.push((String::new(), false));
+            lines.push((
+                format!(
+                    "Wave {}: {} in {:.1}s",
+                    wave + 1,
+                    if report.cleared { "cleared" } else { "lost" },
+                    report.time
+                ),
+                false,
+            ));
+            lines.push((
+                format!(
+                    "{} enemies, {} mechs lost, {:.0}% damage taken",
+                    report.enemies,
+                    report.mechs_lost,
+                    report.damage_taken * 100.0
+                ),
+                false,
+            ));
+        }
+        lines.push((String::new(), false));
+        lines.push((
+            "N - add wave, A - add spawn, Backspace - remove".to_owned(),
+            false,
+        ));
+        lines.push((
+            "Tab - field, Left/Right - change, Space - simulate".to_owned(),
+            false,
+        ));
+
+        for (index, (text, selected)) in lines.iter().enumerate() {
+            let color = if *selected { Rgba::WHITE } else { Rgba::GRAY };
+            font.draw(
+                framebuffer,
+                camera,
+                text,
+                vec2(
+                    panel.x_min + 10.0,
+                    panel.y_max - LINE_HEIGHT * (index + 1) as f32,
+                ),
+                geng::TextAlign::LEFT,
+                20.0,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::*;
+
+use model::{Effect, Id, Model, Unit};
+use std::collections::HashSet;
+
+/// Change of a value with a single key press, relative to the value.
+const TWEAK_STEP: f32 = 0.1;
+/// Smallest change of a value with a single key press.
+const MIN_TWEAK_STEP: f32 = 0.05;
+
+/// A debug tool listing the effect trees of the inspected unit as expandable nodes,
+/// the numeric values in the trees can be changed while the game runs.
+#[derive(Debug, Default)]
+pub struct EffectTreeEditor {
+    pub open: bool,
+    /// The unit whose trees are shown.
+    unit: Option<Id>,
+    /// Copies of the unit's trees, written back after every change.
+    roots: Vec<(EffectRoot, Effect)>,
+    /// Paths of the expanded nodes, starting with the index of the root.
+    expanded: HashSet<Vec<usize>>,
+    /// Index of the selected line.
+    pub selected: usize,
+    /// The visible nodes and values, rebuilt after every change.
+    pub lines: Vec<TreeLine>,
+}
+
+/// Where the tree is stored in the unit.
+#[derive(Debug, Clone, Copy)]
+enum EffectRoot {
+    /// Started at the frame of the action's animation
+    ActionFrame(usize),
+    Weapon,
+    OnDeath,
+}
+
+#[derive(Debug)]
+pub struct TreeLine {
+    pub depth: usize,
+    pub text: String,
+    path: Vec<usize>,
+    /// Index of the value in the node, the line is the node itself if not set.
+    param: Option<usize>,
+}
+
+impl EffectTreeEditor {
+    /// Shows the trees of the unit if it is not shown already.
+    pub fn sync(&mut self, unit: Option<Id>, model: &Model) {
+        if self.unit == unit {
+            return;
+        }
+        self.unit = unit;
+        self.roots = unit
+            .and_then(|id| model.world.units.get(&id))
+            .map(|unit| unit_roots(unit, model))
+            .unwrap_or_default();
+        self.expanded = (0..self.roots.len()).map(|index| vec![index]).collect();
+        self.selected = 0;
+        self.rebuild();
+    }
+
+    /// Handles the key press while the editor is open. Returns whether the key was consumed.
+    pub fn handle_key(&mut self, key: geng::Key, model: &mut Model) -> bool {
+        match key {
+            geng::Key::Up => self.selected = self.selected.saturating_sub(1),
+            geng::Key::Down => {
+                self.selected = (self.selected + 1).min(self.lines.len().saturating_sub(1));
+            }
+            geng::Key::Enter => {
+                let line = match self.lines.get(self.selected) {
+                    Some(line) if line.param.is_none() => line,
+                    _ => return true,
+                };
+                if !self.expanded.remove(&line.path) {
+                    self.expanded.insert(line.path.clone());
+                }
+                self.rebuild();
+            }
+            geng::Key::Left | geng::Key::Right => {
+                let sign = if key == geng::Key::Left { -1.0 } else { 1.0 };
+                self.tweak(sign, model);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Changes the selected value and writes its tree back to the unit.
+    fn tweak(&mut self, sign: f32, model: &mut Model) {
+        let (path, param) = match self.lines.get(self.selected) {
+            Some(TreeLine {
+                path,
+                param: Some(param),
+                ..
+            }) => (path.clone(), *param),
+            _ => return,
+        };
+        let node = self.roots[path[0]].1.node_at_mut(&path[1..]);
+        if let Some((_, value)) = node.and_then(|node| node.params_mut().into_iter().nth(param)) {
+            let step = (value.as_f32().abs() * TWEAK_STEP).max(MIN_TWEAK_STEP);
+            *value += r32(sign * step);
+        }
+
+        let (root, effect) = self.roots[path[0]].clone();
+        if let Some(unit) = self.unit.and_then(|id| model.world.units.get_mut(&id)) {
+            match root {
+                EffectRoot::ActionFrame(frame) => {
+                    unit.action.animation =
+                        model
+                            .world
+                            .animations
+                            .modify(unit.action.animation, |animation| {
+                                animation.keyframes[frame].start_effect = Some(effect);
+                            });
+                }
+                EffectRoot::Weapon => {
+                    if let Some(weapon) = &mut unit.weapon {
+                        weapon.effect = effect;
+                    }
+                }
+                EffectRoot::OnDeath => unit.on_death = effect,
+            }
+        }
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.lines.clear();
+        for (index, (root, effect)) in self.roots.iter().enumerate() {
+            let label = match root {
+                EffectRoot::ActionFrame(frame) => format!("action frame {}: ", frame + 1),
+                EffectRoot::Weapon => "weapon: ".to_owned(),
+                EffectRoot::OnDeath => "on death: ".to_owned(),
+            };
+            collect_lines(
+                &label,
+                effect,
+                vec![index],
+                0,
+                &self.expanded,
+                &mut self.lines,
+            );
+        }
+        self.selected = self.selected.min(self.lines.len().saturating_sub(1));
+    }
+}
+
+/// The effect trees of the unit.
+fn unit_roots(unit: &Unit, model: &Model) -> Vec<(EffectRoot, Effect)> {
+    let mut roots: Vec<_> = model
+        .world
+        .animations
+        .get(unit.action.animation)
+        .keyframes
+        .iter()
+        .enumerate()
+        .filter_map(|(frame, keyframe)| {
+            let effect = keyframe.start_effect.clone()?;
+            Some((EffectRoot::ActionFrame(frame), effect))
+        })
+        .collect();
+    if let Some(weapon) = &unit.weapon {
+        roots.push((EffectRoot::Weapon, weapon.effect.clone()));
+    }
+    if !unit.on_death.is_noop() {
+        roots.push((EffectRoot::OnDeath, unit.on_death.clone()));
+    }
+    roots
+}
+
+fn collect_lines(
+    label: &str,
+    effect: &Effect,
+    path: Vec<usize>,
+    depth: usize,
+    expanded: &HashSet<Vec<usize>>,
+    lines: &mut Vec<TreeLine>,
+) {
+    let mut node = effect.node().clone();
+    let name = node.name();
+    let params: Vec<String> = node
+        .params_mut()
+        .into_iter()
+        .map(|(name, value)| format!("{}: {:.2}", name, value))
+        .collect();
+    let children = effect.children();
+    let is_expanded = expanded.contains(&path);
+    let marker = if params.is_empty() && children.is_empty() {
+        " "
+    } else if is_expanded {
+        "-"
+    } else {
+        "+"
+    };
+    lines.push(TreeLine {
+        depth,
+        text: format!("{} {}{}", marker, label, name),
+        path: path.clone(),
+        param: None,
+    });
+    if !is_expanded {
+        return;
+    }
+    for (index, text) in params.into_iter().enumerate() {
+        lines.push(TreeLine {
+            depth: depth + 1,
+            text,
+            path: path.clone(),
+            param: Some(index),
+        });
+    }
+    for (index, child) in children.iter().enumerate() {
+        let mut path = path.clone();
+        path.push(index);
+        collect_lines("", child, path, depth + 1, expanded, lines);
+    }
+}
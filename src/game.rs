@@ -1,5 +1,7 @@
 use super::*;
 
+use capture::Capture;
+use crash::CrashReport;
 use model::*;
 use render::Render;
 
@@ -8,99 +10,662 @@ pub struct Game {
     geng: Geng,
     assets: Rc<Assets>,
     render: Render,
+    capture: Capture,
+    display: DisplaySettings,
+    frame_limiter: FrameLimiter,
     model: Model,
+    controls: Controls,
+    paused: bool,
+    pause_menu: PauseMenu,
+    console: Console,
+    /// The held actions last sent to the model, `None` while a rewind replays its own.
+    held: Option<HeldActions>,
+    effect_tree: EffectTreeEditor,
+    /// Index of the selected unit of the sandbox palette.
+    sandbox_unit: usize,
+    game_over: Option<GameOver>,
+    /// Set when the simulation panicked, the game can only be left afterwards.
+    crash: Option<CrashReport>,
+    transition: Option<geng::Transition>,
 }
 
+/// Results of the finished run.
 #[derive(Debug)]
+pub struct GameOver {
+    pub score: u64,
+    /// Place of the run in the high score table, if it got there.
+    pub place: Option<usize>,
+    pub high_scores: Option<HighScores>,
+    /// Everything unlocked by the run.
+    pub unlocked: Vec<Unlock>,
+}
+
+/// Titles of the tabs of the pause menu.
+pub const PAUSE_TABS: [&str; 2] = ["pause.tab_game", "pause.tab_controls"];
+/// The tab listing the actions, selecting one rebinds it.
+pub const CONTROLS_TAB: usize = 1;
+/// Options of the first tab of the pause menu.
+pub const PAUSE_OPTIONS: [&str; 2] = ["pause.resume", "pause.quit"];
+/// Time scales the sandbox steps through.
+const SANDBOX_TIME_SCALES: [f64; 7] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// State of the menu shown while the game is paused.
+#[derive(Debug, Default)]
+pub struct PauseMenu {
+    pub menu: ui::Menu,
+    /// Whether the next key press should be bound to the selected action.
+    pub rebinding: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MechType {
     Artillery,
     Tank,
     Healer,
 }
 
-#[derive(Debug)]
+impl MechType {
+    pub const ALL: [Self; 3] = [Self::Artillery, Self::Tank, Self::Healer];
+
+    /// The amount of energy required to spawn the mech.
+    pub fn cost(&self) -> Currency {
+        match self {
+            Self::Artillery => Currency::new(40.0),
+            Self::Tank => Currency::new(30.0),
+            Self::Healer => Currency::new(25.0),
+        }
+    }
+}
+
+/// Input from the player, applied to the model at the tick boundary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PlayerEvent {
     SpawnMech(MechType),
+    /// Choose one of the offered perks by its index.
+    ChoosePerk(usize),
+    /// Start reloading the weapons of all mechs.
+    Reload,
+    /// Use the closest interactable object in range of a mech.
+    Interact,
+    /// Place a ping at the world position.
+    Ping(Position),
+    /// Show the whole dialogue line or continue to the next one.
+    AdvanceCutscene,
+    SkipCutscene,
+    /// Spawn the unit of the sandbox palette at the world position.
+    SpawnSandboxUnit {
+        index: usize,
+        position: Position,
+    },
+    /// The held actions changed, they stay like this until the next change.
+    Held(HeldActions),
+    Debug(DebugCommand),
+}
+
+/// The actions the player holds down, sent as an event whenever they change so that
+/// every input of the simulation goes through the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct HeldActions {
+    /// World position to fire at.
+    pub aim: Option<Position>,
+    pub blocking: bool,
+    pub gliding: bool,
+}
+
+/// A change of the model from the console or the debug keys, queued like the rest of the
+/// input so that it is applied at the tick boundary and recorded in the input history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DebugCommand {
+    SpawnUnit {
+        /// Name of the template, see [UnitTemplates::get].
+        template: String,
+        position: Position,
+        faction: Faction,
+    },
+    /// Give the status, by its console name, to all mechs.
+    GiveStatus {
+        status: String,
+        time: Time,
+    },
+    /// Set the hp of all mechs.
+    SetHp(Hp),
+    /// Change a field of the unit, one of [UNIT_FIELDS].
+    EditUnit {
+        unit: Id,
+        field: String,
+        value: R32,
+    },
+    ClearStatuses(Id),
+    /// Kill everything that is not a mech.
+    KillAll,
+}
+
+/// The unit fields the console can edit.
+pub const UNIT_FIELDS: [&str; 10] = [
+    "hp",
+    "max_hp",
+    "x",
+    "y",
+    "vx",
+    "vy",
+    "speed",
+    "acceleration",
+    "cooldown",
+    "engage_radius",
+];
+
+/// The statuses the console can give by name.
+pub fn debug_status(name: &str, time: Time) -> Option<Status> {
+    let status = match name {
+        "stunned" => Status::Stunned { time },
+        "invulnerable" => Status::Invulnerable { time },
+        "shield" => Status::Shield {
+            hp: Hp::new(50.0),
+            time: Some(time),
+        },
+        "burning" => Status::Burning {
+            time,
+            damage_per_second: Hp::new(5.0),
+        },
+        "armor_broken" => Status::ArmorBroken {
+            time,
+            multiplier: r32(1.5),
+        },
+        "wet" => Status::Wet { time },
+        "slow_fall" => Status::SlowFall {
+            time,
+            max_fall_speed: Coord::new(2.0),
+        },
+        _ => return None,
+    };
+    Some(status)
 }
 
 impl Game {
-    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+    pub fn new(geng: &Geng, assets: &Rc<Assets>, config: RunConfig, level: &Level) -> Self {
+        let mut model = Model::new(assets, config);
+        model.load_level(level);
         Self {
             geng: geng.clone(),
             assets: assets.clone(),
             render: Render::new(geng, assets),
-            model: Model::new(assets),
+            capture: Capture::new(geng),
+            display: DisplaySettings::load(),
+            frame_limiter: default(),
+            model,
+            controls: Controls::load(),
+            paused: false,
+            pause_menu: default(),
+            console: default(),
+            held: None,
+            effect_tree: default(),
+            sandbox_unit: 0,
+            game_over: None,
+            crash: None,
+            transition: None,
+        }
+    }
+
+    /// A run in step with the other player, see [Lockstep].
+    pub fn co_op(
+        geng: &Geng,
+        assets: &Rc<Assets>,
+        config: RunConfig,
+        level: &Level,
+        peer: net::Peer,
+        host: bool,
+    ) -> Self {
+        let mut game = Self::new(geng, assets, config, level);
+        let start_tick = game.model.world.current_tick;
+        game.model.lockstep = Some(Lockstep::new(peer, host, start_tick));
+        game
+    }
+
+    fn finish_run(&mut self) {
+        let score = self.model.score();
+        let mut place = None;
+        let mut high_scores = None;
+        if self.model.config.mode.is_endless() {
+            let mut table = HighScores::load();
+            place = table.insert(HighScore {
+                score,
+                wave: self.model.world.wave_number,
+                kills: self.model.world.score.kills,
+                time: self.model.time().as_f32(),
+                difficulty: self.model.config.difficulty,
+                mode: self.model.config.mode,
+                seed: self.model.config.seed,
+                date: today(),
+            });
+            table.save();
+            high_scores = Some(table);
+        }
+        let mut profile = Profile::load();
+        let unlocked = profile.record_run(&self.model);
+        profile.save();
+        self.game_over = Some(GameOver {
+            score,
+            place,
+            high_scores,
+            unlocked,
+        });
+    }
+
+    fn back_to_menu(&mut self) {
+        crash::stop_recording();
+        let menu = menu::MainMenu::new(&self.geng, &self.assets);
+        self.transition = Some(geng::Transition::Switch(Box::new(menu)));
+    }
+
+    fn handle_action(&mut self, action: ControlAction) {
+        match action {
+            ControlAction::Pause => {
+                self.paused = !self.paused;
+                self.pause_menu = default();
+            }
+            ControlAction::Screenshot => self.capture.screenshot(),
+            ControlAction::SaveClip => self.capture.save_clip(),
+            ControlAction::ToggleFullscreen => {
+                self.display.fullscreen = !self.display.fullscreen;
+                self.display.save();
+                self.display.apply_fullscreen(&self.geng);
+            }
+            _ if self.paused => {}
+            ControlAction::ToggleDebug => {
+                self.render.debug_overlay = !self.render.debug_overlay;
+            }
+            ControlAction::ToggleMinimap => {
+                self.render.show_minimap = !self.render.show_minimap;
+            }
+            ControlAction::ToggleProfiler => {
+                self.render.show_profiler = !self.render.show_profiler;
+            }
+            ControlAction::ToggleEffectTree => {
+                self.effect_tree.open = !self.effect_tree.open;
+                self.effect_tree.sync(self.console.inspected, &self.model);
+            }
+            ControlAction::ToggleCombatLog => {
+                self.render.show_combat_log = !self.render.show_combat_log;
+            }
+            ControlAction::SpawnArtillery => {
+                self.model
+                    .handle_event(PlayerEvent::SpawnMech(MechType::Artillery));
+            }
+            ControlAction::SpawnTank => {
+                self.model
+                    .handle_event(PlayerEvent::SpawnMech(MechType::Tank));
+            }
+            ControlAction::SpawnHealer => {
+                self.model
+                    .handle_event(PlayerEvent::SpawnMech(MechType::Healer));
+            }
+            ControlAction::Reload => {
+                self.model.handle_event(PlayerEvent::Reload);
+            }
+            ControlAction::Interact => {
+                self.model.handle_event(PlayerEvent::Interact);
+            }
+            ControlAction::Ping => {
+                let window = self.geng.window();
+                let position = self
+                    .render
+                    .screen_to_world(window.mouse_pos().map(|x| x as f32));
+                self.model.handle_event(PlayerEvent::Ping(position));
+            }
+            ControlAction::NextSandboxUnit => {
+                let palette = self.model.world.templates.sandbox_palette();
+                self.sandbox_unit = (self.sandbox_unit + 1) % palette.len();
+            }
+            ControlAction::SpawnSandboxUnit => {
+                let window = self.geng.window();
+                let position = self
+                    .render
+                    .screen_to_world(window.mouse_pos().map(|x| x as f32));
+                self.model.handle_event(PlayerEvent::SpawnSandboxUnit {
+                    index: self.sandbox_unit,
+                    position,
+                });
+            }
+            ControlAction::SlowDown if self.model.config.mode.is_sandbox() => {
+                self.console.time_scale = step_time_scale(self.console.time_scale, -1);
+            }
+            ControlAction::SpeedUp if self.model.config.mode.is_sandbox() => {
+                self.console.time_scale = step_time_scale(self.console.time_scale, 1);
+            }
+            ControlAction::Rewind => {
+                // The restored state replaces whatever the panic left behind
+                if self.model.rewind().is_some() {
+                    self.crash = None;
+                }
+            }
+            // Only the sandbox lets the time be changed
+            ControlAction::SlowDown | ControlAction::SpeedUp => {}
+            // Checked every frame in `update`
+            ControlAction::Aim | ControlAction::Block | ControlAction::Glide => {}
+        }
+    }
+
+    /// Spawns a unit next to the level start for the number keys, only while debugging.
+    fn spawn_debug_unit(&mut self, key: geng::Key) {
+        let (template, x, faction) = match key {
+            geng::Key::Num1 => ("healer", -10.0, Faction::Mech),
+            geng::Key::Num2 => ("artillery", -5.0, Faction::Mech),
+            geng::Key::Num3 => ("tank", 0.0, Faction::Mech),
+            geng::Key::Num6 => ("blighter", 10.0, Faction::Alien),
+            geng::Key::Num7 => ("ravager", 15.0, Faction::Alien),
+            geng::Key::Num8 => ("stinger", 20.0, Faction::Alien),
+            geng::Key::Num9 => ("ravager_alpha", 25.0, Faction::Alien),
+            _ => return,
+        };
+        self.model
+            .handle_event(PlayerEvent::Debug(DebugCommand::SpawnUnit {
+                template: template.to_owned(),
+                position: vec2(x, 5.0).map(Coord::new),
+                faction,
+            }));
+    }
+
+    /// Selects the unit at the screen position for the inspector.
+    fn inspect(&mut self, position: Vec2<f32>) {
+        let position = self.render.screen_to_world(position);
+        self.console.inspected = self.model.world.units.ids().copied().find(|&id| {
+            self.model
+                .world
+                .bodies
+                .bounds(id)
+                .map_or(false, |bounds| bounds.contains(position))
+        });
+        self.effect_tree.sync(self.console.inspected, &self.model);
+    }
+
+    /// Handles the key press in the pause menu. Returns whether the key was consumed.
+    fn handle_menu_key(&mut self, key: geng::Key) -> bool {
+        let pause_menu = &mut self.pause_menu;
+        if pause_menu.rebinding {
+            pause_menu.rebinding = false;
+            if key != geng::Key::Escape {
+                self.controls
+                    .bind(ControlAction::ALL[pause_menu.menu.selected], key);
+                self.controls.save();
+            }
+            return true;
         }
+        if key == geng::Key::Backspace && pause_menu.menu.tab == CONTROLS_TAB {
+            self.controls
+                .reset(ControlAction::ALL[pause_menu.menu.selected]);
+            self.controls.save();
+            return true;
+        }
+        let input = match ui::UiInput::from_key(key) {
+            Some(input) => input,
+            None => return false,
+        };
+        let widgets = self.render.pause_widgets(&self.controls, &self.pause_menu);
+        if let Some(event) = self
+            .pause_menu
+            .menu
+            .handle_input(input, PAUSE_TABS.len(), &widgets)
+        {
+            self.handle_menu_event(event);
+        }
+        true
+    }
+
+    fn handle_menu_click(&mut self, position: Vec2<f32>) {
+        let widgets = self.render.pause_widgets(&self.controls, &self.pause_menu);
+        if let Some(event) = self.pause_menu.menu.handle_click(position, &widgets) {
+            self.handle_menu_event(event);
+        }
+    }
+
+    fn handle_menu_event(&mut self, event: ui::UiEvent) {
+        if let ui::UiEvent::Pressed(index) = event {
+            if self.pause_menu.menu.tab == CONTROLS_TAB {
+                self.pause_menu.rebinding = true;
+            } else if index == 0 {
+                self.handle_action(ControlAction::Pause);
+            } else {
+                self.back_to_menu();
+            }
+        }
+    }
+
+    fn draw_frame(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        let start = self.model.profiler.start();
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        self.render.draw(&self.model, framebuffer);
+        self.render.draw_interact_prompt(
+            &self.model,
+            self.controls.keys(ControlAction::Interact),
+            framebuffer,
+        );
+        self.render
+            .draw_tutorial_prompt(&self.model, &self.controls, framebuffer);
+        self.render.draw_co_op_status(&self.model, framebuffer);
+        self.render.draw_sandbox(
+            &self.model,
+            &self.controls,
+            self.sandbox_unit,
+            self.console.time_scale,
+            framebuffer,
+        );
+        if let Some(crash) = &self.crash {
+            self.render.draw_crash(crash, &self.controls, framebuffer);
+        } else if let Some(game_over) = &self.game_over {
+            self.render
+                .draw_game_over(&self.model, game_over, framebuffer);
+        } else if self.paused {
+            self.render
+                .draw_pause_menu(&self.controls, &mut self.pause_menu, framebuffer);
+        }
+        self.render
+            .draw_inspector(&self.model, self.console.inspected, framebuffer);
+        self.render.draw_effect_tree(&self.effect_tree, framebuffer);
+        self.render.draw_combat_log(&self.model, framebuffer);
+        self.render.draw_console(&self.console, framebuffer);
+        self.model.profiler.record(ProfilePhase::Render, start);
+        self.render.draw_profiler(&self.model.profiler, framebuffer);
+        self.model.profiler.finish_frame();
     }
 }
 
+/// The sandbox time scale `delta` steps away from the closest one to `scale`, without wrapping.
+fn step_time_scale(scale: f64, delta: isize) -> f64 {
+    let last = SANDBOX_TIME_SCALES.len() as isize - 1;
+    let index = SANDBOX_TIME_SCALES
+        .iter()
+        .position(|&other| other >= scale)
+        .map_or(last, |index| index as isize);
+    SANDBOX_TIME_SCALES[(index + delta).clamp(0, last) as usize]
+}
+
 impl geng::State for Game {
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
-        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
-        self.render.draw(&self.model, framebuffer);
+        if self.capture.wants_frame() {
+            let mut frame = self.capture.take_frame(framebuffer.size());
+            self.draw_frame(&mut ugli::Framebuffer::new_color(
+                self.geng.ugli(),
+                ugli::ColorAttachment::Texture(&mut frame),
+            ));
+            self.capture.present(frame, framebuffer);
+        } else {
+            self.draw_frame(framebuffer);
+        }
+        self.frame_limiter.wait(self.display.frame_limit);
     }
 
     fn handle_event(&mut self, event: geng::Event) {
-        #[allow(clippy::single_match)]
+        if let geng::Event::KeyDown { key } = event {
+            if self.crash.is_some() && self.controls.action(key) == Some(ControlAction::Rewind) {
+                self.handle_action(ControlAction::Rewind);
+                return;
+            }
+        }
+        if self.game_over.is_some() || self.crash.is_some() {
+            if let geng::Event::KeyDown {
+                key: geng::Key::Enter | geng::Key::Escape,
+            }
+            | geng::Event::MouseDown { .. }
+            | geng::Event::TouchStart { .. } = event
+            {
+                self.back_to_menu();
+            }
+            return;
+        }
+        if self.model.world.cutscene.is_some() {
+            match event {
+                geng::Event::KeyDown {
+                    key: geng::Key::Escape,
+                } => self.model.handle_event(PlayerEvent::SkipCutscene),
+                geng::Event::KeyDown {
+                    key: geng::Key::Enter | geng::Key::Space,
+                }
+                | geng::Event::MouseDown { .. }
+                | geng::Event::TouchStart { .. } => {
+                    self.model.handle_event(PlayerEvent::AdvanceCutscene)
+                }
+                _ => {}
+            }
+            return;
+        }
+        if let geng::Event::KeyDown { key } = event {
+            if key == geng::Key::Grave {
+                self.console.open = !self.console.open;
+                return;
+            }
+            if self.console.open {
+                let shift = self.geng.window().is_key_pressed(geng::Key::LShift);
+                self.console.handle_key(key, shift, &mut self.model);
+                return;
+            }
+            if self.effect_tree.open && self.effect_tree.handle_key(key, &mut self.model) {
+                return;
+            }
+        }
         match event {
-            geng::Event::KeyDown { key } => match key {
-                geng::Key::F1 => {
-                    self.render.visualize_hitboxes = !self.render.visualize_hitboxes;
+            geng::Event::TouchStart { ref touches } => {
+                if touches.iter().any(|touch| {
+                    self.render
+                        .pause_button_contains(touch.position.map(|x| x as f32))
+                }) {
+                    self.handle_action(ControlAction::Pause);
+                    return;
                 }
-                geng::Key::Num1 => {
-                    self.model.spawn_unit(
-                        self.model.templates.healer.clone(),
-                        vec2(-10.0, 5.0).map(Coord::new),
-                        Faction::Mech,
-                    );
+                if self.paused {
+                    if let Some(touch) = touches.first() {
+                        self.handle_menu_click(touch.position.map(|x| x as f32));
+                    }
+                    return;
                 }
-                geng::Key::Num2 => {
-                    self.model.spawn_unit(
-                        self.model.templates.artillery.clone(),
-                        vec2(-5.0, 5.0).map(Coord::new),
-                        Faction::Mech,
-                    );
+            }
+            geng::Event::MouseDown {
+                position,
+                button: geng::MouseButton::Left,
+            } if self.paused => {
+                self.handle_menu_click(position.map(|x| x as f32));
+                return;
+            }
+            geng::Event::KeyDown { key } => {
+                if self.paused && self.handle_menu_key(key) {
+                    return;
                 }
-                geng::Key::Num3 => {
-                    self.model.spawn_unit(
-                        self.model.templates.tank.clone(),
-                        vec2(0.0, 5.0).map(Coord::new),
-                        Faction::Mech,
-                    );
+                if let Some(action) = self.controls.action(key) {
+                    self.handle_action(action);
                 }
-                geng::Key::Num6 => {
-                    self.model.spawn_unit(
-                        self.model.templates.blighter.clone(),
-                        vec2(10.0, 5.0).map(Coord::new),
-                        Faction::Alien,
-                    );
+                if self.paused {
+                    return;
                 }
-                geng::Key::Num7 => {
-                    self.model.spawn_unit(
-                        self.model.templates.ravager.clone(),
-                        vec2(15.0, 5.0).map(Coord::new),
-                        Faction::Alien,
-                    );
+                if self.model.world.perk_choice.is_some() {
+                    let index = match key {
+                        geng::Key::Num1 => Some(0),
+                        geng::Key::Num2 => Some(1),
+                        geng::Key::Num3 => Some(2),
+                        _ => None,
+                    };
+                    if let Some(index) = index {
+                        self.model.handle_event(PlayerEvent::ChoosePerk(index));
+                        return;
+                    }
                 }
-                geng::Key::Num8 => {
-                    self.model.spawn_unit(
-                        self.model.templates.stinger.clone(),
-                        vec2(20.0, 5.0).map(Coord::new),
-                        Faction::Alien,
-                    );
+                if self.render.debug_overlay {
+                    self.spawn_debug_unit(key);
                 }
-                _ => {}
-            },
+            }
+            geng::Event::MouseDown {
+                button: geng::MouseButton::Middle,
+                ..
+            } => self.handle_action(ControlAction::Ping),
             _ => {}
         }
 
+        if self.paused {
+            return;
+        }
+        if let geng::Event::MouseDown {
+            position,
+            button: geng::MouseButton::Left,
+        } = event
+        {
+            if self.render.debug_overlay {
+                self.inspect(position.map(|x| x as f32));
+            }
+        }
         for event in self.render.handle_event(event) {
             self.model.handle_event(event);
         }
     }
 
     fn update(&mut self, delta_time: f64) {
-        let delta_time = Time::new(delta_time as _);
-        self.model.update(delta_time);
+        if self.game_over.is_some() || self.crash.is_some() || self.paused {
+            return;
+        }
+        if self.model.world.game_over {
+            self.finish_run();
+            return;
+        }
+        let window = self.geng.window();
+        // The keys typed into the console are not held actions
+        let held = |action| !self.console.open && self.controls.is_pressed(action, window);
+        let aiming = held(ControlAction::Aim)
+            || !self.console.open && window.is_button_pressed(geng::MouseButton::Right);
+        let touch = &self.render.touch;
+        let actions = HeldActions {
+            aim: self.render.touch_aim(&self.model).or_else(|| {
+                aiming.then(|| {
+                    self.render
+                        .screen_to_world(window.mouse_pos().map(|x| x as f32))
+                })
+            }),
+            blocking: held(ControlAction::Block) || touch.is_held(ControlAction::Block),
+            gliding: held(ControlAction::Glide) || touch.is_held(ControlAction::Glide),
+        };
+        if self
+            .model
+            .rewind
+            .as_ref()
+            .map_or(false, |rewind| rewind.is_replaying())
+        {
+            // The replayed actions replace the held ones, which are sent again once it catches up
+            self.held = None;
+        } else if self.held != Some(actions) {
+            self.held = Some(actions);
+            self.model.handle_event(PlayerEvent::Held(actions));
+        }
+
+        let delta_time = Time::new((delta_time * self.console.time_scale) as _);
+        let model = &mut self.model;
+        // The panic hook saves the crash file, on the web the panics abort right after it
+        if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| model.update(delta_time)))
+            .is_err()
+        {
+            self.crash = Some(CrashReport::take());
+        }
+        for error in std::mem::take(&mut self.model.script_errors) {
+            self.console.log(error);
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
     }
 }
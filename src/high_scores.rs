@@ -0,0 +1,161 @@
+use super::*;
+
+const PREFERENCES_KEY: &str = "high_scores";
+/// How many of the best scores are kept.
+const TABLE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u64,
+    pub wave: usize,
+    pub kills: usize,
+    /// Duration of the run in seconds.
+    pub time: f32,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub mode: GameMode,
+    #[serde(default)]
+    pub seed: u64,
+    /// The day the run was played, as YYYY-MM-DD.
+    #[serde(default)]
+    pub date: String,
+}
+
+/// The best endless mode scores, sorted from the highest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    /// Inserts the score into the table. Returns its place if it made it into the table.
+    pub fn insert(&mut self, score: HighScore) -> Option<usize> {
+        let place = self
+            .scores
+            .iter()
+            .position(|other| other.score < score.score)
+            .unwrap_or(self.scores.len());
+        if place >= TABLE_SIZE {
+            return None;
+        }
+        self.scores.insert(place, score);
+        self.scores.truncate(TABLE_SIZE);
+        Some(place)
+    }
+}
+
+/// Seed of the daily mode, the same for everyone playing on the same day.
+pub fn daily_seed() -> u64 {
+    // Spread the consecutive days over the seed space
+    days_since_epoch().wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+/// Today's date in UTC, as YYYY-MM-DD.
+pub fn today() -> String {
+    date(days_since_epoch())
+}
+
+/// The date of the day counted from the unix epoch, as YYYY-MM-DD.
+fn date(days: u64) -> String {
+    // Converts the days to the civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn days_since_epoch() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as u64 / MILLIS_PER_DAY)
+}
+
+/// The system clock is not available on the web, the browser's is used instead.
+#[cfg(target_arch = "wasm32")]
+fn days_since_epoch() -> u64 {
+    js_sys::Date::now() as u64 / MILLIS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(score: u64) -> HighScore {
+        HighScore {
+            score,
+            wave: 0,
+            kills: 0,
+            time: 0.0,
+            difficulty: default(),
+            mode: GameMode::Endless,
+            seed: score,
+            date: String::new(),
+        }
+    }
+
+    fn scores(table: &HighScores) -> Vec<u64> {
+        table.scores.iter().map(|score| score.score).collect()
+    }
+
+    #[test]
+    fn scores_are_sorted_from_the_highest() {
+        let mut table = HighScores::default();
+        assert_eq!(table.insert(high_score(20)), Some(0));
+        assert_eq!(table.insert(high_score(30)), Some(0));
+        assert_eq!(table.insert(high_score(10)), Some(2));
+        assert_eq!(table.insert(high_score(25)), Some(1));
+        assert_eq!(scores(&table), [30, 25, 20, 10]);
+    }
+
+    #[test]
+    fn a_tie_goes_below_the_older_score() {
+        let mut table = HighScores::default();
+        table.insert(high_score(10));
+        let mut tie = high_score(10);
+        tie.seed = 0;
+        assert_eq!(table.insert(tie), Some(1));
+        assert_eq!(table.scores[0].seed, 10);
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept() {
+        let mut table = HighScores::default();
+        for score in 1..=TABLE_SIZE as u64 {
+            table.insert(high_score(score * 10));
+        }
+        assert_eq!(table.insert(high_score(5)), None);
+        assert_eq!(table.insert(high_score(15)), Some(TABLE_SIZE - 1));
+        assert_eq!(table.scores.len(), TABLE_SIZE);
+        assert_eq!(table.scores.last().unwrap().score, 15);
+    }
+
+    #[test]
+    fn days_are_converted_to_dates() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(20_088), "2024-12-31");
+        assert_eq!(date(47_541), "2100-03-01");
+    }
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+const PREFERENCES_KEY: &str = "motion";
+/// Camera offset in world units at the full shake strength.
+const MAX_SHAKE_OFFSET: f32 = 0.4;
+/// How fast the camera moves back and forth while shaking.
+const SHAKE_FREQUENCY: f32 = 40.0;
+/// Tint of a unit right after it was hit.
+const FLASH_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.3,
+    b: 0.3,
+    a: 1.0,
+};
+/// How much a hit unit is tinted in the reduced motion mode, barely noticeable.
+const SUBTLE_FLASH_STRENGTH: f32 = 0.2;
+
+/// Decides how the screen shake, hit flashes and pulsing markers are played,
+/// the renderer asks it instead of applying them directly.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct JuicePolicy {
+    /// Disables the shaking, flashing and pulsing, for players sensitive to motion or flashes.
+    pub reduced_motion: bool,
+}
+
+impl JuicePolicy {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    /// Camera offset for the shake strength from 0 to 1.
+    pub fn shake_offset(&self, strength: f32, time: f32) -> Vec2<f32> {
+        if self.reduced_motion {
+            return Vec2::ZERO;
+        }
+        // Squared, so that small hits barely shake the screen
+        let amplitude = strength * strength * MAX_SHAKE_OFFSET;
+        let phase = time * SHAKE_FREQUENCY;
+        vec2(phase.sin(), (phase * 1.3).cos()) * amplitude
+    }
+
+    /// Tint of a hit unit, `left` goes from 1 right after the hit to 0 at the end of the flash.
+    pub fn flash_tint(&self, left: f32) -> Rgba<f32> {
+        if self.reduced_motion {
+            // Fades out instead of blinking
+            let tint = 1.0 - SUBTLE_FLASH_STRENGTH * left;
+            return Rgba::new(1.0, tint, tint, 1.0);
+        }
+        // Full strength until the very end, so that the flash reads as a blink
+        if left > 0.3 {
+            FLASH_COLOR
+        } else {
+            Rgba::WHITE
+        }
+    }
+
+    /// Extra size of a pulsing marker, `amplitude` at the peak of the pulse.
+    pub fn pulse(&self, amplitude: f32, time: f32) -> f32 {
+        if self.reduced_motion {
+            return 0.0;
+        }
+        amplitude * time.sin()
+    }
+}
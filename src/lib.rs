@@ -0,0 +1,78 @@
+use geng::prelude::*;
+
+mod assets;
+pub mod bench;
+mod capture;
+mod codex;
+mod console;
+mod controls;
+mod crash;
+mod display;
+mod editor;
+mod effect_tree;
+mod game;
+mod high_scores;
+mod juice;
+mod loading;
+mod lobby;
+mod locale;
+mod logic;
+mod menu;
+mod model;
+mod mods;
+mod net;
+mod palette;
+mod profile;
+mod profiler;
+mod render;
+mod storage;
+mod ui;
+mod validation;
+
+use assets::{Assets, Library, MenuAssets, SoundId};
+use console::Console;
+use controls::*;
+use display::*;
+use effect_tree::EffectTreeEditor;
+use high_scores::*;
+use juice::*;
+use locale::*;
+use model::{Difficulty, GameMode, RunConfig};
+use palette::*;
+use profile::*;
+use profiler::*;
+
+/// Opens the window and runs the game, starting from the loading screen.
+pub fn run() {
+    logger::init().unwrap();
+    geng::setup_panic_handler();
+    crash::install_hook();
+
+    let display = DisplaySettings::load();
+    let geng = Geng::new_with(geng::ContextOptions {
+        title: "Wowie Jam 4".to_owned(),
+        vsync: display.frame_limit == FrameLimit::VSync,
+        ..Default::default()
+    });
+    display.apply_fullscreen(&geng);
+    // The menu is shown once its assets are in, the rest is loaded while it is open
+    let library = Library::load(&geng, &static_path());
+
+    geng::run(
+        &geng,
+        geng::LoadingScreen::new(&geng, loading::LoadingScreen::new(&geng), library, {
+            let geng = geng.clone();
+            move |library| match library {
+                Ok(library) => {
+                    Box::new(menu::MainMenu::with_library(&geng, library)) as Box<dyn geng::State>
+                }
+                Err(error) => {
+                    // E.g. a missing texture or sound file
+                    let problem = format!("Failed to load the assets: {:#}", error);
+                    error!("{}", problem);
+                    Box::new(validation::InvalidAssets::new(&geng, vec![problem]))
+                }
+            }
+        }),
+    )
+}
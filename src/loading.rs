@@ -0,0 +1,62 @@
+use super::*;
+use geng::Draw2d;
+
+const BAR_COLOR: Rgba<f32> = Rgba {
+    r: 0.17,
+    g: 0.85,
+    b: 1.0,
+    a: 1.0,
+};
+
+/// Shown while the assets are loading, so that the web build does not stall on a blank screen.
+/// First until the menu can be shown, then if a screen is opened before the [Library] is done.
+pub struct LoadingScreen {
+    geng: Geng,
+    /// Fraction of the assets loaded so far.
+    progress: f64,
+}
+
+impl LoadingScreen {
+    pub fn new(geng: &Geng) -> Self {
+        Self {
+            geng: geng.clone(),
+            progress: 0.0,
+        }
+    }
+}
+
+impl geng::ProgressScreen for LoadingScreen {
+    fn update_progress(&mut self, progress: f64) {
+        self.progress = progress.clamp(0.0, 1.0);
+    }
+}
+
+impl geng::State for LoadingScreen {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let center = screen.center();
+
+        self.geng.default_font().draw(
+            framebuffer,
+            camera,
+            "Loading...",
+            center + vec2(0.0, 40.0),
+            geng::TextAlign::CENTER,
+            40.0,
+            Rgba::WHITE,
+        );
+
+        let bar = AABB::point(center).extend_symmetric(vec2(screen.width() * 0.3, 10.0));
+        draw_2d::Quad::new(bar.extend_uniform(3.0), Rgba::GRAY).draw_2d(
+            &self.geng,
+            framebuffer,
+            camera,
+        );
+        draw_2d::Quad::new(bar, Rgba::BLACK).draw_2d(&self.geng, framebuffer, camera);
+        let mut filled = bar;
+        filled.x_max = filled.x_min + filled.width() * self.progress as f32;
+        draw_2d::Quad::new(filled, BAR_COLOR).draw_2d(&self.geng, framebuffer, camera);
+    }
+}
@@ -0,0 +1,215 @@
+use super::*;
+
+use model::{Level, RunConfig};
+use net::{Host, NetMessage, Peer};
+
+/// Where the two players of a co-op run find each other: the host waits for the guest
+/// and sends them the run configured in the menu, then both start it together.
+pub struct Lobby {
+    geng: Geng,
+    assets: Rc<Assets>,
+    locale: Locale,
+    state: LobbyState,
+    transition: Option<geng::Transition>,
+}
+
+enum LobbyState {
+    Hosting {
+        host: Host,
+        config: RunConfig,
+        level: Level,
+    },
+    /// The guest types the address of the host.
+    Joining {
+        address: String,
+    },
+    /// The guest waits for the host to send the run.
+    Connected(Peer),
+    Failed(String),
+}
+
+impl Lobby {
+    pub fn host(geng: &Geng, assets: &Rc<Assets>, config: RunConfig, level: Level) -> Self {
+        let state = match Host::bind(net::DEFAULT_PORT) {
+            Ok(host) => LobbyState::Hosting {
+                host,
+                config,
+                level,
+            },
+            Err(error) => LobbyState::Failed(error),
+        };
+        Self::new(geng, assets, state)
+    }
+
+    pub fn join(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let address = String::new();
+        Self::new(geng, assets, LobbyState::Joining { address })
+    }
+
+    fn new(geng: &Geng, assets: &Rc<Assets>, state: LobbyState) -> Self {
+        Self {
+            geng: geng.clone(),
+            assets: assets.clone(),
+            locale: Locale::new(&assets.menu, Language::load()),
+            state,
+            transition: None,
+        }
+    }
+
+    fn back_to_menu(&mut self) {
+        let menu = menu::MainMenu::new(&self.geng, &self.assets);
+        self.transition = Some(geng::Transition::Switch(Box::new(menu)));
+    }
+
+    fn start(&mut self, config: RunConfig, level: &Level, peer: Peer, host: bool) {
+        let game = game::Game::co_op(&self.geng, &self.assets, config, level, peer, host);
+        self.transition = Some(geng::Transition::Switch(Box::new(game)));
+    }
+
+    fn fail(&mut self, error: String) {
+        error!("Co-op: {}", error);
+        self.state = LobbyState::Failed(error);
+    }
+}
+
+impl geng::State for Lobby {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(&self.geng);
+        let locale = &self.locale;
+        let center = screen.center();
+
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("lobby.title"),
+            center + vec2(0.0, 200.0),
+            geng::TextAlign::CENTER,
+            64.0,
+            Rgba::WHITE,
+        );
+        let (status, field, color) = match &self.state {
+            LobbyState::Hosting { .. } => (
+                locale.format("lobby.hosting", &[("port", &net::DEFAULT_PORT)]),
+                None,
+                Rgba::WHITE,
+            ),
+            LobbyState::Joining { address } => (
+                locale.get("lobby.join").to_owned(),
+                Some(format!("{}_", address)),
+                Rgba::WHITE,
+            ),
+            LobbyState::Connected(_) => {
+                (locale.get("lobby.connected").to_owned(), None, Rgba::WHITE)
+            }
+            LobbyState::Failed(error) => (error.clone(), None, Rgba::RED),
+        };
+        font.draw(
+            framebuffer,
+            camera,
+            &status,
+            center + vec2(0.0, 40.0),
+            geng::TextAlign::CENTER,
+            32.0,
+            color,
+        );
+        if let Some(field) = field {
+            font.draw(
+                framebuffer,
+                camera,
+                &field,
+                center - vec2(0.0, 20.0),
+                geng::TextAlign::CENTER,
+                32.0,
+                Rgba::GRAY,
+            );
+        }
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("lobby.help"),
+            vec2(center.x, screen.y_min + 40.0),
+            geng::TextAlign::CENTER,
+            24.0,
+            Rgba::GRAY,
+        );
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        let key = match event {
+            geng::Event::KeyDown { key } => key,
+            _ => return,
+        };
+        if key == geng::Key::Escape {
+            self.back_to_menu();
+            return;
+        }
+        let address = match &mut self.state {
+            LobbyState::Joining { address } => address,
+            _ => return,
+        };
+        match key {
+            geng::Key::Enter => match Peer::connect(address.trim()) {
+                Ok(peer) => self.state = LobbyState::Connected(peer),
+                Err(error) => self.fail(error),
+            },
+            geng::Key::Backspace => {
+                address.pop();
+            }
+            key => {
+                let shift = self.geng.window().is_key_pressed(geng::Key::LShift);
+                if let Some(c) = console::key_char(key, shift) {
+                    address.push(c);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, _delta_time: f64) {
+        if self.transition.is_some() {
+            return;
+        }
+        match &mut self.state {
+            LobbyState::Hosting {
+                host,
+                config,
+                level,
+            } => match host.accept() {
+                Ok(Some(mut peer)) => {
+                    let (config, level) = (config.clone(), level.clone());
+                    let start = NetMessage::Start {
+                        config: config.clone(),
+                        level: level.clone(),
+                    };
+                    match peer.send(&start) {
+                        Ok(()) => self.start(config, &level, peer, true),
+                        Err(error) => self.fail(error),
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => self.fail(error),
+            },
+            // The inputs the host sends right after stay in the peer for the run
+            LobbyState::Connected(peer) => match peer.receive_next() {
+                Ok(Some(NetMessage::Start { config, level })) => {
+                    if let LobbyState::Connected(peer) = std::mem::replace(
+                        &mut self.state,
+                        LobbyState::Joining { address: default() },
+                    ) {
+                        self.start(config, &level, peer, false);
+                    }
+                }
+                Ok(Some(_)) => self.fail("The host sent an input before the run".to_owned()),
+                Ok(None) => {}
+                Err(error) => self.fail(error),
+            },
+            LobbyState::Joining { .. } | LobbyState::Failed(_) => {}
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+const PREFERENCES_KEY: &str = "language";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Self::English
+    }
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Russian];
+
+    /// The name of the language in the language itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Russian => "Русский",
+        }
+    }
+
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+}
+
+/// The user-facing strings in one language, loaded from `static/locale/<language>.txt`.
+pub struct Locale {
+    strings: HashMap<String, String>,
+    /// The English strings, used for the keys missing in the translation.
+    fallback: HashMap<String, String>,
+    /// The font with the glyphs of the language's script, the default one if not set.
+    font: Option<Rc<geng::Font>>,
+}
+
+impl Locale {
+    pub fn new(assets: &MenuAssets, language: Language) -> Self {
+        let (source, font) = match language {
+            Language::English => (&assets.locale.en, None),
+            Language::Russian => (&assets.locale.ru, Some(&assets.fonts.cyrillic)),
+        };
+        Self {
+            strings: parse(source),
+            fallback: parse(&assets.locale.en),
+            font: font.cloned(),
+        }
+    }
+
+    /// The string for the key, or the key itself if no locale has it,
+    /// so that untranslated text, like the lines of a modded cutscene, is shown as is.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// The string for the key with every `{name}` replaced with the value of the argument.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_owned();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    /// The numbers of the effect preview, e.g. "3.0 damage, 2.0 radius".
+    pub fn preview(&self, preview: &model::EffectPreview) -> String {
+        let mut parts = vec![];
+        if preview.damage > model::Hp::ZERO {
+            let value = format!("{:.1}", preview.damage);
+            parts.push(self.format("preview.damage", &[("value", &value)]));
+        }
+        if preview.heal > model::Hp::ZERO {
+            let value = format!("{:.1}", preview.heal);
+            parts.push(self.format("preview.heal", &[("value", &value)]));
+        }
+        if let Some(radius) = preview.radius {
+            let value = format!("{:.1}", radius);
+            parts.push(self.format("preview.radius", &[("value", &value)]));
+        }
+        if !preview.statuses.is_empty() {
+            let value = preview.statuses.join(", ");
+            parts.push(self.format("preview.statuses", &[("value", &value)]));
+        }
+        parts.join(", ")
+    }
+
+    pub fn font<'a>(&'a self, geng: &'a Geng) -> &'a Rc<geng::Font> {
+        self.font.as_ref().unwrap_or_else(|| geng.default_font())
+    }
+}
+
+/// The locale key of the perk's name, its description is under `<key>.description`.
+pub fn perk_key(name: &str) -> String {
+    format!("perk.{}", name.to_lowercase().replace(' ', "_"))
+}
+
+/// Parses the `key = text` lines, skipping the empty ones and the comments starting with `#`.
+fn parse(source: &str) -> HashMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, text) = line.split_once('=')?;
+            Some((key.trim().to_owned(), text.trim().to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_key_lines() {
+        let strings = parse(
+            "# Menu
+            menu.play = Play
+
+            menu.quit=Quit = leave
+            not a string",
+        );
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings["menu.play"], "Play");
+        // Only the first `=` separates the key
+        assert_eq!(strings["menu.quit"], "Quit = leave");
+    }
+
+    #[test]
+    fn falls_back_to_english_then_the_key() {
+        let locale = Locale {
+            strings: parse("menu.play = Играть"),
+            fallback: parse("menu.play = Play\nmenu.quit = Quit"),
+            font: None,
+        };
+        assert_eq!(locale.get("menu.play"), "Играть");
+        assert_eq!(locale.get("menu.quit"), "Quit");
+        assert_eq!(locale.get("Modded line"), "Modded line");
+        let locale = Locale {
+            strings: parse("wave = Wave {number} of {total}"),
+            fallback: default(),
+            font: None,
+        };
+        assert_eq!(
+            locale.format("wave", &[("number", &3), ("total", &10)]),
+            "Wave 3 of 10"
+        );
+    }
+}
@@ -2,20 +2,57 @@ use super::*;
 use model::*;
 use std::collections::VecDeque;
 
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
 mod action;
 mod animation;
+mod attack_tokens;
 mod behaviour;
+mod blocking;
+mod bosses;
+mod bounds;
+mod combat_log;
+mod combo;
 mod deaths;
+mod debug;
+mod dps_meters;
 mod effects;
+mod events;
+mod flocking;
+mod gliding;
+mod grapples;
 mod handle_event;
+mod hazards;
+mod interactables;
+mod low_health;
+mod melee;
 mod movement;
+mod objectives;
+mod obstacles;
 mod particles;
+mod perception;
+mod pickups;
+mod pings;
+mod point_defense;
 mod projectiles;
+mod sandbox;
+mod spawns;
 mod statuses;
+mod tutorial;
+mod water;
 mod waves;
+mod weather;
+mod zones;
 
 pub use effects::*;
 
+/// Fixed duration of a single simulation tick, so that the simulation
+/// runs identically regardless of the framerate.
+pub const TICK_TIME: f32 = 1.0 / 60.0;
+/// Maximum time simulated in a single frame, to avoid falling behind forever.
+const MAX_FRAME_TIME: f32 = 0.25;
+
 pub struct Logic<'a> {
     pub delta_time: Time,
     pub model: &'a mut Model,
@@ -24,40 +61,219 @@ pub struct Logic<'a> {
 
 impl Model {
     pub fn update(&mut self, delta_time: Time) {
-        let mut logic = Logic {
-            delta_time,
-            model: self,
-            effects: default(),
-        };
-        logic.process();
+        self.accumulated_time += delta_time.min(Time::new(MAX_FRAME_TIME));
+        let tick_time = Time::new(TICK_TIME);
+        while self.accumulated_time >= tick_time {
+            if !self.lockstep_ready() {
+                // Caught up quickly once the other player's inputs arrive
+                self.accumulated_time = self.accumulated_time.min(Time::new(MAX_FRAME_TIME));
+                break;
+            }
+            self.accumulated_time -= tick_time;
+            self.tick();
+        }
+    }
+
+    /// Applies the inputs queued for this tick and advances the simulation by [TICK_TIME].
+    /// The ticks of the cutscenes and the perk choices count as well, so that the inputs
+    /// given during them are recorded and replayed at the same point.
+    fn tick(&mut self) {
+        self.take_snapshot();
+        self.replay_input();
+        self.exchange_input();
+        self.record_input();
+        self.apply_queued_events();
+        self.events.clear();
+        if self.update_pause() {
+            self.world.paused_ticks += 1;
+        } else {
+            let mut logic = Logic {
+                delta_time: Time::new(TICK_TIME),
+                model: self,
+                effects: default(),
+            };
+            logic.process();
+        }
+        self.world.current_tick += 1;
     }
 
-    pub fn spawn_unit(&mut self, template: UnitTemplate, position: Position, faction: Faction) {
-        self.units
-            .insert(template.instance(&mut self.id_gen, position, faction));
+    /// Plays the cutscene, returns whether the simulation is paused for it or a perk choice.
+    fn update_pause(&mut self) -> bool {
+        if let Some(cutscene) = &mut self.world.cutscene {
+            cutscene.update(
+                Time::new(TICK_TIME),
+                &mut self.world.units,
+                &mut self.world.bodies,
+                &self.world.animations,
+            );
+            if cutscene.is_finished() {
+                self.world.cutscene = None;
+            }
+            return true;
+        }
+        // The simulation is stopped until the player chooses a perk
+        self.world.perk_choice.is_some()
+    }
+
+    fn apply_queued_events(&mut self) {
+        for event in std::mem::take(&mut self.queued_events) {
+            self.apply_event(event);
+        }
+    }
+
+    pub fn spawn_unit(&mut self, mut template: UnitTemplate, position: Position, faction: Faction) {
+        if let Faction::Alien = faction {
+            template.apply_difficulty(&self.config.difficulty.scaling());
+        }
+        let (unit, body) = template.instance(
+            &mut self.world.id_gen,
+            &self.world.animations,
+            position,
+            faction,
+        );
+        self.world.bodies.insert(unit.id, body);
+        self.world.units.insert(unit);
     }
 }
 
 impl Logic<'_> {
+    /// Runs the phases of a tick in order, the order of the systems within
+    /// a phase matters as well since they all queue effects.
     fn process(&mut self) {
+        self.profile(ProfilePhase::Ai, Self::process_intents);
+        self.profile(ProfilePhase::Movement, Self::process_integration);
+        self.profile(ProfilePhase::Collision, Self::process_collisions);
+        self.profile(ProfilePhase::Particles, Self::process_particles);
+        self.profile(ProfilePhase::Effects, Self::process_resolution);
+        self.model.clean_up_bodies();
+    }
+
+    fn profile(&mut self, phase: ProfilePhase, f: impl FnOnce(&mut Self)) {
+        let start = self.model.profiler.start();
+        f(self);
+        self.model.profiler.record(phase, start);
+    }
+
+    /// Decides what the units want to do this tick.
+    fn process_intents(&mut self) {
+        self.process_perception();
+        self.process_pings();
+        self.process_blocking();
         self.process_statuses();
+        self.process_gliding();
+        self.process_attack_tokens();
         self.process_behaviour();
+        self.process_flocking();
         self.process_animations();
         self.process_actions();
+        self.process_point_defense();
+    }
+
+    /// Moves the units according to their velocities and constraints.
+    fn process_integration(&mut self) {
         self.process_movement();
+        self.process_grapples();
+        self.process_weather();
+        self.process_kill_plane();
+    }
+
+    /// Checks the contacts between the entities and the level, queueing the resulting effects.
+    fn process_collisions(&mut self) {
+        self.process_hazards();
+        self.process_zones();
+        self.process_water();
         self.process_projectiles();
-        self.process_particles();
+        self.process_obstacles();
+        self.process_melee();
+        self.process_pickups();
+        self.process_interactables();
+    }
+
+    /// Applies the queued effects and their consequences.
+    fn process_resolution(&mut self) {
         self.process_effects();
+        self.process_events();
+        self.process_combat_log();
+        self.process_combo();
+        self.process_bosses();
         self.process_deaths();
+        self.process_low_health();
+        self.process_sandbox();
+        self.process_dps_meters();
+        self.process_objectives();
+        self.process_tutorial();
+        self.process_spawns();
         self.process_waves();
     }
 
     fn process_units(&mut self, mut f: impl FnMut(&mut Self, &mut Unit)) {
-        let ids: Vec<_> = self.model.units.ids().copied().collect();
+        let ids: Vec<_> = self.model.world.units.ids().copied().collect();
         for id in ids {
-            let mut unit = self.model.units.remove(&id).unwrap();
+            let mut unit = self.model.world.units.remove(&id).unwrap();
             f(self, &mut unit);
-            self.model.units.insert(unit);
+            self.model.world.units.insert(unit);
         }
     }
 }
+
+/// Runs the pass over every entity of the collection, on all the threads where there are any.
+/// The pass only sees the data it captures, so whatever it has to queue on the model comes back
+/// in the results, in the same order the sequential passes visit the entities.
+fn par_map<T, R>(collection: &mut Collection<T>, f: impl Fn(&mut T) -> R + Sync + Send) -> Vec<R>
+where
+    T: HasId + Send,
+    R: Send,
+{
+    let ids: Vec<T::Id> = collection.ids().cloned().collect();
+    let mut items: Vec<T> = ids
+        .iter()
+        .map(|id| collection.remove(id).unwrap())
+        .collect();
+    #[cfg(not(target_arch = "wasm32"))]
+    let results = items.par_iter_mut().map(f).collect();
+    #[cfg(target_arch = "wasm32")]
+    let results = items.iter_mut().map(f).collect();
+    for item in items {
+        collection.insert(item);
+    }
+    results
+}
+
+/// Same as [par_map], with the bodies of the entities taken out of the storages during the pass.
+/// The pass can still read the bodies of the entities outside of the collection,
+/// the bodies of the ones in it are taken out and missing from the storages.
+fn par_map_bodies<T, R>(
+    collection: &mut Collection<T>,
+    bodies: &mut Bodies,
+    f: impl Fn(&mut T, &mut Body, &Bodies) -> R + Sync + Send,
+) -> Vec<R>
+where
+    T: HasId<Id = Id> + Send,
+    R: Send,
+{
+    let ids: Vec<Id> = collection.ids().copied().collect();
+    let mut items: Vec<(T, Body)> = ids
+        .iter()
+        .map(|&id| {
+            let item = collection.remove(&id).unwrap();
+            let body = bodies.remove(id).expect("the entity has no body");
+            (item, body)
+        })
+        .collect();
+    let others = &*bodies;
+    #[cfg(not(target_arch = "wasm32"))]
+    let results = items
+        .par_iter_mut()
+        .map(|(item, body)| f(item, body, others))
+        .collect();
+    #[cfg(target_arch = "wasm32")]
+    let results = items
+        .iter_mut()
+        .map(|(item, body)| f(item, body, others))
+        .collect();
+    for (item, body) in items {
+        bodies.insert(*item.id(), body);
+        collection.insert(item);
+    }
+    results
+}
@@ -6,11 +6,15 @@ impl Logic<'_> {
     }
 
     fn process_unit_actions(&mut self, unit: &mut Unit) {
+        if let Some(ammo) = &mut unit.ammo {
+            ammo.update(self.delta_time);
+        }
+        let cooldown_rate = unit.stat(Stat::CooldownRate);
         match &mut unit.action_state {
             ActionState::Ready => {}
             ActionState::InProgress { .. } => {} // Action effect is processed in the animation
             ActionState::Cooldown { time_left } => {
-                *time_left -= self.delta_time;
+                *time_left -= self.delta_time * cooldown_rate;
                 if *time_left <= Time::ZERO {
                     unit.action_state = ActionState::Ready;
                     if let UnitAI::Engage {
@@ -1,37 +1,61 @@
 use super::*;
 
+/// Animation speed of the units wading through water.
+const WATER_ANIMATION_SPEED: f32 = 0.6;
+
 impl Logic<'_> {
     pub fn process_animations(&mut self) {
-        self.process_units(Self::process_unit_animation);
-        for projectile in &mut self.model.projectiles {
-            projectile.animation_state.update(self.delta_time);
+        let delta_time = self.delta_time;
+        let animations = &self.model.world.animations;
+        let effects = par_map(&mut self.model.world.units, |unit| {
+            animate_unit(unit, delta_time, animations)
+        });
+        par_map(&mut self.model.world.projectiles, |projectile| {
+            projectile.animation_state.update(delta_time, animations);
+        });
+        for effect in effects.into_iter().flatten() {
+            self.effects.push_front(effect);
         }
     }
+}
 
-    fn process_unit_animation(&mut self, unit: &mut Unit) {
-        let looped = unit.animation_state.update(self.delta_time);
-        let target = if let ActionState::InProgress { target } = unit.action_state {
-            target
-        } else {
-            None
-        };
+/// Returns the effects of the frames the unit's animation went through.
+fn animate_unit(unit: &mut Unit, delta_time: Time, animations: &Animations) -> Vec<QueuedEffect> {
+    let delta_time = if unit.in_water {
+        delta_time * r32(WATER_ANIMATION_SPEED)
+    } else {
+        delta_time
+    };
+    let looped = unit.animation_state.update(delta_time, animations);
+    let (target, aim) = if let ActionState::InProgress { target, aim } = unit.action_state {
+        (target, aim)
+    } else {
+        (None, None)
+    };
 
-        if looped {
-            if let ActionState::InProgress { .. } = unit.action_state {
-                // Stop action
-                unit.action_state = ActionState::Cooldown {
-                    time_left: unit.action.cooldown,
-                };
-                unit.animation_state.switch(&unit.idle_animation);
-            }
+    if looped {
+        if let ActionState::InProgress { .. } = unit.action_state {
+            // Stop action
+            unit.action_state = ActionState::Cooldown {
+                time_left: unit.action.cooldown,
+            };
+            unit.animation_state.switch(unit.idle_animation, animations);
         }
+    }
 
-        for effect in unit.animation_state.take_effects() {
-            let context = EffectContext {
+    unit.animation_state
+        .take_effects()
+        .into_iter()
+        .map(|effect| QueuedEffect {
+            effect,
+            context: EffectContext {
                 caster: Some(unit.id),
                 target,
-            };
-            self.effects.push_front(QueuedEffect { effect, context });
-        }
-    }
+                position: aim,
+                direction: None,
+                damage_multiplier: R32::ONE,
+                missing: MissingUnit::Skip,
+            },
+        })
+        .collect()
 }
@@ -0,0 +1,10 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_attack_tokens(&mut self) {
+        self.model
+            .world
+            .attack_tokens
+            .release_finished(&self.model.world.units);
+    }
+}
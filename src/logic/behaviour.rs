@@ -2,46 +2,105 @@ use geng::prelude::itertools::Itertools;
 
 use super::*;
 
+/// The enemies waiting for an attack token back off to this part of their engage radius.
+const WAITING_DISTANCE: f32 = 0.75;
+
 impl Logic<'_> {
     pub fn process_behaviour(&mut self) {
         self.process_units(Self::process_unit_behaviour);
     }
 
     fn process_unit_behaviour(&mut self, unit: &mut Unit) {
+        let position = self.model.world.bodies.positions[unit.id];
+        let vy = self.model.world.bodies.velocities[unit.id].y;
+        if unit.is_stunned() {
+            unit.target_velocity = vec2(Coord::ZERO, vy);
+            return;
+        }
         match &unit.ai {
             UnitAI::Idle => {}
             UnitAI::Engage {
                 target, default, ..
             } => {
-                let target = find_target(unit, &self.model.units, target);
-                if let Some(target) = target {
-                    let distance = (target.position - unit.position).len();
+                // The position ordered by the player takes priority over the target
+                let aim = self
+                    .model
+                    .aim
+                    .filter(|_| unit.is_aimable(&self.model.world.animations));
+                let engage = match aim {
+                    Some(aim) => Some((self.model.assist_aim(unit, aim), None)),
+                    None => pinged_target(unit, target, self.model)
+                        .or_else(|| {
+                            find_target(
+                                unit,
+                                &self.model.world.units,
+                                &self.model.world.bodies,
+                                target,
+                            )
+                        })
+                        .map(|target| {
+                            (
+                                self.model.world.bodies.positions[target.id],
+                                Some(target.id),
+                            )
+                        }),
+                };
+                if let Some((target_pos, target)) = engage {
+                    let distance = (target_pos - position).len();
                     if distance > unit.action.engage_radius {
                         // Go towards the target
-                        let vx = (target.position.x - unit.position.x).clamp_abs(unit.speed);
-                        unit.target_velocity = vec2(vx, unit.velocity.y);
-                        unit.animation_state.switch(&unit.move_animation);
+                        let vx = (target_pos.x - position.x).clamp_abs(unit.move_speed());
+                        unit.target_velocity = vec2(vx, vy);
+                        unit.animation_state
+                            .switch(unit.move_animation, &self.model.world.animations);
                         return;
                     } else if let ActionState::Ready = unit.action_state {
-                        // The target is in range -> attack
-                        unit.action_state = ActionState::InProgress {
-                            target: Some(target.id),
-                        };
-                        unit.animation_state.switch(&unit.action.animation);
+                        if unit.can_start_action() {
+                            if self.model.world.attack_tokens.try_take(unit) {
+                                // The target is in range -> attack
+                                unit.start_action(target, aim, &self.model.world.animations);
+                            } else if distance
+                                < unit.action.engage_radius * Coord::new(WAITING_DISTANCE)
+                            {
+                                // Wait for a free token out of the way of the attackers
+                                let vx = (position.x - target_pos.x).signum() * unit.move_speed()
+                                    / Coord::new(2.0);
+                                unit.target_velocity = vec2(vx, vy);
+                                unit.animation_state
+                                    .switch(unit.move_animation, &self.model.world.animations);
+                                return;
+                            }
+                        }
                     }
                 } else {
                     // Default
-                    let target_dir = match default {
-                        PositionAI::Advance => {
+                    let pinged = (unit.faction == Faction::Mech && !unit.npc)
+                        .then(|| {
+                            self.model
+                                .latest_ping(PingKind::PickUp)
+                                .or_else(|| self.model.latest_ping(PingKind::GoHere))
+                        })
+                        .flatten();
+                    let target_dir = match (pinged, default) {
+                        (Some(ping), _) => {
+                            let delta = ping.position.x - position.x;
+                            if delta.abs() < Coord::new(0.5) {
+                                Coord::ZERO
+                            } else {
+                                delta
+                            }
+                        }
+                        (None, PositionAI::Advance) => {
                             let friend = self
                                 .model
+                                .world
                                 .units
                                 .iter()
                                 .filter(|other| other.faction == unit.faction)
-                                .map(|other| other.position.x)
+                                .map(|other| self.model.world.bodies.positions[other.id].x)
                                 .min();
                             if let Some(min) = friend {
-                                if unit.position.x - min < Coord::new(7.0) {
+                                if position.x - min < Coord::new(7.0) {
                                     Coord::ONE
                                 } else {
                                     Coord::ZERO
@@ -50,13 +109,14 @@ impl Logic<'_> {
                                 Coord::ONE
                             }
                         }
-                        PositionAI::Follow => {
+                        (None, PositionAI::Follow) => {
                             let friends = match self
                                 .model
+                                .world
                                 .units
                                 .iter()
                                 .filter(|other| other.faction == unit.faction)
-                                .map(|other| other.position.x)
+                                .map(|other| self.model.world.bodies.positions[other.id].x)
                                 .minmax()
                             {
                                 itertools::MinMaxResult::NoElements => None,
@@ -65,16 +125,16 @@ impl Logic<'_> {
                             };
                             if let Some((min, max)) = friends {
                                 let target = (min + max) / Coord::new(2.0) + Coord::new(0.5);
-                                target - unit.position.x
+                                target - position.x
                             } else {
                                 Coord::ONE
                             }
                         }
                     };
                     if target_dir != Coord::ZERO {
-                        unit.target_velocity =
-                            vec2(unit.speed * target_dir.signum(), unit.velocity.y);
-                        unit.animation_state.switch(&unit.move_animation);
+                        unit.target_velocity = vec2(unit.move_speed() * target_dir.signum(), vy);
+                        unit.animation_state
+                            .switch(unit.move_animation, &self.model.world.animations);
                         return;
                     }
                 }
@@ -86,50 +146,72 @@ impl Logic<'_> {
                 charge_speed,
             } => {
                 // Counteract gravity
-                unit.velocity -= self.model.gravity * self.delta_time;
+                self.model.world.bodies.velocities[unit.id] -=
+                    self.model.world.gravity * self.delta_time;
                 match &unit.action_state {
                     ActionState::Ready => {
                         let preferred_distance = *charge_speed / Coord::new(4.0);
-                        if let Some(target) = find_target(unit, &self.model.units, target) {
-                            let delta = target.position - unit.position;
-                            if delta.len() < preferred_distance {
-                                // Start the attack animation
-                                unit.action_state = ActionState::InProgress {
-                                    target: Some(target.id),
-                                };
-                                unit.animation_state.switch(&unit.action.animation);
-                            } else {
-                                unit.animation_state.switch(&unit.move_animation);
-                            }
+                        if let Some(target) = find_target(
+                            unit,
+                            &self.model.world.units,
+                            &self.model.world.bodies,
+                            target,
+                        ) {
+                            let delta = self.model.world.bodies.positions[target.id] - position;
                             // Fly towards the target
                             unit.target_velocity = delta.normalize_or_zero() * *charge_speed;
+                            if delta.len() < preferred_distance && unit.can_start_action() {
+                                if self.model.world.attack_tokens.try_take(unit) {
+                                    // Start the attack animation
+                                    unit.start_action(
+                                        Some(target.id),
+                                        None,
+                                        &self.model.world.animations,
+                                    );
+                                } else {
+                                    // Circle the target until a token is free
+                                    unit.target_velocity = delta.rotate_90().normalize_or_zero()
+                                        * *charge_speed
+                                        / Coord::new(2.0);
+                                    unit.animation_state
+                                        .switch(unit.move_animation, &self.model.world.animations);
+                                }
+                            } else {
+                                unit.animation_state
+                                    .switch(unit.move_animation, &self.model.world.animations);
+                            }
                         }
                     }
-                    ActionState::InProgress { target } => {
+                    ActionState::InProgress { target, .. } => {
                         // Fly towards the target
-                        if let Some(target) = target.and_then(|id| self.model.units.get(&id)) {
-                            let delta = target.position - unit.position;
+                        if let Some(target) = target.and_then(|id| self.model.world.units.get(&id))
+                        {
+                            let delta = self.model.world.bodies.positions[target.id] - position;
                             unit.target_velocity = delta.normalize_or_zero() * *charge_speed;
                         }
                     }
                     ActionState::Cooldown { .. } => {
                         // Fly around at the preferred height
-                        unit.animation_state.switch(&unit.move_animation);
+                        unit.animation_state
+                            .switch(unit.move_animation, &self.model.world.animations);
                         let bounds = AABB::points_bounding_box(
-                            std::iter::once(vec2(self.model.left_border, Coord::ZERO)).chain(
+                            std::iter::once(vec2(self.model.world.left_border, Coord::ZERO)).chain(
                                 self.model
+                                    .world
                                     .units
                                     .iter()
                                     .filter(|unit| unit.faction == Faction::Mech)
-                                    .map(|unit| unit.position),
+                                    .map(|unit| self.model.world.bodies.positions[unit.id]),
                             ),
                         )
                         .extend_uniform(Coord::new(2.0));
                         let delta = if *preferred_right {
-                            bounds.x_max - unit.position.x
+                            bounds.x_max - position.x
                         } else {
-                            unit.position.x
-                                - bounds.x_min.max(self.model.left_border + Coord::new(2.0))
+                            position.x
+                                - bounds
+                                    .x_min
+                                    .max(self.model.world.left_border + Coord::new(2.0))
                         };
                         let preferred_right = if delta < Coord::ZERO {
                             !*preferred_right
@@ -148,8 +230,8 @@ impl Logic<'_> {
                         } else {
                             -Coord::ONE
                         };
-                        let preferred_distance = unit.speed / Coord::new(2.0);
-                        let y = (preferred_height - unit.position.y).clamp_abs(preferred_distance);
+                        let preferred_distance = unit.move_speed() / Coord::new(2.0);
+                        let y = (preferred_height - position.y).clamp_abs(preferred_distance);
                         let x = (preferred_distance.sqr() - y * y).max(Coord::ZERO).sqrt() * vx;
                         unit.target_velocity = vec2(x, y);
                     }
@@ -157,109 +239,153 @@ impl Logic<'_> {
                 return;
             }
         }
-        unit.target_velocity = vec2(Coord::ZERO, unit.velocity.y);
+        unit.target_velocity = vec2(Coord::ZERO, vy);
 
         match &unit.action_state {
             ActionState::Ready | ActionState::Cooldown { .. } => {
-                unit.animation_state.switch(&unit.idle_animation);
+                unit.animation_state
+                    .switch(unit.idle_animation, &self.model.world.animations);
             }
-            ActionState::InProgress { target } => {
+            ActionState::InProgress { target, aim } => {
                 if let Some(target_pos) = target
-                    .and_then(|id| self.model.units.get(&id))
-                    .map(|unit| unit.position)
+                    .and_then(|id| self.model.world.units.get(&id))
+                    .map(|unit| self.model.world.bodies.positions[unit.id])
+                    .or(*aim)
                 {
-                    if let Some(ExtraUnitRender::Tank {
-                        hand_pos,
-                        weapon_pos,
-                        shoot_pos,
-                        rotation,
-                    }) = &mut unit.extra_render
+                    // Aim at the target
+                    if let Some((index, effect)) = self
+                        .model
+                        .world
+                        .animations
+                        .get(unit.animation_state.animation)
+                        .keyframes
+                        .iter()
+                        .skip(unit.animation_state.frame + 1)
+                        .enumerate()
+                        .find_map(|(index, frame)| {
+                            let effect = frame.start_effect.as_ref()?;
+                            let effect = if effect.fires_weapon() {
+                                unit.weapon.as_ref()?.effect.find_projectile()?
+                            } else {
+                                effect.find_projectile()?
+                            };
+                            Some((index, effect))
+                        })
                     {
-                        // Aim at the target
-                        if let Some((index, frame)) = unit
-                            .animation_state
-                            .animation
-                            .keyframes
-                            .iter()
-                            .skip(unit.animation_state.frame + 1)
-                            .enumerate()
-                            .find(|(_, frame)| {
-                                matches!(frame.start_effect, Some(Effect::Projectile(_)))
-                            })
+                        // The points attached to missing ones are reported by the validation
+                        let aim_point = unit.attachments.iter().find(|point| point.aim);
+                        let pivot = aim_point.and_then(|point| {
+                            Some((point, local_transform(&unit.attachments, &point.name)?))
+                        });
+                        let muzzle = effect.attachment.as_ref().and_then(|name| {
+                            Some((
+                                local_transform(&unit.attachments, name)?,
+                                unit.attachment_position(&self.model.world.bodies, name)?,
+                            ))
+                        });
+                        if let (Some((aim_point, pivot)), Some((muzzle, muzzle_position))) =
+                            (pivot, muzzle)
                         {
-                            if let Some(Effect::Projectile(effect)) = &frame.start_effect {
-                                let mut offset =
-                                    *hand_pos + (*weapon_pos + *shoot_pos).rotate(*rotation);
+                            let reach = (muzzle.offset - pivot.offset).len_sqr();
+                            let delta = target_pos - muzzle_position;
+                            // Avoid awkward aim
+                            if delta.len_sqr() > reach {
+                                let dir = aim_parabollically(
+                                    delta,
+                                    self.model.world.gravity.y,
+                                    effect.speed,
+                                )
+                                .map(|(dir, _)| dir)
+                                .unwrap_or(delta);
+                                let mut angle = dir.arg();
                                 if unit.flip_sprite {
-                                    offset.x = -offset.x;
+                                    angle = Coord::PI - angle;
                                 }
-                                let delta = target_pos - (unit.position + offset);
-                                // Avoid awkward aim
-                                if delta.len_sqr() > (*weapon_pos + *shoot_pos).len_sqr() {
-                                    let dir = aim_parabollically(
-                                        delta,
-                                        self.model.gravity.y,
-                                        effect.speed,
-                                    )
-                                    .map(|(dir, _)| dir)
-                                    .unwrap_or(delta);
-                                    let mut angle = dir.arg();
-                                    if unit.flip_sprite {
-                                        angle = Coord::PI - angle;
-                                    }
-                                    let time = unit
-                                        .animation_state
-                                        .animation
+                                let time = self
+                                    .model
+                                    .world
+                                    .animations
+                                    .get(unit.animation_state.animation)
+                                    .keyframes
+                                    .get(unit.animation_state.frame)
+                                    .unwrap()
+                                    .time
+                                    - unit.animation_state.frame_time
+                                    + self
+                                        .model
+                                        .world
+                                        .animations
+                                        .get(unit.animation_state.animation)
                                         .keyframes
-                                        .get(unit.animation_state.frame)
-                                        .unwrap()
-                                        .time
-                                        - unit.animation_state.frame_time
-                                        + unit
-                                            .animation_state
-                                            .animation
-                                            .keyframes
-                                            .iter()
-                                            .take(index)
-                                            .skip(unit.animation_state.frame + 1)
-                                            .map(|frame| frame.time)
-                                            .fold(Time::ZERO, Time::add);
-                                    let delta = angle - *rotation;
-                                    *rotation += if time > Time::ZERO {
-                                        delta.clamp_abs(delta / time * self.delta_time)
-                                    } else {
-                                        delta
-                                    };
-                                }
+                                        .iter()
+                                        .take(index)
+                                        .skip(unit.animation_state.frame + 1)
+                                        .map(|frame| frame.time)
+                                        .fold(Time::ZERO, Time::add);
+                                let aim_name = aim_point.name.clone();
+                                let aim_point = unit
+                                    .attachments
+                                    .iter_mut()
+                                    .find(|point| point.name == aim_name)
+                                    .unwrap();
+                                let delta = angle - pivot.rotation;
+                                aim_point.rotation += if time > Time::ZERO {
+                                    delta.clamp_abs(delta / time * self.delta_time)
+                                } else {
+                                    delta
+                                };
                             }
                         }
                     }
 
                     // Look at the target
-                    unit.flip_sprite = (target_pos - unit.position).x < Coord::ZERO;
+                    unit.flip_sprite = (target_pos - position).x < Coord::ZERO;
                 }
             }
         }
     }
 }
 
+/// The enemy next to the latest danger ping, the mechs focus it over their usual target.
+fn pinged_target<'a>(caster: &Unit, target: &TargetAI, model: &'a Model) -> Option<&'a Unit> {
+    if caster.faction != Faction::Mech || caster.npc || matches!(target, TargetAI::LowestHp) {
+        return None;
+    }
+    let ping = model.latest_ping(PingKind::Danger)?;
+    model
+        .world
+        .units
+        .iter()
+        .filter(|other| other.faction != caster.faction)
+        .filter(|other| {
+            (model.world.bodies.positions[other.id] - ping.position).len()
+                <= Coord::new(PING_RADIUS)
+        })
+        .min_by_key(|other| (model.world.bodies.positions[other.id] - ping.position).len_sqr())
+}
+
 fn find_target<'a>(
     caster: &Unit,
     units: impl IntoIterator<Item = &'a Unit>,
+    bodies: &Bodies,
     target: &TargetAI,
 ) -> Option<&'a Unit> {
+    let distance =
+        |other: &Unit| (bodies.positions[caster.id] - bodies.positions[other.id]).len_sqr();
     match target {
         TargetAI::Closest => units
             .into_iter()
             .filter(|other| other.faction != caster.faction)
-            .min_by_key(|other| (caster.position - other.position).len_sqr()),
+            .min_by_key(|other| distance(other)),
         TargetAI::Farthest => units
             .into_iter()
             .filter(|other| other.faction != caster.faction)
-            .max_by_key(|other| (caster.position - other.position).len_sqr()),
+            .max_by_key(|other| distance(other)),
         TargetAI::LowestHp => units
             .into_iter()
-            .filter(|other| other.faction == caster.faction && other.health.ratio() < Hp::ONE)
-            .min_by_key(|other| other.health.hp),
+            .filter(|other| {
+                other.faction == caster.faction && bodies.healths[other.id].ratio() < Hp::ONE
+            })
+            .min_by_key(|other| bodies.healths[other.id].hp),
     }
 }
@@ -0,0 +1,27 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Raises and lowers the blocks of the mechs following the player's input.
+    pub fn process_blocking(&mut self) {
+        let blocking = self.model.blocking;
+        for unit in &mut self.model.world.units {
+            let block = match &unit.block {
+                Some(block) if unit.faction == Faction::Mech => block,
+                _ => continue,
+            };
+            if blocking && !unit.is_blocking() {
+                unit.statuses.push(Status::Block.into());
+                unit.statuses.push(
+                    Status::Parry {
+                        time: block.parry_window,
+                    }
+                    .into(),
+                );
+            } else if !blocking {
+                unit.statuses.retain(|status| {
+                    !matches!(status.status, Status::Block | Status::Parry { .. })
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_bosses(&mut self) {
+        self.process_units(Self::process_unit_boss);
+    }
+
+    fn process_unit_boss(&mut self, unit: &mut Unit) {
+        let boss = match &mut unit.boss {
+            Some(boss) => boss,
+            None => return,
+        };
+        // Several phases can be skipped by a single big hit
+        while let Some(phase) = boss.phases.get(boss.phase) {
+            if self.model.world.bodies.healths[unit.id].ratio() > phase.health_threshold {
+                break;
+            }
+            boss.phase += 1;
+            unit.ai = phase.ai.clone();
+            unit.action = phase.action.clone();
+            unit.action_state = ActionState::Cooldown {
+                time_left: phase.invulnerability,
+            };
+            unit.animation_state
+                .switch(unit.idle_animation, &self.model.world.animations);
+            if phase.invulnerability > Time::ZERO {
+                unit.statuses.push(
+                    Status::Invulnerable {
+                        time: phase.invulnerability,
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+}
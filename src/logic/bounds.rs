@@ -0,0 +1,23 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Kills the units that fell below the kill plane, regardless of their invulnerability.
+    pub fn process_kill_plane(&mut self) {
+        let kill_plane = self.model.world_area().y_min;
+        let bodies = &mut self.model.world.bodies;
+        for unit in &self.model.world.units {
+            let health = &mut bodies.healths[unit.id];
+            if bodies.positions[unit.id].y >= kill_plane || !health.is_alive() {
+                continue;
+            }
+            let amount = health.hp;
+            health.change(-amount);
+            self.model.events.push(GameEvent::Damaged {
+                caster: None,
+                target: unit.id,
+                amount,
+                killed: true,
+            });
+        }
+    }
+}
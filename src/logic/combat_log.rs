@@ -0,0 +1,45 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Records the events of the tick in the combat log, before the dead units are removed.
+    pub fn process_combat_log(&mut self) {
+        let model = &mut *self.model;
+        let time = model.time();
+        for event in &model.events {
+            let (caster, target, kind) = match *event {
+                GameEvent::Damaged {
+                    caster,
+                    target,
+                    amount,
+                    killed,
+                } => (caster, target, CombatLogKind::Damage { amount, killed }),
+                GameEvent::Healed {
+                    caster,
+                    target,
+                    amount,
+                } => (caster, target, CombatLogKind::Heal { amount }),
+                GameEvent::StatusApplied {
+                    caster,
+                    target,
+                    status,
+                } => (caster, target, CombatLogKind::Status { name: status }),
+                GameEvent::ChargeEnded { .. } => continue,
+            };
+            let target = match model.world.units.get(&target) {
+                Some(unit) => unit,
+                None => continue,
+            };
+            let caster = caster.and_then(|id| model.world.units.get(&id));
+            let entry = CombatLogEntry {
+                time,
+                source: caster.map_or(OTHER_SOURCE, |caster| caster.name),
+                target: target.name,
+                kind,
+            };
+            model
+                .world
+                .combat_log
+                .push(entry, caster.map(|caster| caster.faction), target.faction);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_combo(&mut self) {
+        let model = &mut *self.model;
+        model.world.combo.time_left -= self.delta_time;
+        if model.world.combo.time_left <= Time::ZERO {
+            model.world.combo.reset();
+        }
+        for event in &model.events {
+            let (caster, target, amount, killed) = match *event {
+                GameEvent::Damaged {
+                    caster,
+                    target,
+                    amount,
+                    killed,
+                } => (caster, target, amount, killed),
+                _ => continue,
+            };
+            let faction = |id: Id| {
+                model
+                    .world
+                    .units
+                    .get(&id)
+                    .map(|unit| unit.faction)
+                    .or_else(|| model.world.remains.get(&id).map(|remains| remains.faction))
+            };
+            match faction(target) {
+                Some(Faction::Mech) if amount > Hp::ZERO => model.world.combo.reset(),
+                Some(Faction::Alien)
+                    if killed && caster.and_then(faction) == Some(Faction::Mech) =>
+                {
+                    let points = model.world.combo.add_kill();
+                    model.world.score.style += points;
+                    model.world.score.best_combo =
+                        model.world.score.best_combo.max(model.world.combo.kills);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bench::{test_effect, test_model, test_spawn};
+
+    fn damage(hp: f32) -> Effect {
+        Effect::new(EffectNode::Damage(DamageEffect {
+            damage_type: DamageType::Physical,
+            value: Value::Flat(Hp::new(hp)),
+            hits_caster: false,
+            impact: None,
+        }))
+    }
+
+    #[test]
+    fn kills_of_the_mechs_continue_the_combo() {
+        let mut model = test_model();
+        let mech = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        for x in [20.0, 22.0] {
+            let enemy = test_spawn(&mut model, "blighter", x, Faction::Alien);
+            test_effect(&mut model, damage(1000.0), Some(mech), enemy);
+        }
+        assert_eq!(model.world.combo.kills, 2);
+        assert_eq!(model.world.score.best_combo, 2);
+        assert!(model.world.score.style > R32::ZERO);
+
+        // A kill without a mech to credit does not count
+        let enemy = test_spawn(&mut model, "blighter", 24.0, Faction::Alien);
+        test_effect(&mut model, damage(1000.0), None, enemy);
+        assert_eq!(model.world.combo.kills, 2);
+    }
+
+    #[test]
+    fn damage_to_the_mechs_breaks_the_combo() {
+        let mut model = test_model();
+        let mech = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        let enemy = test_spawn(&mut model, "blighter", 20.0, Faction::Alien);
+        test_effect(&mut model, damage(1000.0), Some(mech), enemy);
+        assert_eq!(model.world.combo.kills, 1);
+        test_effect(&mut model, damage(1.0), None, mech);
+        assert_eq!(model.world.combo.kills, 0);
+        assert_eq!(model.world.score.best_combo, 1);
+    }
+
+    #[test]
+    fn the_combo_runs_out() {
+        let mut model = test_model();
+        let mech = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        let enemy = test_spawn(&mut model, "blighter", 20.0, Faction::Alien);
+        test_effect(&mut model, damage(1000.0), Some(mech), enemy);
+        assert_eq!(model.world.combo.kills, 1);
+        for _ in 0..(COMBO_WINDOW / TICK_TIME) as usize + 1 {
+            model.update(Time::new(TICK_TIME));
+        }
+        assert_eq!(model.world.combo.kills, 0);
+    }
+}
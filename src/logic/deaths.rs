@@ -1,22 +1,107 @@
 use super::*;
 
+/// Chance of a regular enemy to drop a weapon, bosses always drop one.
+const WEAPON_DROP_CHANCE: f64 = 0.05;
+const AMMO_DROP_CHANCE: f64 = 0.1;
+const PICKUP_LIFETIME: f32 = 15.0;
+/// Vertical speed of the pilot thrown out of a destroyed vehicle.
+const EJECT_SPEED: f32 = 6.0;
+
 impl Logic<'_> {
     pub fn process_deaths(&mut self) {
+        for remains in self.model.world.remains.values_mut() {
+            remains.time_left -= self.delta_time;
+        }
+        self.model
+            .world
+            .remains
+            .retain(|_, remains| remains.time_left > Time::ZERO);
+
         // Units
-        self.model.units.retain(|unit| {
-            let alive = unit.health.is_alive();
+        let mut drops = Vec::new();
+        let mut ammo_drops = Vec::new();
+        let mut pilots = Vec::new();
+        let bodies = &self.model.world.bodies;
+        self.model.world.units.retain(|unit| {
+            let alive = bodies.is_alive(unit.id);
             if !alive {
-                self.model.player_energy.change(Hp::new(15.0));
+                let position = bodies.positions[unit.id];
+                if let Some(pilot) = &unit.pilot {
+                    let (pilot, mut body) = (**pilot).clone();
+                    body.position = position;
+                    pilots.push((pilot, body));
+                }
+                self.model
+                    .world
+                    .remains
+                    .insert(unit.id, Remains::new(unit, position));
+                self.model.world.id_gen.free(unit.id);
+                self.model.world.player_energy.change(Hp::new(15.0));
+                if let Faction::Alien = unit.faction {
+                    self.model.world.score.kills += 1;
+                    self.model.world.score.killed_types.insert(unit.name);
+                    if unit.boss.is_some() {
+                        self.model.world.score.bosses += 1;
+                    }
+                    if unit.boss.is_some() || self.model.world.rng.gen_bool(WEAPON_DROP_CHANCE) {
+                        drops.push(position);
+                    } else if self.model.world.rng.gen_bool(AMMO_DROP_CHANCE) {
+                        ammo_drops.push(position);
+                    }
+                }
             }
             alive
         });
 
-        // Projectiles
-        for projectile in &mut self.model.projectiles {
-            projectile.lifetime -= self.delta_time;
+        // Eject the pilots of the destroyed vehicles
+        for (mut pilot, mut body) in pilots {
+            pilot.id = self.model.world.id_gen.gen();
+            body.velocity = vec2(Coord::ZERO, Coord::new(EJECT_SPEED));
+            self.model.world.bodies.insert(pilot.id, body);
+            self.model.world.units.insert(pilot);
         }
+
+        // Weapon drops
+        // The headless simulation has no sprites for the pickups
+        if let Some(assets) = self.model.assets.clone() {
+            for position in drops {
+                let weapon = Weapon::tank_weapons(&assets, &mut self.model.world.animations)
+                    .into_iter()
+                    .choose(&mut self.model.world.rng)
+                    .expect("No weapons to drop");
+                let sprite = weapon
+                    .sprite
+                    .clone()
+                    .expect("Dropped weapons must have a sprite");
+                self.model.world.pickups.insert(Pickup {
+                    id: self.model.world.id_gen.gen(),
+                    position: vec2(position.x, self.model.world.ground_level + Coord::new(0.5)),
+                    collider: Collider::Aabb {
+                        size: vec2(1.0, 1.0).map(Coord::new),
+                    },
+                    sprite,
+                    lifetime: Time::new(PICKUP_LIFETIME),
+                    effect: Effect::new(EffectNode::GiveWeapon(GiveWeaponEffect { weapon })),
+                });
+            }
+            for position in ammo_drops {
+                self.model.world.pickups.insert(Pickup {
+                    id: self.model.world.id_gen.gen(),
+                    position: vec2(position.x, self.model.world.ground_level + Coord::new(0.5)),
+                    collider: Collider::Aabb {
+                        size: vec2(1.0, 1.0).map(Coord::new),
+                    },
+                    sprite: Sprite::new(&assets.mech.tank.projectile, 1.0 / 12.0),
+                    lifetime: Time::new(PICKUP_LIFETIME),
+                    effect: Effect::new(EffectNode::RestoreAmmo(RestoreAmmoEffect {
+                        amount: None,
+                    })),
+                });
+            }
+        }
+
+        // Projectiles
         self.model
-            .projectiles
-            .retain(|projectile| projectile.lifetime > Time::ZERO);
+            .retain_projectiles(|projectile| projectile.lifetime > Time::ZERO);
     }
 }
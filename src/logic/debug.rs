@@ -0,0 +1,71 @@
+use crate::game::{debug_status, DebugCommand};
+
+use super::*;
+
+impl Model {
+    pub(super) fn apply_debug_command(&mut self, command: DebugCommand) {
+        match command {
+            DebugCommand::SpawnUnit {
+                template,
+                position,
+                faction,
+            } => {
+                if let Some(template) = self.world.templates.get(&template).cloned() {
+                    self.spawn_unit(template, position, faction);
+                }
+            }
+            DebugCommand::GiveStatus { status, time } => {
+                if let Some(status) = debug_status(&status, time) {
+                    for unit in &mut self.world.units {
+                        if unit.faction == Faction::Mech {
+                            unit.statuses.push(status.clone().into());
+                        }
+                    }
+                }
+            }
+            DebugCommand::SetHp(hp) => {
+                for unit in &self.world.units {
+                    if unit.faction == Faction::Mech {
+                        let health = &mut self.world.bodies.healths[unit.id];
+                        health.hp = hp.clamp(Hp::ZERO, health.max_hp);
+                    }
+                }
+            }
+            DebugCommand::EditUnit { unit, field, value } => {
+                if let Some(unit) = self.world.units.get_mut(&unit) {
+                    edit_unit(unit, &mut self.world.bodies, &field, value);
+                }
+            }
+            DebugCommand::ClearStatuses(unit) => {
+                if let Some(unit) = self.world.units.get_mut(&unit) {
+                    unit.statuses.clear();
+                }
+            }
+            DebugCommand::KillAll => {
+                for unit in &self.world.units {
+                    if unit.faction != Faction::Mech {
+                        self.world.bodies.healths[unit.id].hp = Hp::ZERO;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sets the field, one of [crate::game::UNIT_FIELDS].
+fn edit_unit(unit: &mut Unit, bodies: &mut Bodies, field: &str, value: R32) {
+    let health = &mut bodies.healths[unit.id];
+    match field {
+        "hp" => health.hp = value.clamp(Hp::ZERO, health.max_hp),
+        "max_hp" => health.set_max_hp(value.max(Hp::ONE), MaxHpChange::Clamp),
+        "x" => bodies.positions[unit.id].x = value,
+        "y" => bodies.positions[unit.id].y = value,
+        "vx" => bodies.velocities[unit.id].x = value,
+        "vy" => bodies.velocities[unit.id].y = value,
+        "speed" => unit.speed = value,
+        "acceleration" => unit.acceleration = value,
+        "cooldown" => unit.action.cooldown = value,
+        "engage_radius" => unit.action.engage_radius = value,
+        _ => {}
+    }
+}
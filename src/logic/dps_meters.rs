@@ -0,0 +1,26 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Measures the damage dealt to the target dummies, the meters reset once they are left alone.
+    pub fn process_dps_meters(&mut self) {
+        let model = &mut *self.model;
+        let time = model.time();
+        let units = &model.world.units;
+        model
+            .world
+            .dps_meters
+            .retain(|id, meter| units.get(id).is_some() && !meter.is_idle(time));
+        for event in &model.events {
+            if let GameEvent::Damaged { target, amount, .. } = *event {
+                if units.get(&target).map_or(false, |unit| unit.is_dummy()) {
+                    model
+                        .world
+                        .dps_meters
+                        .entry(target)
+                        .or_insert_with(|| DpsMeter::new(time))
+                        .record(time, amount);
+                }
+            }
+        }
+    }
+}
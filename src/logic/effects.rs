@@ -6,13 +6,21 @@ pub struct QueuedEffect {
     pub context: EffectContext,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EffectContext {
     pub caster: Option<Id>,
     pub target: Option<Id>,
+    /// World position the effect is aimed at, used when there is no target unit.
+    pub position: Option<Position>,
+    /// Direction the effect travels in when it reaches the target, e.g. the velocity of a projectile.
+    pub direction: Option<Vec2<Coord>>,
+    /// Scales the damage dealt by the effect, e.g. by the falloff of an explosion.
+    pub damage_multiplier: R32,
+    /// How the caster and the target resolve once they are gone.
+    pub missing: MissingUnit,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Who {
     Caster,
     Target,
@@ -20,6 +28,20 @@ pub enum Who {
 
 impl Logic<'_> {
     pub fn process_effects(&mut self) {
+        let delta_time = self.delta_time;
+        let effects = &mut self.effects;
+        self.model.world.delayed_effects.retain_mut(|delayed| {
+            delayed.time_left -= delta_time;
+            if delayed.time_left > Time::ZERO {
+                return true;
+            }
+            effects.push_back(QueuedEffect {
+                effect: delayed.effect.clone(),
+                context: delayed.context.clone(),
+            });
+            false
+        });
+
         const MAX_ITERATIONS: usize = 1000;
         let mut iterations = 0;
         while let Some(QueuedEffect { effect, context }) = self.effects.pop_front() {
@@ -32,6 +54,7 @@ impl Logic<'_> {
                 break;
             }
         }
+        self.model.effects_processed = iterations;
     }
 }
 
@@ -43,40 +66,91 @@ impl EffectContext {
         }
     }
 
+    /// The id of the unit after the [MissingUnit] policy: a retargeted missing target
+    /// becomes the closest enemy of the caster.
+    fn resolve(&self, who: Who, logic: &Logic<'_>) -> Option<Id> {
+        let id = self.id(who)?;
+        if logic.model.world.units.get(&id).is_some() {
+            return Some(id);
+        }
+        match (who, self.missing) {
+            (Who::Target, MissingUnit::Retarget) => {
+                let (faction, position) = self.last_known(Who::Caster, logic)?;
+                closest_enemy(faction, position, logic).map(|unit| unit.id)
+            }
+            _ => None,
+        }
+    }
+
     pub fn get<'a>(&self, who: Who, logic: &'a Logic<'_>) -> Option<&'a Unit> {
-        self.id(who).and_then(|id| logic.model.units.get(&id))
+        self.resolve(who, logic)
+            .and_then(|id| logic.model.world.units.get(&id))
     }
 
     pub fn get_mut<'a>(&self, who: Who, logic: &'a mut Logic<'_>) -> Option<&'a mut Unit> {
-        self.id(who).and_then(|id| logic.model.units.get_mut(&id))
+        self.resolve(who, logic)
+            .and_then(|id| logic.model.world.units.get_mut(&id))
     }
 
-    pub fn id_expect(&self, who: Who) -> Id {
-        self.id(who).unwrap_or_else(|| {
-            panic!(
-                "In the context {:?}, attempted to find {:?}, but they turned out to be None",
-                self, who
-            )
-        })
+    /// Same as [EffectContext::get_mut], along with the body components, e.g. to damage the unit.
+    pub fn get_mut_with_bodies<'a>(
+        &self,
+        who: Who,
+        logic: &'a mut Logic<'_>,
+    ) -> Option<(&'a mut Unit, &'a mut Bodies)> {
+        let id = self.resolve(who, logic)?;
+        let model = &mut *logic.model;
+        Some((model.world.units.get_mut(&id)?, &mut model.world.bodies))
     }
 
-    pub fn get_expect<'a>(&self, who: Who, logic: &'a Logic<'_>) -> &'a Unit {
-        let id = self.id_expect(who);
-        logic.model.units.get(&id).unwrap_or_else(|| {
-            panic!(
-                "In the context {:?}, attempted to find {:?}, which have id {:?}, but they are not present in the model",
-                self, who, id
-            )
+    /// The position of the unit. Once it is gone, falls back to the position stored
+    /// in the context and then, with [MissingUnit::LastKnownPosition], to where the unit
+    /// died, so that e.g. an explosion still goes off where its target was killed.
+    pub fn position_of(&self, who: Who, logic: &Logic<'_>) -> Option<Position> {
+        if let Some(unit) = self.get(who, logic) {
+            return Some(logic.model.world.bodies.positions[unit.id]);
+        }
+        self.position.or_else(|| {
+            self.resolve_missing(who, logic)
+                .map(|(_, position)| position)
         })
     }
 
-    pub fn get_mut_expect<'a>(&self, who: Who, logic: &'a mut Logic<'_>) -> &'a mut Unit {
-        let id = self.id_expect(who);
-        logic.model.units.get_mut(&id).unwrap_or_else(|| {
-            panic!(
-                "In the context {:?}, attempted to find {:?}, which have id {:?}, but they are not present in the model",
-                self, who, id
-            )
-        })
+    /// The faction and the position that stand in for the missing unit, only with
+    /// [MissingUnit::LastKnownPosition].
+    pub fn resolve_missing(&self, who: Who, logic: &Logic<'_>) -> Option<(Faction, Position)> {
+        match self.missing {
+            MissingUnit::LastKnownPosition => self.last_known(who, logic),
+            MissingUnit::Skip | MissingUnit::Retarget => None,
+        }
+    }
+
+    /// The faction and the position of the unit, or of its remains if it has died.
+    pub fn last_known(&self, who: Who, logic: &Logic<'_>) -> Option<(Faction, Position)> {
+        let id = self.id(who)?;
+        match logic.model.world.units.get(&id) {
+            Some(unit) => Some((unit.faction, logic.model.world.bodies.positions[unit.id])),
+            None => logic
+                .model
+                .world
+                .remains
+                .get(&id)
+                .map(|remains| (remains.faction, remains.position)),
+        }
     }
 }
+
+/// The closest unit to the position that is hostile to the faction.
+pub fn closest_enemy<'a>(
+    faction: Faction,
+    position: Position,
+    logic: &'a Logic<'_>,
+) -> Option<&'a Unit> {
+    logic
+        .model
+        .world
+        .units
+        .iter()
+        .filter(|unit| unit.faction != faction)
+        .min_by_key(|unit| (logic.model.world.bodies.positions[unit.id] - position).len_sqr())
+}
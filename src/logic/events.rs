@@ -0,0 +1,29 @@
+use super::*;
+
+/// How long a unit is dazed after charging into a wall.
+const WALL_STUN_TIME: f32 = 0.5;
+
+impl Logic<'_> {
+    pub fn process_events(&mut self) {
+        for index in 0..self.model.events.len() {
+            match self.model.events[index] {
+                GameEvent::ChargeEnded {
+                    unit,
+                    reason: ChargeEnd::Wall,
+                } => {
+                    if let Some(unit) = self.model.world.units.get_mut(&unit) {
+                        let mut status = Status::Stunned {
+                            time: Time::new(WALL_STUN_TIME),
+                        };
+                        unit.size.scale_status(&mut status);
+                        unit.statuses.push(status.into());
+                    }
+                }
+                GameEvent::ChargeEnded { .. }
+                | GameEvent::Damaged { .. }
+                | GameEvent::Healed { .. }
+                | GameEvent::StatusApplied { .. } => {}
+            }
+        }
+    }
+}
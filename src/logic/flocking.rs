@@ -0,0 +1,95 @@
+use super::*;
+
+/// Distance within which the enemies steer relative to each other.
+const FLOCK_RADIUS: f32 = 3.0;
+/// Distance below which the enemies push each other apart.
+const SEPARATION_RADIUS: f32 = 1.5;
+const SEPARATION_WEIGHT: f32 = 3.0;
+const ALIGNMENT_WEIGHT: f32 = 0.3;
+const COHESION_WEIGHT: f32 = 0.2;
+
+impl Logic<'_> {
+    /// Adds the separation, alignment and cohesion steering to the movement the enemies want,
+    /// so that the crowds spread out instead of stacking into one spot.
+    pub fn process_flocking(&mut self) {
+        let radius = Coord::new(FLOCK_RADIUS);
+        let positions = &self.model.world.bodies.positions;
+        let mut hash = SpatialHash::new(radius);
+        for unit in self.flock() {
+            hash.insert(unit.id, AABB::point(positions[unit.id]));
+        }
+
+        let steering: Vec<(Id, Velocity)> = self
+            .flock()
+            .map(|unit| {
+                let area = AABB::point(positions[unit.id]).extend_uniform(radius);
+                let neighbours: Vec<&Unit> = hash
+                    .query(area)
+                    .into_iter()
+                    .filter(|&id| id != unit.id)
+                    .filter_map(|id| self.model.world.units.get(&id))
+                    .filter(|other| (positions[other.id] - positions[unit.id]).len() < radius)
+                    .collect();
+                (
+                    unit.id,
+                    flock_steering(unit, &neighbours, &self.model.world.bodies),
+                )
+            })
+            .collect();
+
+        for (id, steering) in steering {
+            let unit = self.model.world.units.get_mut(&id).unwrap();
+            let steering = if let UnitAI::Stinger { .. } = unit.ai {
+                steering
+            } else {
+                // The ground units only steer sideways
+                vec2(steering.x, Coord::ZERO)
+            };
+            unit.target_velocity += steering * unit.move_speed();
+        }
+    }
+
+    /// The enemies that move on their own.
+    fn flock(&self) -> impl Iterator<Item = &Unit> {
+        self.model
+            .world
+            .units
+            .iter()
+            .filter(|unit| unit.faction == Faction::Alien && !unit.is_stunned())
+    }
+}
+
+/// The steering relative to the unit's speed.
+fn flock_steering(unit: &Unit, neighbours: &[&Unit], bodies: &Bodies) -> Velocity {
+    if neighbours.is_empty() {
+        return Velocity::ZERO;
+    }
+    let count = Coord::new(neighbours.len() as f32);
+    let mut separation = Velocity::ZERO;
+    let mut velocity = Velocity::ZERO;
+    let mut center = Position::ZERO;
+    for other in neighbours {
+        let delta = bodies.positions[unit.id] - bodies.positions[other.id];
+        let distance = delta.len();
+        if distance < Coord::new(SEPARATION_RADIUS) {
+            // Stronger the closer they are, units at the same spot are split by their ids
+            let direction = if distance > Coord::ZERO {
+                delta / distance
+            } else if unit.id < other.id {
+                vec2(-Coord::ONE, Coord::ZERO)
+            } else {
+                vec2(Coord::ONE, Coord::ZERO)
+            };
+            separation += direction * (Coord::ONE - distance / Coord::new(SEPARATION_RADIUS));
+        }
+        velocity += bodies.velocities[other.id];
+        center += bodies.positions[other.id];
+    }
+    let speed = unit.move_speed().max(Coord::new(0.1));
+    let alignment = (velocity / count - bodies.velocities[unit.id]) / speed;
+    let cohesion = (center / count - bodies.positions[unit.id]) / Coord::new(FLOCK_RADIUS);
+    (separation * Coord::new(SEPARATION_WEIGHT)
+        + alignment.clamp_len(..=Coord::ONE) * Coord::new(ALIGNMENT_WEIGHT)
+        + cohesion * Coord::new(COHESION_WEIGHT))
+    .clamp_len(..=Coord::ONE)
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Makes the airborne mechs glide while the player holds the glide control,
+    /// and updates the glide animations of the slow-falling units.
+    pub fn process_gliding(&mut self) {
+        let gliding = self.model.gliding;
+        let ground_level = self.model.world.ground_level;
+        let animations = &self.model.world.animations;
+        let bodies = &self.model.world.bodies;
+        for unit in &mut self.model.world.units {
+            let airborne = bodies.is_airborne(unit.id, ground_level);
+            if gliding && airborne && unit.faction == Faction::Mech && !unit.npc {
+                unit.slow_fall(self.delta_time, Coord::new(GLIDE_FALL_SPEED));
+            }
+
+            match unit.glide_animation {
+                Some(animation) if airborne && unit.max_fall_speed().is_some() => {
+                    unit.glide_state
+                        .get_or_insert_with(|| AnimationState::new(animation, animations))
+                        .update(self.delta_time, animations);
+                }
+                _ => unit.glide_state = None,
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+/// Size of the hook used to check what it hits.
+const HOOK_SIZE: f32 = 0.3;
+/// The hook is released once the rope is reeled in to this length.
+const MIN_ROPE_LENGTH: f32 = 1.0;
+
+impl Logic<'_> {
+    pub fn process_grapples(&mut self) {
+        let mut grapples = std::mem::take(&mut self.model.world.grapples);
+        grapples.retain_mut(|grapple| self.process_grapple(grapple));
+        self.model.world.grapples = grapples;
+    }
+
+    /// Updates the grapple and returns whether it should be kept.
+    fn process_grapple(&mut self, grapple: &mut Grapple) -> bool {
+        grapple.time_left -= self.delta_time;
+        if grapple.time_left <= Time::ZERO {
+            return false;
+        }
+        let caster = match self.model.world.units.get(&grapple.caster) {
+            Some(caster) => caster,
+            None => return false,
+        };
+        let origin = grapple.origin(caster, &self.model.world.bodies);
+        let faction = caster.faction;
+
+        match &mut grapple.state {
+            GrappleState::Flying { position, velocity } => {
+                integrate(
+                    position,
+                    velocity,
+                    self.model.world.gravity,
+                    self.delta_time,
+                );
+                let position = *position;
+                let length = (position - origin).len();
+                if length > grapple.max_length {
+                    return false;
+                }
+
+                // Check what the hook hits
+                let hook = Collider::Aabb {
+                    size: vec2(HOOK_SIZE, HOOK_SIZE).map(Coord::new),
+                };
+                let bodies = &self.model.world.bodies;
+                let anchor = if let Some(unit) = self.model.world.units.iter().find(|unit| {
+                    unit.faction != faction && bodies.overlaps(unit.id, &hook, position)
+                }) {
+                    self.effects.push_front(QueuedEffect {
+                        effect: grapple.on_attach.clone(),
+                        context: EffectContext {
+                            caster: Some(grapple.caster),
+                            target: Some(unit.id),
+                            position: None,
+                            direction: Some(velocity.normalize_or_zero()),
+                            damage_multiplier: R32::ONE,
+                            missing: MissingUnit::Skip,
+                        },
+                    });
+                    Some(GrappleAnchor::Unit(unit.id))
+                } else if let Some(obstacle) =
+                    self.model.world.obstacles.iter().find(|obstacle| {
+                        hook.check(&obstacle.collider, obstacle.position - position)
+                    })
+                {
+                    Some(GrappleAnchor::Obstacle(obstacle.id))
+                } else if position.y <= self.model.world.ground_level {
+                    Some(GrappleAnchor::Point(vec2(
+                        position.x,
+                        self.model.world.ground_level,
+                    )))
+                } else {
+                    None
+                };
+                if let Some(anchor) = anchor {
+                    grapple.state = GrappleState::Attached { anchor, length };
+                }
+                true
+            }
+            GrappleState::Attached { anchor, length } => {
+                // Light units are pulled towards the caster, otherwise the caster is reeled in
+                let pulled_scale = match anchor {
+                    GrappleAnchor::Unit(id) => self
+                        .model
+                        .world
+                        .units
+                        .get(id)
+                        .and_then(|unit| unit.size.pull_scale())
+                        .map(|scale| (*id, scale)),
+                    _ => None,
+                };
+                let reel_scale = pulled_scale.map_or(R32::ONE, |(_, scale)| scale);
+                *length -= grapple.reel_speed * reel_scale * self.delta_time;
+                if *length <= Coord::new(MIN_ROPE_LENGTH) {
+                    return false;
+                }
+                let length = *length;
+                let anchor_position = match grapple.hook_position(self.model) {
+                    Some(position) => position,
+                    None => return false,
+                };
+
+                let (pulled, fixed) = match pulled_scale {
+                    Some((id, _)) => (id, origin),
+                    None => (grapple.caster, anchor_position),
+                };
+                constrain_rope(&mut self.model.world.bodies, pulled, fixed, length);
+                true
+            }
+        }
+    }
+}
+
+/// Keeps the unit within the rope's length from the fixed point,
+/// removing the velocity that would stretch the rope.
+fn constrain_rope(bodies: &mut Bodies, unit: Id, fixed: Position, length: Coord) {
+    let position = &mut bodies.positions[unit];
+    let delta = *position - fixed;
+    let distance = delta.len();
+    if distance <= length {
+        return;
+    }
+    let direction = delta / distance;
+    *position = fixed + direction * length;
+    let velocity = &mut bodies.velocities[unit];
+    let outward = Vec2::dot(*velocity, direction);
+    if outward > Coord::ZERO {
+        *velocity -= direction * outward;
+    }
+}
@@ -3,27 +3,90 @@ use crate::game::PlayerEvent;
 use super::*;
 
 impl Model {
+    /// Queues the event to be applied at the start of the next tick.
     pub fn handle_event(&mut self, event: PlayerEvent) {
+        self.queued_events.push(event);
+    }
+
+    pub(super) fn apply_event(&mut self, event: PlayerEvent) {
         match event {
             PlayerEvent::SpawnMech(mech) => {
-                let (template, cost) = match mech {
-                    game::MechType::Artillery => {
-                        (self.templates.artillery.clone(), Currency::new(40.0))
-                    }
-                    game::MechType::Tank => (self.templates.tank.clone(), Currency::new(30.0)),
-                    game::MechType::Healer => (self.templates.healer.clone(), Currency::new(25.0)),
-                };
-                if self.player_energy.hp < cost {
+                let cost = mech.cost();
+                if !self.config.commander.roster().contains(&mech)
+                    || self.world.player_energy.hp < cost
+                {
                     return;
                 }
-                self.player_energy.change(-cost);
-                let mut rng = global_rng();
+                self.world.player_energy.change(-cost);
                 let position = vec2(
-                    self.left_border + Coord::new(rng.gen_range(2.0..=10.0)),
-                    self.ground_level + Coord::new(rng.gen_range(2.0..=10.0)),
+                    self.world.left_border + Coord::new(self.world.rng.gen_range(2.0..=10.0)),
+                    self.world.ground_level + Coord::new(self.world.rng.gen_range(2.0..=10.0)),
                 );
+                let template = self.world.templates.mech(mech).clone();
                 self.spawn_unit(template, position, Faction::Mech);
+                self.complete_tutorial(TutorialAction::Spawn);
+            }
+            PlayerEvent::Reload => {
+                for unit in &mut self.world.units {
+                    if let (Faction::Mech, Some(ammo)) = (unit.faction, &mut unit.ammo) {
+                        ammo.reload();
+                    }
+                }
+                self.complete_tutorial(TutorialAction::Reload);
+            }
+            PlayerEvent::Interact => {
+                if let Some((object, unit)) = self
+                    .interactable_in_range()
+                    .map(|(object, unit)| (object.id, unit.id))
+                {
+                    self.world.interactables.get_mut(&object).unwrap().used_by = Some(unit);
+                    self.complete_tutorial(TutorialAction::Interact);
+                }
+            }
+            PlayerEvent::Ping(position) => {
+                self.place_ping(position);
+                self.complete_tutorial(TutorialAction::Ping);
+            }
+            PlayerEvent::AdvanceCutscene => {
+                if let Some(cutscene) = &mut self.world.cutscene {
+                    cutscene.advance();
+                }
+            }
+            PlayerEvent::SkipCutscene => {
+                self.world.cutscene = None;
+            }
+            PlayerEvent::SpawnSandboxUnit { index, position } => {
+                if !self.config.mode.is_sandbox() {
+                    return;
+                }
+                let unit = self
+                    .world
+                    .templates
+                    .sandbox_palette()
+                    .get(index)
+                    .map(|&(template, faction)| (template.clone(), faction));
+                if let Some((template, faction)) = unit {
+                    self.spawn_unit(template, position, faction);
+                }
+            }
+            PlayerEvent::ChoosePerk(index) => {
+                // An invalid index keeps the choice offered
+                let valid = self
+                    .world
+                    .perk_choice
+                    .as_ref()
+                    .map_or(false, |perks| index < perks.len());
+                if valid {
+                    let mut perks = self.world.perk_choice.take().unwrap();
+                    self.apply_perk(perks.swap_remove(index));
+                }
+            }
+            PlayerEvent::Held(actions) => {
+                self.aim = actions.aim;
+                self.blocking = actions.blocking;
+                self.gliding = actions.gliding;
             }
+            PlayerEvent::Debug(command) => self.apply_debug_command(command),
         }
     }
 }
@@ -0,0 +1,72 @@
+use super::*;
+
+/// How long the units keep burning after leaving the lava.
+const LAVA_BURN_TIME: f32 = 2.0;
+
+impl Logic<'_> {
+    pub fn process_hazards(&mut self) {
+        for hazard in &mut self.model.world.hazards {
+            for (_, time) in &mut hazard.touched {
+                *time -= self.delta_time;
+            }
+            hazard.touched.retain(|(_, time)| *time > Time::ZERO);
+
+            let bodies = &mut self.model.world.bodies;
+            for unit in &mut self.model.world.units {
+                let inside = bodies.colliders.get(unit.id).map_or(false, |collider| {
+                    collider.intersects_area(bodies.positions[unit.id], &hazard.area)
+                });
+                if !inside {
+                    continue;
+                }
+                let damage = match &hazard.kind {
+                    HazardKind::Spikes { damage, cooldown } => {
+                        if hazard.touched.iter().any(|(id, _)| *id == unit.id) {
+                            continue;
+                        }
+                        hazard.touched.push((unit.id, *cooldown));
+                        *damage
+                    }
+                    HazardKind::Lava { damage_per_second } => {
+                        unit.statuses
+                            .retain(|status| !matches!(status.status, Status::Burning { .. }));
+                        unit.statuses.push(
+                            Status::Burning {
+                                time: Time::new(LAVA_BURN_TIME),
+                                damage_per_second: *damage_per_second / Hp::new(2.0),
+                            }
+                            .into(),
+                        );
+                        *damage_per_second * self.delta_time
+                    }
+                    HazardKind::Wind { force } => {
+                        bodies.velocities[unit.id] += *force * self.delta_time;
+                        continue;
+                    }
+                };
+                self.effects.push_back(QueuedEffect {
+                    effect: Effect::new(EffectNode::Damage(DamageEffect {
+                        damage_type: DamageType::Physical,
+                        value: Value::Flat(damage),
+                        hits_caster: false,
+                        impact: None,
+                    })),
+                    context: EffectContext {
+                        caster: None,
+                        target: Some(unit.id),
+                        position: None,
+                        direction: None,
+                        damage_multiplier: R32::ONE,
+                        missing: MissingUnit::Skip,
+                    },
+                });
+            }
+        }
+
+        let min_x = self.model.despawn_x();
+        self.model
+            .world
+            .hazards
+            .retain(|hazard| hazard.area.x_max > min_x);
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_interactables(&mut self) {
+        let used: Vec<Interactable> = self
+            .model
+            .world
+            .interactables
+            .iter()
+            .filter(|object| object.used_by.is_some())
+            .cloned()
+            .collect();
+        for object in used {
+            self.model.world.interactables.remove(&object.id);
+            let unit = match object.used_by {
+                Some(unit) if self.model.world.units.get(&unit).is_some() => unit,
+                _ => continue,
+            };
+            let context = EffectContext {
+                caster: Some(unit),
+                target: Some(unit),
+                position: Some(object.position),
+                direction: None,
+                damage_multiplier: R32::ONE,
+                missing: MissingUnit::Skip,
+            };
+            match object.kind {
+                InteractableKind::Chest { weapon } => {
+                    self.effects.push_back(QueuedEffect {
+                        effect: Effect::new(EffectNode::GiveWeapon(GiveWeaponEffect {
+                            weapon: *weapon,
+                        })),
+                        context,
+                    });
+                }
+                InteractableKind::Lever { radius } => {
+                    self.apply_level_event(LevelEvent::ClearHazards {
+                        center: object.position,
+                        radius,
+                    });
+                }
+                InteractableKind::Survivor { effect } => {
+                    self.effects.push_back(QueuedEffect { effect, context });
+                }
+                InteractableKind::Vehicle { template } => self.board_vehicle(unit, *template),
+            }
+        }
+
+        let min_x = self.model.despawn_x();
+        self.model
+            .world
+            .interactables
+            .retain(|object| object.position.x > min_x);
+    }
+
+    pub fn apply_level_event(&mut self, event: LevelEvent) {
+        match event {
+            LevelEvent::ClearHazards { center, radius } => {
+                self.model.world.hazards.retain(|hazard| {
+                    let closest = vec2(
+                        center.x.clamp(hazard.area.x_min, hazard.area.x_max),
+                        center.y.clamp(hazard.area.y_min, hazard.area.y_max),
+                    );
+                    (closest - center).len() > radius
+                });
+            }
+            LevelEvent::OpenDoor => {
+                if let Some(door) = self
+                    .model
+                    .world
+                    .doors
+                    .iter()
+                    .min_by_key(|door| door.position.x)
+                    .map(|door| door.id)
+                {
+                    self.model.world.doors.remove(&door);
+                }
+            }
+            LevelEvent::ProtectGenerator { x, duration } => {
+                let unit = self.spawn_ally(self.model.world.templates.generator.clone(), x);
+                self.model.world.objectives.push(Objective {
+                    unit,
+                    goal: ObjectiveGoal::Survive {
+                        time_left: duration,
+                    },
+                });
+            }
+            LevelEvent::Ally { x, destination } => {
+                let unit = self.spawn_ally(self.model.world.templates.engineer.clone(), x);
+                if let Some(x) = destination {
+                    self.model.world.objectives.push(Objective {
+                        unit,
+                        goal: ObjectiveGoal::Reach { x },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Replaces the unit with the vehicle, keeping the unit inside until the vehicle is destroyed.
+    fn board_vehicle(&mut self, unit: Id, template: UnitTemplate) {
+        let (pilot, body) = match (
+            self.model.world.units.remove(&unit),
+            self.model.world.bodies.remove(unit),
+        ) {
+            (Some(pilot), Some(body)) => (pilot, body),
+            _ => return,
+        };
+        let (mut vehicle, vehicle_body) = template.instance(
+            &mut self.model.world.id_gen,
+            &self.model.world.animations,
+            body.position,
+            pilot.faction,
+        );
+        // The vehicle takes the id of the pilot, so that the targets and the orders carry over
+        self.model.world.id_gen.free(vehicle.id);
+        vehicle.id = pilot.id;
+        vehicle.flip_sprite = pilot.flip_sprite;
+        vehicle.pilot = Some(Box::new((pilot, body)));
+        self.model.world.bodies.insert(vehicle.id, vehicle_body);
+        self.model.world.units.insert(vehicle);
+    }
+
+    /// Places the unit on the ground at `x` on the side of the mechs.
+    fn spawn_ally(&mut self, template: UnitTemplate, x: Coord) -> Id {
+        let Collider::Aabb { size } = &template.collider;
+        let position = vec2(x, self.model.world.ground_level + size.y / Coord::new(2.0));
+        let (unit, body) = template.instance(
+            &mut self.model.world.id_gen,
+            &self.model.world.animations,
+            position,
+            Faction::Mech,
+        );
+        let id = unit.id;
+        self.model.world.bodies.insert(id, body);
+        self.model.world.units.insert(unit);
+        id
+    }
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Updates the low health state once the health of a mech changes.
+    pub fn process_low_health(&mut self) {
+        let model = &self.model;
+        let changed = model.world.low_health
+            || model.events.iter().any(|event| match *event {
+                GameEvent::Damaged { target, .. } | GameEvent::Healed { target, .. } => model
+                    .world
+                    .units
+                    .get(&target)
+                    .map_or(false, |unit| unit.faction == Faction::Mech),
+                _ => false,
+            });
+        if changed {
+            self.model.world.low_health = self.model.check_low_health();
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_melee(&mut self) {
+        let bodies = &mut self.model.world.bodies;
+        for swing in &mut self.model.world.melee_swings {
+            swing.time_left -= self.delta_time;
+            let caster = match self.model.world.units.get(&swing.caster) {
+                Some(caster) => caster,
+                None => {
+                    // The caster died mid-swing
+                    swing.time_left = Time::ZERO;
+                    continue;
+                }
+            };
+            let origin = swing.origin(caster, bodies);
+            let in_reach = |id: Id, bodies: &Bodies| match bodies.colliders.get(id) {
+                Some(collider) => {
+                    swing
+                        .shape
+                        .check(origin, caster.flip_sprite, bodies.positions[id], collider)
+                }
+                None => false,
+            };
+            for unit in &self.model.world.units {
+                if unit.faction == caster.faction
+                    || swing.hit.contains(&unit.id)
+                    || !in_reach(unit.id, bodies)
+                {
+                    continue;
+                }
+                swing.hit.push(unit.id);
+                self.effects.push_front(QueuedEffect {
+                    effect: swing.effect.clone(),
+                    context: EffectContext {
+                        caster: Some(caster.id),
+                        target: Some(unit.id),
+                        position: None,
+                        direction: Some((bodies.positions[unit.id] - origin).normalize_or_zero()),
+                        damage_multiplier: R32::ONE,
+                        missing: MissingUnit::Skip,
+                    },
+                });
+            }
+
+            // Reflect enemy projectiles
+            if let Some(speed_multiplier) = swing.deflect {
+                for projectile in &mut self.model.world.projectiles {
+                    if projectile.friend_faction == Some(caster.faction)
+                        || swing.hit.contains(&projectile.id)
+                        || !in_reach(projectile.id, bodies)
+                    {
+                        continue;
+                    }
+                    swing.hit.push(projectile.id);
+                    projectile.friend_faction = Some(caster.faction);
+                    projectile.target = projectile.caster;
+                    projectile.caster = Some(caster.id);
+                    let velocity = &mut bodies.velocities[projectile.id];
+                    *velocity = -*velocity * speed_multiplier;
+                }
+            }
+        }
+        self.model
+            .world
+            .melee_swings
+            .retain(|swing| swing.time_left > Time::ZERO);
+    }
+}
@@ -1,41 +1,114 @@
 use super::*;
 
+/// Part of the charge speed the unit bounces back with after running into a wall.
+const CHARGE_RECOIL: f32 = 0.3;
+const CHARGE_RECOIL_JUMP: f32 = 3.0;
+
+/// The parts of the level the units move through, read by all the threads at once.
+struct Terrain<'a> {
+    delta_time: Time,
+    gravity: Velocity,
+    ground_level: Coord,
+    left_border: Coord,
+    doors: &'a Collection<Door>,
+    obstacles: &'a Collection<Obstacle>,
+}
+
 impl Logic<'_> {
     pub fn process_movement(&mut self) {
-        self.process_units(Self::process_unit_movement);
+        let terrain = Terrain {
+            delta_time: self.delta_time,
+            gravity: self.model.world.gravity,
+            ground_level: self.model.world.ground_level,
+            left_border: self.model.world.left_border,
+            doors: &self.model.world.doors,
+            obstacles: &self.model.world.obstacles,
+        };
+        let events = par_map_bodies(
+            &mut self.model.world.units,
+            &mut self.model.world.bodies,
+            |unit, body, _| terrain.move_unit(unit, body),
+        );
+        self.model.events.extend(events.into_iter().flatten());
     }
+}
 
-    fn process_unit_movement(&mut self, unit: &mut Unit) {
+impl Terrain<'_> {
+    /// Returns the event of the unit's charge ending against a wall.
+    fn move_unit(&self, unit: &mut Unit, body: &mut Body) -> Option<GameEvent> {
         let acceleration = if unit
             .statuses
             .iter()
-            .any(|status| matches!(status, Status::Charge { .. }))
+            .any(|status| matches!(status.status, Status::Charge { .. }))
         {
             Velocity::ZERO
         } else {
-            (unit.target_velocity - unit.velocity).clamp_len(..=unit.acceleration * self.delta_time)
+            (unit.target_velocity - body.velocity).clamp_len(..=unit.acceleration * self.delta_time)
         };
-        unit.velocity += acceleration + self.model.gravity * self.delta_time;
-        unit.position += unit.velocity * self.delta_time;
+        body.velocity += acceleration + self.gravity * self.delta_time;
+        if let Some(max_fall_speed) = unit.max_fall_speed() {
+            body.velocity.y = body.velocity.y.max(-max_fall_speed);
+        }
+        let previous_x = body.position.x;
+        body.position += body.velocity * self.delta_time;
 
         // Check ground
-        let down = match &unit.collider {
-            Collider::Aabb { size } => size.y / Coord::new(2.0),
+        let down = match &body.collider {
+            Some(Collider::Aabb { size }) => size.y / Coord::new(2.0),
+            None => Coord::ZERO,
         };
-        if unit.position.y - down <= self.model.ground_level {
-            unit.position.y = self.model.ground_level + down;
-            unit.velocity.y = Coord::ZERO;
+        if body.position.y - down <= self.ground_level {
+            body.position.y = self.ground_level + down;
+            body.velocity.y = Coord::ZERO;
         }
         // Check left border
-        if unit.position.x <= self.model.left_border {
-            unit.position.x = self.model.left_border;
-            unit.velocity.x = Coord::ZERO;
+        let velocity_x = body.velocity.x;
+        let mut hit_wall = false;
+        if body.position.x <= self.left_border {
+            body.position.x = self.left_border;
+            hit_wall = body.velocity.x < Coord::ZERO;
+            body.velocity.x = Coord::ZERO;
+        }
+        // Check locked doors
+        for door in &self.doors {
+            if door.block(previous_x, &mut body.position) {
+                hit_wall = true;
+                body.velocity.x = Coord::ZERO;
+            }
+        }
+
+        // Charges end at walls
+        let mut event = None;
+        let charging = unit
+            .statuses
+            .iter()
+            .any(|status| matches!(status.status, Status::Charge { .. }));
+        if charging {
+            hit_wall = hit_wall
+                || body.collider.as_ref().map_or(false, |collider| {
+                    self.obstacles.iter().any(|obstacle| {
+                        collider.check(&obstacle.collider, obstacle.position - body.position)
+                    })
+                });
+            if hit_wall {
+                unit.statuses
+                    .retain(|status| !matches!(status.status, Status::Charge { .. }));
+                body.velocity = vec2(
+                    -velocity_x * Coord::new(CHARGE_RECOIL),
+                    Coord::new(CHARGE_RECOIL_JUMP),
+                );
+                event = Some(GameEvent::ChargeEnded {
+                    unit: unit.id,
+                    reason: ChargeEnd::Wall,
+                });
+            }
         }
 
-        unit.flip_sprite = match unit.velocity.x.cmp(&Coord::ZERO) {
+        unit.flip_sprite = match body.velocity.x.cmp(&Coord::ZERO) {
             std::cmp::Ordering::Less => true,
             std::cmp::Ordering::Equal => unit.flip_sprite,
             std::cmp::Ordering::Greater => false,
         };
+        event
     }
 }
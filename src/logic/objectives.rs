@@ -0,0 +1,50 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_objectives(&mut self) {
+        // Fire the triggers
+        let (ready, waiting) = std::mem::take(&mut self.model.world.triggers)
+            .into_iter()
+            .partition(|trigger: &LevelTrigger| trigger.condition.is_met(self.model));
+        self.model.world.triggers = waiting;
+        for trigger in ready {
+            self.apply_level_event(trigger.action.event());
+        }
+
+        // The run is lost with a protected unit
+        if self
+            .model
+            .world
+            .objectives
+            .iter()
+            .any(|objective| self.model.world.units.get(&objective.unit).is_none())
+        {
+            if !self.model.respawn() {
+                self.model.world.game_over = true;
+            }
+            return;
+        }
+
+        for objective in &mut self.model.world.objectives {
+            if let ObjectiveGoal::Survive { time_left } = &mut objective.goal {
+                *time_left -= self.delta_time;
+            }
+        }
+        let units = &self.model.world.units;
+        let positions = &self.model.world.bodies.positions;
+        let (completed, active) = std::mem::take(&mut self.model.world.objectives)
+            .into_iter()
+            .partition(|objective: &Objective| match objective.goal {
+                ObjectiveGoal::Survive { time_left } => time_left <= Time::ZERO,
+                ObjectiveGoal::Reach { x } => units
+                    .get(&objective.unit)
+                    .map_or(false, |unit| positions[unit.id].x >= x),
+            });
+        self.model.world.objectives = active;
+        for objective in completed {
+            // The unit is done and leaves the level
+            self.model.world.units.remove(&objective.unit);
+            self.model.world.id_gen.free(objective.unit);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+const DEBRIS_COUNT: usize = 6;
+
+impl Logic<'_> {
+    pub fn process_obstacles(&mut self) {
+        // Block projectiles
+        let bodies = &self.model.world.bodies;
+        for projectile in &mut self.model.world.projectiles {
+            if projectile.lifetime <= Time::ZERO || !projectile.is_armed() {
+                continue;
+            }
+            if let Some(obstacle) = self.model.world.obstacles.iter_mut().find(|obstacle| {
+                bodies.overlaps(projectile.id, &obstacle.collider, obstacle.position)
+            }) {
+                projectile.lifetime = Time::ZERO;
+                let damage = projectile
+                    .on_hit
+                    .contact_obstacle_damage(projectile.is_heavy(bodies));
+                obstacle.health.change(-damage);
+                // E.g. the explosions go off against the obstacle
+                self.effects.push_front(QueuedEffect {
+                    effect: projectile.on_hit.clone(),
+                    context: EffectContext {
+                        caster: projectile.caster,
+                        target: None,
+                        position: Some(bodies.positions[projectile.id]),
+                        direction: Some(bodies.velocities[projectile.id].normalize_or_zero()),
+                        damage_multiplier: projectile.damage_multiplier(),
+                        missing: projectile.missing,
+                    },
+                });
+            }
+        }
+
+        // Destroy obstacles
+        let destroyed: Vec<Obstacle> = self
+            .model
+            .world
+            .obstacles
+            .iter()
+            .filter(|obstacle| !obstacle.health.is_alive())
+            .cloned()
+            .collect();
+        for obstacle in destroyed {
+            self.model.world.obstacles.remove(&obstacle.id);
+            self.spawn_debris(&obstacle);
+        }
+        let min_x = self.model.despawn_x();
+        self.model
+            .world
+            .obstacles
+            .retain(|obstacle| obstacle.position.x > min_x);
+    }
+
+    /// Damages the obstacles in the radius around the center.
+    pub fn damage_obstacles(&mut self, center: Position, radius: Coord, damage: Hp) {
+        for obstacle in &mut self.model.world.obstacles {
+            if (obstacle.position - center).len() <= radius {
+                obstacle.health.change(-damage);
+            }
+        }
+    }
+
+    fn spawn_debris(&mut self, obstacle: &Obstacle) {
+        let sprite = Sprite {
+            texture: obstacle.sprite.texture,
+            size: obstacle.sprite.size / 4.0,
+        };
+        let name = format!("debris {:?} {:?}", sprite.texture, sprite.size);
+        let animation = self
+            .model
+            .world
+            .animations
+            .get_or_insert_with(name, || Animation {
+                keyframes: vec![AnimationFrame {
+                    sprite,
+                    time: Time::new(1.0),
+                    start_effect: None,
+                }],
+            });
+        for _ in 0..DEBRIS_COUNT {
+            let velocity = vec2(
+                self.model.world.rng.gen_range(-4.0..=4.0),
+                self.model.world.rng.gen_range(3.0..=8.0),
+            )
+            .map(Coord::new);
+            let id = self.model.world.id_gen.gen();
+            self.model
+                .world
+                .bodies
+                .insert(id, Body::new(obstacle.position, velocity));
+            let reused = self.model.particle_pool.reuse();
+            self.model.world.particles.insert(Particle {
+                id,
+                alive: true,
+                follow_unit: None,
+                attachment: None,
+                animation_state: AnimationState::reuse(
+                    reused.map(|particle| particle.animation_state),
+                    animation,
+                    &self.model.world.animations,
+                ),
+            });
+        }
+    }
+}
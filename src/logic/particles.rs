@@ -2,25 +2,71 @@ use super::*;
 
 impl Logic<'_> {
     pub fn process_particles(&mut self) {
-        for particle in &mut self.model.particles {
-            if let Some(unit) = particle
-                .follow_unit
-                .and_then(|id| self.model.units.get(&id))
-            {
-                particle.position = unit.position;
-            }
-            let looped = particle.animation_state.update(self.delta_time);
-            particle.alive = !looped;
-            for effect in particle.animation_state.take_effects() {
-                self.effects.push_front(QueuedEffect {
-                    effect,
-                    context: EffectContext {
-                        caster: None,
-                        target: particle.follow_unit,
-                    },
-                });
-            }
+        let delta_time = self.delta_time;
+        let gravity = self.model.world.gravity;
+        let units = &self.model.world.units;
+        let animations = &self.model.world.animations;
+        let particles = &mut self.model.world.particles;
+        let effects = par_map_bodies(
+            particles,
+            &mut self.model.world.bodies,
+            |particle, body, others| {
+                let followed = particle.follow_unit.and_then(|id| units.get(&id));
+                if let Some(unit) = followed {
+                    body.position = particle
+                        .attachment
+                        .as_ref()
+                        .and_then(|name| unit.attachment_position(others, name))
+                        .unwrap_or(others.positions[unit.id]);
+                } else if body.velocity != Velocity::ZERO {
+                    body.integrate(gravity, delta_time);
+                }
+                let looped = particle.animation_state.update(delta_time, animations);
+                particle.alive = !looped;
+                let target = particle.follow_unit;
+                particle
+                    .animation_state
+                    .take_effects()
+                    .into_iter()
+                    .map(move |effect| QueuedEffect {
+                        effect,
+                        context: EffectContext {
+                            caster: None,
+                            target,
+                            position: None,
+                            direction: None,
+                            damage_multiplier: R32::ONE,
+                            missing: MissingUnit::Skip,
+                        },
+                    })
+                    .collect::<Vec<_>>()
+            },
+        );
+        for effect in effects.into_iter().flatten() {
+            self.effects.push_front(effect);
         }
-        self.model.particles.retain(|particle| particle.alive);
+        self.model.retain_particles(|particle| particle.alive);
+
+        for spark in &mut self.model.sparks {
+            spark.position += spark.velocity * self.delta_time;
+            spark.lifetime -= self.delta_time;
+        }
+        self.model
+            .sparks
+            .retain(|spark| spark.lifetime > Time::ZERO);
+
+        for number in &mut self.model.damage_numbers {
+            number.update(self.delta_time);
+        }
+        self.model
+            .damage_numbers
+            .retain(|number| number.lifetime > Time::ZERO);
+
+        for indicator in &mut self.model.damage_indicators {
+            indicator.time_left -= self.delta_time;
+        }
+        self.model
+            .damage_indicators
+            .retain(|indicator| indicator.time_left > Time::ZERO);
     }
 }
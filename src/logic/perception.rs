@@ -0,0 +1,9 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_perception(&mut self) {
+        for unit in &mut self.model.world.units {
+            unit.record_motion(&self.model.world.bodies);
+        }
+    }
+}
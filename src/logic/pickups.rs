@@ -0,0 +1,31 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_pickups(&mut self) {
+        let bodies = &self.model.world.bodies;
+        for pickup in &mut self.model.world.pickups {
+            pickup.lifetime -= self.delta_time;
+            if let Some(unit) = self.model.world.units.iter().find(|unit| {
+                pickup.can_collect(unit)
+                    && bodies.overlaps(unit.id, &pickup.collider, pickup.position)
+            }) {
+                pickup.lifetime = Time::ZERO;
+                self.effects.push_front(QueuedEffect {
+                    effect: pickup.effect.clone(),
+                    context: EffectContext {
+                        caster: None,
+                        target: Some(unit.id),
+                        position: None,
+                        direction: None,
+                        damage_multiplier: R32::ONE,
+                        missing: MissingUnit::Skip,
+                    },
+                });
+            }
+        }
+        self.model
+            .world
+            .pickups
+            .retain(|pickup| pickup.lifetime > Time::ZERO);
+    }
+}
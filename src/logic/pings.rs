@@ -0,0 +1,18 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_pings(&mut self) {
+        for ping in &mut self.model.world.pings {
+            ping.lifetime -= self.delta_time;
+        }
+        for ping in &self.model.world.pings {
+            if ping.lifetime <= Time::ZERO {
+                self.model.world.id_gen.free(ping.id);
+            }
+        }
+        self.model
+            .world
+            .pings
+            .retain(|ping| ping.lifetime > Time::ZERO);
+    }
+}
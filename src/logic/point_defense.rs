@@ -0,0 +1,53 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_point_defense(&mut self) {
+        self.process_units(Self::process_unit_point_defense);
+    }
+
+    fn process_unit_point_defense(&mut self, unit: &mut Unit) {
+        let stunned = unit.is_stunned();
+        let bodies = &self.model.world.bodies;
+        let (id, position, faction) = (unit.id, bodies.positions[unit.id], unit.faction);
+        let defense = match &mut unit.point_defense {
+            Some(defense) => defense,
+            None => return,
+        };
+        defense.cooldown_left = (defense.cooldown_left - self.delta_time).max(Time::ZERO);
+        if stunned || defense.cooldown_left > Time::ZERO {
+            return;
+        }
+        let target = self
+            .model
+            .world
+            .projectiles
+            .iter()
+            .filter(|projectile| projectile.friend_faction != Some(faction))
+            .map(|projectile| {
+                (
+                    projectile,
+                    (bodies.positions[projectile.id] - position).len(),
+                )
+            })
+            .filter(|&(_, distance)| distance <= defense.range)
+            .min_by_key(|&(_, distance)| distance);
+        if let Some((projectile, distance)) = target {
+            // Lead the projectile by the time the interceptor takes to get there
+            let lead = distance / defense.shot.speed;
+            defense.cooldown_left = defense.cooldown;
+            self.effects.push_back(QueuedEffect {
+                effect: Effect::new(EffectNode::Projectile(defense.shot.clone())),
+                context: EffectContext {
+                    caster: Some(id),
+                    target: None,
+                    position: Some(
+                        bodies.positions[projectile.id] + bodies.velocities[projectile.id] * lead,
+                    ),
+                    direction: None,
+                    damage_multiplier: R32::ONE,
+                    missing: MissingUnit::Skip,
+                },
+            });
+        }
+    }
+}
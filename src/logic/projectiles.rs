@@ -1,47 +1,80 @@
 use super::*;
 
+/// Size of the cells of the broad phase between the projectiles.
+const PROJECTILE_CELL_SIZE: f32 = 2.0;
+
 impl Logic<'_> {
     pub fn process_projectiles(&mut self) {
         // Control behaviour
-        for projectile in &mut self.model.projectiles {
+        let bodies = &mut self.model.world.bodies;
+        for projectile in &self.model.world.projectiles {
             match &projectile.ai {
-                ProjectileAI::Idle => {}
+                ProjectileAI::Idle | ProjectileAI::Orbit { .. } | ProjectileAI::Attached { .. } => {
+                }
                 ProjectileAI::Rocket {
                     speed,
                     acceleration,
                     preferred_height,
                 } => {
-                    let mut target_velocity = projectile.velocity.normalize_or_zero() * *speed;
-                    if let Some(target) = projectile.target.and_then(|id| self.model.units.get(&id))
+                    let position = bodies.positions[projectile.id];
+                    let velocity = bodies.velocities[projectile.id];
+                    let mut target_velocity = velocity.normalize_or_zero() * *speed;
+                    if let Some(&target) = projectile
+                        .target
+                        .filter(|id| self.model.world.units.get(id).is_some())
+                        .and_then(|id| bodies.positions.get(id))
                     {
                         let preferred_distance = *speed / Coord::new(2.0);
-                        let delta = target.position - projectile.position;
+                        let delta = target - position;
                         if delta.x.abs() < preferred_distance {
                             target_velocity = delta.normalize_or_zero() * *speed;
                         } else {
-                            let y = (*preferred_height - projectile.position.y)
-                                .clamp_abs(preferred_distance);
+                            let y = (*preferred_height - position.y).clamp_abs(preferred_distance);
                             let x = (preferred_distance.sqr() - y * y).max(Coord::ZERO).sqrt()
                                 * delta.x.signum();
                             target_velocity = vec2(x, y);
                         }
                     }
-                    projectile.velocity += (target_velocity - projectile.velocity)
+                    bodies.velocities[projectile.id] += (target_velocity - velocity)
                         .clamp_len(..=*acceleration * self.delta_time)
-                        - self.model.gravity * self.delta_time;
+                        - self.model.world.gravity * self.delta_time;
                 }
             }
         }
 
         // Move projectiles
-        for projectile in &mut self.model.projectiles {
-            projectile.velocity += self.model.gravity * self.delta_time;
-            projectile.position += projectile.velocity * self.delta_time;
+        let bodies = &mut self.model.world.bodies;
+        for projectile in &mut self.model.world.projectiles {
+            if projectile.ai.follows_caster() {
+                let caster = projectile
+                    .caster
+                    .filter(|id| self.model.world.units.get(id).is_some())
+                    .and_then(|id| bodies.positions.get(id).copied());
+                match caster {
+                    Some(caster) => follow_caster(projectile, caster, bodies, self.delta_time),
+                    // Despawns with the caster
+                    None => projectile.lifetime = Time::ZERO,
+                }
+            }
+        }
+        let free = self
+            .model
+            .world
+            .projectiles
+            .iter()
+            .filter(|projectile| !projectile.ai.follows_caster())
+            .map(|projectile| projectile.id);
+        bodies.integrate(free, self.model.world.gravity, self.delta_time);
+        for projectile in &mut self.model.world.projectiles {
+            projectile.distance += bodies.velocities[projectile.id].len() * self.delta_time;
         }
 
+        self.process_interceptions();
+
         // Check for collisions
-        for projectile in &mut self.model.projectiles {
-            for unit in &self.model.units {
+        let bodies = &self.model.world.bodies;
+        for projectile in &mut self.model.world.projectiles {
+            for unit in &self.model.world.units {
                 if projectile
                     .friend_faction
                     .map(|faction| unit.faction == faction)
@@ -49,28 +82,144 @@ impl Logic<'_> {
                 {
                     continue;
                 }
-                if projectile
-                    .collider
-                    .check(&unit.collider, unit.position - projectile.position)
-                {
+                if projectile.is_armed() && bodies.collide(projectile.id, unit.id) {
                     projectile.lifetime = Time::ZERO;
                     self.effects.push_front(QueuedEffect {
                         effect: projectile.on_hit.clone(),
                         context: EffectContext {
                             caster: projectile.caster,
                             target: Some(unit.id),
+                            // Keep the hit position in case the target dies before the effect resolves
+                            position: Some(bodies.positions[projectile.id]),
+                            direction: Some(bodies.velocities[projectile.id].normalize_or_zero()),
+                            damage_multiplier: projectile.damage_multiplier(),
+                            missing: projectile.missing,
                         },
                     });
                 }
-                if projectile.position.y <= self.model.ground_level {
+                if bodies.positions[projectile.id].y <= self.model.world.ground_level
+                    && !projectile.ai.follows_caster()
+                {
                     projectile.lifetime = Time::ZERO;
                 }
             }
         }
 
-        // Remove collided projectiles
+        // Expire projectiles that ran out of time or range, or left the world
+        let world = self.model.world_area();
+        for projectile in &mut self.model.world.projectiles {
+            if projectile.lifetime <= Time::ZERO {
+                // Collided
+                continue;
+            }
+            projectile.lifetime -= self.delta_time;
+            let position = bodies.positions[projectile.id];
+            if projectile.lifetime > Time::ZERO
+                && !projectile.is_out_of_range()
+                && world.contains(position)
+            {
+                continue;
+            }
+            projectile.lifetime = Time::ZERO;
+            self.effects.push_front(QueuedEffect {
+                effect: projectile.on_expire.clone(),
+                context: EffectContext {
+                    caster: projectile.caster,
+                    target: None,
+                    position: Some(position),
+                    direction: Some(bodies.velocities[projectile.id].normalize_or_zero()),
+                    damage_multiplier: R32::ONE,
+                    missing: projectile.missing,
+                },
+            });
+        }
+
+        // Remove collided and expired projectiles
         self.model
-            .projectiles
-            .retain(|projectile| projectile.lifetime > Time::ZERO);
+            .retain_projectiles(|projectile| projectile.lifetime > Time::ZERO);
+    }
+    /// Destroys the pairs of an intercepting projectile and an enemy projectile touching it.
+    fn process_interceptions(&mut self) {
+        let projectiles = &self.model.world.projectiles;
+        let bodies = &self.model.world.bodies;
+        if !projectiles.iter().any(|projectile| projectile.intercepts) {
+            return;
+        }
+        let mut hash = SpatialHash::new(Coord::new(PROJECTILE_CELL_SIZE));
+        for projectile in projectiles {
+            if let Some(bounds) = bodies.bounds(projectile.id) {
+                hash.insert(projectile.id, bounds);
+            }
+        }
+        let mut hits = Vec::new();
+        for interceptor in projectiles
+            .iter()
+            .filter(|projectile| projectile.intercepts && projectile.is_armed())
+        {
+            let bounds = match bodies.bounds(interceptor.id) {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            for id in hash.query(bounds) {
+                let other = match projectiles.get(&id) {
+                    Some(other) => other,
+                    None => continue,
+                };
+                if other.friend_faction != interceptor.friend_faction
+                    && bodies.collide(interceptor.id, other.id)
+                {
+                    hits.push((interceptor.id, other.id));
+                }
+            }
+        }
+        for (interceptor, other) in hits {
+            let alive = |id| {
+                self.model
+                    .world
+                    .projectiles
+                    .get(&id)
+                    .filter(|projectile| projectile.lifetime > Time::ZERO)
+                    .map(|projectile| self.model.world.bodies.positions[projectile.id])
+            };
+            // A projectile is destroyed by the first interceptor that reaches it
+            if let (Some(a), Some(b)) = (alive(interceptor), alive(other)) {
+                for id in [interceptor, other] {
+                    if let Some(projectile) = self.model.world.projectiles.get_mut(&id) {
+                        projectile.lifetime = Time::ZERO;
+                    }
+                }
+                let impact = DamageType::Physical.impact();
+                self.model.spawn_sparks((a + b) / Coord::new(2.0), &impact);
+            }
+        }
+    }
+}
+
+/// Moves the projectile along with its caster.
+fn follow_caster(
+    projectile: &mut Projectile,
+    caster: Position,
+    bodies: &mut Bodies,
+    delta_time: Time,
+) {
+    let position = &mut bodies.positions[projectile.id];
+    let velocity = &mut bodies.velocities[projectile.id];
+    match &mut projectile.ai {
+        ProjectileAI::Orbit {
+            radius,
+            angular_speed,
+            angle,
+        } => {
+            *angle += *angular_speed * delta_time;
+            let direction = vec2(Coord::ONE, Coord::ZERO).rotate(*angle);
+            *position = caster + direction * *radius;
+            // Tangential, so that the orb is drawn facing where it flies
+            *velocity = direction.rotate_90() * *radius * *angular_speed;
+        }
+        ProjectileAI::Attached { offset } => {
+            *offset += *velocity * delta_time;
+            *position = caster + *offset;
+        }
+        ProjectileAI::Idle | ProjectileAI::Rocket { .. } => {}
     }
 }
@@ -0,0 +1,11 @@
+use super::*;
+
+impl Logic<'_> {
+    /// Keeps the energy full in the sandbox.
+    pub fn process_sandbox(&mut self) {
+        if self.model.config.mode.is_sandbox() {
+            let energy = &mut self.model.world.player_energy;
+            energy.hp = energy.max_hp;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_spawns(&mut self) {
+        for spawn in &mut self.model.world.pending_spawns {
+            spawn.time_left -= self.delta_time;
+        }
+        let (ready, waiting) = std::mem::take(&mut self.model.world.pending_spawns)
+            .into_iter()
+            .partition(|spawn: &PendingSpawn| spawn.time_left <= Time::ZERO);
+        self.model.world.pending_spawns = waiting;
+        for mut spawn in ready {
+            let time = spawn.style.animation_time;
+            if time > Time::ZERO {
+                spawn.template.statuses.push(Status::Spawning {
+                    time,
+                    duration: time,
+                });
+            }
+            self.model
+                .spawn_unit(spawn.template, spawn.position, spawn.faction);
+        }
+    }
+}
@@ -1,42 +1,176 @@
 use super::*;
 
+/// Speed below which the units do not leave their trails.
+const TRAIL_MIN_SPEED: f32 = 0.5;
+
 impl Logic<'_> {
     pub fn process_statuses(&mut self) {
         self.process_units(Self::process_unit_statuses);
     }
 
     fn process_unit_statuses(&mut self, unit: &mut Unit) {
+        let stunned = unit.is_stunned();
+        let position = self.model.world.bodies.positions[unit.id];
         for status in &mut unit.statuses {
-            match status {
-                Status::Charge { time, on_contact } => {
+            match &mut status.status {
+                Status::Charge {
+                    time,
+                    on_contact,
+                    touched,
+                } => {
+                    if stunned {
+                        *time = Time::ZERO;
+                        self.model.events.push(GameEvent::ChargeEnded {
+                            unit: unit.id,
+                            reason: ChargeEnd::Stunned,
+                        });
+                        continue;
+                    }
                     *time -= self.delta_time;
+                    if *time <= Time::ZERO {
+                        self.model.events.push(GameEvent::ChargeEnded {
+                            unit: unit.id,
+                            reason: ChargeEnd::Timeout,
+                        });
+                    }
                     let effects: Vec<_> = self
                         .model
+                        .world
                         .units
                         .iter()
                         .filter(|other| {
                             other.faction != unit.faction
-                                && other
-                                    .collider
-                                    .check(&unit.collider, unit.position - other.position)
+                                && !touched.contains(&other.id)
+                                && self.model.world.bodies.collide(other.id, unit.id)
                         })
                         .map(|other| QueuedEffect {
                             effect: on_contact.clone(),
                             context: EffectContext {
                                 caster: Some(unit.id),
                                 target: Some(other.id),
+                                position: None,
+                                direction: Some(
+                                    (self.model.world.bodies.positions[other.id] - position)
+                                        .normalize_or_zero(),
+                                ),
+                                damage_multiplier: R32::ONE,
+                                missing: MissingUnit::Skip,
                             },
                         })
                         .collect();
                     for effect in effects {
+                        touched.extend(effect.context.target);
                         self.effects.push_front(effect);
                     }
                 }
+                Status::Invulnerable { time }
+                | Status::Parry { time }
+                | Status::Modified { time, .. }
+                | Status::Stunned { time }
+                | Status::Spawning { time, .. }
+                | Status::ArmorBroken { time, .. }
+                | Status::Wet { time }
+                | Status::SlowFall { time, .. } => {
+                    *time -= self.delta_time;
+                }
+                Status::Burning {
+                    time,
+                    damage_per_second,
+                } => {
+                    *time -= self.delta_time;
+                    self.effects.push_back(QueuedEffect {
+                        effect: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Physical,
+                            value: Value::Flat(*damage_per_second * self.delta_time),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                        context: EffectContext {
+                            caster: None,
+                            target: Some(unit.id),
+                            position: None,
+                            direction: None,
+                            damage_multiplier: R32::ONE,
+                            missing: MissingUnit::Skip,
+                        },
+                    });
+                }
+                Status::Shield {
+                    time: Some(time), ..
+                } => {
+                    *time -= self.delta_time;
+                }
+                Status::MaxHp {
+                    time,
+                    amount,
+                    change,
+                } => {
+                    *time -= self.delta_time;
+                    if *time <= Time::ZERO {
+                        let health = &mut self.model.world.bodies.healths[unit.id];
+                        let max_hp = health.max_hp - *amount;
+                        health.set_max_hp(max_hp, *change);
+                    }
+                }
+                Status::Trail {
+                    interval,
+                    next,
+                    zone,
+                } => {
+                    *next -= self.delta_time;
+                    let speed = self.model.world.bodies.velocities[unit.id].len();
+                    if *next <= Time::ZERO && speed > Coord::new(TRAIL_MIN_SPEED) {
+                        *next = *interval;
+                        self.effects.push_back(QueuedEffect {
+                            effect: zone.clone(),
+                            context: EffectContext {
+                                caster: Some(unit.id),
+                                target: None,
+                                position: Some(position),
+                                direction: None,
+                                damage_multiplier: R32::ONE,
+                                missing: MissingUnit::Skip,
+                            },
+                        });
+                    }
+                }
+                Status::Shield { time: None, .. } | Status::Block => {}
             }
         }
 
-        unit.statuses.retain(|status| match status {
-            Status::Charge { time, .. } => *time > Time::ZERO,
+        let effects = &mut self.effects;
+        unit.statuses.retain_mut(|status| {
+            let active = match &status.status {
+                Status::Charge { time, .. }
+                | Status::Invulnerable { time }
+                | Status::Parry { time }
+                | Status::Burning { time, .. }
+                | Status::Modified { time, .. }
+                | Status::Stunned { time }
+                | Status::Spawning { time, .. }
+                | Status::ArmorBroken { time, .. }
+                | Status::Wet { time }
+                | Status::SlowFall { time, .. }
+                | Status::MaxHp { time, .. } => *time > Time::ZERO,
+                Status::Shield { hp, time } => {
+                    *hp > Hp::ZERO && time.map_or(true, |time| time > Time::ZERO)
+                }
+                Status::Block | Status::Trail { .. } => true,
+            };
+            if let Some(expiry) = status.on_expire.take().filter(|_| !active) {
+                effects.push_back(QueuedEffect {
+                    effect: expiry.effect,
+                    context: EffectContext {
+                        caster: expiry.caster,
+                        target: Some(unit.id),
+                        position: None,
+                        direction: None,
+                        damage_multiplier: R32::ONE,
+                        missing: MissingUnit::Skip,
+                    },
+                });
+            }
+            active
         });
     }
 }
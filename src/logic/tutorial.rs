@@ -0,0 +1,32 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_tutorial(&mut self) {
+        let model = &mut *self.model;
+        // The held controls are not events, so they are checked every tick
+        let held = [
+            (TutorialAction::Aim, model.aim.is_some()),
+            (TutorialAction::Block, model.blocking),
+            (TutorialAction::Glide, model.gliding),
+        ];
+        for (action, held) in held {
+            if held {
+                model.complete_tutorial(action);
+            }
+        }
+
+        if model.world.tutorial_prompt.is_some() {
+            return;
+        }
+        let triggered = match model.world.tutorial.front() {
+            Some(step) => match step.trigger {
+                TutorialTrigger::Start => true,
+                TutorialTrigger::Reach(x) => model.world.left_border >= Coord::new(x),
+            },
+            None => false,
+        };
+        if triggered {
+            model.world.tutorial_prompt = model.world.tutorial.pop_front();
+        }
+    }
+}
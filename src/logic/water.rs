@@ -0,0 +1,58 @@
+use super::*;
+
+/// Fraction of the gravity countered by the water when fully submerged.
+const BUOYANCY: f32 = 1.2;
+/// Fraction of the velocity lost per second when fully submerged.
+const UNIT_DRAG: f32 = 2.0;
+const PROJECTILE_DRAG: f32 = 1.5;
+
+impl Logic<'_> {
+    pub fn process_water(&mut self) {
+        let bodies = &mut self.model.world.bodies;
+        for unit in &mut self.model.world.units {
+            let submerged = match bodies.colliders.get(unit.id) {
+                Some(collider) => self
+                    .model
+                    .world
+                    .water
+                    .iter()
+                    .map(|water| {
+                        collider.submerged_fraction(bodies.positions[unit.id], &water.area)
+                    })
+                    .max()
+                    .unwrap_or(R32::ZERO),
+                None => R32::ZERO,
+            };
+            unit.in_water = submerged > R32::ZERO;
+            if !unit.in_water {
+                continue;
+            }
+            let velocity = &mut bodies.velocities[unit.id];
+            *velocity -= self.model.world.gravity * submerged * r32(BUOYANCY) * self.delta_time;
+            *velocity *= (R32::ONE - submerged * r32(UNIT_DRAG) * self.delta_time).max(R32::ZERO);
+            // Water puts out the fire
+            unit.statuses
+                .retain(|status| !matches!(status.status, Status::Burning { .. }));
+            unit.soak();
+        }
+
+        for projectile in &self.model.world.projectiles {
+            let wet = match bodies.colliders.get(projectile.id) {
+                Some(collider) => self.model.world.water.iter().any(|water| {
+                    collider.intersects_area(bodies.positions[projectile.id], &water.area)
+                }),
+                None => false,
+            };
+            if wet {
+                bodies.velocities[projectile.id] *=
+                    (R32::ONE - r32(PROJECTILE_DRAG) * self.delta_time).max(R32::ZERO);
+            }
+        }
+
+        let min_x = self.model.despawn_x();
+        self.model
+            .world
+            .water
+            .retain(|water| water.area.x_max > min_x);
+    }
+}
@@ -1,49 +1,155 @@
 use super::*;
 
+use crate::game::MechType;
+
+/// Every this many waves a boss joins the wave.
+const BOSS_WAVE_INTERVAL: usize = 5;
+/// Additional difficulty of every next wave in the endless mode.
+const ENDLESS_SCALING: f32 = 0.1;
+/// Increase in the chance of an enemy being elite with every wave.
+const ELITE_CHANCE_PER_WAVE: f64 = 0.02;
+const MAX_ELITE_CHANCE: f64 = 0.5;
+/// Extra share of the elite chance at midnight.
+const NIGHT_ELITE_BONUS: f64 = 0.5;
+const MAX_OBSTACLES_PER_WAVE: usize = 2;
+const HAZARD_CHANCE: f64 = 0.5;
+const WATER_CHANCE: f64 = 0.3;
+const INTERACTABLE_CHANCE: f64 = 0.4;
+
 impl Logic<'_> {
     pub fn process_waves(&mut self) {
-        // Move the left border
+        // Move the left border, the allies hold it back like the mechs
         let min_pos = self
             .model
+            .world
             .units
             .iter()
             .filter(|unit| unit.faction == Faction::Mech)
-            .map(|unit| unit.position.x)
+            .map(|unit| self.model.world.bodies.positions[unit.id].x)
             .min();
-        match min_pos {
-            None => {
-                // TODO: player lost
+        let has_mechs = self
+            .model
+            .world
+            .units
+            .iter()
+            .any(|unit| unit.faction == Faction::Mech && !unit.npc);
+        if !has_mechs {
+            let cheapest = self
+                .model
+                .config
+                .commander
+                .roster()
+                .iter()
+                .map(MechType::cost)
+                .min()
+                .unwrap_or(Currency::ZERO);
+            if self.model.world.player_energy.hp < cheapest && !self.model.respawn() {
+                self.model.world.game_over = true;
             }
-            Some(min_pos) => {
-                self.model.left_border = (min_pos - Coord::new(5.0)).max(self.model.left_border);
+        } else if let Some(min_pos) = min_pos {
+            self.model.world.left_border =
+                (min_pos - Coord::new(5.0)).max(self.model.world.left_border);
+        }
+        if self.model.config.mode.is_sandbox() {
+            // Nothing comes unless spawned by the player
+            return;
+        }
+
+        // Offer perks once the wave is cleared
+        if self.model.world.wave_in_progress
+            && self.model.world.spawn_queue.is_empty()
+            && self.model.world.pending_spawns.is_empty()
+            && !self
+                .model
+                .world
+                .units
+                .iter()
+                .any(|unit| unit.faction == Faction::Alien)
+        {
+            self.model.world.wave_in_progress = false;
+            self.model.world.waves_cleared += 1;
+            let available = &self.model.config.perks;
+            let perks = Perk::all()
+                .into_iter()
+                .filter(|perk| available.contains(&perk.name))
+                .choose_multiple(&mut self.model.world.rng, PERK_CHOICES);
+            self.model.world.perk_choice = Some(perks);
+            if self.model.world.wave_number % CHECKPOINT_INTERVAL == 0 {
+                self.model.save_checkpoint();
             }
         }
 
+        if self.model.world.tutorial_prompt.is_some() {
+            // The next waves wait for the player to learn the controls
+            return;
+        }
+
         // Check for waves
-        if self.model.waves.is_empty() {
+        if self.model.world.waves.is_empty() {
             self.generate_wave();
         }
-        while let Some(wave) = self.model.waves.front() {
-            if wave.position <= self.model.left_border {
-                let wave = self.model.waves.pop_front().unwrap();
-                for unit in wave.units {
-                    let y = self.model.ground_level
-                        + global_rng().gen_range(Coord::ZERO..=Coord::new(0.0));
-                    let x = self.model.left_border
-                        + global_rng().gen_range(Coord::new(50.0)..=Coord::new(70.0));
-                    let position = vec2(x, y);
-                    self.model.spawn_unit(unit, position, Faction::Alien);
-                }
+        while let Some(wave) = self.model.world.waves.front() {
+            if wave.position <= self.model.world.left_border {
+                let wave = self.model.world.waves.pop_front().unwrap();
+                self.model.world.wave_in_progress = true;
+                self.model.world.spawn_queue.extend(wave.units);
             } else {
                 break;
             }
         }
+
+        // Spawn the units whose delay has passed, slower to give a low mech a breather
+        let delta_time = if self.model.world.low_health {
+            self.delta_time * Time::new(LOW_HEALTH_SPAWN_PACE)
+        } else {
+            self.delta_time
+        };
+        for unit in &mut self.model.world.spawn_queue {
+            unit.delay -= delta_time;
+        }
+        let (ready, waiting) = std::mem::take(&mut self.model.world.spawn_queue)
+            .into_iter()
+            .partition(|unit| unit.delay <= Time::ZERO);
+        self.model.world.spawn_queue = waiting;
+        for unit in ready {
+            self.spawn_wave_unit(unit);
+        }
+    }
+
+    fn spawn_wave_unit(&mut self, unit: WaveUnit) {
+        let y = self.model.world.ground_level
+            + self
+                .model
+                .world
+                .rng
+                .gen_range(Coord::ZERO..=Coord::new(0.0));
+        let x = match unit.spawn_x {
+            Some(x) => x,
+            None => {
+                self.model.world.left_border
+                    + self
+                        .model
+                        .world
+                        .rng
+                        .gen_range(Coord::new(50.0)..=Coord::new(70.0))
+            }
+        };
+        let position = self
+            .model
+            .spawn_position(vec2(x, y), &unit.template.collider);
+        if let Some(boss) = &unit.template.boss {
+            let script = self.model.assets().cutscenes.boss.clone();
+            self.model.play_cutscene(&script, &[("boss", &boss.name)]);
+        }
+        self.model
+            .spawn_telegraphed(unit.template, position, Faction::Alien);
     }
 
     fn get_difficulty(&self) -> R32 {
-        let distance = (self.model.left_border / r32(5.0)).max(R32::new(10.0));
+        let distance = (self.model.world.left_border / r32(5.0)).max(R32::new(10.0));
         let team_size = self
             .model
+            .world
             .units
             .iter()
             .filter(|unit| unit.faction == Faction::Mech)
@@ -54,32 +160,129 @@ impl Logic<'_> {
     }
 
     fn generate_wave(&mut self) {
-        let mut difficulty = self.get_difficulty();
+        let mut difficulty =
+            self.get_difficulty() * self.model.config.difficulty.scaling().spawn_count;
+        if self.model.config.mode.is_endless() {
+            difficulty *= r32(1.0 + ENDLESS_SCALING * self.model.world.wave_number as f32);
+        }
         let mut units = Vec::new();
 
+        // The tougher enemies come out at night
+        let night = self.model.night().as_f32();
         let templates = vec![
-            (r32(5.0), &self.model.templates.blighter),
-            (r32(3.0), &self.model.templates.ravager),
-            (r32(10.0), &self.model.templates.stinger),
+            (
+                r32(5.0),
+                1.0 - 0.5 * night,
+                &self.model.world.templates.blighter,
+            ),
+            (r32(3.0), 1.0, &self.model.world.templates.ravager),
+            (r32(10.0), 0.5 + night, &self.model.world.templates.stinger),
         ];
-        let mut rng = global_rng();
-        while let Some((diff, template)) = templates
-            .iter()
-            .filter(|(diff, _)| *diff <= difficulty)
-            .choose(&mut rng)
-        {
+        loop {
+            let affordable: Vec<_> = templates
+                .iter()
+                .filter(|(diff, _, _)| *diff <= difficulty)
+                .collect();
+            let (diff, _, template) = match affordable
+                .choose_weighted(&mut self.model.world.rng, |(_, weight, _)| *weight)
+            {
+                Ok(choice) => choice,
+                Err(_) => break,
+            };
             difficulty -= *diff;
             units.push((*template).clone());
         }
 
+        let mut elite_chance = self.model.world.wave_number as f64 * ELITE_CHANCE_PER_WAVE;
+        if self.model.config.mode.is_endless() {
+            elite_chance *= 2.0;
+        }
+        elite_chance *= 1.0 + f64::from(night) * NIGHT_ELITE_BONUS;
+        let elite_chance = elite_chance.min(MAX_ELITE_CHANCE);
+        for unit in &mut units {
+            if self.model.world.rng.gen_bool(elite_chance) {
+                let modifier = *EliteModifier::ALL
+                    .choose(&mut self.model.world.rng)
+                    .unwrap();
+                unit.apply_elite(modifier, self.model.assets());
+            }
+        }
+
+        self.model.world.wave_number += 1;
+        if self.model.world.wave_number % BOSS_WAVE_INTERVAL == 0 {
+            units.push(self.model.world.templates.ravager_alpha.clone());
+        }
+
         let position = self
             .model
+            .world
             .waves
             .back()
             .map(|wave| wave.position)
-            .unwrap_or(self.model.left_border)
+            .unwrap_or(self.model.world.left_border)
             + Coord::new(30.0);
-        let wave = Wave { position, units };
-        self.model.waves.push_back(wave);
+        // Place some terrain in front of the enemies
+        let obstacles = self.model.world.rng.gen_range(0..=MAX_OBSTACLES_PER_WAVE);
+        for _ in 0..obstacles {
+            let assets = self.model.assets().clone();
+            let texture = [&assets.background.pillar1, &assets.background.pillar2]
+                .choose(&mut self.model.world.rng)
+                .unwrap();
+            let x = position + Coord::new(self.model.world.rng.gen_range(35.0..=45.0));
+            let obstacle = Obstacle::new(
+                self.model.world.id_gen.gen(),
+                Sprite::new(texture, 0.03),
+                x,
+                self.model.world.ground_level,
+                Hp::new(OBSTACLE_HP),
+            );
+            self.model.world.obstacles.insert(obstacle);
+        }
+
+        if self.model.world.rng.gen_bool(HAZARD_CHANCE) {
+            let x = position + Coord::new(self.model.world.rng.gen_range(20.0..=30.0));
+            let hazard = Hazard::random(
+                self.model.world.id_gen.gen(),
+                x,
+                self.model.world.ground_level,
+                &mut self.model.world.rng,
+            );
+            self.model.world.hazards.insert(hazard);
+        }
+
+        if self.model.world.rng.gen_bool(WATER_CHANCE) {
+            let x = position + Coord::new(self.model.world.rng.gen_range(5.0..=15.0));
+            let width = Coord::new(self.model.world.rng.gen_range(4.0..=8.0));
+            let water = Water {
+                id: self.model.world.id_gen.gen(),
+                area: AABB {
+                    x_min: x,
+                    x_max: x + width,
+                    y_min: self.model.world.ground_level,
+                    y_max: self.model.world.ground_level + Coord::new(WATER_DEPTH),
+                },
+            };
+            self.model.world.water.insert(water);
+        }
+
+        if self.model.world.rng.gen_bool(INTERACTABLE_CHANCE) {
+            let x = position + Coord::new(self.model.world.rng.gen_range(0.0..=10.0));
+            let object = Interactable::random(
+                self.model.world.id_gen.gen(),
+                x,
+                self.model.world.ground_level,
+                &self.model.assets().clone(),
+                &mut self.model.world.animations,
+                &self.model.world.templates,
+                &mut self.model.world.rng,
+            );
+            self.model.world.interactables.insert(object);
+        }
+
+        let wave = Wave {
+            position,
+            units: units.into_iter().map(WaveUnit::new).collect(),
+        };
+        self.model.world.waves.push_back(wave);
     }
 }
@@ -0,0 +1,28 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_weather(&mut self) {
+        let weather = self.model.world.weather;
+        if weather.extinguishes() {
+            for unit in &mut self.model.world.units {
+                unit.statuses
+                    .retain(|status| !matches!(status.status, Status::Burning { .. }));
+                unit.soak();
+            }
+            self.model
+                .world
+                .zones
+                .retain(|zone| zone.kind != ZoneKind::Burning);
+        }
+
+        let wind = weather.wind();
+        if wind != Coord::ZERO {
+            let bodies = &mut self.model.world.bodies;
+            for projectile in &self.model.world.projectiles {
+                if projectile.is_light(bodies) {
+                    bodies.velocities[projectile.id].x += wind * self.delta_time;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+impl Logic<'_> {
+    pub fn process_zones(&mut self) {
+        for zone in &mut self.model.world.zones {
+            zone.time_left -= self.delta_time;
+            zone.next_tick -= self.delta_time;
+            if zone.next_tick > Time::ZERO || zone.time_left <= Time::ZERO {
+                continue;
+            }
+            zone.next_tick += zone.tick_time;
+            // The area is centered on the zone through the context position
+            self.effects.push_back(QueuedEffect {
+                effect: Effect::new(EffectNode::Area(zone.area.clone())),
+                context: EffectContext {
+                    caster: zone.caster,
+                    target: None,
+                    position: Some(zone.position),
+                    direction: None,
+                    damage_multiplier: R32::ONE,
+                    missing: MissingUnit::Skip,
+                },
+            });
+        }
+        self.model
+            .world
+            .zones
+            .retain(|zone| zone.time_left > Time::ZERO);
+    }
+}
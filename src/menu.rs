@@ -0,0 +1,494 @@
+use super::*;
+
+/// The first screen of the game, where the run is configured.
+pub struct MainMenu {
+    geng: Geng,
+    library: Library,
+    /// Missing until the library has loaded every stage.
+    assets: Option<Rc<Assets>>,
+    menu: ui::Menu,
+    mode: GameMode,
+    /// Index of the level of the mods, the level of the editor comes first.
+    level: usize,
+    difficulty: Difficulty,
+    commander: Commander,
+    profile: Profile,
+    colors: ColorSettings,
+    motion: JuicePolicy,
+    controls: Controls,
+    display: DisplaySettings,
+    language: Language,
+    locale: Locale,
+    transition: Option<geng::Transition>,
+}
+
+/// An option of the menu, the tabs list them in the order they are shown.
+#[derive(Debug, Clone, Copy)]
+enum MenuOption {
+    Mode,
+    Level,
+    Commander,
+    Difficulty,
+    Start,
+    Host,
+    Join,
+    Editor,
+    Codex,
+    Colors,
+    ReducedMotion,
+    AimAssist,
+    Language,
+    Fullscreen,
+    RenderScale,
+    FrameLimit,
+    UiScale,
+    SafeArea,
+}
+
+const OPTION_SPACING: f32 = 38.0;
+const TABS: [&str; 2] = ["menu.tab_run", "menu.tab_settings"];
+const RUN_OPTIONS: [MenuOption; 9] = [
+    MenuOption::Mode,
+    MenuOption::Level,
+    MenuOption::Commander,
+    MenuOption::Difficulty,
+    MenuOption::Start,
+    MenuOption::Host,
+    MenuOption::Join,
+    MenuOption::Editor,
+    MenuOption::Codex,
+];
+const SETTINGS_OPTIONS: [MenuOption; 9] = [
+    MenuOption::Colors,
+    MenuOption::ReducedMotion,
+    MenuOption::AimAssist,
+    MenuOption::Language,
+    MenuOption::Fullscreen,
+    MenuOption::RenderScale,
+    MenuOption::FrameLimit,
+    MenuOption::UiScale,
+    MenuOption::SafeArea,
+];
+
+/// Returns the item `delta` steps away from `current`, wrapping around.
+pub fn cycle<T: Copy + PartialEq>(all: &[T], current: T, delta: isize) -> T {
+    let index = all.iter().position(|&item| item == current).unwrap_or(0) as isize;
+    all[(index + delta).rem_euclid(all.len() as isize) as usize]
+}
+
+/// A slider over the values, for the settings picked from a list.
+fn slider<T: Copy + PartialEq>(label: &str, value: String, all: &[T], current: T) -> ui::Widget {
+    let index = all.iter().position(|&item| item == current).unwrap_or(0);
+    ui::Widget::slider(label, value, index, all.len())
+}
+
+impl MainMenu {
+    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let mut menu = Self::with_library(geng, Library::loaded(geng, assets));
+        menu.assets = Some(assets.clone());
+        menu
+    }
+
+    /// The menu shown while the rest of the assets are loading.
+    pub fn with_library(geng: &Geng, library: Library) -> Self {
+        Self {
+            geng: geng.clone(),
+            locale: Locale::new(&library.menu(), Language::load()),
+            library,
+            assets: None,
+            menu: default(),
+            mode: default(),
+            level: 0,
+            difficulty: default(),
+            commander: default(),
+            profile: Profile::load(),
+            colors: ColorSettings::load(),
+            motion: JuicePolicy::load(),
+            controls: Controls::load(),
+            display: DisplaySettings::load(),
+            language: Language::load(),
+            transition: None,
+        }
+    }
+
+    /// The levels of the mods, none until the mods are applied with the rest of the assets.
+    fn mod_levels(&self) -> &[(String, model::Level)] {
+        self.assets
+            .as_ref()
+            .map_or(&[], |assets| assets.mods.levels.as_slice())
+    }
+
+    fn options(&self) -> &'static [MenuOption] {
+        match self.menu.tab {
+            0 => &RUN_OPTIONS,
+            _ => &SETTINGS_OPTIONS,
+        }
+    }
+
+    fn widgets(&self) -> Vec<ui::Widget> {
+        self.options()
+            .iter()
+            .map(|&option| self.widget(option))
+            .collect()
+    }
+
+    fn widget(&self, option: MenuOption) -> ui::Widget {
+        let locale = &self.locale;
+        let label = |key: &str| locale.get(key).to_owned();
+        let percent = |value: f32| format!("{}%", (value * 100.0).round());
+        match option {
+            MenuOption::Mode => ui::Widget::choice(label("menu.mode"), label(self.mode.key())),
+            MenuOption::Level => {
+                let name = match self.level.checked_sub(1) {
+                    Some(index) => self.mod_levels()[index].0.clone(),
+                    None => label("menu.level_editor"),
+                };
+                ui::Widget::choice(label("menu.level"), name)
+            }
+            MenuOption::Commander => {
+                ui::Widget::choice(label("menu.commander"), label(self.commander.key()))
+                    .with_hint(label(self.commander.description_key()))
+            }
+            MenuOption::Difficulty => {
+                let difficulty = label(self.difficulty.key());
+                let value = if self
+                    .profile
+                    .is_unlocked(&Unlock::Difficulty(self.difficulty))
+                {
+                    difficulty
+                } else {
+                    locale.format("menu.difficulty_locked", &[("difficulty", &difficulty)])
+                };
+                ui::Widget::choice(label("menu.difficulty"), value)
+            }
+            MenuOption::Start => ui::Widget::button(label("menu.start")),
+            MenuOption::Host => ui::Widget::button(label("menu.host")),
+            MenuOption::Join => ui::Widget::button(label("menu.join")),
+            MenuOption::Editor => ui::Widget::button(label("menu.editor")),
+            MenuOption::Codex => ui::Widget::button(label("menu.codex")),
+            MenuOption::Colors => {
+                ui::Widget::choice(label("menu.colors"), label(self.colors.mode.key()))
+            }
+            MenuOption::ReducedMotion => {
+                ui::Widget::toggle(label("menu.reduced_motion"), self.motion.reduced_motion)
+            }
+            MenuOption::AimAssist => slider(
+                &label("menu.aim_assist"),
+                percent(self.controls.aim_assist),
+                &model::AIM_ASSIST_STRENGTHS,
+                self.controls.aim_assist,
+            )
+            .with_hint(label("menu.aim_assist_description")),
+            MenuOption::Language => {
+                ui::Widget::choice(label("menu.language"), self.language.name())
+            }
+            MenuOption::Fullscreen => {
+                ui::Widget::toggle(label("menu.fullscreen"), self.display.fullscreen)
+            }
+            MenuOption::RenderScale => slider(
+                &label("menu.render_scale"),
+                percent(self.display.render_scale),
+                &RENDER_SCALES,
+                self.display.render_scale,
+            ),
+            MenuOption::FrameLimit => ui::Widget::choice(
+                label("menu.frame_limit"),
+                label(self.display.frame_limit.key()),
+            )
+            .with_hint(label("menu.frame_limit_description")),
+            MenuOption::UiScale => slider(
+                &label("menu.ui_scale"),
+                percent(self.display.ui_scale),
+                &UI_SCALES,
+                self.display.ui_scale,
+            ),
+            MenuOption::SafeArea => slider(
+                &label("menu.safe_area"),
+                percent(self.display.safe_area),
+                &SAFE_AREAS,
+                self.display.safe_area,
+            ),
+        }
+    }
+
+    fn handle_ui_event(&mut self, event: ui::UiEvent) {
+        match event {
+            ui::UiEvent::Pressed(index) => match self.options()[index] {
+                MenuOption::Editor => self.open_editor(),
+                MenuOption::Codex => self.open_codex(),
+                MenuOption::Host => self.host(),
+                MenuOption::Join => {
+                    self.open(|geng, assets| Box::new(lobby::Lobby::join(geng, assets)))
+                }
+                // Confirming any option of the run starts it
+                MenuOption::Mode
+                | MenuOption::Level
+                | MenuOption::Commander
+                | MenuOption::Difficulty
+                | MenuOption::Start => self.start(),
+                option => self.change_option(option, 1),
+            },
+            ui::UiEvent::Changed { widget, delta } => {
+                self.change_option(self.options()[widget], delta)
+            }
+            ui::UiEvent::TabChanged => {}
+        }
+    }
+
+    fn change_option(&mut self, option: MenuOption, delta: isize) {
+        match option {
+            MenuOption::Mode => self.mode = cycle(&GameMode::ALL, self.mode, delta),
+            MenuOption::Level => {
+                let levels: Vec<usize> = (0..=self.mod_levels().len()).collect();
+                self.level = cycle(&levels, self.level, delta);
+            }
+            MenuOption::Commander => self.commander = cycle(&Commander::ALL, self.commander, delta),
+            MenuOption::Difficulty => {
+                self.difficulty = cycle(&Difficulty::ALL, self.difficulty, delta)
+            }
+            MenuOption::Start
+            | MenuOption::Host
+            | MenuOption::Join
+            | MenuOption::Editor
+            | MenuOption::Codex => {}
+            MenuOption::Colors => {
+                self.colors.mode = cycle(&ColorMode::ALL, self.colors.mode, delta);
+                self.colors.save();
+            }
+            MenuOption::ReducedMotion => {
+                self.motion.reduced_motion = !self.motion.reduced_motion;
+                self.motion.save();
+            }
+            MenuOption::AimAssist => {
+                self.controls.aim_assist = cycle(
+                    &model::AIM_ASSIST_STRENGTHS,
+                    self.controls.aim_assist,
+                    delta,
+                );
+                self.controls.save();
+            }
+            MenuOption::Language => {
+                self.language = cycle(&Language::ALL, self.language, delta);
+                self.language.save();
+                self.locale = Locale::new(&self.library.menu(), self.language);
+            }
+            MenuOption::Fullscreen => {
+                self.display.fullscreen = !self.display.fullscreen;
+                self.display.apply_fullscreen(&self.geng);
+                self.display.save();
+            }
+            MenuOption::RenderScale => {
+                self.display.render_scale = cycle(&RENDER_SCALES, self.display.render_scale, delta);
+                self.display.save();
+            }
+            MenuOption::FrameLimit => {
+                self.display.frame_limit = cycle(&FrameLimit::ALL, self.display.frame_limit, delta);
+                self.display.save();
+            }
+            MenuOption::UiScale => {
+                self.display.ui_scale = cycle(&UI_SCALES, self.display.ui_scale, delta);
+                self.display.save();
+            }
+            MenuOption::SafeArea => {
+                self.display.safe_area = cycle(&SAFE_AREAS, self.display.safe_area, delta);
+                self.display.save();
+            }
+        }
+    }
+
+    fn start(&mut self) {
+        if let Some((config, level)) = self.run() {
+            self.open(move |geng, assets| Box::new(game::Game::new(geng, assets, config, &level)));
+        }
+    }
+
+    /// Waits for the other player in the lobby, then starts the configured run with them.
+    fn host(&mut self) {
+        if let Some((config, level)) = self.run() {
+            self.open(move |geng, assets| {
+                Box::new(lobby::Lobby::host(geng, assets, config, level))
+            });
+        }
+    }
+
+    /// The configured run, `None` if its difficulty is locked.
+    fn run(&self) -> Option<(RunConfig, model::Level)> {
+        if !self
+            .profile
+            .is_unlocked(&Unlock::Difficulty(self.difficulty))
+        {
+            return None;
+        }
+        let config = RunConfig {
+            seed: match self.mode {
+                GameMode::Daily => daily_seed(),
+                _ => global_rng().gen(),
+            },
+            difficulty: self.difficulty,
+            mode: self.mode,
+            commander: self.commander,
+            perks: self.profile.available_perks(),
+            language: self.language,
+            aim_assist: self.controls.aim_assist,
+        };
+        let level = if self.mode.is_sandbox() {
+            model::Level::sandbox()
+        } else {
+            match self.level.checked_sub(1) {
+                Some(index) => self.mod_levels()[index].1.clone(),
+                None => model::Level::load(),
+            }
+        };
+        Some((config, level))
+    }
+
+    fn open_editor(&mut self) {
+        self.open(|geng, assets| Box::new(editor::Editor::new(geng, assets)));
+    }
+
+    fn open_codex(&mut self) {
+        self.open(|geng, assets| Box::new(codex::Codex::new(geng, assets)));
+    }
+
+    /// Switches to the screen, after the loading screen if the assets are not all loaded yet.
+    fn open(&mut self, screen: impl FnOnce(&Geng, &Rc<Assets>) -> Box<dyn geng::State> + 'static) {
+        let state = match &self.assets {
+            Some(assets) => screen(&self.geng, assets),
+            None => {
+                let geng = self.geng.clone();
+                Box::new(geng::LoadingScreen::new(
+                    &self.geng,
+                    loading::LoadingScreen::new(&self.geng),
+                    self.library.demand(),
+                    move |assets| match assets {
+                        Ok(assets) => screen(&geng, &assets),
+                        Err(problems) => Box::new(validation::InvalidAssets::new(&geng, problems)),
+                    },
+                ))
+            }
+        };
+        self.transition = Some(geng::Transition::Switch(state));
+    }
+}
+
+impl geng::State for MainMenu {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(&self.geng);
+        let locale = &self.locale;
+        let center = screen.center();
+
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("menu.title"),
+            center + vec2(0.0, 230.0),
+            geng::TextAlign::CENTER,
+            80.0,
+            Rgba::WHITE,
+        );
+        font.draw(
+            framebuffer,
+            camera,
+            &locale.format(
+                "menu.profile",
+                &[
+                    ("level", &self.profile.level()),
+                    ("runs", &self.profile.stats.runs),
+                    ("kills", &self.profile.stats.kills),
+                    ("best_wave", &self.profile.stats.best_wave),
+                ],
+            ),
+            center + vec2(0.0, 170.0),
+            geng::TextAlign::CENTER,
+            24.0,
+            Rgba::GRAY,
+        );
+        let tabs = TABS.map(|key| locale.get(key).to_owned());
+        let widgets = self.widgets();
+        let style = ui::MenuStyle {
+            top: center + vec2(0.0, 120.0),
+            width: 800.0,
+            spacing: OPTION_SPACING,
+            text_size: 32.0,
+        };
+        self.menu
+            .draw(&self.geng, font, &tabs, &widgets, style, framebuffer);
+        let problems = self
+            .assets
+            .as_ref()
+            .map_or(&[][..], |assets| assets.mods.problems.as_slice());
+        if let Some(problem) = problems.first() {
+            font.draw(
+                framebuffer,
+                camera,
+                &locale.format("menu.mod_problems", &[("count", &problems.len())]),
+                vec2(center.x, screen.y_min + 110.0),
+                geng::TextAlign::CENTER,
+                20.0,
+                Rgba::RED,
+            );
+            font.draw(
+                framebuffer,
+                camera,
+                problem,
+                vec2(center.x, screen.y_min + 80.0),
+                geng::TextAlign::CENTER,
+                20.0,
+                Rgba::RED,
+            );
+        }
+        font.draw(
+            framebuffer,
+            camera,
+            locale.get("menu.help"),
+            vec2(center.x, screen.y_min + 40.0),
+            geng::TextAlign::CENTER,
+            24.0,
+            Rgba::GRAY,
+        );
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        let widgets = self.widgets();
+        let ui_event = match event {
+            geng::Event::KeyDown { key: geng::Key::E } => {
+                self.open_editor();
+                None
+            }
+            geng::Event::KeyDown { key } => ui::UiInput::from_key(key)
+                .and_then(|input| self.menu.handle_input(input, TABS.len(), &widgets)),
+            geng::Event::MouseDown { position, .. } => {
+                self.menu.handle_click(position.map(|x| x as f32), &widgets)
+            }
+            geng::Event::TouchStart { ref touches } => touches.first().and_then(|touch| {
+                self.menu
+                    .handle_click(touch.position.map(|x| x as f32), &widgets)
+            }),
+            _ => None,
+        };
+        if let Some(ui_event) = ui_event {
+            self.handle_ui_event(ui_event);
+        }
+    }
+
+    fn update(&mut self, _delta_time: f64) {
+        if self.assets.is_some() || self.transition.is_some() {
+            return;
+        }
+        match self.library.poll() {
+            Some(Ok(assets)) => self.assets = Some(assets),
+            Some(Err(problems)) => {
+                let invalid = validation::InvalidAssets::new(&self.geng, problems);
+                self.transition = Some(geng::Transition::Switch(Box::new(invalid)));
+            }
+            None => {}
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
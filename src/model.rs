@@ -1,23 +1,140 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use super::*;
 
+use game::PlayerEvent;
+
+mod aim_assist;
+mod ammo;
 mod animation;
+mod armor;
+mod attachment;
+mod attack_tokens;
+mod block;
+mod body;
+mod bounds;
+mod checkpoint;
 mod collider;
+mod combat_log;
+mod combo;
+mod commander;
+mod config;
+mod cutscene;
+mod damage_indicator;
+mod day_cycle;
+mod difficulty;
+mod door;
 mod effect;
+mod effect_tree;
+mod elite;
+mod event;
+mod glide;
+mod grapple;
+mod hazard;
 mod health;
 mod id;
+mod impact;
+mod input;
+mod interactable;
+mod level;
+mod lockstep;
+mod low_health;
+mod melee;
+mod objective;
+mod obstacle;
+mod perception;
+mod perk;
+mod pickup;
+mod ping;
+mod point_defense;
+mod pool;
+mod preview;
+mod reaction;
+mod remains;
+mod rewind;
+mod rng;
+mod sandbox;
+mod score;
+mod script;
+mod serialization;
+mod size_class;
+mod spark;
+mod spatial_hash;
+mod spawn;
 mod sprite;
+mod stats;
+mod tutorial;
 pub mod unit_template;
+mod water;
 mod wave;
+mod weapon;
+mod weather;
+mod zone;
 
+pub use aim_assist::*;
+pub use ammo::*;
 pub use animation::*;
+pub use armor::*;
+pub use attachment::*;
+pub use attack_tokens::*;
+pub use block::*;
+pub use body::*;
+pub use bounds::*;
+pub use checkpoint::*;
 pub use collider::*;
+pub use combat_log::*;
+pub use combo::*;
+pub use commander::*;
+pub use config::*;
+pub use cutscene::*;
+pub use damage_indicator::*;
+pub use day_cycle::*;
+pub use difficulty::*;
+pub use door::*;
 pub use effect::*;
+pub use elite::*;
+pub use event::*;
+pub use glide::*;
+pub use grapple::*;
+pub use hazard::*;
 pub use health::*;
 pub use id::*;
+pub use impact::*;
+pub use input::*;
+pub use interactable::*;
+pub use level::*;
+pub use lockstep::*;
+pub use low_health::*;
+pub use melee::*;
+pub use objective::*;
+pub use obstacle::*;
+pub use perception::*;
+pub use perk::*;
+pub use pickup::*;
+pub use ping::*;
+pub use point_defense::*;
+pub use pool::*;
+pub use preview::*;
+pub use reaction::*;
+pub use remains::*;
+pub use rewind::*;
+pub use rng::*;
+pub use sandbox::*;
+pub use score::*;
+pub use script::*;
+pub use serialization::*;
+pub use size_class::*;
+pub use spark::*;
+pub use spatial_hash::*;
+pub use spawn::*;
 pub use sprite::*;
+pub use stats::*;
+pub use tutorial::*;
+pub use water::*;
 pub use wave::*;
+pub use weapon::*;
+pub use weather::*;
+pub use zone::*;
 
 pub type Time = R32;
 pub type Hp = R32;
@@ -29,60 +146,385 @@ pub type Currency = R32;
 const GRAVITY: Vec2<f32> = vec2(0.0, -9.8);
 
 pub struct Model {
-    pub assets: Rc<Assets>,
-    pub id_gen: IdGen,
-    pub player_energy: Health,
-    pub left_border: Coord,
-    pub ground_level: Coord,
-    pub gravity: Velocity,
-    pub waves: VecDeque<Wave>,
-    pub units: Collection<Unit>,
-    pub templates: UnitTemplates,
-    pub projectiles: Collection<Projectile>,
-    pub particles: Collection<Particle>,
+    /// Missing in the headless simulation of the benchmarks, see [Model::headless].
+    pub assets: Option<Rc<Assets>>,
+    pub scripts: Rc<Scripts>,
+    /// Failures of the effect scripts not shown in the console yet.
+    pub script_errors: Vec<String>,
+    pub config: RunConfig,
+    pub accumulated_time: Time,
+    /// Player inputs to be applied at the start of the next tick.
+    pub queued_events: Vec<PlayerEvent>,
+    /// Inputs of the last ticks, played again after a rewind.
+    pub input_history: VecDeque<TickInput>,
+    /// Snapshots of the last ticks to rewind to, the oldest first.
+    pub snapshots: VecDeque<Snapshot>,
+    pub rewind: Option<Rewind>,
+    /// Set in a co-op run, see [Lockstep].
+    pub lockstep: Option<Lockstep>,
+    /// Events raised during the last tick.
+    pub events: Vec<GameEvent>,
+    /// World position the player orders the mechs to fire at.
+    pub aim: Option<Position>,
+    /// Whether the player orders the mechs to block.
+    pub blocking: bool,
+    /// Whether the player orders the airborne mechs to glide.
+    pub gliding: bool,
+    /// Number of effects processed in the last tick.
+    pub effects_processed: usize,
+    pub profiler: Profiler,
+    pub projectile_pool: Pool<Projectile>,
+    pub particle_pool: Pool<Particle>,
+    pub sparks: Vec<Spark>,
+    pub damage_numbers: Vec<DamageNumber>,
+    pub damage_indicators: Vec<DamageIndicator>,
+    /// Whether the player does not see the run, see [Model::preview].
+    pub preview: bool,
+    /// The looped lava ambience, stopped when the model is dropped.
+    lava: Option<geng::SoundEffect>,
+    /// The simulation state, snapshotted as a whole for the rewinds and the crash reports.
+    pub world: World,
 }
 
 impl Model {
-    pub fn new(assets: &Rc<Assets>) -> Self {
+    pub fn new(assets: &Rc<Assets>, config: RunConfig) -> Self {
+        let mut model = Self::with_assets(assets, config);
+        model.lava = model.start_sound(SoundId::Lava);
+        model.play_cutscene(&assets.cutscenes.intro, &[]);
+        model
+    }
+
+    /// A run the player does not see, like the wave preview of the editor.
+    /// It plays no sounds and leaves the crash recording of the actual run alone.
+    pub fn preview(assets: &Rc<Assets>, config: RunConfig) -> Self {
+        let mut model = Self::with_assets(assets, config);
+        model.preview = true;
+        model
+    }
+
+    /// A preview without the assets, for the benchmarks to run without a window.
+    /// It drops no pickups, the cutscenes and the generated waves need the assets
+    /// and must not come up.
+    pub fn headless(config: RunConfig, templates: UnitTemplates, animations: Animations) -> Self {
+        let mut model = Self::with_templates(None, Scripts::new(""), config, templates, animations);
+        model.preview = true;
+        model
+    }
+
+    fn with_assets(assets: &Rc<Assets>, config: RunConfig) -> Self {
+        let mut animations = Animations::new();
+        let templates = UnitTemplates::new(assets, &mut animations);
+        Self::with_templates(
+            Some(assets.clone()),
+            Scripts::new(&assets.scripts.effects),
+            config,
+            templates,
+            animations,
+        )
+    }
+
+    fn with_templates(
+        assets: Option<Rc<Assets>>,
+        scripts: Scripts,
+        config: RunConfig,
+        templates: UnitTemplates,
+        animations: Animations,
+    ) -> Self {
+        let rng = SimRng::seed_from_u64(config.seed);
         let mut model = Self {
-            assets: assets.clone(),
-            id_gen: IdGen::new(),
-            player_energy: Health {
-                hp: Hp::new(100.0),
-                max_hp: Hp::new(100.0),
+            assets,
+            scripts: Rc::new(scripts),
+            script_errors: vec![],
+            config,
+            accumulated_time: Time::ZERO,
+            queued_events: vec![],
+            input_history: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            rewind: None,
+            lockstep: None,
+            events: vec![],
+            aim: None,
+            blocking: false,
+            gliding: false,
+            effects_processed: 0,
+            profiler: default(),
+            projectile_pool: default(),
+            particle_pool: default(),
+            sparks: vec![],
+            damage_numbers: vec![],
+            damage_indicators: vec![],
+            preview: false,
+            lava: None,
+            world: World {
+                rng,
+                current_tick: 0,
+                paused_ticks: 0,
+                combat_log: default(),
+                score: default(),
+                combo: default(),
+                game_over: false,
+                id_gen: IdGen::new(),
+                player_energy: Health {
+                    hp: Hp::new(100.0),
+                    max_hp: Hp::new(100.0),
+                },
+                left_border: Coord::new(-20.0),
+                ground_level: Coord::new(0.0),
+                bounds: default(),
+                gravity: GRAVITY.map(Coord::new),
+                waves: Wave::start_waves(),
+                spawn_queue: vec![],
+                pending_spawns: vec![],
+                attack_tokens: default(),
+                wave_number: 0,
+                wave_in_progress: false,
+                waves_cleared: 0,
+                triggers: vec![],
+                objectives: vec![],
+                cutscene: None,
+                perk_choice: None,
+                tutorial: default(),
+                tutorial_prompt: None,
+                perks: vec![],
+                checkpoint: None,
+                respawns: 0,
+                units: default(),
+                bodies: default(),
+                remains: default(),
+                animations,
+                templates,
+                projectiles: default(),
+                particles: default(),
+                delayed_effects: vec![],
+                pickups: default(),
+                pings: default(),
+                melee_swings: vec![],
+                grapples: vec![],
+                obstacles: default(),
+                doors: default(),
+                hazards: default(),
+                water: default(),
+                interactables: default(),
+                decorations: vec![],
+                low_health: false,
+                dps_meters: default(),
+                zones: vec![],
+                weather: default(),
+                day_cycle: None,
             },
-            left_border: Coord::new(-20.0),
-            ground_level: Coord::new(0.0),
-            gravity: GRAVITY.map(Coord::new),
-            waves: Wave::start_waves(),
-            units: default(),
-            templates: UnitTemplates::new(assets),
-            projectiles: default(),
-            particles: default(),
         };
-        model.play_sound(&assets.sound_design.lava);
+        for (mech, modifier) in model.config.commander.modifiers() {
+            modifier.apply_template(
+                model.world.templates.mech_mut(mech),
+                &mut model.world.animations,
+                &mut default(),
+            );
+        }
         model
     }
 
-    pub fn play_sound(&mut self, sound: &Rc<geng::Sound>) {
-        let _sound = sound.play();
+    /// The assets of the game, only the headless simulation has none.
+    #[track_caller]
+    pub fn assets(&self) -> &Rc<Assets> {
+        self.assets
+            .as_ref()
+            .expect("The headless simulation has no assets")
     }
+
+    pub fn play_sound(&mut self, sound: SoundId) {
+        self.start_sound(sound);
+    }
+
+    /// Plays the sound, returns the handle to stop it unless the player cannot hear the run.
+    fn start_sound(&self, sound: SoundId) -> Option<geng::SoundEffect> {
+        let assets = self.assets.as_ref().filter(|_| !self.preview)?;
+        let mut sound = assets.sound_design.get(sound).play();
+        if self.world.low_health {
+            sound.set_volume(LOW_HEALTH_VOLUME);
+        }
+        Some(sound)
+    }
+}
+
+impl Drop for Model {
+    fn drop(&mut self) {
+        if let Some(lava) = &mut self.lava {
+            lava.stop();
+        }
+    }
+}
+
+/// The state of the simulation, everything a tick reads and changes.
+/// The assets, the player's inputs and the visuals stay in the [Model].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct World {
+    /// The only source of randomness for the simulation.
+    pub rng: SimRng,
+    pub current_tick: u64,
+    /// Ticks spent in the cutscenes and the perk choices, left out of the run time.
+    pub paused_ticks: u64,
+    /// Damage, heals and statuses of the run, for balancing.
+    pub combat_log: CombatLog,
+    pub score: Score,
+    pub combo: Combo,
+    /// Set when the player has no mechs left and cannot afford a new one.
+    pub game_over: bool,
+    pub id_gen: IdGen,
+    pub player_energy: Health,
+    pub left_border: Coord,
+    pub ground_level: Coord,
+    pub bounds: WorldBounds,
+    pub gravity: Velocity,
+    pub waves: VecDeque<Wave>,
+    /// Units of the started waves waiting for their spawn delay.
+    pub spawn_queue: Vec<WaveUnit>,
+    /// Units whose spawn telegraph is playing.
+    pub pending_spawns: Vec<PendingSpawn>,
+    pub attack_tokens: AttackTokens,
+    /// The number of waves generated so far.
+    pub wave_number: usize,
+    /// Whether the last spawned wave still has enemies alive.
+    pub wave_in_progress: bool,
+    pub waves_cleared: usize,
+    /// Triggers of the level that have not fired yet.
+    pub triggers: Vec<LevelTrigger>,
+    pub objectives: Vec<Objective>,
+    /// The simulation is stopped while a cutscene is playing.
+    pub cutscene: Option<Cutscene>,
+    /// Perks offered to the player, the simulation is stopped until one is chosen.
+    pub perk_choice: Option<Vec<Perk>>,
+    /// Tutorial steps of the level that have not been triggered yet.
+    pub tutorial: VecDeque<TutorialStep>,
+    /// The tutorial prompt shown to the player, the waves wait until it is completed.
+    pub tutorial_prompt: Option<TutorialStep>,
+    /// Perks chosen during the run.
+    pub perks: Vec<Perk>,
+    /// The last checkpoint reached, the run continues from it when all mechs are lost.
+    pub checkpoint: Option<Checkpoint>,
+    /// The number of times the player has respawned at a checkpoint.
+    pub respawns: usize,
+    #[serde(with = "collection")]
+    pub units: Collection<Unit>,
+    /// Positions, velocities, colliders and health of the units, projectiles and particles.
+    pub bodies: Bodies,
+    /// Dead units that effects may still refer to, e.g. the casters of projectiles in flight.
+    #[serde(with = "id_map")]
+    pub remains: HashMap<Id, Remains>,
+    pub animations: Animations,
+    pub templates: UnitTemplates,
+    #[serde(with = "collection")]
+    pub projectiles: Collection<Projectile>,
+    /// Visual, but their animations trigger effects and their ids are taken from [World::id_gen].
+    #[serde(with = "collection")]
+    pub particles: Collection<Particle>,
+    /// Effects waiting to be processed, e.g. the rest of a burst.
+    pub delayed_effects: Vec<DelayedEffect>,
+    #[serde(with = "collection")]
+    pub pickups: Collection<Pickup>,
+    /// Markers placed by the player.
+    #[serde(with = "collection")]
+    pub pings: Collection<Ping>,
+    pub melee_swings: Vec<MeleeSwing>,
+    pub grapples: Vec<Grapple>,
+    #[serde(with = "collection")]
+    pub obstacles: Collection<Obstacle>,
+    #[serde(with = "collection")]
+    pub doors: Collection<Door>,
+    #[serde(with = "collection")]
+    pub hazards: Collection<Hazard>,
+    #[serde(with = "collection")]
+    pub water: Collection<Water>,
+    #[serde(with = "collection")]
+    pub interactables: Collection<Interactable>,
+    pub decorations: Vec<Decoration>,
+    /// Whether a mech of the player is low on health.
+    pub low_health: bool,
+    /// Damage meters of the target dummies being hit.
+    #[serde(with = "id_map")]
+    pub dps_meters: HashMap<Id, DpsMeter>,
+    pub zones: Vec<Zone>,
+    pub weather: Weather,
+    pub day_cycle: Option<DayCycle>,
 }
 
-#[derive(HasId, Debug, Clone)]
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
 pub struct Particle {
     pub id: Id,
     pub alive: bool,
     pub follow_unit: Option<Id>,
-    pub position: Position,
+    /// Attachment point of the followed unit to stick to
+    pub attachment: Option<String>,
     pub animation_state: AnimationState,
 }
 
-#[derive(Debug, Clone)]
+/// A status on a unit, with the effect triggered once it runs out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedStatus {
+    pub status: Status,
+    pub on_expire: Option<StatusExpiry>,
+}
+
+/// The effect triggered by the status' caster, targeting the unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusExpiry {
+    pub caster: Option<Id>,
+    pub effect: Effect,
+}
+
+impl From<Status> for AppliedStatus {
+    fn from(status: Status) -> Self {
+        Self {
+            status,
+            on_expire: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Status {
-    Charge { time: Time, on_contact: Effect },
+    Charge {
+        time: Time,
+        on_contact: Effect,
+        /// Units that have already been hit by the charge
+        touched: Vec<Id>,
+    },
+    /// Can not move or act, cancels charges
+    Stunned { time: Time },
+    /// Ignores all incoming damage
+    Invulnerable { time: Time },
+    /// Appearing after the spawn telegraph, can not act and ignores all incoming damage
+    Spawning { time: Time, duration: Time },
+    /// Absorbs incoming damage until depleted or timed out
+    Shield { hp: Hp, time: Option<Time> },
+    /// Reduces the damage from the front, lasts while the player holds the block
+    Block,
+    /// Negates the damage from the front and performs a riposte
+    Parry { time: Time },
+    /// Deals damage over time, put out by water
+    Burning { time: Time, damage_per_second: Hp },
+    /// Adds the modifier to the unit's stats
+    Modified { time: Time, modifier: StatModifier },
+    /// Soaked by the water or the rain, conducts the energy damage
+    Wet { time: Time },
+    /// Takes more physical damage, broken by a heavy hit to an armored unit
+    ArmorBroken { time: Time, multiplier: R32 },
+    /// Caps the speed of falling down
+    SlowFall { time: Time, max_fall_speed: Coord },
+    /// Leaves the zone under the unit every interval while it moves
+    Trail {
+        interval: Time,
+        next: Time,
+        zone: Effect,
+    },
+    /// Reverts a temporary change of the maximum hp once the time runs out
+    MaxHp {
+        time: Time,
+        amount: Hp,
+        change: MaxHpChange,
+    },
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct UnitTemplates {
     pub artillery: UnitTemplate,
     pub tank: UnitTemplate,
@@ -90,15 +532,23 @@ pub struct UnitTemplates {
     pub blighter: UnitTemplate,
     pub ravager: UnitTemplate,
     pub stinger: UnitTemplate,
+    pub ravager_alpha: UnitTemplate,
+    /// Placed by the level triggers for the mechs to protect, not spawned by the waves.
+    pub generator: UnitTemplate,
+    pub engineer: UnitTemplate,
+    /// A target that stands still, spawned in the sandbox to measure the damage.
+    pub dummy: UnitTemplate,
+    /// The templates added by the mods, see [crate::mods::ModTemplate].
+    pub modded: Vec<UnitTemplate>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Faction {
     Mech,
     Alien,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UnitAI {
     Idle,
     Stinger {
@@ -114,26 +564,26 @@ pub enum UnitAI {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PositionAI {
     Advance,
     Follow,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwitchAction {
     pub next_action: Action,
     pub next_ai: Box<UnitAI>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TargetAI {
     Closest,
     Farthest,
     LowestHp,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProjectileAI {
     Idle,
     Rocket {
@@ -141,84 +591,247 @@ pub enum ProjectileAI {
         acceleration: Coord,
         preferred_height: Coord,
     },
+    /// Circles around the caster, e.g. shield orbs
+    Orbit {
+        radius: Coord,
+        /// In radians per second
+        angular_speed: Coord,
+        angle: Coord,
+    },
+    /// Flies relative to the caster, e.g. flames staying in front of a moving flamethrower
+    Attached {
+        offset: Position,
+    },
+}
+
+impl ProjectileAI {
+    /// Whether the projectile's position is defined relative to its caster,
+    /// such projectiles despawn with the caster.
+    pub fn follows_caster(&self) -> bool {
+        matches!(self, Self::Orbit { .. } | Self::Attached { .. })
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Action {
     pub cooldown: Time,
     pub engage_radius: Coord,
-    pub animation: Rc<Animation>,
+    pub animation: AnimationId,
 }
 
-#[derive(Debug, Clone)]
+impl Action {
+    /// Calls `f` on every effect in the action's animation, the modified animation is stored
+    /// as a copy shared with the other actions modified with the copies.
+    pub fn modify_effects(
+        &mut self,
+        animations: &mut Animations,
+        copies: &mut AnimationCopies,
+        mut f: impl FnMut(&mut EffectNode<EffectId>, &mut EffectNodes),
+    ) {
+        self.animation = animations.modify_shared(self.animation, copies, |animation| {
+            for frame in &mut animation.keyframes {
+                if let Some(effect) = &mut frame.start_effect {
+                    effect.walk_mut(&mut f);
+                }
+            }
+        });
+    }
+
+    /// Returns the projectile launched by the action, if any.
+    pub fn projectile<'a>(
+        &self,
+        animations: &'a Animations,
+    ) -> Option<&'a ProjectileEffect<EffectId>> {
+        animations
+            .get(self.animation)
+            .keyframes
+            .iter()
+            .find_map(|frame| frame.start_effect.as_ref()?.find_projectile())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionState {
     Ready,
-    InProgress { target: Option<Id> },
-    Cooldown { time_left: Time },
-}
-
-#[derive(Debug, Clone)]
-pub enum ExtraUnitRender {
-    Tank {
-        hand_pos: Position,
-        weapon_pos: Position,
-        shoot_pos: Position,
-        /// Default is 0 degrees directed to the right
-        rotation: Coord,
+    InProgress {
+        target: Option<Id>,
+        aim: Option<Position>,
+    },
+    Cooldown {
+        time_left: Time,
     },
 }
 
-#[derive(HasId, Clone)]
+/// A unit that changes its behaviour as it loses health.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Boss {
+    /// The locale key of the name.
+    pub name: String,
+    /// Phases that follow the initial one, in order.
+    pub phases: Vec<BossPhase>,
+    /// The number of phases already entered.
+    pub phase: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BossPhase {
+    /// The phase starts once the health ratio drops to this value.
+    pub health_threshold: Hp,
+    pub ai: UnitAI,
+    pub action: Action,
+    /// For how long the boss is invulnerable after entering the phase.
+    pub invulnerability: Time,
+}
+
+#[derive(HasId, Clone, Serialize, Deserialize)]
 pub struct Unit {
     pub id: Id,
+    /// Name of the template the unit was made from.
+    #[serde(deserialize_with = "deserialize_static_str")]
+    pub name: &'static str,
     pub faction: Faction,
     pub ai: UnitAI,
-    pub health: Health,
     pub sanity: Option<Health>,
-    pub collider: Collider,
-    pub position: Position,
-    pub velocity: Velocity,
     pub speed: Coord,
     pub acceleration: Coord,
     pub target_velocity: Velocity,
-    pub statuses: Vec<Status>,
+    pub statuses: Vec<AppliedStatus>,
     pub action: Action,
     pub action_state: ActionState,
     pub flip_sprite: bool,
+    /// Whether the unit is at least partially submerged in water
+    pub in_water: bool,
     pub animation_state: AnimationState,
-    pub idle_animation: Rc<Animation>,
-    pub move_animation: Rc<Animation>,
-    pub extra_render: Option<ExtraUnitRender>,
+    pub glide_state: Option<AnimationState>,
+    pub idle_animation: AnimationId,
+    pub move_animation: AnimationId,
+    /// Played instead of the other animations while the unit is slow-falling
+    pub glide_animation: Option<AnimationId>,
+    pub attachments: Vec<AttachmentPoint>,
+    pub weapon: Option<Weapon>,
+    pub ammo: Option<Ammo>,
+    pub block: Option<BlockStats>,
+    pub boss: Option<Boss>,
+    pub stats: Stats,
+    pub elites: Vec<EliteModifier>,
     pub on_death: Effect,
+    pub point_defense: Option<PointDefense>,
+    pub skill: AiSkill,
+    pub size: SizeClass,
+    /// An allied unit the player does not command, it does not count as one of the mechs.
+    pub npc: bool,
+    /// The unit inside the vehicle, it ejects once the vehicle is destroyed.
+    pub pilot: Option<Box<(Unit, Body)>>,
+    /// Recent positions and velocities, the latest first, as seen by the slower enemies.
+    pub motion_history: VecDeque<(Position, Velocity)>,
+}
+
+impl Unit {
+    /// Whether the player can order the unit to fire at a position.
+    pub fn is_aimable(&self, animations: &Animations) -> bool {
+        self.faction == Faction::Mech && !self.npc && self.action.projectile(animations).is_some()
+    }
+
+    /// The total amount of damage the unit's shields can absorb.
+    pub fn shield(&self) -> Hp {
+        self.statuses
+            .iter()
+            .map(|status| match status.status {
+                Status::Shield { hp, .. } => hp,
+                _ => Hp::ZERO,
+            })
+            .fold(Hp::ZERO, Hp::add)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.statuses.iter().any(|status| {
+            matches!(
+                status.status,
+                Status::Stunned { .. } | Status::Spawning { .. }
+            )
+        })
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.statuses.iter().any(|status| {
+            matches!(
+                status.status,
+                Status::Invulnerable { .. } | Status::Spawning { .. }
+            )
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnitTemplate {
+    /// The name the template is listed under in [UnitTemplates::named].
+    #[serde(deserialize_with = "deserialize_static_str")]
+    pub name: &'static str,
     pub ai: UnitAI,
     pub health: Health,
     pub sanity: Option<Health>,
     pub collider: Collider,
     pub speed: Coord,
     pub acceleration: Coord,
+    /// Statuses the unit spawns with.
+    pub statuses: Vec<Status>,
     pub start_action_state: ActionState,
     pub action: Action,
-    pub idle_animation: Rc<Animation>,
-    pub move_animation: Rc<Animation>,
-    pub extra_render: Option<ExtraUnitRender>,
+    pub idle_animation: AnimationId,
+    pub move_animation: AnimationId,
+    /// Played instead of the other animations while the unit is slow-falling
+    pub glide_animation: Option<AnimationId>,
+    pub attachments: Vec<AttachmentPoint>,
+    pub weapon: Option<Weapon>,
+    pub ammo: Option<Ammo>,
+    pub block: Option<BlockStats>,
+    pub boss: Option<Boss>,
+    pub stats: Stats,
+    pub elites: Vec<EliteModifier>,
     pub on_death: Effect,
+    pub point_defense: Option<PointDefense>,
+    pub skill: AiSkill,
+    pub size: SizeClass,
+    pub npc: bool,
+    /// The telegraph shown before the unit appears in the waves.
+    pub spawn: Option<SpawnStyle>,
 }
 
-#[derive(HasId)]
+#[derive(HasId, Clone, Serialize, Deserialize)]
 pub struct Projectile {
     pub id: Id,
     pub ai: ProjectileAI,
     pub lifetime: Time,
-    pub collider: Collider,
     pub on_hit: Effect,
+    pub on_expire: Effect,
+    /// Distance traveled so far
+    pub distance: Coord,
+    pub max_range: Option<Coord>,
+    pub arming_distance: Coord,
+    pub falloff: Option<Falloff>,
+    /// Destroys the enemy projectiles it touches, along with itself.
+    pub intercepts: bool,
+    /// Passed on to the contexts of the hit and expire effects.
+    pub missing: MissingUnit,
     pub friend_faction: Option<Faction>,
     pub caster: Option<Id>,
     pub target: Option<Id>,
-    pub position: Position,
-    pub velocity: Velocity,
     pub animation_state: AnimationState,
 }
+
+impl Projectile {
+    /// Whether the projectile has traveled far enough to hit anything.
+    pub fn is_armed(&self) -> bool {
+        self.distance >= self.arming_distance
+    }
+
+    pub fn is_out_of_range(&self) -> bool {
+        self.max_range.map_or(false, |range| self.distance >= range)
+    }
+
+    /// How much of its damage the projectile deals at the distance traveled.
+    pub fn damage_multiplier(&self) -> R32 {
+        self.falloff
+            .map_or(R32::ONE, |falloff| falloff.multiplier(self.distance))
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// Half of the angle of the cone around the aim in which the enemies pull it, in radians.
+const AIM_ASSIST_CONE: f32 = 0.35;
+/// Strengths of the aim assist to pick from, 0 turns it off and 1 snaps the aim onto the enemy.
+pub const AIM_ASSIST_STRENGTHS: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+impl Model {
+    /// Pulls the aim of the unit towards the nearest enemy in the cone around it.
+    pub fn assist_aim(&self, unit: &Unit, aim: Position) -> Position {
+        let strength = self.config.aim_assist;
+        let positions = &self.world.bodies.positions;
+        let position = positions[unit.id];
+        let direction = (aim - position).map(|x| x.as_f32());
+        if strength <= 0.0 || direction.len() < 1e-3 {
+            return aim;
+        }
+        let min_cos = AIM_ASSIST_CONE.cos();
+        let nearest = self
+            .world
+            .units
+            .iter()
+            .filter(|other| other.faction != unit.faction && self.world.bodies.is_alive(other.id))
+            .filter(|other| {
+                let delta = (positions[other.id] - position).map(|x| x.as_f32());
+                Vec2::dot(direction, delta) >= direction.len() * delta.len() * min_cos
+            })
+            .min_by_key(|other| (positions[other.id] - position).len_sqr());
+        match nearest {
+            Some(enemy) => aim + (positions[enemy.id] - aim) * Coord::new(strength),
+            None => aim,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::*;
+use logic::*;
+
+/// A limited pool of uses for the unit's action, refilled by reloading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ammo {
+    pub capacity: usize,
+    pub loaded: usize,
+    pub reload_time: Time,
+    /// Time left until the reload is finished, if reloading.
+    pub reload_left: Option<Time>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreAmmoEffect {
+    /// Amount of ammo to restore, the whole capacity if `None`.
+    pub amount: Option<usize>,
+}
+
+impl Ammo {
+    pub fn new(capacity: usize, reload_time: Time) -> Self {
+        Self {
+            capacity,
+            loaded: capacity,
+            reload_time,
+            reload_left: None,
+        }
+    }
+
+    pub fn can_fire(&self) -> bool {
+        self.loaded > 0 && self.reload_left.is_none()
+    }
+
+    /// Uses up a single round, starts reloading if the pool becomes empty.
+    pub fn consume(&mut self) {
+        self.loaded = self.loaded.saturating_sub(1);
+        if self.loaded == 0 {
+            self.reload();
+        }
+    }
+
+    pub fn reload(&mut self) {
+        if self.reload_left.is_none() && self.loaded < self.capacity {
+            self.reload_left = Some(self.reload_time);
+        }
+    }
+
+    pub fn restore(&mut self, amount: Option<usize>) {
+        self.loaded = match amount {
+            Some(amount) => (self.loaded + amount).min(self.capacity),
+            None => self.capacity,
+        };
+        if self.loaded == self.capacity {
+            self.reload_left = None;
+        }
+    }
+
+    pub fn update(&mut self, delta_time: Time) {
+        if let Some(time) = &mut self.reload_left {
+            *time -= delta_time;
+            if *time <= Time::ZERO {
+                self.restore(None);
+            }
+        }
+    }
+}
+
+impl Unit {
+    /// Whether the unit is not blocking and has the ammo to start its action.
+    pub fn can_start_action(&self) -> bool {
+        !self.is_blocking() && self.ammo.as_ref().map(Ammo::can_fire).unwrap_or(true)
+    }
+
+    /// Starts the action at the target, using up the ammo.
+    pub fn start_action(
+        &mut self,
+        target: Option<Id>,
+        aim: Option<Position>,
+        animations: &Animations,
+    ) {
+        self.action_state = ActionState::InProgress { target, aim };
+        self.animation_state
+            .switch(self.action.animation, animations);
+        if let Some(ammo) = &mut self.ammo {
+            ammo.consume();
+        }
+    }
+}
+
+impl RestoreAmmoEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let target = context.get_mut(Who::Target, logic)?;
+        target.ammo.as_mut()?.restore(self.amount);
+        Some(())
+    }
+}
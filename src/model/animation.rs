@@ -1,11 +1,13 @@
 use super::*;
 
-#[derive(Debug, Clone)]
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Animation {
     pub keyframes: Vec<AnimationFrame>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnimationFrame {
     pub sprite: Sprite,
     pub time: Time,
@@ -13,23 +15,140 @@ pub struct AnimationFrame {
     pub start_effect: Option<Effect>,
 }
 
-#[derive(Debug, Clone)]
+/// A handle to an animation in the [Animations] store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AnimationId(usize);
+
+/// The copies made by [Animations::modify_shared], by the original animation.
+pub type AnimationCopies = HashMap<AnimationId, AnimationId>;
+
+/// Owns all animations of a run, referenced by [AnimationId].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Animations {
+    animations: Vec<Animation>,
+    /// Animations that are created during the run and shared by name,
+    /// so that they are not stored again every time.
+    named: HashMap<String, AnimationId>,
+}
+
+impl Animation {
+    /// Creates an animation from the textures played over the cycle time.
+    pub fn from_textures(
+        textures: &[assets::PixelTexture],
+        sprite_scale: f32,
+        cycle_time: Time,
+        actions: Vec<(usize, Effect)>,
+    ) -> Self {
+        let time = cycle_time / Time::new(textures.len() as f32);
+        Self {
+            keyframes: textures
+                .iter()
+                .enumerate()
+                .map(|(frame, texture)| AnimationFrame {
+                    sprite: Sprite::new(texture, sprite_scale),
+                    time,
+                    start_effect: actions.iter().find_map(|(action_frame, action)| {
+                        (frame == *action_frame).then(|| action.clone())
+                    }),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Animations {
+    pub fn new() -> Self {
+        default()
+    }
+
+    pub fn insert(&mut self, animation: Animation) -> AnimationId {
+        let id = AnimationId(self.animations.len());
+        self.animations.push(animation);
+        id
+    }
+
+    /// Returns the animation with the name, creating it the first time.
+    pub fn get_or_insert_with(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce() -> Animation,
+    ) -> AnimationId {
+        let name = name.into();
+        if let Some(&id) = self.named.get(&name) {
+            return id;
+        }
+        let id = self.insert(f());
+        self.named.insert(name, id);
+        id
+    }
+
+    /// The animation shared under the name, if it has been created.
+    pub fn named(&self, name: &str) -> Option<AnimationId> {
+        self.named.get(name).copied()
+    }
+
+    pub fn get(&self, id: AnimationId) -> &Animation {
+        &self.animations[id.0]
+    }
+
+    /// Like [Self::get], `None` if the id is not from this store.
+    pub fn try_get(&self, id: AnimationId) -> Option<&Animation> {
+        self.animations.get(id.0)
+    }
+
+    /// Stores a modified copy of the animation, leaving the original untouched.
+    pub fn modify(&mut self, id: AnimationId, f: impl FnOnce(&mut Animation)) -> AnimationId {
+        let mut animation = self.get(id).clone();
+        f(&mut animation);
+        self.insert(animation)
+    }
+
+    /// Like [Self::modify], but an animation already modified with the copies is not copied
+    /// again, e.g. when a perk changes a template and the units sharing its animation.
+    pub fn modify_shared(
+        &mut self,
+        id: AnimationId,
+        copies: &mut AnimationCopies,
+        f: impl FnOnce(&mut Animation),
+    ) -> AnimationId {
+        if let Some(&copy) = copies.get(&id) {
+            return copy;
+        }
+        let copy = self.modify(id, f);
+        copies.insert(id, copy);
+        copy
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnimationState {
-    pub animation: Rc<Animation>,
+    pub animation: AnimationId,
     pub frame: usize,
     pub frame_time: Time,
     pub effects: Vec<Effect>,
 }
 
 impl AnimationState {
-    pub fn new(animation: &Rc<Animation>) -> Self {
+    pub fn new(animation: AnimationId, animations: &Animations) -> Self {
+        Self::with_buffer(vec![], animation, animations)
+    }
+
+    /// Starts the animation in the effect buffer of the state of a despawned entity, see [Pool].
+    pub fn reuse(old: Option<Self>, animation: AnimationId, animations: &Animations) -> Self {
+        let mut effects = old.map_or(vec![], |state| state.effects);
+        effects.clear();
+        Self::with_buffer(effects, animation, animations)
+    }
+
+    fn with_buffer(effects: Vec<Effect>, animation: AnimationId, animations: &Animations) -> Self {
         let mut state = Self {
-            animation: animation.clone(),
+            animation,
             frame: 0,
             frame_time: Time::ZERO,
-            effects: vec![],
+            effects,
         };
-        let effect = animation
+        let effect = animations
+            .get(animation)
             .keyframes
             .first()
             .expect("Animations cannot have zero frames")
@@ -41,16 +160,21 @@ impl AnimationState {
         state
     }
 
-    pub fn switch(&mut self, animation: &Rc<Animation>) {
-        if Rc::ptr_eq(&self.animation, animation) {
+    pub fn switch(&mut self, animation: AnimationId, animations: &Animations) {
+        if self.animation == animation {
             // Same animation
             return;
         }
-        *self = Self::new(animation);
+        *self = Self::new(animation, animations);
     }
 
-    pub fn get_sprite(&self) -> &Sprite {
-        &self.animation.keyframes.get(self.frame).unwrap().sprite
+    pub fn get_sprite<'a>(&self, animations: &'a Animations) -> &'a Sprite {
+        &animations
+            .get(self.animation)
+            .keyframes
+            .get(self.frame)
+            .unwrap()
+            .sprite
     }
 
     pub fn take_effects(&mut self) -> Vec<Effect> {
@@ -58,12 +182,12 @@ impl AnimationState {
     }
 
     /// Update the animation state and returns whether it has started to loop.
-    pub fn update(&mut self, delta_time: Time) -> bool {
+    pub fn update(&mut self, delta_time: Time, animations: &Animations) -> bool {
+        let animation = animations.get(self.animation);
         self.frame_time += delta_time;
         let mut looped = false;
         loop {
-            let frame = self
-                .animation
+            let frame = animation
                 .keyframes
                 .get(self.frame)
                 .expect("Failed to find animation frame");
@@ -71,15 +195,14 @@ impl AnimationState {
             if delta >= Time::ZERO {
                 // Next frame
                 self.frame_time = delta;
-                if self.animation.keyframes.len() <= self.frame + 1 {
+                if animation.keyframes.len() <= self.frame + 1 {
                     // Repeat
                     looped = true;
                     self.frame = 0;
                 } else {
                     self.frame += 1;
                 }
-                if let Some(effect) = self
-                    .animation
+                if let Some(effect) = animation
                     .keyframes
                     .get(self.frame)
                     .unwrap()
@@ -0,0 +1,133 @@
+use super::*;
+
+/// For how long the armor stays broken after a heavy hit.
+const ARMOR_BREAK_TIME: f32 = 4.0;
+/// Multiplier of the physical damage taken through the broken armor.
+const ARMOR_BREAK_MULTIPLIER: f32 = 1.5;
+/// The hits dealing at least this much damage break the armor, the explosions always do.
+const HEAVY_HIT_DAMAGE: f32 = 2.0;
+
+impl Unit {
+    /// Whether the unit has any defense for a heavy hit to break.
+    pub fn is_armored(&self) -> bool {
+        self.stat(Stat::Defense) > R32::ZERO
+    }
+
+    pub fn is_armor_broken(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|status| matches!(status.status, Status::ArmorBroken { .. }))
+    }
+
+    /// Multiplier of the damage of the type taken through the broken armor.
+    pub fn exposure(&self, damage_type: &DamageType) -> R32 {
+        if !matches!(damage_type, DamageType::Physical) {
+            return R32::ONE;
+        }
+        self.statuses
+            .iter()
+            .filter_map(|status| match status.status {
+                Status::ArmorBroken { multiplier, .. } => Some(multiplier),
+                _ => None,
+            })
+            .fold(R32::ONE, R32::max)
+    }
+
+    /// Breaks the armor, a unit that is already exposed has the time refreshed.
+    pub fn break_armor(&mut self) {
+        self.statuses
+            .retain(|status| !matches!(status.status, Status::ArmorBroken { .. }));
+        self.statuses.push(
+            Status::ArmorBroken {
+                time: Time::new(ARMOR_BREAK_TIME),
+                multiplier: r32(ARMOR_BREAK_MULTIPLIER),
+            }
+            .into(),
+        );
+    }
+}
+
+impl DamageEffect {
+    /// Whether a hit of the damage breaks the armor.
+    pub fn is_heavy(&self, damage: Hp) -> bool {
+        matches!(self.damage_type, DamageType::Explosive) || damage >= Hp::new(HEAVY_HIT_DAMAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bench::{test_effect, test_model, test_spawn};
+
+    fn damage(damage_type: DamageType, hp: f32) -> Effect {
+        Effect::new(EffectNode::Damage(DamageEffect {
+            damage_type,
+            value: Value::Flat(Hp::new(hp)),
+            hits_caster: false,
+            impact: None,
+        }))
+    }
+
+    /// A mech with some defense, standing still.
+    fn armored(model: &mut Model) -> Id {
+        let id = test_spawn(model, "tank", 0.0, Faction::Mech);
+        let unit = model.world.units.get_mut(&id).unwrap();
+        unit.stats.add(Stat::Defense, ModifierKind::Flat, r32(1.0));
+        id
+    }
+
+    #[test]
+    fn broken_armor_only_exposes_to_physical_damage() {
+        let mut model = test_model();
+        let id = armored(&mut model);
+        let unit = model.world.units.get_mut(&id).unwrap();
+        assert_eq!(unit.exposure(&DamageType::Physical), R32::ONE);
+        unit.break_armor();
+        unit.break_armor();
+        let broken = unit
+            .statuses
+            .iter()
+            .filter(|status| matches!(status.status, Status::ArmorBroken { .. }))
+            .count();
+        assert_eq!(broken, 1);
+        assert_eq!(
+            unit.exposure(&DamageType::Physical),
+            r32(ARMOR_BREAK_MULTIPLIER)
+        );
+        assert_eq!(unit.exposure(&DamageType::Energy), R32::ONE);
+        assert_eq!(unit.exposure(&DamageType::Explosive), R32::ONE);
+    }
+
+    fn is_broken(model: &Model, id: Id) -> bool {
+        model.world.units.get(&id).unwrap().is_armor_broken()
+    }
+
+    #[test]
+    fn heavy_hits_break_the_armor() {
+        let mut model = test_model();
+        let id = armored(&mut model);
+        test_effect(&mut model, damage(DamageType::Physical, 1.0), None, id);
+        assert!(!is_broken(&model, id));
+        test_effect(
+            &mut model,
+            damage(DamageType::Physical, HEAVY_HIT_DAMAGE),
+            None,
+            id,
+        );
+        assert!(is_broken(&model, id));
+
+        // Any explosion is heavy
+        let id = armored(&mut model);
+        test_effect(&mut model, damage(DamageType::Explosive, 0.5), None, id);
+        assert!(is_broken(&model, id));
+    }
+
+    #[test]
+    fn units_without_defense_have_no_armor_to_break() {
+        let mut model = test_model();
+        let id = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        test_effect(&mut model, damage(DamageType::Explosive, 1.0), None, id);
+        assert!(!is_broken(&model, id));
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+
+/// A named point on the unit (e.g. hand, muzzle, head) that effects and render can reference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentPoint {
+    pub name: String,
+    /// The point this one is attached to, otherwise it is attached to the unit's position
+    pub parent: Option<String>,
+    pub offset: Position,
+    /// Rotation relative to the parent, default is 0 degrees directed to the right
+    pub rotation: Coord,
+    /// Whether the point rotates to aim the unit's projectiles at the target
+    pub aim: bool,
+    pub sprite: Option<Sprite>,
+}
+
+/// Position and rotation of an attachment point relative to the unit's position.
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentTransform {
+    pub offset: Position,
+    pub rotation: Coord,
+}
+
+impl AttachmentPoint {
+    pub fn new(name: &str, parent: Option<&str>, offset: Position) -> Self {
+        Self {
+            name: name.to_owned(),
+            parent: parent.map(|parent| parent.to_owned()),
+            offset,
+            rotation: Coord::ZERO,
+            aim: false,
+            sprite: None,
+        }
+    }
+}
+
+/// Finds the transform of the point, as if the unit was not flipped.
+/// Returns `None` if the point or one of its parents is missing, or they form a loop.
+pub fn local_transform(attachments: &[AttachmentPoint], name: &str) -> Option<AttachmentTransform> {
+    chain_transform(attachments, name, attachments.len())
+}
+
+/// The transform of the point with at most `depth` parents above it.
+fn chain_transform(
+    attachments: &[AttachmentPoint],
+    name: &str,
+    depth: usize,
+) -> Option<AttachmentTransform> {
+    let point = attachments.iter().find(|point| point.name == name)?;
+    let parent = match &point.parent {
+        Some(parent) => chain_transform(attachments, parent, depth.checked_sub(1)?)?,
+        None => AttachmentTransform {
+            offset: Position::ZERO,
+            rotation: Coord::ZERO,
+        },
+    };
+    Some(AttachmentTransform {
+        offset: parent.offset + point.offset.rotate(parent.rotation),
+        rotation: parent.rotation + point.rotation,
+    })
+}
+
+impl Unit {
+    /// Returns the transform of the point, accounting for the flipped sprite.
+    pub fn attachment_transform(&self, name: &str) -> Option<AttachmentTransform> {
+        let mut transform = local_transform(&self.attachments, name)?;
+        if self.flip_sprite {
+            transform.offset.x = -transform.offset.x;
+            transform.rotation = -transform.rotation;
+        }
+        Some(transform)
+    }
+
+    /// Returns the world position of the point.
+    pub fn attachment_position(&self, bodies: &Bodies, name: &str) -> Option<Position> {
+        let position = *bodies.positions.get(self.id)?;
+        self.attachment_transform(name)
+            .map(|transform| position + transform.offset)
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+/// How many enemies may attack at the same time.
+const MAX_ATTACKERS: usize = 4;
+
+/// Limits the number of enemies attacking at once, so that the fights stay readable.
+/// An enemy holds a token for as long as its action is in progress.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttackTokens {
+    holders: Vec<Id>,
+}
+
+impl AttackTokens {
+    /// Takes a token for the unit if one is free, the mechs and the bosses always get one.
+    pub fn try_take(&mut self, unit: &Unit) -> bool {
+        if unit.faction == Faction::Mech || unit.boss.is_some() {
+            return true;
+        }
+        if self.holders.contains(&unit.id) {
+            return true;
+        }
+        if self.holders.len() >= MAX_ATTACKERS {
+            return false;
+        }
+        self.holders.push(unit.id);
+        true
+    }
+
+    /// Returns the tokens of the units that finished their attacks or died.
+    pub fn release_finished(&mut self, units: &Collection<Unit>) {
+        self.holders.retain(|id| {
+            units.get(id).map_or(false, |unit| {
+                matches!(unit.action_state, ActionState::InProgress { .. })
+            })
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.holders.clear();
+    }
+}
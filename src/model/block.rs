@@ -0,0 +1,30 @@
+use super::*;
+
+/// Ability of the unit to block incoming attacks while the player holds the block control.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockStats {
+    /// Fraction of the damage from the front that is blocked
+    pub reduction: R32,
+    /// Time after raising the block during which hits from the front are parried
+    pub parry_window: Time,
+    /// Effect performed on the attacker after a successful parry
+    pub riposte: Effect,
+}
+
+impl Unit {
+    /// Whether the attack travelling in the given direction hits the unit from the front.
+    pub fn is_facing_attack(&self, direction: Vec2<Coord>) -> bool {
+        let facing = if self.flip_sprite {
+            Coord::new(-1.0)
+        } else {
+            Coord::ONE
+        };
+        direction.x * facing < Coord::ZERO
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|status| matches!(status.status, Status::Block))
+    }
+}
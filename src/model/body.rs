@@ -0,0 +1,245 @@
+use super::*;
+
+use std::ops::{Index, IndexMut};
+
+/// One component of the entities, stored apart from them and keyed by their ids,
+/// so that a system can go over every entity with the component whatever its kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct Storage<T> {
+    #[serde(with = "id_map")]
+    components: HashMap<Id, T>,
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Storage<T> {
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.components.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.components.get_mut(&id)
+    }
+
+    pub fn insert(&mut self, id: Id, component: T) {
+        self.components.insert(id, component);
+    }
+
+    pub fn remove(&mut self, id: Id) -> Option<T> {
+        self.components.remove(&id)
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(Id) -> bool) {
+        self.components.retain(|&id, _| f(id));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.components
+            .iter()
+            .map(|(&id, component)| (id, component))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Id, &mut T)> {
+        self.components
+            .iter_mut()
+            .map(|(&id, component)| (id, component))
+    }
+}
+
+impl<T> Index<Id> for Storage<T> {
+    type Output = T;
+
+    fn index(&self, id: Id) -> &T {
+        self.get(id)
+            .unwrap_or_else(|| panic!("{id:?} has no such component"))
+    }
+}
+
+impl<T> IndexMut<Id> for Storage<T> {
+    fn index_mut(&mut self, id: Id) -> &mut T {
+        self.get_mut(id)
+            .unwrap_or_else(|| panic!("{id:?} has no such component"))
+    }
+}
+
+/// The components of a single entity, moved in and out of the storages together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Body {
+    pub position: Position,
+    pub velocity: Velocity,
+    pub collider: Option<Collider>,
+    pub health: Option<Health>,
+}
+
+impl Body {
+    pub fn new(position: Position, velocity: Velocity) -> Self {
+        Self {
+            position,
+            velocity,
+            collider: None,
+            health: None,
+        }
+    }
+
+    pub fn with_collider(self, collider: Collider) -> Self {
+        Self {
+            collider: Some(collider),
+            ..self
+        }
+    }
+
+    pub fn with_health(self, health: Health) -> Self {
+        Self {
+            health: Some(health),
+            ..self
+        }
+    }
+
+    /// Accelerates the body and moves it by its velocity.
+    pub fn integrate(&mut self, acceleration: Velocity, delta_time: Time) {
+        integrate(
+            &mut self.position,
+            &mut self.velocity,
+            acceleration,
+            delta_time,
+        );
+    }
+}
+
+/// The components shared by the units, projectiles and particles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bodies {
+    pub positions: Storage<Position>,
+    pub velocities: Storage<Velocity>,
+    pub colliders: Storage<Collider>,
+    /// Only the units have health.
+    pub healths: Storage<Health>,
+}
+
+impl Bodies {
+    pub fn insert(&mut self, id: Id, body: Body) {
+        self.positions.insert(id, body.position);
+        self.velocities.insert(id, body.velocity);
+        if let Some(collider) = body.collider {
+            self.colliders.insert(id, collider);
+        }
+        if let Some(health) = body.health {
+            self.healths.insert(id, health);
+        }
+    }
+
+    /// Takes the components of the entity out of the storages.
+    pub fn remove(&mut self, id: Id) -> Option<Body> {
+        let position = self.positions.remove(id);
+        let velocity = self.velocities.remove(id);
+        let collider = self.colliders.remove(id);
+        let health = self.healths.remove(id);
+        Some(Body {
+            position: position?,
+            velocity: velocity?,
+            collider,
+            health,
+        })
+    }
+
+    /// A copy of the components of the entity.
+    pub fn get(&self, id: Id) -> Option<Body> {
+        Some(Body {
+            position: *self.positions.get(id)?,
+            velocity: *self.velocities.get(id)?,
+            collider: self.colliders.get(id).cloned(),
+            health: self.healths.get(id).cloned(),
+        })
+    }
+
+    /// Accelerates every entity that has a velocity and moves it.
+    pub fn integrate(
+        &mut self,
+        ids: impl IntoIterator<Item = Id>,
+        acceleration: Velocity,
+        delta_time: Time,
+    ) {
+        for id in ids {
+            if let (Some(position), Some(velocity)) =
+                (self.positions.get_mut(id), self.velocities.get_mut(id))
+            {
+                integrate(position, velocity, acceleration, delta_time);
+            }
+        }
+    }
+
+    /// The bounding box of the entity's collider at its position.
+    pub fn bounds(&self, id: Id) -> Option<AABB<Coord>> {
+        Some(self.colliders.get(id)?.bounds(*self.positions.get(id)?))
+    }
+
+    /// Whether the colliders of the two entities overlap.
+    pub fn collide(&self, a: Id, b: Id) -> bool {
+        match (
+            self.colliders.get(a),
+            self.positions.get(a),
+            self.colliders.get(b),
+            self.positions.get(b),
+        ) {
+            (Some(collider_a), Some(&position_a), Some(collider_b), Some(&position_b)) => {
+                collider_a.check(collider_b, position_b - position_a)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the entity's collider overlaps a collider placed at the position.
+    pub fn overlaps(&self, id: Id, collider: &Collider, position: Position) -> bool {
+        match (self.colliders.get(id), self.positions.get(id)) {
+            (Some(own), Some(&own_position)) => collider.check(own, own_position - position),
+            _ => false,
+        }
+    }
+
+    /// Whether the entity is alive, the entities without health always are.
+    pub fn is_alive(&self, id: Id) -> bool {
+        self.healths.get(id).map_or(true, Health::is_alive)
+    }
+
+    /// Drops the components of the entities that are gone.
+    pub fn retain(&mut self, f: impl Fn(Id) -> bool) {
+        self.positions.retain(&f);
+        self.velocities.retain(&f);
+        self.colliders.retain(&f);
+        self.healths.retain(&f);
+    }
+}
+
+impl Model {
+    /// Drops the components of the despawned units, projectiles and particles.
+    pub fn clean_up_bodies(&mut self) {
+        let (units, projectiles, particles) = (
+            &self.world.units,
+            &self.world.projectiles,
+            &self.world.particles,
+        );
+        self.world.bodies.retain(|id| {
+            units.get(&id).is_some()
+                || projectiles.get(&id).is_some()
+                || particles.get(&id).is_some()
+        });
+    }
+}
+
+/// Accelerates the velocity and moves the position by it.
+pub fn integrate(
+    position: &mut Position,
+    velocity: &mut Velocity,
+    acceleration: Velocity,
+    delta_time: Time,
+) {
+    *velocity += acceleration * delta_time;
+    *position += *velocity * delta_time;
+}
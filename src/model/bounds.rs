@@ -0,0 +1,47 @@
+use super::*;
+
+/// The part of the world the entities live in, moving along with the left border.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldBounds {
+    /// Distance behind the left border.
+    pub behind: f32,
+    /// Distance ahead of the left border.
+    pub ahead: f32,
+    /// Height above the ground.
+    pub height: f32,
+    /// Depth of the kill plane below the ground.
+    pub depth: f32,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self {
+            behind: 10.0,
+            ahead: 100.0,
+            height: 50.0,
+            depth: 10.0,
+        }
+    }
+}
+
+/// How far behind the world the scenery is kept, so that it does not vanish in view.
+const DESPAWN_MARGIN: f32 = 10.0;
+
+impl Model {
+    /// The world at the current position of the left border, the projectiles leaving it expire
+    /// and the units falling below it die.
+    pub fn world_area(&self) -> AABB<Coord> {
+        AABB {
+            x_min: self.world.left_border - Coord::new(self.world.bounds.behind),
+            x_max: self.world.left_border + Coord::new(self.world.bounds.ahead),
+            y_min: self.world.ground_level - Coord::new(self.world.bounds.depth),
+            y_max: self.world.ground_level + Coord::new(self.world.bounds.height),
+        }
+    }
+
+    /// The obstacles, hazards, water and interactables entirely behind it are removed.
+    pub fn despawn_x(&self) -> Coord {
+        self.world_area().x_min - Coord::new(DESPAWN_MARGIN)
+    }
+}
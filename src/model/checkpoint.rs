@@ -0,0 +1,126 @@
+use super::*;
+
+/// A checkpoint is saved after clearing every this many waves.
+pub const CHECKPOINT_INTERVAL: usize = 3;
+/// How many times the player can respawn at a checkpoint during a run.
+pub const MAX_RESPAWNS: usize = 2;
+
+/// A snapshot of the parts of the model needed to continue the run from a cleared wave.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub wave_number: usize,
+    pub waves_cleared: usize,
+    pub left_border: Coord,
+    pub player_energy: Health,
+    pub score: Score,
+    pub units: Vec<(Unit, Body)>,
+    pub obstacles: Vec<Obstacle>,
+    pub doors: Vec<Door>,
+    pub hazards: Vec<Hazard>,
+    pub water: Vec<Water>,
+    pub interactables: Vec<Interactable>,
+    pub triggers: Vec<LevelTrigger>,
+    pub objectives: Vec<Objective>,
+}
+
+impl Model {
+    pub fn save_checkpoint(&mut self) {
+        self.world.checkpoint = Some(Checkpoint {
+            wave_number: self.world.wave_number,
+            waves_cleared: self.world.waves_cleared,
+            left_border: self.world.left_border,
+            player_energy: self.world.player_energy.clone(),
+            score: self.world.score.clone(),
+            units: self
+                .world
+                .units
+                .iter()
+                .filter_map(|unit| Some((unit.clone(), self.world.bodies.get(unit.id)?)))
+                .collect(),
+            obstacles: self.world.obstacles.iter().cloned().collect(),
+            doors: self.world.doors.iter().cloned().collect(),
+            hazards: self.world.hazards.iter().cloned().collect(),
+            water: self.world.water.iter().cloned().collect(),
+            interactables: self.world.interactables.iter().cloned().collect(),
+            triggers: self.world.triggers.clone(),
+            objectives: self.world.objectives.clone(),
+        });
+    }
+
+    /// Rolls the run back to the last checkpoint, returns whether there was one to respawn at.
+    /// The upcoming waves are generated again with a new seed, so they differ from the lost ones.
+    pub fn respawn(&mut self) -> bool {
+        if self.world.respawns >= MAX_RESPAWNS {
+            return false;
+        }
+        let checkpoint = match self.world.checkpoint.clone() {
+            Some(checkpoint) => checkpoint,
+            None => return false,
+        };
+        self.world.respawns += 1;
+        self.world.rng =
+            SimRng::seed_from_u64(self.config.seed.wrapping_add(self.world.respawns as u64));
+
+        self.world.wave_number = checkpoint.wave_number;
+        self.world.waves_cleared = checkpoint.waves_cleared;
+        self.world.left_border = checkpoint.left_border;
+        self.world.player_energy = checkpoint.player_energy;
+        self.world.score = checkpoint.score;
+        self.world.combo.reset();
+        self.world.waves.clear();
+        self.world.spawn_queue.clear();
+        self.world.pending_spawns.clear();
+        self.world.attack_tokens.clear();
+        self.world.wave_in_progress = false;
+
+        for unit in &self.world.units {
+            self.world.id_gen.free(unit.id);
+        }
+        self.world.units = default();
+        self.retain_projectiles(|_| false);
+        self.retain_particles(|_| false);
+        self.world.bodies = default();
+        self.world.remains.clear();
+        self.world.triggers = checkpoint.triggers;
+        self.world.objectives = checkpoint.objectives.clone();
+        for (mut unit, body) in checkpoint.units {
+            // The ids of the units that died since the checkpoint may have been reused
+            let id = self.world.id_gen.gen();
+            for (saved, objective) in checkpoint.objectives.iter().zip(&mut self.world.objectives) {
+                if saved.unit == unit.id {
+                    objective.unit = id;
+                }
+            }
+            unit.id = id;
+            self.world.bodies.insert(id, body);
+            self.world.units.insert(unit);
+        }
+        self.world.obstacles = default();
+        for obstacle in checkpoint.obstacles {
+            self.world.obstacles.insert(obstacle);
+        }
+        self.world.doors = default();
+        for door in checkpoint.doors {
+            self.world.doors.insert(door);
+        }
+        self.world.hazards = default();
+        for hazard in checkpoint.hazards {
+            self.world.hazards.insert(hazard);
+        }
+        self.world.water = default();
+        for water in checkpoint.water {
+            self.world.water.insert(water);
+        }
+        self.world.interactables = default();
+        for object in checkpoint.interactables {
+            self.world.interactables.insert(object);
+        }
+        self.world.delayed_effects.clear();
+        self.world.pickups = default();
+        self.world.pings = default();
+        self.world.melee_swings.clear();
+        self.world.grapples.clear();
+        self.world.zones.clear();
+        true
+    }
+}
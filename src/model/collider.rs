@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Collider {
     /// An Axis-Aligned Bounding Box centered around the origin
     Aabb { size: Vec2<Coord> },
@@ -16,4 +16,29 @@ impl Collider {
             }
         }
     }
+
+    /// Returns the bounding box of the collider placed at the position.
+    pub fn bounds(&self, position: Position) -> AABB<Coord> {
+        match self {
+            Collider::Aabb { size } => {
+                AABB::point(position).extend_symmetric(*size / Coord::new(2.0))
+            }
+        }
+    }
+
+    /// Returns the fraction of the collider's height that is inside the area.
+    pub fn submerged_fraction(&self, position: Position, area: &AABB<Coord>) -> R32 {
+        let a = self.bounds(position);
+        if a.x_min >= area.x_max || a.x_max <= area.x_min || a.height() <= Coord::ZERO {
+            return R32::ZERO;
+        }
+        let inside = a.y_max.min(area.y_max) - a.y_min.max(area.y_min);
+        (inside / a.height()).clamp(R32::ZERO, R32::ONE)
+    }
+
+    /// Checks whether the collider placed at the position overlaps the area.
+    pub fn intersects_area(&self, position: Position, area: &AABB<Coord>) -> bool {
+        let a = self.bounds(position);
+        a.x_min < area.x_max && a.x_max > area.x_min && a.y_min < area.y_max && a.y_max > area.y_min
+    }
 }
@@ -0,0 +1,114 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// The number of entries kept in the log, the oldest are dropped first.
+const MAX_LOG_ENTRIES: usize = 100;
+/// Listed as the source of the damage without a caster, or whose caster is gone.
+pub const OTHER_SOURCE: &str = "other";
+
+/// The recent damage, heals and statuses, together with their totals for the whole run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CombatLog {
+    pub entries: VecDeque<CombatLogEntry>,
+    /// Totals by the name of the unit that caused them.
+    #[serde(deserialize_with = "deserialize_static_str_map")]
+    pub sources: BTreeMap<&'static str, SourceStats>,
+    /// Totals by the name of the enemy.
+    #[serde(deserialize_with = "deserialize_static_str_map")]
+    pub enemies: BTreeMap<&'static str, EnemyStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub time: Time,
+    #[serde(deserialize_with = "deserialize_static_str")]
+    pub source: &'static str,
+    #[serde(deserialize_with = "deserialize_static_str")]
+    pub target: &'static str,
+    pub kind: CombatLogKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CombatLogKind {
+    Damage {
+        amount: Hp,
+        killed: bool,
+    },
+    Heal {
+        amount: Hp,
+    },
+    Status {
+        #[serde(deserialize_with = "deserialize_static_str")]
+        name: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub damage: Hp,
+    pub healing: Hp,
+    pub kills: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnemyStats {
+    pub killed: usize,
+    pub damage_taken: Hp,
+    pub damage_dealt: Hp,
+}
+
+impl CombatLog {
+    pub fn push(
+        &mut self,
+        entry: CombatLogEntry,
+        source_faction: Option<Faction>,
+        target_faction: Faction,
+    ) {
+        match entry.kind {
+            CombatLogKind::Damage { amount, killed } => {
+                let source = self.sources.entry(entry.source).or_default();
+                source.damage += amount;
+                source.kills += killed as usize;
+                if target_faction == Faction::Alien {
+                    let enemy = self.enemies.entry(entry.target).or_default();
+                    enemy.damage_taken += amount;
+                    enemy.killed += killed as usize;
+                }
+                if source_faction == Some(Faction::Alien) {
+                    self.enemies.entry(entry.source).or_default().damage_dealt += amount;
+                }
+            }
+            CombatLogKind::Heal { amount } => {
+                self.sources.entry(entry.source).or_default().healing += amount;
+            }
+            CombatLogKind::Status { .. } => {}
+        }
+        self.entries.push_back(entry);
+        if self.entries.len() > MAX_LOG_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+}
+
+impl Status {
+    /// Name of the status in the combat log.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Charge { .. } => "charge",
+            Status::Stunned { .. } => "stunned",
+            Status::Invulnerable { .. } => "invulnerable",
+            Status::Spawning { .. } => "spawning",
+            Status::Shield { .. } => "shield",
+            Status::Block => "block",
+            Status::Parry { .. } => "parry",
+            Status::Burning { .. } => "burning",
+            Status::Modified { .. } => "modified",
+            Status::ArmorBroken { .. } => "armor broken",
+            Status::Wet { .. } => "wet",
+            Status::SlowFall { .. } => "slow fall",
+            Status::Trail { .. } => "trail",
+            Status::MaxHp { .. } => "max hp",
+        }
+    }
+}
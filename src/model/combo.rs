@@ -0,0 +1,61 @@
+use super::*;
+
+/// Time after a kill within which the next one continues the combo.
+pub const COMBO_WINDOW: f32 = 3.0;
+/// Multiplier gained with every kill in the combo.
+const COMBO_STEP: f32 = 0.1;
+const MAX_COMBO_MULTIPLIER: f32 = 3.0;
+/// Points of a kill before the multiplier.
+const KILL_POINTS: f32 = 10.0;
+/// Kills in every tier of the HUD flair.
+const KILLS_PER_TIER: usize = 5;
+
+/// Kills of the mechs in quick succession, broken by the time running out or the mechs taking damage.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Combo {
+    pub kills: usize,
+    pub time_left: Time,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> R32 {
+        r32((1.0 + COMBO_STEP * self.kills as f32).min(MAX_COMBO_MULTIPLIER))
+    }
+
+    /// How flashy the combo is shown, grows every few kills.
+    pub fn tier(&self) -> usize {
+        self.kills / KILLS_PER_TIER
+    }
+
+    /// Continues the combo, returns the points of the kill.
+    pub fn add_kill(&mut self) -> R32 {
+        self.kills += 1;
+        self.time_left = Time::new(COMBO_WINDOW);
+        r32(KILL_POINTS) * self.multiplier()
+    }
+
+    pub fn reset(&mut self) {
+        *self = default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_multiplier_grows_up_to_the_cap() {
+        let mut combo = Combo::default();
+        let first = combo.add_kill();
+        assert!((first.as_f32() - KILL_POINTS * (1.0 + COMBO_STEP)).abs() < 1e-5);
+        assert!(combo.add_kill() > first);
+        for _ in 0..100 {
+            combo.add_kill();
+        }
+        assert_eq!(combo.multiplier(), r32(MAX_COMBO_MULTIPLIER));
+        assert_eq!(combo.tier(), 102 / KILLS_PER_TIER);
+        combo.reset();
+        assert_eq!(combo.kills, 0);
+        assert_eq!(combo.multiplier(), R32::ONE);
+    }
+}
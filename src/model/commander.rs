@@ -0,0 +1,86 @@
+use super::*;
+
+use game::MechType;
+
+/// The character the player commands the run as, defines the available mechs and their bonuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Commander {
+    /// Balanced squad with faster artillery.
+    Engineer,
+    /// No healers, but tougher and harder hitting tanks.
+    Warlord,
+    /// No artillery, but stronger healers and sturdier mechs.
+    Medic,
+}
+
+impl Commander {
+    pub const ALL: [Self; 3] = [Self::Engineer, Self::Warlord, Self::Medic];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Engineer => "commander.engineer",
+            Self::Warlord => "commander.warlord",
+            Self::Medic => "commander.medic",
+        }
+    }
+
+    /// The locale key of the description.
+    pub fn description_key(&self) -> &'static str {
+        match self {
+            Self::Engineer => "commander.engineer.description",
+            Self::Warlord => "commander.warlord.description",
+            Self::Medic => "commander.medic.description",
+        }
+    }
+
+    /// Mechs the player can spawn, in the order of the slots.
+    pub fn roster(&self) -> Vec<MechType> {
+        match self {
+            Self::Engineer => vec![MechType::Artillery, MechType::Tank, MechType::Healer],
+            Self::Warlord => vec![MechType::Artillery, MechType::Tank],
+            Self::Medic => vec![MechType::Tank, MechType::Healer],
+        }
+    }
+
+    /// Bonuses applied to the mech templates at the start of the run.
+    pub fn modifiers(&self) -> Vec<(MechType, PerkModifier)> {
+        match self {
+            Self::Engineer => vec![(MechType::Artillery, PerkModifier::Cooldown(r32(0.85)))],
+            Self::Warlord => vec![
+                (MechType::Tank, PerkModifier::Health(r32(1.3))),
+                (MechType::Artillery, PerkModifier::Damage(r32(1.15))),
+                (MechType::Tank, PerkModifier::Damage(r32(1.15))),
+            ],
+            Self::Medic => vec![
+                (MechType::Healer, PerkModifier::Healing(r32(1.5))),
+                (MechType::Tank, PerkModifier::Health(r32(1.1))),
+                (MechType::Healer, PerkModifier::Health(r32(1.1))),
+            ],
+        }
+    }
+}
+
+impl Default for Commander {
+    fn default() -> Self {
+        Self::Engineer
+    }
+}
+
+impl UnitTemplates {
+    pub fn mech(&self, mech: MechType) -> &UnitTemplate {
+        match mech {
+            MechType::Artillery => &self.artillery,
+            MechType::Tank => &self.tank,
+            MechType::Healer => &self.healer,
+        }
+    }
+
+    pub fn mech_mut(&mut self, mech: MechType) -> &mut UnitTemplate {
+        match mech {
+            MechType::Artillery => &mut self.artillery,
+            MechType::Tank => &mut self.tank,
+            MechType::Healer => &mut self.healer,
+        }
+    }
+}
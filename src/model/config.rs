@@ -0,0 +1,59 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    Standard,
+    /// Waves keep getting harder, the score is saved in the high score table.
+    Endless,
+    /// Endless mode with the seed of the day, the same for every player.
+    Daily,
+    /// No waves and no losing, any unit can be spawned to try things out.
+    Sandbox,
+}
+
+impl GameMode {
+    pub const ALL: [Self; 4] = [Self::Standard, Self::Endless, Self::Daily, Self::Sandbox];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Standard => "mode.standard",
+            Self::Endless => "mode.endless",
+            Self::Daily => "mode.daily",
+            Self::Sandbox => "mode.sandbox",
+        }
+    }
+
+    pub fn is_sandbox(&self) -> bool {
+        *self == Self::Sandbox
+    }
+
+    /// Whether the waves keep getting harder and the score is saved in the high score table.
+    pub fn is_endless(&self) -> bool {
+        matches!(self, Self::Endless | Self::Daily)
+    }
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+/// Everything that defines a run, identical configs produce identical simulations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfig {
+    /// Seed of the model's rng.
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub commander: Commander,
+    /// Names of the perks that can be offered during the run.
+    pub perks: Vec<String>,
+    /// Language of the cutscene dialogue.
+    #[serde(default)]
+    pub language: Language,
+    /// How strongly the player's aim is pulled towards the enemies, from 0 to 1.
+    #[serde(default)]
+    pub aim_assist: f32,
+}
@@ -0,0 +1,324 @@
+use super::*;
+
+use std::fmt::Display;
+
+/// Number of dialogue characters revealed per second.
+const TYPEWRITER_SPEED: f32 = 40.0;
+
+/// A single line of a cutscene script.
+///
+/// Scripts consist of one command per line, empty lines and lines starting with `#` are ignored:
+/// - `say <speaker>: <text>` shows a dialogue box until the player continues,
+///   the speaker and the text are looked up in the locale strings
+/// - `pan <offset> <duration>` moves the camera relative to its normal position
+/// - `move <mechs|aliens|boss> <distance> <duration>` walks the units horizontally
+/// - `wait <duration>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CutsceneCommand {
+    Say {
+        speaker: String,
+        text: String,
+    },
+    Pan {
+        offset: Coord,
+        duration: Time,
+    },
+    Move {
+        group: UnitGroup,
+        distance: Coord,
+        duration: Time,
+    },
+    Wait {
+        duration: Time,
+    },
+}
+
+/// Units controlled by a cutscene command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitGroup {
+    Mechs,
+    Aliens,
+    Boss,
+}
+
+/// A playing cutscene, the simulation and the player's input are suppressed until it ends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cutscene {
+    pub commands: Vec<CutsceneCommand>,
+    /// Index of the command being played.
+    pub current: usize,
+    /// Time since the current command has started.
+    pub time: Time,
+    /// Horizontal offset of the camera from its normal position.
+    pub camera_offset: Coord,
+    /// Camera offset at the start of the current pan.
+    pan_start: Coord,
+}
+
+impl UnitGroup {
+    pub fn contains(&self, unit: &Unit) -> bool {
+        match self {
+            Self::Mechs => unit.faction == Faction::Mech,
+            Self::Aliens => unit.faction == Faction::Alien,
+            Self::Boss => unit.boss.is_some(),
+        }
+    }
+}
+
+impl Cutscene {
+    pub fn parse(script: &str) -> Result<Self, String> {
+        let commands = script
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| {
+                parse_command(line).map_err(|err| format!("line {}: {}", index + 1, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            commands,
+            current: 0,
+            time: Time::ZERO,
+            camera_offset: Coord::ZERO,
+            pan_start: Coord::ZERO,
+        })
+    }
+
+    pub fn command(&self) -> Option<&CutsceneCommand> {
+        self.commands.get(self.current)
+    }
+
+    /// Whether the whole text of the current dialogue is shown.
+    fn dialogue_finished(&self, text: &str) -> bool {
+        self.time * Time::new(TYPEWRITER_SPEED) >= Time::new(text.chars().count() as f32)
+    }
+
+    /// Returns the speaker and the part of the dialogue revealed so far.
+    pub fn dialogue(&self) -> Option<(&str, String)> {
+        match self.command()? {
+            CutsceneCommand::Say { speaker, text } => {
+                let chars = (self.time.as_f32() * TYPEWRITER_SPEED) as usize;
+                Some((speaker, text.chars().take(chars).collect()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Reveals the whole dialogue, or goes to the next command if it is already revealed.
+    pub fn advance(&mut self) {
+        if let Some(CutsceneCommand::Say { text, .. }) = self.command() {
+            if self.dialogue_finished(text) {
+                self.next();
+            } else {
+                self.time = Time::new(text.chars().count() as f32 / TYPEWRITER_SPEED);
+            }
+        }
+    }
+
+    fn next(&mut self) {
+        self.current += 1;
+        self.time = Time::ZERO;
+        self.pan_start = self.camera_offset;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.commands.len()
+    }
+
+    /// Plays the current command, moving the units controlled by it.
+    pub fn update(
+        &mut self,
+        delta_time: Time,
+        units: &mut Collection<Unit>,
+        bodies: &mut Bodies,
+        animations: &Animations,
+    ) {
+        let command = match self.command() {
+            Some(command) => command.clone(),
+            None => return,
+        };
+        self.time += delta_time;
+        match command {
+            CutsceneCommand::Say { .. } => {}
+            CutsceneCommand::Pan { offset, duration } => {
+                let t = if duration > Time::ZERO {
+                    (self.time / duration).min(Time::ONE)
+                } else {
+                    Time::ONE
+                };
+                self.camera_offset = self.pan_start + (offset - self.pan_start) * t;
+                if self.time >= duration {
+                    self.next();
+                }
+            }
+            CutsceneCommand::Move {
+                group,
+                distance,
+                duration,
+            } => {
+                let done = self.time >= duration;
+                for unit in units.iter_mut().filter(|unit| group.contains(unit)) {
+                    if done {
+                        unit.animation_state.switch(unit.idle_animation, animations);
+                        continue;
+                    }
+                    if duration > Time::ZERO {
+                        bodies.positions[unit.id].x += distance / duration * delta_time;
+                    }
+                    unit.flip_sprite = distance < Coord::ZERO;
+                    unit.animation_state.switch(unit.move_animation, animations);
+                    unit.animation_state.update(delta_time, animations);
+                    unit.animation_state.take_effects();
+                }
+                if done {
+                    self.next();
+                }
+            }
+            CutsceneCommand::Wait { duration } => {
+                if self.time >= duration {
+                    self.next();
+                }
+            }
+        }
+    }
+}
+
+fn parse_command(line: &str) -> Result<CutsceneCommand, String> {
+    let (name, args) = line.split_once(' ').unwrap_or((line, ""));
+    let args = args.trim();
+    match name {
+        "say" => {
+            let (speaker, text) = args
+                .split_once(':')
+                .ok_or_else(|| "expected `say <speaker>: <text>`".to_owned())?;
+            Ok(CutsceneCommand::Say {
+                speaker: speaker.trim().to_owned(),
+                text: text.trim().to_owned(),
+            })
+        }
+        "pan" => match parse_numbers(args)?[..] {
+            [offset, duration] => Ok(CutsceneCommand::Pan {
+                offset: Coord::new(offset),
+                duration: Time::new(duration),
+            }),
+            _ => Err("expected `pan <offset> <duration>`".to_owned()),
+        },
+        "move" => {
+            let (group, rest) = args.split_once(' ').unwrap_or((args, ""));
+            let group = match group {
+                "mechs" => UnitGroup::Mechs,
+                "aliens" => UnitGroup::Aliens,
+                "boss" => UnitGroup::Boss,
+                _ => return Err(format!("unknown unit group {:?}", group)),
+            };
+            match parse_numbers(rest)?[..] {
+                [distance, duration] => Ok(CutsceneCommand::Move {
+                    group,
+                    distance: Coord::new(distance),
+                    duration: Time::new(duration),
+                }),
+                _ => Err("expected `move <group> <distance> <duration>`".to_owned()),
+            }
+        }
+        "wait" => match parse_numbers(args)?[..] {
+            [duration] => Ok(CutsceneCommand::Wait {
+                duration: Time::new(duration),
+            }),
+            _ => Err("expected `wait <duration>`".to_owned()),
+        },
+        _ => Err(format!("unknown command {:?}", name)),
+    }
+}
+
+fn parse_numbers(args: &str) -> Result<Vec<f32>, String> {
+    args.split_whitespace()
+        .map(|arg| {
+            arg.parse::<f32>()
+                .map_err(|_| format!("expected a number, found {:?}", arg))
+        })
+        .collect()
+}
+
+impl Model {
+    /// Starts the cutscene from the script, logging the error if the script is invalid.
+    /// Every `{name}` in the dialogue is replaced with the translated value of the argument.
+    pub fn play_cutscene(&mut self, script: &str, args: &[(&str, &str)]) {
+        let mut cutscene = match Cutscene::parse(script) {
+            Ok(cutscene) => cutscene,
+            Err(err) => {
+                error!("Failed to parse the cutscene script: {}", err);
+                return;
+            }
+        };
+        let locale = Locale::new(&self.assets().menu, self.config.language);
+        let values: Vec<&str> = args.iter().map(|&(_, value)| locale.get(value)).collect();
+        let args: Vec<(&str, &dyn Display)> = args
+            .iter()
+            .zip(&values)
+            .map(|(&(name, _), value)| (name, value as &dyn Display))
+            .collect();
+        for command in &mut cutscene.commands {
+            if let CutsceneCommand::Say { speaker, text } = command {
+                *speaker = locale.get(speaker).to_owned();
+                *text = locale.format(text, &args);
+            }
+        }
+        self.world.cutscene = Some(cutscene);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_command() {
+        let cutscene = Cutscene::parse(
+            "# The intro
+            say cutscene.commander: cutscene.intro
+
+            pan 5 1.5
+            move boss -3 2
+            wait 0.5",
+        )
+        .unwrap();
+        assert_eq!(cutscene.commands.len(), 4);
+        assert!(matches!(
+            &cutscene.commands[0],
+            CutsceneCommand::Say { speaker, text }
+                if speaker == "cutscene.commander" && text == "cutscene.intro"
+        ));
+        assert!(matches!(
+            cutscene.commands[1],
+            CutsceneCommand::Pan { offset, duration }
+                if offset == Coord::new(5.0) && duration == Time::new(1.5)
+        ));
+        assert!(matches!(
+            cutscene.commands[2],
+            CutsceneCommand::Move { group: UnitGroup::Boss, distance, duration }
+                if distance == Coord::new(-3.0) && duration == Time::new(2.0)
+        ));
+        assert!(matches!(
+            cutscene.commands[3],
+            CutsceneCommand::Wait { duration } if duration == Time::new(0.5)
+        ));
+        assert!(!cutscene.is_finished());
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let error = Cutscene::parse("wait 1\n\njump 2").unwrap_err();
+        assert_eq!(error, "line 3: unknown command \"jump\"");
+        let error = Cutscene::parse("move mechs far 1").unwrap_err();
+        assert_eq!(error, "line 1: expected a number, found \"far\"");
+        assert!(Cutscene::parse("pan 1").is_err());
+        assert!(Cutscene::parse("say nobody").is_err());
+        assert!(Cutscene::parse("move everyone 1 1").is_err());
+    }
+
+    #[test]
+    fn empty_script_is_finished() {
+        assert!(Cutscene::parse("# Nothing\n\n").unwrap().is_finished());
+    }
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+const DAMAGE_INDICATOR_TIME: f32 = 1.0;
+
+/// Points from the edge of the screen towards where a mech was hit from.
+#[derive(Debug, Clone)]
+pub struct DamageIndicator {
+    /// Position of the caster at the moment of the hit.
+    pub source: Position,
+    pub time_left: Time,
+}
+
+impl DamageIndicator {
+    pub fn new(source: Position) -> Self {
+        Self {
+            source,
+            time_left: Time::new(DAMAGE_INDICATOR_TIME),
+        }
+    }
+
+    /// The share of the time left, from 1 to 0.
+    pub fn fade(&self) -> f32 {
+        (self.time_left / Time::new(DAMAGE_INDICATOR_TIME))
+            .as_f32()
+            .clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+/// Length of the day in the endless modes when the level does not set one, in seconds.
+const ENDLESS_DAY_LENGTH: f32 = 240.0;
+
+/// The lighting schedule of the run: the world darkens towards the night and the enemies get
+/// tougher, then the day comes back.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DayCycle {
+    /// Time from one noon to the next.
+    pub length: Time,
+}
+
+impl DayCycle {
+    /// The cycle of the level, the endless modes always have one.
+    pub fn new(day_length: Option<f32>, mode: GameMode) -> Option<Self> {
+        day_length
+            .or_else(|| mode.is_endless().then(|| ENDLESS_DAY_LENGTH))
+            .filter(|&length| length > 0.0)
+            .map(|length| Self {
+                length: Time::new(length),
+            })
+    }
+}
+
+impl Model {
+    /// How dark it is, from 0 at noon, when the run starts, to 1 at midnight.
+    pub fn night(&self) -> R32 {
+        let cycle = match self.world.day_cycle {
+            Some(cycle) => cycle,
+            None => return R32::ZERO,
+        };
+        let phase = (self.time() / cycle.length).as_f32() * std::f32::consts::TAU;
+        r32(0.5 - 0.5 * phase.cos())
+    }
+}
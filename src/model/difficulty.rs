@@ -0,0 +1,80 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// Multipliers applied to the enemies when they are spawned.
+#[derive(Debug, Clone, Copy)]
+pub struct DifficultyScaling {
+    pub enemy_health: R32,
+    pub enemy_damage: R32,
+    /// Scales the difficulty budget of the generated waves.
+    pub spawn_count: R32,
+    pub projectile_speed: R32,
+    /// Scales the reaction time of the enemy AI.
+    pub reaction_time: R32,
+    /// Scales the aim error of the enemy AI.
+    pub aim_error: R32,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Easy => "difficulty.easy",
+            Self::Normal => "difficulty.normal",
+            Self::Hard => "difficulty.hard",
+        }
+    }
+
+    pub fn scaling(&self) -> DifficultyScaling {
+        let (enemy_health, enemy_damage, spawn_count, projectile_speed) = match self {
+            Self::Easy => (0.7, 0.7, 0.75, 0.85),
+            Self::Normal => (1.0, 1.0, 1.0, 1.0),
+            Self::Hard => (1.5, 1.3, 1.4, 1.15),
+        };
+        let (reaction_time, aim_error) = match self {
+            Self::Easy => (1.5, 1.5),
+            Self::Normal => (1.0, 1.0),
+            Self::Hard => (0.5, 0.5),
+        };
+        DifficultyScaling {
+            enemy_health: r32(enemy_health),
+            enemy_damage: r32(enemy_damage),
+            spawn_count: r32(spawn_count),
+            projectile_speed: r32(projectile_speed),
+            reaction_time: r32(reaction_time),
+            aim_error: r32(aim_error),
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl UnitTemplate {
+    pub fn apply_difficulty(&mut self, scaling: &DifficultyScaling) {
+        self.health.set_max_hp(
+            self.health.max_hp * scaling.enemy_health,
+            MaxHpChange::Scale,
+        );
+        self.stats
+            .add(Stat::Attack, ModifierKind::More, scaling.enemy_damage);
+        self.stats.add(
+            Stat::ProjectileSpeed,
+            ModifierKind::More,
+            scaling.projectile_speed,
+        );
+        self.skill.reaction_time *= scaling.reaction_time;
+        self.skill.aim_error *= scaling.aim_error;
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+pub const DOOR_SIZE: Vec2<f32> = vec2(1.0, 4.0);
+
+/// A gate across the level that no unit can walk through until a trigger opens it.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Door {
+    pub id: Id,
+    pub position: Position,
+    pub size: Vec2<Coord>,
+}
+
+impl Door {
+    pub fn new(id: Id, x: Coord, ground_level: Coord) -> Self {
+        let size = DOOR_SIZE.map(Coord::new);
+        Self {
+            id,
+            position: vec2(x, ground_level + size.y / Coord::new(2.0)),
+            size,
+        }
+    }
+
+    /// Keeps a unit moving from `previous_x` on its side of the door, the flying units pass over it.
+    /// Returns whether the unit ran into the door.
+    pub fn block(&self, previous_x: Coord, position: &mut Position) -> bool {
+        if position.y > self.position.y + self.size.y / Coord::new(2.0) {
+            return false;
+        }
+        let half_width = self.size.x / Coord::new(2.0);
+        if previous_x <= self.position.x && position.x > self.position.x - half_width {
+            position.x = self.position.x - half_width;
+            true
+        } else if previous_x > self.position.x && position.x < self.position.x + half_width {
+            position.x = self.position.x + half_width;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -1,147 +1,801 @@
 use super::*;
 use logic::*;
 
+use std::{collections::HashSet, sync::Arc};
+
+/// Maximum number of refinements of the flight time of a fully predicted shot.
+const AIM_MAX_ITERATIONS: usize = 10;
+/// The flight time has converged once it changes by less than this.
+const AIM_TOLERANCE: f32 = 0.01;
+
+/// Index of a node in the arena of an [Effect].
+pub type EffectId = usize;
+
+/// The arena of an effect tree, see [Effect::walk_mut].
+pub type EffectNodes = Vec<EffectNode<EffectId>>;
+
+/// A tree of effects.
+///
+/// The nodes are stored in an arena and refer to their children by index. The arena is shared
+/// between the clones of the tree and its subtrees, so cloning only copies the pointer to it.
+/// Modifying a node through [Effect::walk_mut] copies the arena if it is still shared.
+/// Serialized as the nested nodes, the arena is rebuilt when deserialized.
 #[derive(Debug, Clone)]
-pub enum Effect {
+pub struct Effect {
+    nodes: Arc<EffectNodes>,
+    root: EffectId,
+}
+
+impl Serialize for Effect {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.expand().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Effect {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        EffectNode::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// A node of an effect tree. The children are referred to as `E`: trees of their own
+/// when the node is built or processed, indices into the arena when it is stored in a tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EffectNode<E = Effect> {
     Noop,
-    List(Box<ListEffect>),
-    Sound(Box<SoundEffect>),
-    Projectile(Box<ProjectileEffect>),
-    Damage(Box<DamageEffect>),
-    Heal(Box<HealEffect>),
-    Dash(Box<DashEffect>),
+    List(ListEffect<E>),
+    Sound(SoundEffect),
+    Projectile(ProjectileEffect<E>),
+    Damage(DamageEffect),
+    Heal(HealEffect),
+    Dash(DashEffect<E>),
+    Area(AreaEffect<E>),
+    /// Fire the caster's equipped weapon
+    FireWeapon,
+    GiveWeapon(GiveWeaponEffect),
+    RestoreAmmo(RestoreAmmoEffect),
+    MeleeSwing(MeleeSwingEffect<E>),
+    Grapple(GrappleEffect<E>),
+    SlowFall(SlowFallEffect),
+    ApplyStatus(ApplyStatusEffect<E>),
+    ModifyMaxHp(ModifyMaxHpEffect),
+    Script(ScriptEffect),
+    SpawnZone(SpawnZoneEffect<E>),
 }
 
-#[derive(Debug, Clone)]
-pub struct ListEffect {
-    pub effects: Vec<Effect>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListEffect<E = Effect> {
+    pub effects: Vec<E>,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoundEffect {
-    pub sound: Rc<geng::Sound>,
+    pub sound: SoundId,
 }
 
-#[derive(Debug, Clone)]
-pub struct ProjectileEffect {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectileEffect<E = Effect> {
     pub offset: Position,
+    /// Name of the caster's attachment point to launch from, overrides the offset
+    pub attachment: Option<String>,
     pub ai: ProjectileAI,
     pub collider: Collider,
     pub speed: Coord,
-    pub on_hit: Effect,
-    pub animation: Rc<Animation>,
+    /// Maximum random deviation from the aimed direction, in radians
+    pub spread: Coord,
+    /// Number of projectiles launched per shot
+    pub pellets: usize,
+    /// Angle the pellets are fanned out over evenly, in radians
+    pub fan: Coord,
+    /// Fires several shots in a row instead of one
+    pub burst: Option<Burst>,
+    /// Distance after which the projectile expires
+    pub max_range: Option<Coord>,
+    /// Distance the projectile has to travel before it can hit anything
+    pub arming_distance: Coord,
+    /// Scales the damage down with the distance traveled
+    pub falloff: Option<Falloff>,
+    /// How the projectile leads a moving target
+    pub prediction: AimPrediction,
+    /// What to do if the caster or the target is gone by the time the effect resolves,
+    /// applies to the hit and expire effects too
+    pub missing: MissingUnit,
+    pub on_hit: E,
+    /// Triggered when the projectile runs out of lifetime or leaves the world
+    pub on_expire: E,
+    pub animation: AnimationId,
+    /// Destroys the enemy projectiles it touches, along with itself
+    pub intercepts: bool,
+    /// How long the projectile lasts, [PROJECTILE_LIFETIME] if not set
+    pub lifetime: Option<Time>,
 }
 
-#[derive(Debug, Clone)]
+/// How long the projectiles last by default, most leave the world or hit something earlier.
+pub const PROJECTILE_LIFETIME: f32 = 10.0;
+
+/// How an effect resolves when a unit it refers to is no longer in the model,
+/// e.g. the caster of a projectile died while it was in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissingUnit {
+    /// The effects that need the unit do nothing
+    Skip,
+    /// A missing target is replaced by the closest enemy, a missing caster skips the effect
+    Retarget,
+    /// The missing unit is replaced by its faction and the position it died at
+    LastKnownPosition,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Burst {
+    pub shots: usize,
+    /// Time between the shots of the burst
+    pub delay: Time,
+}
+
+/// An effect processed once the delay runs out, e.g. the later shots of a burst.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelayedEffect {
+    pub time_left: Time,
+    pub effect: Effect,
+    pub context: EffectContext,
+}
+
+/// How a projectile leads a moving target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AimPrediction {
+    /// Aim at the current position of the target.
+    None,
+    /// Lead the target by the straight-line flight time.
+    Linear,
+    /// Refine the parabolic flight time until it converges.
+    Full,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DamageType {
     Physical,
+    /// Lasers and plasma, cannot break obstacles.
+    Energy,
     Explosive,
 }
 
-#[derive(Debug, Clone)]
+/// An amount of hp, evaluated when the effect is processed.
+/// The relative values take fractions, e.g. `0.1` for a tenth of the maximum hp.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Value {
+    Flat(Hp),
+    /// Fraction of the target's maximum hp
+    TargetMaxHp(R32),
+    /// Fraction of the caster's maximum hp, zero if there is no caster
+    CasterMaxHp(R32),
+    /// Fraction of the caster's stat, zero if there is no caster
+    CasterStat(Stat, R32),
+}
+
+/// The caster's side of the [Value]s, a unit with its health or a template previewed in the codex.
+pub trait ValueSource {
+    fn max_hp(&self) -> Hp;
+    fn stat_value(&self, stat: Stat) -> R32;
+}
+
+impl ValueSource for (&Unit, &Health) {
+    fn max_hp(&self) -> Hp {
+        self.1.max_hp
+    }
+
+    fn stat_value(&self, stat: Stat) -> R32 {
+        self.0.stat(stat)
+    }
+}
+
+impl ValueSource for UnitTemplate {
+    fn max_hp(&self) -> Hp {
+        self.health.max_hp
+    }
+
+    fn stat_value(&self, stat: Stat) -> R32 {
+        self.stats.value(stat)
+    }
+}
+
+impl Value {
+    pub fn evaluate(&self, caster: Option<&dyn ValueSource>, target: &Health) -> Hp {
+        match self {
+            Self::Flat(value) => *value,
+            Self::TargetMaxHp(fraction) => target.max_hp * *fraction,
+            Self::CasterMaxHp(fraction) => {
+                caster.map_or(Hp::ZERO, |caster| caster.max_hp() * *fraction)
+            }
+            Self::CasterStat(stat, fraction) => {
+                caster.map_or(Hp::ZERO, |caster| caster.stat_value(*stat) * *fraction)
+            }
+        }
+    }
+
+    /// The part of the value that does not depend on the context.
+    pub fn flat(&self) -> Hp {
+        match self {
+            Self::Flat(value) => *value,
+            Self::TargetMaxHp(_) | Self::CasterMaxHp(_) | Self::CasterStat(..) => Hp::ZERO,
+        }
+    }
+
+    pub fn scale(&mut self, multiplier: R32) {
+        match self {
+            Self::Flat(value)
+            | Self::TargetMaxHp(value)
+            | Self::CasterMaxHp(value)
+            | Self::CasterStat(_, value) => {
+                *value *= multiplier;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DamageEffect {
     pub damage_type: DamageType,
-    pub value: Hp,
+    pub value: Value,
+    /// Whether the caster can be damaged by their own effect
+    pub hits_caster: bool,
+    /// Overrides the default impact of the damage type
+    pub impact: Option<Impact>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealEffect {
-    pub value: Hp,
+    pub value: Value,
+    /// Whether the healing over the maximum hp turns into a temporary shield
+    pub overheal_shield: bool,
 }
 
-#[derive(Debug, Clone)]
-pub struct DashEffect {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashEffect<E = Effect> {
     pub speed: Coord,
     pub duration: Time,
-    pub on_contact: Effect,
+    pub on_contact: E,
+}
+
+/// Applies the effect to every unit in the radius, setting them as the target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaEffect<E = Effect> {
+    /// Whose position is the center of the area, see [EffectContext::position_of].
+    pub center: Who,
+    pub radius: Coord,
+    pub filter: FactionFilter,
+    /// Whether the caster is affected as well
+    pub hits_caster: bool,
+    /// Scales the damage down with the distance from the center
+    pub falloff: Option<Falloff>,
+    pub effect: E,
+}
+
+/// Deals full damage up to `start`, scaling down linearly to `min` at `end` and further.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Falloff {
+    pub start: Coord,
+    pub end: Coord,
+    pub min: R32,
+}
+
+impl Falloff {
+    pub fn multiplier(&self, distance: Coord) -> R32 {
+        if distance <= self.start {
+            return R32::ONE;
+        }
+        let range = self.end - self.start;
+        // A falloff that ends before it starts drops straight to the minimum
+        if distance >= self.end || range <= Coord::ZERO {
+            return self.min;
+        }
+        let t = (distance - self.start) / range;
+        R32::ONE - (R32::ONE - self.min) * t
+    }
+}
+
+/// Selects units based on their faction relative to the caster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FactionFilter {
+    Enemies,
+    Allies,
+    All,
+}
+
+impl FactionFilter {
+    /// Checks the faction against the caster's, every faction passes if there is no caster.
+    pub fn check(&self, caster: Option<Faction>, faction: Faction) -> bool {
+        match (self, caster) {
+            (_, None) | (Self::All, _) => true,
+            (Self::Enemies, Some(caster)) => caster != faction,
+            (Self::Allies, Some(caster)) => caster == faction,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct SpawnCoinEffect {}
 
 impl Effect {
+    /// Builds a tree out of the node, the children are copied into its arena.
+    pub fn new(node: EffectNode) -> Self {
+        let mut nodes = EffectNodes::new();
+        let node = node.map_children(&mut |child| child.copy_into(&mut nodes));
+        nodes.push(node);
+        Self {
+            root: nodes.len() - 1,
+            nodes: Arc::new(nodes),
+        }
+    }
+
+    pub fn noop() -> Self {
+        Self::new(EffectNode::Noop)
+    }
+
+    /// Copies the tree into the arena of another tree, returns the index of its root there.
+    pub fn copy_into(&self, nodes: &mut EffectNodes) -> EffectId {
+        let node = self
+            .node()
+            .clone()
+            .map_children(&mut |child| self.subtree(child).copy_into(nodes));
+        nodes.push(node);
+        nodes.len() - 1
+    }
+
+    /// The root node of the tree.
+    pub fn node(&self) -> &EffectNode<EffectId> {
+        &self.nodes[self.root]
+    }
+
+    /// The tree starting at a node of this one, sharing the arena.
+    pub fn subtree(&self, id: EffectId) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            root: id,
+        }
+    }
+
+    /// The root node with its children as subtrees.
+    pub fn expand(&self) -> EffectNode {
+        self.node()
+            .clone()
+            .map_children(&mut |child| self.subtree(child))
+    }
+
+    pub fn is_noop(&self) -> bool {
+        matches!(self.node(), EffectNode::Noop)
+    }
+
     pub fn process(self, context: EffectContext, logic: &mut Logic) {
-        match self {
-            Effect::Noop => {}
-            Effect::Projectile(effect) => {
+        match self.expand() {
+            EffectNode::Noop => {}
+            EffectNode::Projectile(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::Damage(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::Heal(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::Dash(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::Sound(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::List(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::Area(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::FireWeapon => {
+                fire_weapon(context, logic);
+            }
+            EffectNode::GiveWeapon(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::RestoreAmmo(effect) => {
+                effect.process(context, logic);
+            }
+            EffectNode::MeleeSwing(effect) => {
                 effect.process(context, logic);
             }
-            Effect::Damage(effect) => {
+            EffectNode::Grapple(effect) => {
                 effect.process(context, logic);
             }
-            Effect::Heal(effect) => {
+            EffectNode::SlowFall(effect) => {
                 effect.process(context, logic);
             }
-            Effect::Dash(effect) => {
+            EffectNode::ApplyStatus(effect) => {
                 effect.process(context, logic);
             }
-            Effect::Sound(effect) => {
+            EffectNode::ModifyMaxHp(effect) => {
                 effect.process(context, logic);
             }
-            Effect::List(effect) => {
+            EffectNode::Script(effect) => {
                 effect.process(context, logic);
             }
+            EffectNode::SpawnZone(effect) => {
+                effect.process(context, logic);
+            }
+        }
+    }
+}
+
+impl Effect {
+    /// Calls `f` once on every node of the tree, parents before children, including the trees
+    /// of the weapons it gives. `f` can add nodes to the arena it is given, e.g. to wrap
+    /// a child, the added nodes are not visited.
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&mut EffectNode<EffectId>, &mut EffectNodes)) {
+        let ids = self.reachable();
+        let nodes = Arc::make_mut(&mut self.nodes);
+        for id in ids {
+            // The node is taken out, so that `f` can push to the arena while changing it
+            let mut node = std::mem::replace(&mut nodes[id], EffectNode::Noop);
+            f(&mut node, nodes);
+            if let EffectNode::GiveWeapon(effect) = &mut node {
+                effect.weapon.effect.walk_mut(f);
+            }
+            nodes[id] = node;
+        }
+    }
+
+    /// Indices of the nodes reachable from the root, each once, parents before children.
+    fn reachable(&self) -> Vec<EffectId> {
+        let mut visited = HashSet::new();
+        let mut ids = vec![];
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            ids.push(id);
+            stack.extend(self.nodes[id].children().into_iter().rev());
+        }
+        ids
+    }
+
+    /// The subtrees nested directly in the root, including the tree of the given weapon.
+    pub fn children(&self) -> Vec<Effect> {
+        match self.node() {
+            EffectNode::GiveWeapon(effect) => vec![effect.weapon.effect.clone()],
+            node => node
+                .children()
+                .into_iter()
+                .map(|child| self.subtree(child))
+                .collect(),
+        }
+    }
+
+    /// The node at the path of indices into [Effect::children], the arena is copied if shared.
+    pub fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut EffectNode<EffectId>> {
+        let mut id = self.root;
+        let mut path = path;
+        loop {
+            let (&index, rest) = match path.split_first() {
+                Some(split) => split,
+                None => return Arc::make_mut(&mut self.nodes).get_mut(id),
+            };
+            if matches!(self.nodes[id], EffectNode::GiveWeapon(_)) {
+                return match &mut Arc::make_mut(&mut self.nodes)[id] {
+                    EffectNode::GiveWeapon(effect) if index == 0 => {
+                        effect.weapon.effect.node_at_mut(rest)
+                    }
+                    _ => None,
+                };
+            }
+            id = *self.nodes[id].children().get(index)?;
+            path = rest;
+        }
+    }
+
+    /// Whether the tree fires the caster's weapon.
+    pub fn fires_weapon(&self) -> bool {
+        match self.node() {
+            EffectNode::FireWeapon => true,
+            EffectNode::List(effect) => effect
+                .effects
+                .iter()
+                .any(|&child| self.subtree(child).fires_weapon()),
+            _ => false,
+        }
+    }
+
+    /// Returns the first projectile effect in the tree, if any.
+    pub fn find_projectile(&self) -> Option<&ProjectileEffect<EffectId>> {
+        match &self.nodes[self.find_projectile_from(self.root)?] {
+            EffectNode::Projectile(effect) => Some(effect),
+            _ => None,
+        }
+    }
+
+    /// The first projectile effect in the tree along with its children, if any.
+    pub fn projectile_subtree(&self) -> Option<Effect> {
+        Some(self.subtree(self.find_projectile_from(self.root)?))
+    }
+
+    fn find_projectile_from(&self, id: EffectId) -> Option<EffectId> {
+        match &self.nodes[id] {
+            EffectNode::Projectile(_) => Some(id),
+            EffectNode::List(effect) => effect
+                .effects
+                .iter()
+                .find_map(|&child| self.find_projectile_from(child)),
+            _ => None,
+        }
+    }
+}
+
+impl EffectNode<EffectId> {
+    /// Indices of the children of the node in its arena.
+    pub fn children(&self) -> Vec<EffectId> {
+        match self {
+            Self::List(effect) => effect.effects.clone(),
+            Self::Projectile(effect) => vec![effect.on_hit, effect.on_expire],
+            Self::Dash(effect) => vec![effect.on_contact],
+            Self::Area(effect) => vec![effect.effect],
+            Self::MeleeSwing(effect) => vec![effect.effect],
+            Self::Grapple(effect) => vec![effect.on_attach],
+            Self::ApplyStatus(effect) => vec![effect.on_expire],
+            Self::SpawnZone(effect) => vec![effect.area.effect],
+            Self::Noop
+            | Self::Sound(_)
+            | Self::Damage(_)
+            | Self::Heal(_)
+            | Self::FireWeapon
+            | Self::GiveWeapon(_)
+            | Self::RestoreAmmo(_)
+            | Self::SlowFall(_)
+            | Self::ModifyMaxHp(_)
+            | Self::Script(_) => vec![],
+        }
+    }
+}
+
+impl<E> EffectNode<E> {
+    /// Changes how the node refers to its children, keeping the rest of the node.
+    pub fn map_children<T>(self, f: &mut impl FnMut(E) -> T) -> EffectNode<T> {
+        match self {
+            Self::Noop => EffectNode::Noop,
+            Self::List(effect) => EffectNode::List(ListEffect {
+                effects: effect.effects.into_iter().map(&mut *f).collect(),
+            }),
+            Self::Sound(effect) => EffectNode::Sound(effect),
+            Self::Projectile(effect) => EffectNode::Projectile(effect.map_children(f)),
+            Self::Damage(effect) => EffectNode::Damage(effect),
+            Self::Heal(effect) => EffectNode::Heal(effect),
+            Self::Dash(effect) => EffectNode::Dash(DashEffect {
+                speed: effect.speed,
+                duration: effect.duration,
+                on_contact: f(effect.on_contact),
+            }),
+            Self::Area(effect) => EffectNode::Area(effect.map_children(f)),
+            Self::FireWeapon => EffectNode::FireWeapon,
+            Self::GiveWeapon(effect) => EffectNode::GiveWeapon(effect),
+            Self::RestoreAmmo(effect) => EffectNode::RestoreAmmo(effect),
+            Self::MeleeSwing(effect) => EffectNode::MeleeSwing(MeleeSwingEffect {
+                shape: effect.shape,
+                attachment: effect.attachment,
+                duration: effect.duration,
+                effect: f(effect.effect),
+                deflect: effect.deflect,
+            }),
+            Self::Grapple(effect) => EffectNode::Grapple(GrappleEffect {
+                attachment: effect.attachment,
+                speed: effect.speed,
+                max_length: effect.max_length,
+                reel_speed: effect.reel_speed,
+                duration: effect.duration,
+                on_attach: f(effect.on_attach),
+            }),
+            Self::SlowFall(effect) => EffectNode::SlowFall(effect),
+            Self::ApplyStatus(effect) => EffectNode::ApplyStatus(ApplyStatusEffect {
+                status: effect.status,
+                on_expire: f(effect.on_expire),
+            }),
+            Self::ModifyMaxHp(effect) => EffectNode::ModifyMaxHp(effect),
+            Self::Script(effect) => EffectNode::Script(effect),
+            Self::SpawnZone(effect) => EffectNode::SpawnZone(SpawnZoneEffect {
+                kind: effect.kind,
+                center: effect.center,
+                duration: effect.duration,
+                tick_time: effect.tick_time,
+                stacking: effect.stacking,
+                area: effect.area.map_children(f),
+            }),
+        }
+    }
+}
+
+impl<E> ProjectileEffect<E> {
+    fn map_children<T>(self, f: &mut impl FnMut(E) -> T) -> ProjectileEffect<T> {
+        ProjectileEffect {
+            offset: self.offset,
+            attachment: self.attachment,
+            ai: self.ai,
+            collider: self.collider,
+            speed: self.speed,
+            spread: self.spread,
+            pellets: self.pellets,
+            fan: self.fan,
+            burst: self.burst,
+            max_range: self.max_range,
+            arming_distance: self.arming_distance,
+            falloff: self.falloff,
+            prediction: self.prediction,
+            missing: self.missing,
+            on_hit: f(self.on_hit),
+            on_expire: f(self.on_expire),
+            animation: self.animation,
+            intercepts: self.intercepts,
+            lifetime: self.lifetime,
+        }
+    }
+}
+
+impl<E> AreaEffect<E> {
+    fn map_children<T>(self, f: &mut impl FnMut(E) -> T) -> AreaEffect<T> {
+        AreaEffect {
+            center: self.center,
+            radius: self.radius,
+            filter: self.filter,
+            hits_caster: self.hits_caster,
+            falloff: self.falloff,
+            effect: f(self.effect),
         }
     }
 }
 
 impl ProjectileEffect {
-    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
-        let caster = context.get_expect(Who::Caster, logic);
-        let target = context.get(Who::Target, logic)?;
-        let mut offset = if let Some(ExtraUnitRender::Tank {
-            hand_pos,
-            weapon_pos,
-            shoot_pos,
-            rotation,
-        }) = caster.extra_render
-        {
-            hand_pos + (weapon_pos + shoot_pos).rotate(rotation)
-        } else {
-            self.offset
+    pub fn process(mut self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let context = EffectContext {
+            missing: self.missing,
+            ..context
         };
-        if caster.flip_sprite {
-            offset.x = -offset.x;
+        if let Some(burst) = self.burst.take() {
+            for shot in 1..burst.shots {
+                logic.model.world.delayed_effects.push(DelayedEffect {
+                    time_left: burst.delay * Time::new(shot as f32),
+                    effect: Effect::new(EffectNode::Projectile(self.clone())),
+                    context: context.clone(),
+                });
+            }
         }
-        let position = offset + caster.position;
 
-        // Use simple prediction for better aim
-        let delta = target.position - position;
-        let time = if self.speed.approx_eq(&Coord::ZERO) {
-            Time::ZERO
-        } else {
-            delta.len() / self.speed
+        let (faction, speed, position, skill) = match context.get(Who::Caster, logic) {
+            Some(caster) => (
+                caster.faction,
+                self.speed * caster.stat(Stat::ProjectileSpeed),
+                self.launch_position(caster, &logic.model.world.bodies),
+                caster.skill,
+            ),
+            None => {
+                let (faction, position) = context.resolve_missing(Who::Caster, logic)?;
+                (
+                    faction,
+                    self.speed,
+                    position + self.offset,
+                    AiSkill::PERFECT,
+                )
+            }
         };
-        let target_pos = target.position + target.velocity * time;
+        let bodies = &logic.model.world.bodies;
+        let target = context.get(Who::Target, logic);
+        let (target_position, target_velocity) = match target {
+            Some(target) => target.perceived_motion(bodies, skill.reaction_time),
+            None => (context.position_of(Who::Target, logic)?, Velocity::ZERO),
+        };
+        let target_id = target.map(|target| target.id).or(context.target);
+        let target_acceleration = target.map_or(Velocity::ZERO, |target| {
+            target.expected_acceleration(
+                bodies,
+                logic.model.world.gravity,
+                logic.model.world.ground_level,
+            )
+        });
+        // The target stops falling once it lands
+        let floor = target
+            .and_then(|target| {
+                let position = bodies.positions[target.id];
+                let height = position.y - bodies.bounds(target.id)?.y_min;
+                Some((logic.model.world.ground_level + height).min(position.y))
+            })
+            .unwrap_or(target_position.y);
 
-        // Aim at target_pos, accounting for gravity
-        let gravity = logic.model.gravity.y;
-        let options = aim_parabollically(target_pos - position, gravity, self.speed);
-        let target_real_pos = target.position;
-        let target_vel = target.velocity;
+        // Aim at the predicted position, accounting for gravity
+        let delta = target_position - position;
+        let error = if skill.aim_error > Coord::ZERO {
+            logic
+                .model
+                .world
+                .rng
+                .gen_range(-skill.aim_error..=skill.aim_error)
+        } else {
+            Coord::ZERO
+        };
+        let predict = |time: Time| {
+            let mut target = target_position
+                + target_velocity * time
+                + target_acceleration * time * time / Time::new(2.0);
+            target.y = target.y.max(floor);
+            (target - position).rotate(error)
+        };
+        let gravity = logic.model.world.gravity.y;
+        let aim = solve_aim(predict, gravity, speed, self.prediction)
+            // The target is out of reach, get as close as possible
+            .unwrap_or_else(|| max_range_velocity(delta, speed));
 
-        let options = options.and_then(|(_, time)| {
-            let target_pos = target_real_pos + target_vel * time;
-            aim_parabollically(target_pos - position, gravity, self.speed)
-        });
-        let velocity = options
-            .map(|(v, _)| v)
-            .unwrap_or((target_pos - position).normalize_or_zero() * self.speed);
-        logic.model.projectiles.insert(Projectile {
-            friend_faction: Some(caster.faction),
-            id: logic.model.id_gen.gen(),
-            animation_state: AnimationState::new(&self.animation),
-            ai: self.ai,
-            lifetime: Time::new(10.0),
-            collider: self.collider,
-            on_hit: self.on_hit,
-            caster: context.caster,
-            target: context.target,
-            position,
-            velocity,
+        let caster_position = context.get(Who::Caster, logic).map_or(position, |caster| {
+            logic.model.world.bodies.positions[caster.id]
         });
+        for pellet in 0..self.pellets {
+            let mut angle = if self.pellets > 1 {
+                self.fan * (Coord::new(pellet as f32 / (self.pellets - 1) as f32) - r32(0.5))
+            } else {
+                Coord::ZERO
+            };
+            if self.spread > Coord::ZERO {
+                angle += logic.model.world.rng.gen_range(-self.spread..=self.spread);
+            }
+            let velocity = aim.rotate(angle);
+            let mut ai = self.ai.clone();
+            match &mut ai {
+                // The orbs are spread evenly around the caster
+                ProjectileAI::Orbit { angle: start, .. } => {
+                    *start += velocity.arg()
+                        + Coord::new(std::f32::consts::TAU * pellet as f32 / self.pellets as f32);
+                }
+                ProjectileAI::Attached { offset } => *offset = position - caster_position,
+                ProjectileAI::Idle | ProjectileAI::Rocket { .. } => {}
+            }
+            let id = logic.model.world.id_gen.gen();
+            logic.model.world.bodies.insert(
+                id,
+                Body::new(position, velocity).with_collider(self.collider.clone()),
+            );
+            let reused = logic.model.projectile_pool.reuse();
+            logic.model.world.projectiles.insert(Projectile {
+                friend_faction: Some(faction),
+                id,
+                animation_state: AnimationState::reuse(
+                    reused.map(|projectile| projectile.animation_state),
+                    self.animation,
+                    &logic.model.world.animations,
+                ),
+                ai,
+                lifetime: self.lifetime.unwrap_or(Time::new(PROJECTILE_LIFETIME)),
+                on_hit: self.on_hit.clone(),
+                on_expire: self.on_expire.clone(),
+                distance: Coord::ZERO,
+                max_range: self.max_range,
+                arming_distance: self.arming_distance,
+                falloff: self.falloff,
+                intercepts: self.intercepts,
+                missing: self.missing,
+                caster: context.caster,
+                target: target_id,
+            });
+        }
         Some(())
     }
+
+    pub fn launch_position(&self, caster: &Unit, bodies: &Bodies) -> Position {
+        match self
+            .attachment
+            .as_ref()
+            .and_then(|name| caster.attachment_position(bodies, name))
+        {
+            Some(position) => position,
+            None => {
+                let mut offset = self.offset;
+                if caster.flip_sprite {
+                    offset.x = -offset.x;
+                }
+                offset + bodies.positions[caster.id]
+            }
+        }
+    }
 }
 
 /// Returns possible the most direct (if exists) velocity that will land in the desired location
@@ -199,75 +853,329 @@ pub fn aim_parabollically(
         .min_by_key(|(_, t)| *t)
 }
 
+/// Finds the launch velocity to hit a moving target, returns `None` if the target
+/// cannot be reached with the speed. `predict` returns the position of the target
+/// relative to the launch point after the given time.
+pub fn solve_aim(
+    predict: impl Fn(Time) -> Position,
+    gravity: Coord,
+    speed: Coord,
+    prediction: AimPrediction,
+) -> Option<Velocity> {
+    let delta_pos = predict(Time::ZERO);
+    match prediction {
+        AimPrediction::None => aim_parabollically(delta_pos, gravity, speed).map(|(v, _)| v),
+        AimPrediction::Linear => {
+            let time = if speed > Coord::ZERO {
+                delta_pos.len() / speed
+            } else {
+                Time::ZERO
+            };
+            aim_parabollically(predict(time), gravity, speed).map(|(v, _)| v)
+        }
+        AimPrediction::Full => {
+            let (mut velocity, mut time) = aim_parabollically(delta_pos, gravity, speed)?;
+            for _ in 0..AIM_MAX_ITERATIONS {
+                let target_pos = predict(time);
+                let (new_velocity, new_time) = match aim_parabollically(target_pos, gravity, speed)
+                {
+                    Some(solution) => solution,
+                    // The predicted position is out of reach, keep the last solution
+                    None => break,
+                };
+                let converged = (new_time - time).abs() < Time::new(AIM_TOLERANCE);
+                velocity = new_velocity;
+                time = new_time;
+                if converged {
+                    break;
+                }
+            }
+            Some(velocity)
+        }
+    }
+}
+
+/// The launch velocity with the longest range towards the target.
+pub fn max_range_velocity(delta_pos: Position, speed: Coord) -> Velocity {
+    vec2(delta_pos.x.signum(), Coord::ONE).normalize_or_zero() * speed
+}
+
 impl DamageEffect {
     pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
-        let target = context.get_mut(Who::Target, logic)?;
-        let alive = target.health.is_alive();
-        target.health.change(-self.value); // TODO: account for different damage types
-        let killed = alive && !target.health.is_alive();
-        let sound = if killed {
+        if !self.hits_caster && context.caster.is_some() && context.target == context.caster {
+            return None;
+        }
+        let caster = context.get(Who::Caster, logic);
+        let mut multiplier = caster
+            .map(|caster| caster.stat(Stat::Attack))
+            .unwrap_or(R32::ONE);
+        let crit_chance = caster.map_or(0.0, |caster| caster.crit_chance());
+        let bodies = &logic.model.world.bodies;
+        let caster_position = caster.map(|caster| bodies.positions[caster.id]);
+        let value = {
+            let target = context.get(Who::Target, logic)?;
+            let caster = caster.map(|caster| (caster, &bodies.healths[caster.id]));
+            self.value.evaluate(
+                caster.as_ref().map(|caster| caster as &dyn ValueSource),
+                &bodies.healths[target.id],
+            )
+        };
+        if logic.model.world.rng.gen_bool(crit_chance) {
+            multiplier *= r32(CRIT_MULTIPLIER);
+        }
+        let impact = self.impact.unwrap_or_else(|| self.damage_type.impact());
+        let mode = logic.model.config.mode;
+        let (target, bodies) = context.get_mut_with_bodies(Who::Target, logic)?;
+        if target.is_invulnerable() {
+            return Some(());
+        }
+        let target_position = bodies.positions[target.id];
+        let health = &mut bodies.healths[target.id];
+        let direction = context.direction.or_else(|| {
+            caster_position.map(|position| (target_position - position).normalize_or_zero())
+        });
+        let hit = value * multiplier * context.damage_multiplier;
+        let mut damage = hit * target.damage_taken() * target.exposure(&self.damage_type);
+        if let Some(block) = target
+            .block
+            .as_ref()
+            .filter(|_| direction.map_or(false, |dir| target.is_facing_attack(dir)))
+        {
+            if target
+                .statuses
+                .iter()
+                .any(|status| matches!(status.status, Status::Parry { .. }))
+            {
+                // Parried
+                if context.caster.is_some() {
+                    let riposte = QueuedEffect {
+                        effect: block.riposte.clone(),
+                        context: EffectContext {
+                            caster: Some(target.id),
+                            target: context.caster,
+                            position: None,
+                            direction: direction.map(|dir| -dir),
+                            damage_multiplier: R32::ONE,
+                            missing: MissingUnit::Skip,
+                        },
+                    };
+                    logic.effects.push_front(riposte);
+                }
+                return Some(());
+            }
+            if target.is_blocking() {
+                damage *= R32::ONE - block.reduction;
+            }
+        }
+        let alive = health.is_alive();
+        for status in &mut target.statuses {
+            if let Status::Shield { hp, .. } = &mut status.status {
+                let absorbed = damage.min(*hp);
+                *hp -= absorbed;
+                damage -= absorbed;
+            }
+        }
+        let damage = damage.min(health.hp);
+        if !target.keeps_health(mode) {
+            health.change(-damage);
+        }
+        let killed = alive && !health.is_alive();
+        if health.is_alive() && target.is_armored() && self.is_heavy(hit) {
+            target.break_armor();
+        }
+        let reaction = Reaction::find(&self.damage_type, target);
+        if let Some(reaction) = reaction {
+            target
+                .statuses
+                .retain(|status| status.status.element() != Some(reaction.element));
+        }
+        let target_id = target.id;
+        let faction = target.faction;
+        let npc = target.npc;
+        let on_death = killed.then(|| target.on_death.clone());
+        if damage > Hp::ZERO {
+            logic.model.spawn_impact(&impact, target_position, damage);
+        }
+        if let Some(reaction) = reaction {
+            let mut burst = reaction.damage_type.impact();
+            burst.sparks *= 2;
+            logic.model.spawn_sparks(target_position, &burst);
+            logic.effects.push_back(QueuedEffect {
+                effect: reaction.effect(),
+                context: EffectContext {
+                    caster: context.caster,
+                    target: Some(target_id),
+                    position: None,
+                    direction: None,
+                    damage_multiplier: R32::ONE,
+                    missing: context.missing,
+                },
+            });
+        }
+        logic.model.events.push(GameEvent::Damaged {
+            caster: context.caster,
+            target: target_id,
+            amount: damage,
+            killed,
+        });
+        if let Faction::Mech = faction {
+            logic.model.world.score.damage_taken += damage;
+            if let Some(source) = caster_position.filter(|_| !npc && damage > Hp::ZERO) {
+                logic
+                    .model
+                    .damage_indicators
+                    .push(DamageIndicator::new(source));
+            }
+        }
+        let sound = if let Some(effect) = on_death {
             let effect = QueuedEffect {
-                effect: target.on_death.clone(),
+                effect,
                 context: EffectContext {
-                    caster: Some(target.id),
+                    caster: Some(target_id),
                     target: None,
+                    position: None,
+                    direction: None,
+                    damage_multiplier: R32::ONE,
+                    missing: MissingUnit::Skip,
                 },
             };
-            let faction = target.faction;
             logic.effects.push_front(effect);
             match faction {
-                Faction::Mech => Some(logic.model.assets.sound_design.mechs.death.clone()),
-                Faction::Alien => Some(logic.model.assets.sound_design.enemies.death.clone()),
+                Faction::Mech => Some(SoundId::MechDeath),
+                Faction::Alien => Some(SoundId::EnemyDeath),
             }
         } else {
-            match target.faction {
-                Faction::Mech => Some(logic.model.assets.sound_design.mechs.hit.clone()),
+            match faction {
+                Faction::Mech => impact.sound.map(|effect| effect.sound),
                 Faction::Alien => None,
             }
         };
         if let Some(sound) = sound {
-            logic.model.play_sound(&sound);
+            logic.model.play_sound(sound);
         }
         Some(())
     }
 }
 
+/// How long the shield from overhealing lasts.
+const OVERHEAL_SHIELD_TIME: f32 = 5.0;
+
 impl HealEffect {
     pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
-        let target = context.get_mut(Who::Target, logic)?;
-        target.health.change(self.value);
-        let target_position = target.position;
-        let animation = unit_template::to_animation(
-            &logic.model.assets.effects.heal,
-            1.0 / 32.0,
-            Time::ONE,
-            vec![],
+        let bodies = &logic.model.world.bodies;
+        let caster = context
+            .get(Who::Caster, logic)
+            .map(|caster| (caster, &bodies.healths[caster.id]));
+        let target = context.get(Who::Target, logic)?;
+        let value = self.value.evaluate(
+            caster.as_ref().map(|caster| caster as &dyn ValueSource),
+            &bodies.healths[target.id],
         );
-        logic.model.particles.insert(Particle {
-            id: logic.model.id_gen.gen(),
+        let (target, bodies) = context.get_mut_with_bodies(Who::Target, logic)?;
+        let target_position = bodies.positions[target.id];
+        let overheal = bodies.healths[target.id].heal(value);
+        if self.overheal_shield && overheal > Hp::ZERO {
+            target.statuses.push(
+                Status::Shield {
+                    hp: overheal,
+                    time: Some(Time::new(OVERHEAL_SHIELD_TIME)),
+                }
+                .into(),
+            );
+        }
+        logic.model.events.push(GameEvent::Healed {
+            caster: context.caster,
+            target: target.id,
+            amount: value - overheal,
+        });
+        let animations = &mut logic.model.world.animations;
+        let animation = match &logic.model.assets {
+            Some(assets) => animations.get_or_insert_with("heal", || {
+                Animation::from_textures(&assets.effects.heal, 1.0 / 32.0, Time::ONE, vec![])
+            }),
+            // The headless simulation only has the heal animation if it was given one
+            None => match animations.named("heal") {
+                Some(animation) => animation,
+                None => return Some(()),
+            },
+        };
+        let id = logic.model.world.id_gen.gen();
+        logic
+            .model
+            .world
+            .bodies
+            .insert(id, Body::new(target_position, Velocity::ZERO));
+        let reused = logic.model.particle_pool.reuse();
+        logic.model.world.particles.insert(Particle {
+            id,
             alive: true,
             follow_unit: context.target,
-            position: target_position,
-            animation_state: AnimationState::new(&animation),
+            attachment: None,
+            animation_state: AnimationState::reuse(
+                reused.map(|particle| particle.animation_state),
+                animation,
+                &logic.model.world.animations,
+            ),
+        });
+        Some(())
+    }
+}
+
+/// Applies the status to the target, the effect is triggered by the caster when it expires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyStatusEffect<E = Effect> {
+    pub status: Status,
+    pub on_expire: E,
+}
+
+impl ApplyStatusEffect {
+    pub fn process(mut self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let event = GameEvent::StatusApplied {
+            caster: context.caster,
+            target: context.target?,
+            status: self.status.name(),
+        };
+        let target = context.get_mut(Who::Target, logic)?;
+        target.size.scale_status(&mut self.status);
+        let on_expire = if self.on_expire.is_noop() {
+            None
+        } else {
+            Some(StatusExpiry {
+                caster: context.caster,
+                effect: self.on_expire,
+            })
+        };
+        target.statuses.push(AppliedStatus {
+            status: self.status,
+            on_expire,
         });
+        logic.model.events.push(event);
         Some(())
     }
 }
 
 impl DashEffect {
     pub fn process(self, context: EffectContext, logic: &mut Logic) {
-        let target_pos = context.get(Who::Target, logic).map(|unit| unit.position);
-        let caster = context.get_mut_expect(Who::Caster, logic);
+        let target_pos = context.position_of(Who::Target, logic);
+        // Only the caster can dash
+        let (caster, bodies) = match context.get_mut_with_bodies(Who::Caster, logic) {
+            Some(caster) => caster,
+            None => return,
+        };
 
         let target_dir = target_pos
-            .map(|pos| (pos - caster.position).x.signum())
+            .map(|pos| (pos - bodies.positions[caster.id]).x.signum())
             .unwrap_or_else(|| Coord::new(if caster.flip_sprite { -1.0 } else { 1.0 }));
         let target_velocity = vec2(target_dir, Coord::ZERO) * self.speed;
-        caster.velocity = target_velocity;
-        caster.statuses.push(Status::Charge {
-            time: self.duration,
-            on_contact: self.on_contact,
-        })
+        bodies.velocities[caster.id] = target_velocity;
+        caster.statuses.push(
+            Status::Charge {
+                time: self.duration,
+                on_contact: self.on_contact,
+                touched: vec![],
+            }
+            .into(),
+        )
     }
 }
 
@@ -282,14 +1190,141 @@ impl ListEffect {
     }
 }
 
+impl AreaEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let center = context.position_of(self.center, logic)?;
+        let caster = context
+            .last_known(Who::Caster, logic)
+            .map(|(faction, _)| faction);
+        let targets: Vec<(Id, Coord)> = logic
+            .model
+            .world
+            .units
+            .iter()
+            .map(|unit| {
+                (
+                    unit,
+                    (logic.model.world.bodies.positions[unit.id] - center).len(),
+                )
+            })
+            .filter(|(unit, distance)| {
+                self.filter.check(caster, unit.faction)
+                    && (self.hits_caster || Some(unit.id) != context.caster)
+                    && *distance <= self.radius
+            })
+            .map(|(unit, distance)| (unit.id, distance))
+            .collect();
+        let obstacle_damage = self.effect.obstacle_damage(false);
+        if obstacle_damage > Hp::ZERO {
+            logic.damage_obstacles(center, self.radius, obstacle_damage);
+        }
+        for (target, distance) in targets {
+            let falloff = self
+                .falloff
+                .map_or(R32::ONE, |falloff| falloff.multiplier(distance));
+            logic.effects.push_front(QueuedEffect {
+                effect: self.effect.clone(),
+                context: EffectContext {
+                    target: Some(target),
+                    direction: None,
+                    damage_multiplier: context.damage_multiplier * falloff,
+                    ..context.clone()
+                },
+            });
+        }
+        Some(())
+    }
+}
+
 impl SoundEffect {
     pub fn process(self, _context: EffectContext, logic: &mut Logic) {
-        logic.model.play_sound(&self.sound);
+        logic.model.play_sound(self.sound);
     }
 }
 
-impl Debug for SoundEffect {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct(stringify!(SoundEffect)).finish()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAVITY: f32 = -9.8;
+
+    /// The height of the projectile relative to the launch point when it passes over the target.
+    fn miss(velocity: Velocity, target: impl Fn(Time) -> Position) -> f32 {
+        // The time when the projectile catches up with the target horizontally
+        let mut time = Time::ZERO;
+        for _ in 0..100 {
+            time = target(time).x / velocity.x;
+        }
+        let height = velocity.y * time + Coord::new(GRAVITY) * time * time / Time::new(2.0);
+        (height - target(time).y).as_f32()
+    }
+
+    fn assert_close(value: R32, expected: R32) {
+        assert!(
+            (value - expected).abs() < r32(1e-5),
+            "{value} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn aims_at_a_static_target() {
+        let target = |_: Time| vec2(10.0, 2.0).map(Coord::new);
+        let velocity = solve_aim(
+            target,
+            Coord::new(GRAVITY),
+            Coord::new(20.0),
+            AimPrediction::None,
+        )
+        .unwrap();
+        assert!((velocity.len().as_f32() - 20.0).abs() < 1e-3);
+        assert!(miss(velocity, target).abs() < 1e-3);
+    }
+
+    #[test]
+    fn leads_a_moving_target() {
+        let target = |time: Time| vec2(Coord::new(10.0) + Coord::new(3.0) * time, Coord::ZERO);
+        let speed = Coord::new(20.0);
+        let gravity = Coord::new(GRAVITY);
+        let static_aim = solve_aim(target, gravity, speed, AimPrediction::None).unwrap();
+        let full_aim = solve_aim(target, gravity, speed, AimPrediction::Full).unwrap();
+        assert!(miss(full_aim, target).abs() < 0.1);
+        assert!(miss(full_aim, target).abs() < miss(static_aim, target).abs());
+    }
+
+    #[test]
+    fn out_of_reach_has_no_aim() {
+        let target = |_: Time| vec2(1000.0, 0.0).map(Coord::new);
+        let aim = solve_aim(
+            target,
+            Coord::new(GRAVITY),
+            Coord::new(5.0),
+            AimPrediction::Full,
+        );
+        assert!(aim.is_none());
+    }
+
+    #[test]
+    fn falloff_scales_down_linearly() {
+        let falloff = Falloff {
+            start: Coord::new(1.0),
+            end: Coord::new(3.0),
+            min: r32(0.2),
+        };
+        assert_close(falloff.multiplier(Coord::ZERO), R32::ONE);
+        assert_close(falloff.multiplier(Coord::new(1.0)), R32::ONE);
+        assert_close(falloff.multiplier(Coord::new(2.0)), r32(0.6));
+        assert_close(falloff.multiplier(Coord::new(3.0)), r32(0.2));
+        assert_close(falloff.multiplier(Coord::new(10.0)), r32(0.2));
+    }
+
+    #[test]
+    fn inverted_falloff_drops_to_the_minimum() {
+        let falloff = Falloff {
+            start: Coord::new(2.0),
+            end: Coord::new(1.0),
+            min: r32(0.5),
+        };
+        assert_close(falloff.multiplier(Coord::new(1.5)), R32::ONE);
+        assert_close(falloff.multiplier(Coord::new(2.5)), r32(0.5));
     }
 }
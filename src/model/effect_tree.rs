@@ -0,0 +1,122 @@
+use super::*;
+
+impl<E> EffectNode<E> {
+    /// Name of the node in the effect tree.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Noop => "Noop",
+            Self::List(_) => "List",
+            Self::Sound(_) => "Sound",
+            Self::Projectile(_) => "Projectile",
+            Self::Damage(_) => "Damage",
+            Self::Heal(_) => "Heal",
+            Self::Dash(_) => "Dash",
+            Self::Area(_) => "Area",
+            Self::FireWeapon => "Fire weapon",
+            Self::GiveWeapon(_) => "Give weapon",
+            Self::RestoreAmmo(_) => "Restore ammo",
+            Self::MeleeSwing(_) => "Melee swing",
+            Self::Grapple(_) => "Grapple",
+            Self::SlowFall(_) => "Slow fall",
+            Self::ApplyStatus(_) => "Apply status",
+            Self::ModifyMaxHp(_) => "Modify max hp",
+            Self::Script(_) => "Script",
+            Self::SpawnZone(_) => "Spawn zone",
+        }
+    }
+
+    /// The numeric values of the node.
+    pub fn params_mut(&mut self) -> Vec<(&'static str, &mut R32)> {
+        match self {
+            Self::Projectile(effect) => {
+                let mut params = vec![
+                    ("speed", &mut effect.speed),
+                    ("spread", &mut effect.spread),
+                    ("fan", &mut effect.fan),
+                    ("arming distance", &mut effect.arming_distance),
+                ];
+                if let Some(range) = &mut effect.max_range {
+                    params.push(("max range", range));
+                }
+                if let Some(burst) = &mut effect.burst {
+                    params.push(("burst delay", &mut burst.delay));
+                }
+                if let Some(falloff) = &mut effect.falloff {
+                    params.extend(falloff.params_mut());
+                }
+                params
+            }
+            Self::Damage(effect) => vec![("damage", effect.value.value_mut())],
+            Self::Heal(effect) => vec![("heal", effect.value.value_mut())],
+            Self::Dash(effect) => vec![
+                ("speed", &mut effect.speed),
+                ("duration", &mut effect.duration),
+            ],
+            Self::Area(effect) => {
+                let mut params = vec![("radius", &mut effect.radius)];
+                if let Some(falloff) = &mut effect.falloff {
+                    params.extend(falloff.params_mut());
+                }
+                params
+            }
+            Self::MeleeSwing(effect) => {
+                let mut params = vec![("duration", &mut effect.duration)];
+                if let Some(deflect) = &mut effect.deflect {
+                    params.push(("deflect speed", deflect));
+                }
+                params
+            }
+            Self::Grapple(effect) => vec![
+                ("speed", &mut effect.speed),
+                ("max length", &mut effect.max_length),
+                ("reel speed", &mut effect.reel_speed),
+                ("duration", &mut effect.duration),
+            ],
+            Self::SlowFall(effect) => vec![
+                ("duration", &mut effect.duration),
+                ("max fall speed", &mut effect.max_fall_speed),
+            ],
+            Self::ModifyMaxHp(effect) => {
+                let mut params = vec![("amount", &mut effect.amount)];
+                if let Some(duration) = &mut effect.duration {
+                    params.push(("duration", duration));
+                }
+                params
+            }
+            Self::SpawnZone(effect) => vec![
+                ("duration", &mut effect.duration),
+                ("tick time", &mut effect.tick_time),
+                ("radius", &mut effect.area.radius),
+            ],
+            Self::Noop
+            | Self::List(_)
+            | Self::Sound(_)
+            | Self::FireWeapon
+            | Self::GiveWeapon(_)
+            | Self::RestoreAmmo(_)
+            | Self::ApplyStatus(_)
+            | Self::Script(_) => vec![],
+        }
+    }
+}
+
+impl Value {
+    fn value_mut(&mut self) -> &mut R32 {
+        match self {
+            Self::Flat(value)
+            | Self::TargetMaxHp(value)
+            | Self::CasterMaxHp(value)
+            | Self::CasterStat(_, value) => value,
+        }
+    }
+}
+
+impl Falloff {
+    fn params_mut(&mut self) -> [(&'static str, &mut R32); 3] {
+        [
+            ("falloff start", &mut self.start),
+            ("falloff end", &mut self.end),
+            ("falloff min", &mut self.min),
+        ]
+    }
+}
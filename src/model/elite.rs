@@ -0,0 +1,101 @@
+use super::*;
+use logic::Who;
+
+/// A modifier that turns a regular unit into an elite version of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EliteModifier {
+    /// Doubled health
+    Tough,
+    /// Moves faster
+    Swift,
+    /// Spawns with a shield
+    Shielded,
+    /// Explodes on death, damaging nearby enemies
+    Explosive,
+    /// Leaves a trail of burning ground behind
+    Blazing,
+}
+
+impl EliteModifier {
+    pub const ALL: [Self; 5] = [
+        Self::Tough,
+        Self::Swift,
+        Self::Shielded,
+        Self::Explosive,
+        Self::Blazing,
+    ];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Tough => "elite.tough",
+            Self::Swift => "elite.swift",
+            Self::Shielded => "elite.shielded",
+            Self::Explosive => "elite.explosive",
+            Self::Blazing => "elite.blazing",
+        }
+    }
+}
+
+impl UnitTemplate {
+    /// Composes the modifier onto the template.
+    pub fn apply_elite(&mut self, modifier: EliteModifier, assets: &Assets) {
+        match modifier {
+            EliteModifier::Tough => {
+                self.health
+                    .set_max_hp(self.health.max_hp * Hp::new(2.0), MaxHpChange::Scale);
+            }
+            EliteModifier::Swift => {
+                self.speed *= Coord::new(1.5);
+                self.acceleration *= Coord::new(1.5);
+            }
+            EliteModifier::Shielded => {
+                self.statuses.push(Status::Shield {
+                    hp: self.health.max_hp * Hp::new(0.5),
+                    time: None,
+                });
+            }
+            EliteModifier::Explosive => {
+                let explosion = Effect::new(EffectNode::List(ListEffect {
+                    effects: vec![
+                        Effect::new(EffectNode::Area(AreaEffect {
+                            center: Who::Caster,
+                            radius: Coord::new(3.0),
+                            filter: FactionFilter::Enemies,
+                            hits_caster: false,
+                            falloff: Some(Falloff {
+                                start: Coord::new(1.0),
+                                end: Coord::new(3.0),
+                                min: r32(0.3),
+                            }),
+                            effect: Effect::new(EffectNode::Damage(DamageEffect {
+                                damage_type: DamageType::Explosive,
+                                value: Value::Flat(Hp::new(5.0)),
+                                hits_caster: false,
+                                impact: None,
+                            })),
+                        })),
+                        Effect::new(EffectNode::Sound(SoundEffect {
+                            sound: SoundId::RocketExplode,
+                        })),
+                    ],
+                }));
+                let on_death = std::mem::replace(&mut self.on_death, Effect::noop());
+                self.on_death = Effect::new(EffectNode::List(ListEffect {
+                    effects: vec![on_death, explosion],
+                }));
+            }
+            EliteModifier::Blazing => {
+                self.statuses.push(Status::Trail {
+                    interval: Time::new(0.5),
+                    next: Time::ZERO,
+                    zone: Effect::new(EffectNode::SpawnZone(SpawnZoneEffect {
+                        center: Who::Caster,
+                        ..SpawnZoneEffect::burning(Time::new(3.0), Coord::new(1.0), Hp::new(0.5))
+                    })),
+                });
+            }
+        }
+        self.elites.push(modifier);
+    }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+/// Something that happened during the tick, for the systems reacting to it.
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    ChargeEnded {
+        unit: Id,
+        reason: ChargeEnd,
+    },
+    /// The target lost health, the amount does not include the absorbed damage.
+    Damaged {
+        caster: Option<Id>,
+        target: Id,
+        amount: Hp,
+        killed: bool,
+    },
+    Healed {
+        caster: Option<Id>,
+        target: Id,
+        amount: Hp,
+    },
+    StatusApplied {
+        caster: Option<Id>,
+        target: Id,
+        status: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeEnd {
+    Timeout,
+    Stunned,
+    /// Ran into an obstacle or the left border
+    Wall,
+}
@@ -0,0 +1,93 @@
+use super::*;
+use logic::*;
+
+/// Maximum fall speed of the mechs gliding on the player's order.
+pub const GLIDE_FALL_SPEED: f32 = 2.0;
+
+/// Caps the fall speed of the target for a while.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowFallEffect {
+    pub duration: Time,
+    pub max_fall_speed: Coord,
+}
+
+impl Bodies {
+    /// Whether the entity is above the ground.
+    pub fn is_airborne(&self, id: Id, ground_level: Coord) -> bool {
+        self.bounds(id).map_or(false, |bounds| {
+            bounds.y_min > ground_level + Coord::new(0.01)
+        })
+    }
+}
+
+impl Unit {
+    /// The sprite drawn for the unit, the glide animation replaces the usual one.
+    pub fn sprite<'a>(&self, animations: &'a Animations) -> &'a Sprite {
+        self.glide_state
+            .as_ref()
+            .unwrap_or(&self.animation_state)
+            .get_sprite(animations)
+    }
+
+    /// The acceleration the unit is expected to keep, used to lead it when aiming.
+    pub fn expected_acceleration(
+        &self,
+        bodies: &Bodies,
+        gravity: Velocity,
+        ground_level: Coord,
+    ) -> Velocity {
+        let flying = matches!(self.ai, UnitAI::Stinger { .. });
+        if flying || !bodies.is_airborne(self.id, ground_level) {
+            return Velocity::ZERO;
+        }
+        match self.max_fall_speed() {
+            // Gliding at the capped speed
+            Some(max_fall_speed) if bodies.velocities[self.id].y <= -max_fall_speed => {
+                Velocity::ZERO
+            }
+            _ => gravity,
+        }
+    }
+
+    /// The lowest fall speed cap among the unit's statuses, if any.
+    pub fn max_fall_speed(&self) -> Option<Coord> {
+        self.statuses
+            .iter()
+            .filter_map(|status| match status.status {
+                Status::SlowFall { max_fall_speed, .. } => Some(max_fall_speed),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Slows down the fall of the unit, refreshing an existing slow fall with the same cap.
+    pub fn slow_fall(&mut self, duration: Time, max_fall_speed: Coord) {
+        for status in &mut self.statuses {
+            if let Status::SlowFall {
+                time,
+                max_fall_speed: speed,
+            } = &mut status.status
+            {
+                if *speed == max_fall_speed {
+                    *time = (*time).max(duration);
+                    return;
+                }
+            }
+        }
+        self.statuses.push(
+            Status::SlowFall {
+                time: duration,
+                max_fall_speed,
+            }
+            .into(),
+        );
+    }
+}
+
+impl SlowFallEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let target = context.get_mut(Who::Target, logic)?;
+        target.slow_fall(self.duration, self.max_fall_speed);
+        Some(())
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+use logic::*;
+
+/// Fires a hook that attaches to whatever it hits and then shortens the rope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrappleEffect<E = Effect> {
+    /// Attachment point of the caster the hook is fired from
+    pub attachment: Option<String>,
+    pub speed: Coord,
+    /// The hook is released if the rope gets longer than this
+    pub max_length: Coord,
+    /// How fast the rope is shortened while attached
+    pub reel_speed: Coord,
+    /// How long the hook stays out before being released
+    pub duration: Time,
+    /// Effect applied to the unit the hook attaches to
+    pub on_attach: E,
+}
+
+/// What the hook is attached to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GrappleAnchor {
+    Unit(Id),
+    Obstacle(Id),
+    /// A point on the ground
+    Point(Position),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GrappleState {
+    Flying {
+        position: Position,
+        velocity: Velocity,
+    },
+    Attached {
+        anchor: GrappleAnchor,
+        length: Coord,
+    },
+}
+
+/// A hook fired by a unit, connected to it with a rope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Grapple {
+    pub caster: Id,
+    pub attachment: Option<String>,
+    pub state: GrappleState,
+    pub max_length: Coord,
+    pub reel_speed: Coord,
+    pub time_left: Time,
+    pub on_attach: Effect,
+}
+
+impl Grapple {
+    /// Returns the position the rope starts at.
+    pub fn origin(&self, caster: &Unit, bodies: &Bodies) -> Position {
+        self.attachment
+            .as_ref()
+            .and_then(|name| caster.attachment_position(bodies, name))
+            .unwrap_or(bodies.positions[caster.id])
+    }
+
+    /// Returns the position of the hook, `None` if the anchor no longer exists.
+    pub fn hook_position(&self, model: &Model) -> Option<Position> {
+        match &self.state {
+            GrappleState::Flying { position, .. } => Some(*position),
+            GrappleState::Attached { anchor, .. } => match anchor {
+                GrappleAnchor::Unit(id) => model.world.bodies.positions.get(*id).copied(),
+                GrappleAnchor::Obstacle(id) => model
+                    .world
+                    .obstacles
+                    .get(id)
+                    .map(|obstacle| obstacle.position),
+                GrappleAnchor::Point(position) => Some(*position),
+            },
+        }
+    }
+}
+
+impl GrappleEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let caster = context.get(Who::Caster, logic)?;
+        let bodies = &logic.model.world.bodies;
+        let origin = self
+            .attachment
+            .as_ref()
+            .and_then(|name| caster.attachment_position(bodies, name))
+            .unwrap_or(bodies.positions[caster.id]);
+        let target = context.position_of(Who::Target, logic)?;
+        let caster = caster.id;
+        let velocity = (target - origin).normalize_or_zero() * self.speed;
+
+        // Only one hook per unit
+        logic
+            .model
+            .world
+            .grapples
+            .retain(|grapple| grapple.caster != caster);
+        logic.model.world.grapples.push(Grapple {
+            caster,
+            attachment: self.attachment,
+            state: GrappleState::Flying {
+                position: origin,
+                velocity,
+            },
+            max_length: self.max_length,
+            reel_speed: self.reel_speed,
+            time_left: self.duration,
+            on_attach: self.on_attach,
+        });
+        Some(())
+    }
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+/// A non-enemy threat occupying an area of the level.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Hazard {
+    pub id: Id,
+    pub kind: HazardKind,
+    pub area: AABB<Coord>,
+    /// Units recently damaged by spikes and the time until they can be damaged again
+    pub touched: Vec<(Id, Time)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HazardKind {
+    /// Deals damage on touch, then waits for the cooldown before damaging the same unit again
+    Spikes { damage: Hp, cooldown: Time },
+    /// Deals damage continuously while inside and sets the units on fire
+    Lava { damage_per_second: Hp },
+    /// Applies a constant force to the units inside
+    Wind { force: Vec2<Coord> },
+}
+
+impl Hazard {
+    pub fn new(id: Id, kind: HazardKind, area: AABB<Coord>) -> Self {
+        Self {
+            id,
+            kind,
+            area,
+            touched: vec![],
+        }
+    }
+
+    /// Generates a random hazard on the ground starting at `x`.
+    pub fn random(id: Id, x: Coord, ground_level: Coord, rng: &mut impl Rng) -> Self {
+        let width = Coord::new(rng.gen_range(3.0..=6.0));
+        let ground = |height: f32| AABB {
+            x_min: x,
+            x_max: x + width,
+            y_min: ground_level,
+            y_max: ground_level + Coord::new(height),
+        };
+        let (kind, area) = match rng.gen_range(0..3) {
+            0 => (
+                HazardKind::Spikes {
+                    damage: Hp::new(2.0),
+                    cooldown: Time::new(1.0),
+                },
+                ground(0.5),
+            ),
+            1 => (
+                HazardKind::Lava {
+                    damage_per_second: Hp::new(3.0),
+                },
+                ground(0.3),
+            ),
+            _ => (
+                HazardKind::Wind {
+                    force: vec2(Coord::new(rng.gen_range(-8.0..=8.0)), Coord::new(2.0)),
+                },
+                ground(8.0),
+            ),
+        };
+        Self::new(id, kind, area)
+    }
+}
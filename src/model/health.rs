@@ -1,6 +1,19 @@
 use super::*;
+use logic::*;
 
-#[derive(Debug, Clone)]
+/// The lowest the maximum hp can be lowered to.
+const MIN_MAX_HP: f32 = 1.0;
+
+/// How the current hp follows a change of the maximum hp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaxHpChange {
+    /// Keeps the ratio of the current hp to the maximum
+    Scale,
+    /// Keeps the current hp, cutting it down to the new maximum
+    Clamp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Health {
     pub hp: Hp,
     pub max_hp: Hp,
@@ -19,7 +32,53 @@ impl Health {
         self.hp = (self.hp + delta).min(self.max_hp).max(Hp::ZERO);
     }
 
+    /// Heals up to the maximum hp and returns the part of the value that did not fit.
+    pub fn heal(&mut self, value: Hp) -> Hp {
+        let overheal = (self.hp + value - self.max_hp).max(Hp::ZERO);
+        self.change(value);
+        overheal
+    }
+
+    /// Changes the maximum hp, keeping it positive.
+    pub fn set_max_hp(&mut self, max_hp: Hp, change: MaxHpChange) {
+        let max_hp = max_hp.max(Hp::new(MIN_MAX_HP));
+        match change {
+            MaxHpChange::Scale => self.hp *= max_hp / self.max_hp,
+            MaxHpChange::Clamp => self.hp = self.hp.min(max_hp),
+        }
+        self.max_hp = max_hp;
+    }
+
     pub fn is_alive(&self) -> bool {
         self.hp > Hp::ZERO
     }
 }
+
+/// Changes the target's maximum hp, reverting the change after the duration if there is one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifyMaxHpEffect {
+    pub amount: Hp,
+    pub duration: Option<Time>,
+    pub change: MaxHpChange,
+}
+
+impl ModifyMaxHpEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let (target, bodies) = context.get_mut_with_bodies(Who::Target, logic)?;
+        let health = &mut bodies.healths[target.id];
+        let max_hp = health.max_hp;
+        health.set_max_hp(max_hp + self.amount, self.change);
+        if let Some(time) = self.duration {
+            // Revert only what was actually changed, the maximum might have been limited
+            target.statuses.push(
+                Status::MaxHp {
+                    time,
+                    amount: health.max_hp - max_hp,
+                    change: self.change,
+                }
+                .into(),
+            );
+        }
+        Some(())
+    }
+}
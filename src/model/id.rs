@@ -1,21 +1,58 @@
 use geng::prelude::{Deserialize, Serialize};
 
+/// A generational index: the index of a freed id is reused with the next generation,
+/// so stale references to a despawned entity never match the entity that reused its index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct Id(u64);
+pub struct Id {
+    index: u32,
+    generation: u32,
+}
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct IdGen {
-    next_id: Id,
+    /// The current generation of every index given out so far.
+    generations: Vec<u32>,
+    /// Freed indices available for reuse.
+    free: Vec<u32>,
 }
 
 impl IdGen {
     pub fn new() -> Self {
-        Self { next_id: Id(0) }
+        Self {
+            generations: vec![],
+            free: vec![],
+        }
     }
 
     pub fn gen(&mut self) -> Id {
-        let id = self.next_id;
-        self.next_id.0 += 1;
-        id
+        match self.free.pop() {
+            Some(index) => Id {
+                index,
+                generation: self.generations[index as usize],
+            },
+            None => {
+                let index = self.generations.len() as u32;
+                self.generations.push(0);
+                Id {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Frees the id of a despawned entity, its index is reused by the next generation.
+    pub fn free(&mut self, id: Id) {
+        let generation = match self.generations.get_mut(id.index as usize) {
+            Some(generation) => generation,
+            None => return,
+        };
+        if *generation != id.generation {
+            // Already freed
+            return;
+        }
+        *generation += 1;
+        self.free.push(id.index);
     }
 }
 
@@ -24,3 +61,41 @@ impl Default for IdGen {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_index_is_reused_by_the_next_generation() {
+        let mut id_gen = IdGen::new();
+        let first = id_gen.gen();
+        let second = id_gen.gen();
+        assert_ne!(first, second);
+
+        id_gen.free(first);
+        let reused = id_gen.gen();
+        assert_eq!(reused.index, first.index);
+        assert_eq!(reused.generation, first.generation + 1);
+        assert_ne!(reused, first);
+
+        // Nothing else is free, a new index is given out
+        assert_eq!(id_gen.gen().index, 2);
+    }
+
+    #[test]
+    fn stale_ids_are_not_freed_twice() {
+        let mut id_gen = IdGen::new();
+        let stale = id_gen.gen();
+        id_gen.free(stale);
+        let current = id_gen.gen();
+
+        id_gen.free(stale);
+        id_gen.free(stale);
+        assert_eq!(id_gen.gen().index, 1);
+        assert_eq!(
+            id_gen.generations[current.index as usize],
+            current.generation
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use super::*;
+
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8;
+/// How fast the damage numbers float up.
+const DAMAGE_NUMBER_SPEED: f32 = 1.5;
+
+/// How a hit looks and sounds, each damage type has its own by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Impact {
+    /// Color of the sparks and the damage number.
+    pub color: Rgba<f32>,
+    /// Number of sparks thrown out of the target.
+    pub sparks: usize,
+    pub spark_speed: f32,
+    /// Played when a mech is hit.
+    pub sound: Option<SoundEffect>,
+}
+
+/// The damage dealt by a hit, floating up from the target.
+#[derive(Debug, Clone)]
+pub struct DamageNumber {
+    pub position: Position,
+    pub value: Hp,
+    pub color: Rgba<f32>,
+    pub lifetime: Time,
+}
+
+impl DamageNumber {
+    /// The share of the lifetime left, from 1 to 0.
+    pub fn fade(&self) -> f32 {
+        (self.lifetime / Time::new(DAMAGE_NUMBER_LIFETIME))
+            .as_f32()
+            .clamp(0.0, 1.0)
+    }
+
+    pub fn update(&mut self, delta_time: Time) {
+        self.position.y += Coord::new(DAMAGE_NUMBER_SPEED) * delta_time;
+        self.lifetime -= delta_time;
+    }
+}
+
+impl DamageType {
+    /// The default impact of the hits of this type.
+    pub fn impact(&self) -> Impact {
+        let (color, sparks, spark_speed, sound) = match self {
+            Self::Physical => (Rgba::new(1.0, 0.85, 0.4, 1.0), 6, 6.0, SoundId::MechHit),
+            Self::Energy => (Rgba::new(0.4, 0.85, 1.0, 1.0), 8, 4.0, SoundId::HealEffect),
+            Self::Explosive => (
+                Rgba::new(1.0, 0.45, 0.15, 1.0),
+                12,
+                9.0,
+                SoundId::RocketExplode,
+            ),
+        };
+        Impact {
+            color,
+            sparks,
+            spark_speed,
+            sound: Some(SoundEffect { sound }),
+        }
+    }
+}
+
+impl Model {
+    /// Throws the sparks of the impact out of the position and shows the damage dealt.
+    pub fn spawn_impact(&mut self, impact: &Impact, position: Position, damage: Hp) {
+        self.spawn_sparks(position, impact);
+        self.damage_numbers.push(DamageNumber {
+            position,
+            value: damage,
+            color: impact.color,
+            lifetime: Time::new(DAMAGE_NUMBER_LIFETIME),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bench::{test_effect, test_model, test_spawn};
+
+    fn damage(damage_type: DamageType, impact: Option<Impact>) -> Effect {
+        Effect::new(EffectNode::Damage(DamageEffect {
+            damage_type,
+            value: Value::Flat(Hp::new(1.0)),
+            hits_caster: false,
+            impact,
+        }))
+    }
+
+    #[test]
+    fn damage_types_look_different() {
+        let colors = [
+            DamageType::Physical,
+            DamageType::Energy,
+            DamageType::Explosive,
+        ]
+        .map(|damage_type| damage_type.impact().color);
+        assert_ne!(colors[0], colors[1]);
+        assert_ne!(colors[1], colors[2]);
+        assert_ne!(colors[0], colors[2]);
+    }
+
+    #[test]
+    fn hits_show_the_impact_of_their_type() {
+        let mut model = test_model();
+        let target = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        test_effect(&mut model, damage(DamageType::Energy, None), None, target);
+        let number = model.damage_numbers.last().unwrap();
+        assert_eq!(number.color, DamageType::Energy.impact().color);
+        assert_eq!(number.value, Hp::new(1.0));
+
+        // The effect can override the look of its type
+        let impact = Impact {
+            color: Rgba::new(0.0, 1.0, 0.0, 1.0),
+            ..DamageType::Energy.impact()
+        };
+        test_effect(
+            &mut model,
+            damage(DamageType::Energy, Some(impact)),
+            None,
+            target,
+        );
+        let number = model.damage_numbers.last().unwrap();
+        assert_eq!(number.color, Rgba::new(0.0, 1.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// Number of the last ticks whose inputs are kept for the rewinds.
+pub const MAX_INPUT_HISTORY: usize = 600;
+
+/// Everything the player did during a tick, together with the seed enough to replay it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickInput {
+    pub tick: u64,
+    pub events: Vec<PlayerEvent>,
+    pub aim: Option<Position>,
+    pub blocking: bool,
+    pub gliding: bool,
+}
+
+impl Model {
+    /// Remembers the inputs applied in this tick.
+    pub fn record_input(&mut self) {
+        let input = TickInput {
+            tick: self.world.current_tick,
+            events: self.queued_events.clone(),
+            aim: self.aim,
+            blocking: self.blocking,
+            gliding: self.gliding,
+        };
+        if !self.preview {
+            crash::record_input(&input);
+        }
+        self.input_history.push_back(input);
+        if self.input_history.len() > MAX_INPUT_HISTORY {
+            self.input_history.pop_front();
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+use logic::Who;
+
+/// How close a mech has to be to an interactable to use it.
+pub const INTERACT_RADIUS: f32 = 2.5;
+
+/// A non-combat object in the level that the player can use with a nearby mech.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Interactable {
+    pub id: Id,
+    pub kind: InteractableKind,
+    pub position: Position,
+    pub size: Vec2<Coord>,
+    /// The mech that used the object, the interaction is performed on the next tick
+    pub used_by: Option<Id>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InteractableKind {
+    /// Gives a weapon to the mech that opens it
+    Chest { weapon: Box<Weapon> },
+    /// Turns off the hazards around it
+    Lever { radius: Coord },
+    /// A survivor that repairs the nearby mechs
+    Survivor { effect: Effect },
+    /// A damaged vehicle that the mech boards, fighting as the vehicle until it is destroyed
+    Vehicle { template: Box<UnitTemplate> },
+}
+
+/// Something that happens to the level itself rather than to a unit.
+#[derive(Debug, Clone)]
+pub enum LevelEvent {
+    ClearHazards {
+        center: Position,
+        radius: Coord,
+    },
+    OpenDoor,
+    ProtectGenerator {
+        x: Coord,
+        duration: Time,
+    },
+    /// An engineer joins at `x`, escorted to the destination if there is one.
+    Ally {
+        x: Coord,
+        destination: Option<Coord>,
+    },
+}
+
+impl Interactable {
+    /// Generates a random interactable on the ground at `x`.
+    pub fn random(
+        id: Id,
+        x: Coord,
+        ground_level: Coord,
+        assets: &Assets,
+        animations: &mut Animations,
+        templates: &UnitTemplates,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let (kind, size) = match rng.gen_range(0..4) {
+            0 => {
+                let weapon = Weapon::tank_weapons(assets, animations)
+                    .into_iter()
+                    .choose(rng)
+                    .expect("No weapons to put into the chest");
+                (
+                    InteractableKind::Chest {
+                        weapon: Box::new(weapon),
+                    },
+                    vec2(1.2, 0.8),
+                )
+            }
+            1 => (
+                InteractableKind::Lever {
+                    radius: Coord::new(30.0),
+                },
+                vec2(0.3, 1.2),
+            ),
+            2 => {
+                let mut template = templates.tank.clone();
+                template.health.hp = template.health.max_hp / Hp::new(2.0);
+                (
+                    InteractableKind::Vehicle {
+                        template: Box::new(template),
+                    },
+                    vec2(2.0, 1.4),
+                )
+            }
+            _ => (
+                InteractableKind::Survivor {
+                    effect: Effect::new(EffectNode::Area(AreaEffect {
+                        center: Who::Caster,
+                        radius: Coord::new(8.0),
+                        filter: FactionFilter::Allies,
+                        hits_caster: true,
+                        falloff: None,
+                        effect: Effect::new(EffectNode::Heal(HealEffect {
+                            value: Value::TargetMaxHp(r32(0.1)),
+                            overheal_shield: true,
+                        })),
+                    })),
+                },
+                vec2(0.8, 1.6),
+            ),
+        };
+        let size = size.map(Coord::new);
+        Self {
+            id,
+            kind,
+            position: vec2(x, ground_level + size.y / Coord::new(2.0)),
+            size,
+            used_by: None,
+        }
+    }
+
+    /// The locale key of the text shown to the player when a mech is close enough.
+    pub fn prompt(&self) -> &'static str {
+        match self.kind {
+            InteractableKind::Chest { .. } => "interact.chest",
+            InteractableKind::Lever { .. } => "interact.lever",
+            InteractableKind::Survivor { .. } => "interact.survivor",
+            InteractableKind::Vehicle { .. } => "interact.vehicle",
+        }
+    }
+
+    /// Whether the mech can use the object.
+    pub fn can_use(&self, unit: &Unit, bodies: &Bodies) -> bool {
+        unit.faction == Faction::Mech
+            && !unit.npc
+            && (bodies.positions[unit.id] - self.position).len() <= Coord::new(INTERACT_RADIUS)
+            && match &self.kind {
+                InteractableKind::Chest { weapon } => unit.can_equip(weapon),
+                InteractableKind::Lever { .. } | InteractableKind::Survivor { .. } => true,
+                InteractableKind::Vehicle { .. } => unit.pilot.is_none(),
+            }
+    }
+}
+
+impl Model {
+    /// Returns the interactable closest to a mech that can use it, along with that mech.
+    pub fn interactable_in_range(&self) -> Option<(&Interactable, &Unit)> {
+        self.world
+            .interactables
+            .iter()
+            .filter(|object| object.used_by.is_none())
+            .flat_map(|object| {
+                self.world
+                    .units
+                    .iter()
+                    .filter(|unit| object.can_use(unit, &self.world.bodies))
+                    .map(move |unit| (object, unit))
+            })
+            .min_by_key(|(object, unit)| {
+                (object.position - self.world.bodies.positions[unit.id]).len()
+            })
+    }
+}
@@ -0,0 +1,277 @@
+use super::*;
+
+const PREFERENCES_KEY: &str = "level";
+/// Width of the hazards and the water placed in a level.
+pub const LEVEL_AREA_WIDTH: f32 = 4.0;
+/// Distance between the units spawned together at a spawn point.
+const SPAWN_SPACING: f32 = 1.0;
+
+/// A hand-made level, built in the editor and placed in front of the generated waves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Level {
+    pub objects: Vec<LevelObject>,
+    pub waves: Vec<LevelWave>,
+    /// Prompts teaching the controls, played in order.
+    #[serde(default)]
+    pub tutorial: Vec<TutorialStep>,
+    #[serde(default)]
+    pub weather: Weather,
+    /// Length of the day and night cycle in seconds, the endless modes have one by default.
+    #[serde(default)]
+    pub day_length: Option<f32>,
+    /// Events of the level, e.g. opening the doors once the waves are cleared.
+    #[serde(default)]
+    pub triggers: Vec<LevelTrigger>,
+    /// Where the projectiles expire and the units die, the camera stays inside.
+    #[serde(default)]
+    pub bounds: WorldBounds,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            objects: vec![],
+            waves: vec![],
+            tutorial: TutorialStep::basics(),
+            weather: default(),
+            day_length: None,
+            triggers: vec![],
+            bounds: default(),
+        }
+    }
+}
+
+/// An object standing on the ground of the level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelObject {
+    pub kind: LevelObjectKind,
+    pub x: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelObjectKind {
+    Pillar1,
+    Pillar2,
+    Spikes,
+    Lava,
+    Wind,
+    Water,
+    /// A background sprite the units walk through
+    Tower,
+    Town,
+    /// Where the enemies of the waves appear
+    SpawnPoint,
+    /// Blocks the way until a trigger opens it
+    Door,
+}
+
+/// A wave of the level, started once the left border reaches its position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelWave {
+    pub position: f32,
+    pub spawns: Vec<WaveSpawn>,
+}
+
+/// A group of enemies of the same kind in a wave.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveSpawn {
+    /// Name of the unit template.
+    pub template: String,
+    pub count: usize,
+    /// Seconds after the start of the wave.
+    pub delay: f32,
+    /// Index of the spawn point counting from the left,
+    /// random ahead of the left border if not set.
+    pub spawn_point: Option<usize>,
+}
+
+/// A sprite placed in the level for the looks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decoration {
+    pub position: Position,
+    pub sprite: Sprite,
+}
+
+impl Level {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    /// Positions of the spawn points from left to right.
+    pub fn spawn_points(&self) -> Vec<f32> {
+        let mut points: Vec<f32> = self
+            .objects
+            .iter()
+            .filter(|object| object.kind == LevelObjectKind::SpawnPoint)
+            .map(|object| object.x)
+            .collect();
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        points
+    }
+}
+
+impl LevelObjectKind {
+    pub const ALL: [Self; 10] = [
+        Self::Pillar1,
+        Self::Pillar2,
+        Self::Spikes,
+        Self::Lava,
+        Self::Wind,
+        Self::Water,
+        Self::Tower,
+        Self::Town,
+        Self::SpawnPoint,
+        Self::Door,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pillar1 => "Pillar",
+            Self::Pillar2 => "Broken pillar",
+            Self::Spikes => "Spikes",
+            Self::Lava => "Lava",
+            Self::Wind => "Wind",
+            Self::Water => "Water",
+            Self::Tower => "Tower",
+            Self::Town => "Town",
+            Self::SpawnPoint => "Spawn point",
+            Self::Door => "Door",
+        }
+    }
+
+    /// The sprite of the obstacles and the decorations.
+    pub fn sprite(&self, assets: &Assets) -> Option<Sprite> {
+        let texture = match self {
+            Self::Pillar1 => &assets.background.pillar1,
+            Self::Pillar2 => &assets.background.pillar2,
+            Self::Tower => &assets.background.tower,
+            Self::Town => &assets.background.town,
+            _ => return None,
+        };
+        Some(Sprite::new(texture, 0.03))
+    }
+
+    /// The hazard and its height.
+    pub fn hazard(&self) -> Option<(HazardKind, f32)> {
+        match self {
+            Self::Spikes => Some((
+                HazardKind::Spikes {
+                    damage: Hp::new(2.0),
+                    cooldown: Time::new(1.0),
+                },
+                0.5,
+            )),
+            Self::Lava => Some((
+                HazardKind::Lava {
+                    damage_per_second: Hp::new(3.0),
+                },
+                0.3,
+            )),
+            Self::Wind => Some((
+                HazardKind::Wind {
+                    force: vec2(Coord::new(4.0), Coord::new(2.0)),
+                },
+                8.0,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl LevelWave {
+    /// Builds the wave from the templates, the enemies of unknown templates are left out.
+    pub fn build(&self, spawn_points: &[f32], templates: &UnitTemplates) -> Wave {
+        let mut units = vec![];
+        for spawn in &self.spawns {
+            let template = match templates.get(&spawn.template) {
+                Some(template) => template,
+                None => {
+                    warn!("Level wave spawns an unknown unit {:?}", spawn.template);
+                    continue;
+                }
+            };
+            let point = spawn
+                .spawn_point
+                .and_then(|index| spawn_points.get(index).copied());
+            for index in 0..spawn.count {
+                units.push(WaveUnit {
+                    template: template.clone(),
+                    spawn_x: point.map(|x| Coord::new(x + index as f32 * SPAWN_SPACING)),
+                    delay: Time::new(spawn.delay),
+                });
+            }
+        }
+        Wave {
+            position: Coord::new(self.position),
+            units,
+        }
+    }
+}
+
+impl Model {
+    /// Places the objects of the level and queues its waves before the generated ones.
+    pub fn load_level(&mut self, level: &Level) {
+        self.world.tutorial = level.tutorial.iter().cloned().collect();
+        self.world.weather = level.weather;
+        self.world.day_cycle = DayCycle::new(level.day_length, self.config.mode);
+        self.world.triggers = level.triggers.clone();
+        self.world.bounds = level.bounds;
+        let ground_level = self.world.ground_level;
+        for object in &level.objects {
+            let x = Coord::new(object.x);
+            let area = |height: f32| AABB {
+                x_min: x,
+                x_max: x + Coord::new(LEVEL_AREA_WIDTH),
+                y_min: ground_level,
+                y_max: ground_level + Coord::new(height),
+            };
+            match object.kind {
+                LevelObjectKind::Pillar1 | LevelObjectKind::Pillar2 => {
+                    let sprite = object.kind.sprite(self.assets()).unwrap();
+                    let obstacle = Obstacle::new(
+                        self.world.id_gen.gen(),
+                        sprite,
+                        x,
+                        ground_level,
+                        Hp::new(OBSTACLE_HP),
+                    );
+                    self.world.obstacles.insert(obstacle);
+                }
+                LevelObjectKind::Spikes | LevelObjectKind::Lava | LevelObjectKind::Wind => {
+                    let (kind, height) = object.kind.hazard().unwrap();
+                    let hazard = Hazard::new(self.world.id_gen.gen(), kind, area(height));
+                    self.world.hazards.insert(hazard);
+                }
+                LevelObjectKind::Water => {
+                    self.world.water.insert(Water {
+                        id: self.world.id_gen.gen(),
+                        area: area(WATER_DEPTH),
+                    });
+                }
+                LevelObjectKind::Tower | LevelObjectKind::Town => {
+                    let sprite = object.kind.sprite(self.assets()).unwrap();
+                    let position = vec2(x, ground_level + Coord::new(sprite.size.y / 2.0));
+                    self.world.decorations.push(Decoration { position, sprite });
+                }
+                LevelObjectKind::Door => {
+                    let door = Door::new(self.world.id_gen.gen(), x, ground_level);
+                    self.world.doors.insert(door);
+                }
+                LevelObjectKind::SpawnPoint => {}
+            }
+        }
+
+        let spawn_points = level.spawn_points();
+        let mut waves: Vec<Wave> = level
+            .waves
+            .iter()
+            .map(|wave| wave.build(&spawn_points, &self.world.templates))
+            .collect();
+        waves.sort_by_key(|wave| wave.position);
+        self.world.waves.extend(waves);
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+use net::{NetMessage, Peer};
+use std::collections::BTreeMap;
+
+/// Ticks between an input and the tick it is applied at, to hide the latency to the other player.
+const INPUT_DELAY: u64 = 6;
+/// The players compare the digests of their simulations every this many ticks.
+const DIGEST_INTERVAL: u64 = 60;
+
+/// Keeps the simulation of a co-op run in step with the other player's: the events of both
+/// players are sent ahead for a later tick, which only runs once both have arrived.
+/// The runs stay identical as long as the simulation is deterministic, the digests tell if not.
+pub struct Lockstep {
+    peer: Peer,
+    /// The events of the host are applied first in every tick.
+    host: bool,
+    /// The first tick of the run, the ones until the first inputs arrive have none.
+    start_tick: u64,
+    /// Events of this player sent for the next ticks.
+    local: BTreeMap<u64, Vec<PlayerEvent>>,
+    /// Events of the other player received for the next ticks.
+    remote: BTreeMap<u64, Vec<PlayerEvent>>,
+    /// Digests of the ticks only one of the players has simulated so far.
+    local_digests: BTreeMap<u64, u64>,
+    remote_digests: BTreeMap<u64, u64>,
+    /// Whether the last frame waited for the inputs of the other player.
+    pub waiting: bool,
+    /// The first tick whose digest differs between the players.
+    pub desync: Option<u64>,
+    /// Set once the connection is lost, the run goes on with this player alone.
+    pub error: Option<String>,
+}
+
+impl Lockstep {
+    pub fn new(peer: Peer, host: bool, start_tick: u64) -> Self {
+        Self {
+            peer,
+            host,
+            start_tick,
+            local: default(),
+            remote: default(),
+            local_digests: default(),
+            remote_digests: default(),
+            waiting: false,
+            desync: None,
+            error: None,
+        }
+    }
+
+    /// Whether the inputs of both players for the tick are in.
+    fn is_ready(&mut self, tick: u64) -> bool {
+        if self.error.is_none() {
+            match self.peer.receive() {
+                Ok(messages) => {
+                    for message in messages {
+                        self.receive(message);
+                    }
+                }
+                Err(error) => self.fail(error),
+            }
+        }
+        self.error.is_some()
+            || tick < self.start_tick + INPUT_DELAY
+            || self.remote.contains_key(&tick)
+    }
+
+    fn receive(&mut self, message: NetMessage) {
+        match message {
+            NetMessage::Input { tick, events } => {
+                self.remote.insert(tick, events);
+            }
+            NetMessage::Digest { tick, digest } => {
+                self.remote_digests.insert(tick, digest);
+                self.compare_digests();
+            }
+            NetMessage::Start { .. } => self.fail("The run was started twice".to_owned()),
+        }
+    }
+
+    fn send(&mut self, message: NetMessage) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(error) = self.peer.send(&message) {
+            self.fail(error);
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        error!("Co-op: {}", error);
+        self.error = Some(error);
+    }
+
+    fn compare_digests(&mut self) {
+        let local = &mut self.local_digests;
+        let remote = &mut self.remote_digests;
+        while let (Some((&local_tick, &local_digest)), Some((&remote_tick, &remote_digest))) =
+            (local.iter().next(), remote.iter().next())
+        {
+            if local_tick == remote_tick && local_digest != remote_digest && self.desync.is_none() {
+                error!("Co-op: the runs diverged at tick {}", local_tick);
+                self.desync = Some(local_tick);
+            }
+            // The older one has no match left
+            if local_tick <= remote_tick {
+                local.remove(&local_tick);
+            }
+            if remote_tick <= local_tick {
+                remote.remove(&remote_tick);
+            }
+        }
+    }
+}
+
+impl Model {
+    /// Whether the next tick can run, a co-op run waits for the inputs of the other player.
+    pub(super) fn lockstep_ready(&mut self) -> bool {
+        let tick = self.world.current_tick;
+        let lockstep = match &mut self.lockstep {
+            Some(lockstep) => lockstep,
+            None => return true,
+        };
+        let ready = lockstep.is_ready(tick);
+        lockstep.waiting = !ready;
+        ready
+    }
+
+    /// Sends the events queued by this player for a later tick, and queues the events
+    /// of both players for this one instead.
+    pub(super) fn exchange_input(&mut self) {
+        let tick = self.world.current_tick;
+        let digest = (tick % DIGEST_INTERVAL == 0).then(|| self.digest());
+        let lockstep = match &mut self.lockstep {
+            Some(lockstep) => lockstep,
+            None => return,
+        };
+        if let Some(digest) = digest {
+            lockstep.send(NetMessage::Digest { tick, digest });
+            lockstep.local_digests.insert(tick, digest);
+            lockstep.compare_digests();
+        }
+        let events = std::mem::take(&mut self.queued_events);
+        let later = tick + INPUT_DELAY;
+        lockstep.send(NetMessage::Input {
+            tick: later,
+            events: events.clone(),
+        });
+        lockstep.local.insert(later, events);
+        let local = lockstep.local.remove(&tick).unwrap_or_default();
+        let remote = lockstep.remote.remove(&tick).unwrap_or_default();
+        self.queued_events = if lockstep.host {
+            local.into_iter().chain(remote).collect()
+        } else {
+            remote.into_iter().chain(local).collect()
+        };
+    }
+}
@@ -0,0 +1,21 @@
+use super::*;
+
+/// Health ratio of a mech below which the low health state is on.
+const LOW_HEALTH_RATIO: f32 = 0.3;
+/// How fast the wave units come in while the health is low.
+pub const LOW_HEALTH_SPAWN_PACE: f32 = 0.8;
+/// Volume of the sounds while the health is low, as if heard through the hull.
+pub const LOW_HEALTH_VOLUME: f64 = 0.5;
+
+impl Model {
+    /// Whether one of the player's mechs is alive with low health.
+    pub fn check_low_health(&self) -> bool {
+        self.world.units.iter().any(|unit| {
+            let health = &self.world.bodies.healths[unit.id];
+            unit.faction == Faction::Mech
+                && !unit.npc
+                && health.is_alive()
+                && health.ratio() < Hp::new(LOW_HEALTH_RATIO)
+        })
+    }
+}
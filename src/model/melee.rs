@@ -0,0 +1,100 @@
+use super::*;
+use logic::*;
+
+/// Area in front of the caster that a melee swing hits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MeleeShape {
+    /// A circle sector directed where the caster is facing
+    Arc {
+        radius: Coord,
+        /// Half of the sector's angle, in radians
+        half_angle: Coord,
+    },
+    /// A box with its center offset in the direction the caster is facing
+    Box { offset: Position, size: Vec2<Coord> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeleeSwingEffect<E = Effect> {
+    pub shape: MeleeShape,
+    /// Attachment point of the caster the swing originates from
+    pub attachment: Option<String>,
+    /// How long the hitbox stays active
+    pub duration: Time,
+    /// Effect applied once to every enemy hit by the swing
+    pub effect: E,
+    /// Speed multiplier of the enemy projectiles reflected by the swing, they are not reflected if `None`
+    pub deflect: Option<R32>,
+}
+
+/// An active melee hitbox attached to its caster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeleeSwing {
+    pub caster: Id,
+    pub shape: MeleeShape,
+    pub attachment: Option<String>,
+    pub time_left: Time,
+    pub effect: Effect,
+    pub deflect: Option<R32>,
+    /// Units and projectiles that have already been hit by the swing
+    pub hit: Vec<Id>,
+}
+
+impl MeleeShape {
+    /// Checks whether the collider overlaps the shape of the swing performed by the caster.
+    pub fn check(
+        &self,
+        origin: Position,
+        facing_left: bool,
+        position: Position,
+        collider: &Collider,
+    ) -> bool {
+        let direction = if facing_left {
+            Coord::new(-1.0)
+        } else {
+            Coord::ONE
+        };
+        match self {
+            Self::Arc { radius, half_angle } => {
+                let Collider::Aabb { size } = collider;
+                let delta = position - origin;
+                let reach = *radius + size.x.max(size.y) / Coord::new(2.0);
+                if delta.len() > reach {
+                    return false;
+                }
+                let angle = vec2(delta.x * direction, delta.y).arg().abs();
+                angle <= *half_angle
+            }
+            Self::Box { offset, size } => {
+                let center = origin + vec2(offset.x * direction, offset.y);
+                Collider::Aabb { size: *size }.check(collider, position - center)
+            }
+        }
+    }
+}
+
+impl MeleeSwing {
+    /// Returns the origin of the swing if the caster is still alive.
+    pub fn origin(&self, caster: &Unit, bodies: &Bodies) -> Position {
+        self.attachment
+            .as_ref()
+            .and_then(|name| caster.attachment_position(bodies, name))
+            .unwrap_or(bodies.positions[caster.id])
+    }
+}
+
+impl MeleeSwingEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let caster = context.caster?;
+        logic.model.world.melee_swings.push(MeleeSwing {
+            caster,
+            shape: self.shape,
+            attachment: self.attachment,
+            time_left: self.duration,
+            effect: self.effect,
+            deflect: self.deflect,
+            hit: vec![],
+        });
+        Some(())
+    }
+}
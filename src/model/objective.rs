@@ -0,0 +1,73 @@
+use super::*;
+
+/// Something the level does once the condition is met, each trigger fires once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelTrigger {
+    pub condition: TriggerCondition,
+    pub action: TriggerAction,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TriggerCondition {
+    /// Seconds after the start of the run.
+    Time(f32),
+    /// Once the number of waves has been cleared.
+    WavesCleared(usize),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TriggerAction {
+    /// Opens the leftmost locked door.
+    OpenDoor,
+    /// Places a generator at `x` that the mechs have to protect for `duration` seconds.
+    ProtectGenerator { x: f32, duration: f32 },
+    /// An engineer joins the mechs at `x`.
+    Ally { x: f32 },
+    /// An engineer joins the mechs at `x` and has to be escorted to the `destination`.
+    Escort { x: f32, destination: f32 },
+}
+
+/// An allied unit that has to survive until the goal is reached, the run is lost with it.
+/// The unit leaves the level once the objective is complete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Objective {
+    pub unit: Id,
+    pub goal: ObjectiveGoal,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ObjectiveGoal {
+    /// Survive until the time runs out.
+    Survive { time_left: Time },
+    /// Reach the position.
+    Reach { x: Coord },
+}
+
+impl TriggerCondition {
+    pub fn is_met(&self, model: &Model) -> bool {
+        match *self {
+            Self::Time(time) => model.time() >= Time::new(time),
+            Self::WavesCleared(waves) => model.world.waves_cleared >= waves,
+        }
+    }
+}
+
+impl TriggerAction {
+    pub fn event(&self) -> LevelEvent {
+        match *self {
+            Self::OpenDoor => LevelEvent::OpenDoor,
+            Self::ProtectGenerator { x, duration } => LevelEvent::ProtectGenerator {
+                x: Coord::new(x),
+                duration: Time::new(duration),
+            },
+            Self::Ally { x } => LevelEvent::Ally {
+                x: Coord::new(x),
+                destination: None,
+            },
+            Self::Escort { x, destination } => LevelEvent::Ally {
+                x: Coord::new(x),
+                destination: Some(Coord::new(destination)),
+            },
+        }
+    }
+}
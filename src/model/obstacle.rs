@@ -0,0 +1,75 @@
+use super::*;
+
+/// Collider size of a projectile above which it counts as heavy and damages obstacles.
+const HEAVY_PROJECTILE_SIZE: f32 = 0.6;
+pub const OBSTACLE_HP: f32 = 10.0;
+
+/// A piece of terrain that blocks projectiles and can be destroyed by explosions.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Obstacle {
+    pub id: Id,
+    pub position: Position,
+    pub collider: Collider,
+    pub health: Health,
+    pub sprite: Sprite,
+}
+
+impl Obstacle {
+    pub fn new(id: Id, sprite: Sprite, x: Coord, ground_level: Coord, hp: Hp) -> Self {
+        let size = sprite.size.map(Coord::new);
+        Self {
+            id,
+            position: vec2(x, ground_level + size.y / Coord::new(2.0)),
+            collider: Collider::Aabb { size },
+            health: Health::new(hp),
+            sprite,
+        }
+    }
+}
+
+impl Projectile {
+    pub fn is_heavy(&self, bodies: &Bodies) -> bool {
+        match bodies.colliders.get(self.id) {
+            Some(Collider::Aabb { size }) => {
+                size.x.max(size.y) >= Coord::new(HEAVY_PROJECTILE_SIZE)
+            }
+            None => false,
+        }
+    }
+}
+
+impl Effect {
+    /// The damage the effect deals to obstacles, only explosions
+    /// and damage from heavy projectiles affect the terrain.
+    /// Values relative to the units' health do not damage obstacles.
+    pub fn obstacle_damage(&self, heavy: bool) -> Hp {
+        match self.node() {
+            EffectNode::Damage(effect) => match effect.damage_type {
+                DamageType::Explosive => effect.value.flat(),
+                DamageType::Physical if heavy => effect.value.flat(),
+                DamageType::Physical | DamageType::Energy => Hp::ZERO,
+            },
+            EffectNode::List(effect) => effect
+                .effects
+                .iter()
+                .map(|&effect| self.subtree(effect).obstacle_damage(heavy))
+                .fold(Hp::ZERO, Hp::add),
+            EffectNode::Area(effect) => self.subtree(effect.effect).obstacle_damage(heavy),
+            _ => Hp::ZERO,
+        }
+    }
+
+    /// The part of the [Self::obstacle_damage] dealt by the projectile hitting the obstacle
+    /// itself, the areas damage the obstacles around them once they are processed.
+    pub fn contact_obstacle_damage(&self, heavy: bool) -> Hp {
+        match self.node() {
+            EffectNode::List(effect) => effect
+                .effects
+                .iter()
+                .map(|&effect| self.subtree(effect).contact_obstacle_damage(heavy))
+                .fold(Hp::ZERO, Hp::add),
+            EffectNode::Area(_) => Hp::ZERO,
+            _ => self.obstacle_damage(heavy),
+        }
+    }
+}
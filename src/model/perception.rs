@@ -0,0 +1,52 @@
+use super::*;
+
+/// The longest reaction time the motion history covers.
+const MAX_REACTION_TIME: f32 = 1.0;
+
+/// How well the unit's AI aims, the mechs aim perfectly.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AiSkill {
+    /// How late the unit notices the target changing its movement.
+    pub reaction_time: Time,
+    /// Maximum angle the aim is turned by at random.
+    pub aim_error: Coord,
+}
+
+impl AiSkill {
+    pub const PERFECT: Self = Self {
+        reaction_time: Time::ZERO,
+        aim_error: Coord::ZERO,
+    };
+
+    pub fn new(reaction_time: f32, aim_error: f32) -> Self {
+        Self {
+            reaction_time: Time::new(reaction_time),
+            aim_error: Coord::new(aim_error),
+        }
+    }
+}
+
+impl Unit {
+    /// Records the current position and velocity, the latest first.
+    pub fn record_motion(&mut self, bodies: &Bodies) {
+        let capacity = (MAX_REACTION_TIME / logic::TICK_TIME) as usize;
+        self.motion_history
+            .push_front((bodies.positions[self.id], bodies.velocities[self.id]));
+        self.motion_history.truncate(capacity);
+    }
+
+    /// Where the unit seems to be and how it seems to move to an observer that reacts
+    /// with the delay: the motion the unit had back then, continued until now.
+    pub fn perceived_motion(&self, bodies: &Bodies, delay: Time) -> (Position, Velocity) {
+        // Newly spawned units have a shorter history
+        let ticks = ((delay.as_f32() / logic::TICK_TIME) as usize)
+            .min(self.motion_history.len().saturating_sub(1));
+        match self.motion_history.get(ticks) {
+            Some(&(position, velocity)) if ticks > 0 => {
+                let age = Time::new(ticks as f32 * logic::TICK_TIME);
+                (position + velocity * age, velocity)
+            }
+            _ => (bodies.positions[self.id], bodies.velocities[self.id]),
+        }
+    }
+}
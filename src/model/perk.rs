@@ -0,0 +1,245 @@
+use super::*;
+
+/// An upgrade for the mechs, chosen by the player after clearing a wave.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Perk {
+    /// Identifies the perk in the unlocks, its text is in the locale files under [crate::perk_key].
+    pub name: String,
+    pub modifiers: Vec<PerkModifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PerkModifier {
+    /// Multiplies the maximum health
+    Health(R32),
+    /// Multiplies the movement speed
+    Speed(R32),
+    /// Multiplies the action cooldown
+    Cooldown(R32),
+    /// Multiplies the damage dealt
+    Damage(R32),
+    /// Adds the modifier to the stats
+    Stat(StatModifier),
+    /// Multiplies the healing of heal effects
+    Healing(R32),
+    /// Adds the effect to the on-hit effects of the projectiles
+    OnHit(Effect),
+}
+
+/// Number of perks offered to the player at once.
+pub const PERK_CHOICES: usize = 3;
+/// Maximum hp of the hypothetical target the on-hit effects are previewed against.
+const PREVIEW_TARGET_HP: f32 = 20.0;
+
+impl Perk {
+    fn new(name: &str, modifiers: Vec<PerkModifier>) -> Self {
+        Self {
+            name: name.to_owned(),
+            modifiers,
+        }
+    }
+
+    /// All perks that can be offered to the player.
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::new("Reinforced Plating", vec![PerkModifier::Health(r32(1.25))]),
+            Self::new("Servo Upgrade", vec![PerkModifier::Speed(r32(1.2))]),
+            Self::new("Overclock", vec![PerkModifier::Cooldown(r32(0.8))]),
+            Self::new("Hardened Rounds", vec![PerkModifier::Damage(r32(1.25))]),
+            Self::new(
+                "Targeting Computer",
+                vec![PerkModifier::Stat(StatModifier::new(
+                    Stat::Crit,
+                    ModifierKind::Flat,
+                    0.1,
+                ))],
+            ),
+            Self::new("Nanite Swarm", vec![PerkModifier::Healing(r32(1.5))]),
+            Self::new(
+                "Shrapnel",
+                vec![PerkModifier::OnHit(Effect::new(EffectNode::Area(
+                    AreaEffect {
+                        center: logic::Who::Target,
+                        radius: Coord::new(2.0),
+                        filter: FactionFilter::Enemies,
+                        hits_caster: false,
+                        falloff: Some(Falloff {
+                            start: Coord::new(0.5),
+                            end: Coord::new(2.0),
+                            min: r32(0.5),
+                        }),
+                        effect: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Explosive,
+                            value: Value::Flat(Hp::new(1.0)),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                    },
+                )))],
+            ),
+            Self::new(
+                "Glass Cannon",
+                vec![
+                    PerkModifier::Damage(r32(1.5)),
+                    PerkModifier::Health(r32(0.75)),
+                ],
+            ),
+            Self::new(
+                "Piercing Rounds",
+                vec![PerkModifier::OnHit(Effect::new(EffectNode::Damage(
+                    DamageEffect {
+                        damage_type: DamageType::Physical,
+                        value: Value::Flat(Hp::new(2.0)),
+                        hits_caster: false,
+                        impact: None,
+                    },
+                )))],
+            ),
+            Self::new(
+                "Executioner",
+                vec![PerkModifier::OnHit(Effect::new(EffectNode::Script(
+                    ScriptEffect {
+                        function: "execute".to_owned(),
+                    },
+                )))],
+            ),
+        ]
+    }
+}
+
+impl Perk {
+    /// What the on-hit effects of the perk do to a typical enemy, `None` if it has none.
+    pub fn preview(&self) -> Option<EffectPreview> {
+        let target = Health::new(Hp::new(PREVIEW_TARGET_HP));
+        let mut preview = EffectPreview::default();
+        for modifier in &self.modifiers {
+            if let PerkModifier::OnHit(effect) = modifier {
+                preview.merge(effect.describe(None, &target));
+            }
+        }
+        if preview.is_empty() {
+            None
+        } else {
+            Some(preview)
+        }
+    }
+}
+
+impl PerkModifier {
+    pub fn apply_template(
+        &self,
+        template: &mut UnitTemplate,
+        animations: &mut Animations,
+        copies: &mut AnimationCopies,
+    ) {
+        self.apply(
+            &mut template.health,
+            &mut template.action,
+            &mut template.weapon,
+            &mut template.stats,
+            animations,
+            copies,
+        );
+    }
+
+    pub fn apply_unit(
+        &self,
+        unit: &mut Unit,
+        health: &mut Health,
+        animations: &mut Animations,
+        copies: &mut AnimationCopies,
+    ) {
+        self.apply(
+            health,
+            &mut unit.action,
+            &mut unit.weapon,
+            &mut unit.stats,
+            animations,
+            copies,
+        );
+    }
+
+    fn apply(
+        &self,
+        health: &mut Health,
+        action: &mut Action,
+        weapon: &mut Option<Weapon>,
+        stats: &mut Stats,
+        animations: &mut Animations,
+        copies: &mut AnimationCopies,
+    ) {
+        match self {
+            Self::Health(multiplier) => {
+                health.set_max_hp(health.max_hp * *multiplier, MaxHpChange::Scale);
+            }
+            Self::Speed(multiplier) => stats.add(Stat::MoveSpeed, ModifierKind::More, *multiplier),
+            Self::Cooldown(multiplier) => {
+                // Recovering faster is the same as a shorter cooldown
+                stats.add(
+                    Stat::CooldownRate,
+                    ModifierKind::More,
+                    R32::ONE / *multiplier,
+                );
+            }
+            Self::Damage(multiplier) => stats.add(Stat::Attack, ModifierKind::More, *multiplier),
+            Self::Stat(modifier) => stats.modifiers.push(*modifier),
+            Self::Healing(multiplier) => {
+                modify_effects(action, weapon, animations, copies, |effect, _| {
+                    if let EffectNode::Heal(heal) = effect {
+                        heal.value.scale(*multiplier);
+                    }
+                });
+            }
+            Self::OnHit(on_hit) => {
+                modify_effects(action, weapon, animations, copies, |effect, nodes| {
+                    if let EffectNode::Projectile(projectile) = effect {
+                        let extra = on_hit.copy_into(nodes);
+                        nodes.push(EffectNode::List(ListEffect {
+                            effects: vec![projectile.on_hit, extra],
+                        }));
+                        projectile.on_hit = nodes.len() - 1;
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Calls `f` on every effect of the action and the weapon.
+fn modify_effects(
+    action: &mut Action,
+    weapon: &mut Option<Weapon>,
+    animations: &mut Animations,
+    copies: &mut AnimationCopies,
+    mut f: impl FnMut(&mut EffectNode<EffectId>, &mut EffectNodes),
+) {
+    action.modify_effects(animations, copies, &mut f);
+    if let Some(weapon) = weapon {
+        weapon.effect.walk_mut(&mut f);
+    }
+}
+
+impl Model {
+    pub fn apply_perk(&mut self, perk: Perk) {
+        for modifier in &perk.modifiers {
+            let mut copies = AnimationCopies::new();
+            for mech in game::MechType::ALL {
+                modifier.apply_template(
+                    self.world.templates.mech_mut(mech),
+                    &mut self.world.animations,
+                    &mut copies,
+                );
+            }
+            for unit in self
+                .world
+                .units
+                .iter_mut()
+                .filter(|unit| unit.faction == Faction::Mech)
+            {
+                let health = &mut self.world.bodies.healths[unit.id];
+                modifier.apply_unit(unit, health, &mut self.world.animations, &mut copies);
+            }
+        }
+        self.world.perks.push(perk);
+    }
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+/// An item lying on the ground that mechs collect by walking over it.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Pickup {
+    pub id: Id,
+    pub position: Position,
+    pub collider: Collider,
+    pub sprite: Sprite,
+    pub lifetime: Time,
+    /// Effect applied to the unit that collects the pickup
+    pub effect: Effect,
+}
+
+impl Pickup {
+    pub fn can_collect(&self, unit: &Unit) -> bool {
+        if unit.faction != Faction::Mech {
+            return false;
+        }
+        match self.effect.node() {
+            EffectNode::GiveWeapon(effect) => unit.can_equip(&effect.weapon),
+            EffectNode::RestoreAmmo(_) => unit
+                .ammo
+                .as_ref()
+                .map(|ammo| ammo.loaded < ammo.capacity)
+                .unwrap_or(false),
+            _ => true,
+        }
+    }
+}
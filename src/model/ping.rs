@@ -0,0 +1,64 @@
+use super::*;
+
+/// How long a ping stays in the world.
+pub const PING_LIFETIME: f32 = 4.0;
+/// Distance from the ping within which the mechs pay attention to the pinged object.
+pub const PING_RADIUS: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PingKind {
+    /// The mechs focus the enemy next to the ping.
+    Danger,
+    /// The mechs move towards the ping.
+    GoHere,
+    /// The mechs move towards the pickup next to the ping.
+    PickUp,
+}
+
+/// A marker placed by the player to direct the attention of the mechs and the other players.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Ping {
+    pub id: Id,
+    pub kind: PingKind,
+    pub position: Position,
+    pub lifetime: Time,
+}
+
+impl Model {
+    /// Places a ping at the position, its kind depends on what is pinged.
+    pub fn place_ping(&mut self, position: Position) {
+        let radius = Coord::new(PING_RADIUS);
+        let near = |pos: Position| (pos - position).len() <= radius;
+        let kind =
+            if self.world.units.iter().any(|unit| {
+                unit.faction != Faction::Mech && near(self.world.bodies.positions[unit.id])
+            }) {
+                PingKind::Danger
+            } else if self
+                .world
+                .pickups
+                .iter()
+                .any(|pickup| near(pickup.position))
+            {
+                PingKind::PickUp
+            } else {
+                PingKind::GoHere
+            };
+        let id = self.world.id_gen.gen();
+        self.world.pings.insert(Ping {
+            id,
+            kind,
+            position,
+            lifetime: Time::new(PING_LIFETIME),
+        });
+    }
+
+    /// The latest ping of the kind.
+    pub fn latest_ping(&self, kind: PingKind) -> Option<&Ping> {
+        self.world
+            .pings
+            .iter()
+            .filter(|ping| ping.kind == kind)
+            .max_by_key(|ping| ping.lifetime)
+    }
+}
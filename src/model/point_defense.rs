@@ -0,0 +1,46 @@
+use super::*;
+
+/// Shoots down the enemy projectiles flying close to the unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointDefense {
+    /// Distance at which the enemy projectiles are targeted.
+    pub range: Coord,
+    pub cooldown: Time,
+    pub cooldown_left: Time,
+    /// The interceptor launched at the projectile.
+    pub shot: ProjectileEffect,
+}
+
+impl PointDefense {
+    /// Fast flak fired from the tank's cannon.
+    pub fn flak(assets: &Assets, animations: &mut Animations) -> Self {
+        Self {
+            range: Coord::new(6.0),
+            cooldown: Time::new(1.5),
+            cooldown_left: Time::ZERO,
+            shot: ProjectileEffect {
+                offset: Position::ZERO,
+                attachment: Some("muzzle".to_owned()),
+                ai: ProjectileAI::Idle,
+                collider: Collider::Aabb {
+                    size: vec2(0.4, 0.4).map(Coord::new),
+                },
+                speed: Coord::new(25.0),
+                spread: Coord::ZERO,
+                pellets: 1,
+                fan: Coord::ZERO,
+                burst: None,
+                max_range: Some(Coord::new(8.0)),
+                arming_distance: Coord::ZERO,
+                falloff: None,
+                prediction: AimPrediction::None,
+                missing: MissingUnit::Skip,
+                on_hit: Effect::noop(),
+                on_expire: Effect::noop(),
+                animation: tank_projectile_animation(assets, animations, 0.5),
+                intercepts: true,
+                lifetime: None,
+            },
+        }
+    }
+}
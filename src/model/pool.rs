@@ -0,0 +1,122 @@
+use super::*;
+
+/// Maximum number of despawned entities kept for reuse.
+const MAX_POOL_SIZE: usize = 256;
+
+/// Despawned entities of one kind, reused by the next ones spawned,
+/// so that the dense bullet patterns do not keep allocating.
+/// Not a part of the [World], the pooled entities are gone from the simulation.
+pub struct Pool<T> {
+    free: Vec<T>,
+    /// Buffer of the ids despawned by the last [Pool::retain].
+    despawned: Vec<Id>,
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Self {
+        Self {
+            free: vec![],
+            despawned: vec![],
+        }
+    }
+}
+
+impl<T: HasId<Id = Id>> Pool<T> {
+    /// A despawned entity whose buffers the new one can take over.
+    pub fn reuse(&mut self) -> Option<T> {
+        self.free.pop()
+    }
+
+    /// Despawns the entities that do not satisfy the predicate.
+    /// Their ids and bodies are freed and the entities are kept for reuse.
+    pub fn retain(
+        &mut self,
+        entities: &mut Collection<T>,
+        id_gen: &mut IdGen,
+        bodies: &mut Bodies,
+        mut f: impl FnMut(&T) -> bool,
+    ) {
+        self.despawned.extend(
+            entities
+                .iter()
+                .filter(|entity| !f(entity))
+                .map(|entity| *entity.id()),
+        );
+        // The ids given out next must not depend on the storage order
+        self.despawned.sort();
+        for id in self.despawned.drain(..) {
+            let entity = match entities.remove(&id) {
+                Some(entity) => entity,
+                None => continue,
+            };
+            bodies.remove(id);
+            id_gen.free(id);
+            if self.free.len() < MAX_POOL_SIZE {
+                self.free.push(entity);
+            }
+        }
+    }
+}
+
+impl Model {
+    /// Despawns the projectiles that do not satisfy the predicate, see [Pool::retain].
+    pub fn retain_projectiles(&mut self, f: impl FnMut(&Projectile) -> bool) {
+        let world = &mut self.world;
+        self.projectile_pool.retain(
+            &mut world.projectiles,
+            &mut world.id_gen,
+            &mut world.bodies,
+            f,
+        );
+    }
+
+    /// Despawns the particles that do not satisfy the predicate, see [Pool::retain].
+    pub fn retain_particles(&mut self, f: impl FnMut(&Particle) -> bool) {
+        let world = &mut self.world;
+        self.particle_pool.retain(
+            &mut world.particles,
+            &mut world.id_gen,
+            &mut world.bodies,
+            f,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(HasId)]
+    struct Entity {
+        id: Id,
+    }
+
+    #[test]
+    fn despawned_ids_are_reused() {
+        let mut id_gen = IdGen::new();
+        let mut bodies = Bodies::default();
+        let mut entities: Collection<Entity> = default();
+        let mut pool = Pool::default();
+        for _ in 0..3 {
+            let id = id_gen.gen();
+            bodies.insert(id, Body::new(Position::ZERO, Velocity::ZERO));
+            entities.insert(Entity { id });
+        }
+        let first = entities.iter().map(|entity| entity.id).min().unwrap();
+
+        pool.retain(&mut entities, &mut id_gen, &mut bodies, |entity| {
+            entity.id != first
+        });
+        assert_eq!(entities.len(), 2);
+        assert!(bodies.get(first).is_none());
+        assert_eq!(pool.reuse().map(|entity| entity.id), Some(first));
+        assert!(pool.reuse().is_none());
+
+        // The index is reused, but the stale id does not match the new entity
+        let reused = id_gen.gen();
+        assert_ne!(reused, first);
+        pool.retain(&mut entities, &mut id_gen, &mut bodies, |_| false);
+        assert_eq!(entities.len(), 0);
+        assert_eq!(pool.free.len(), 2);
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+/// What an effect is expected to do to a single target, worked out from the data without
+/// processing it, so that the tooltips follow the balance changes.
+#[derive(Debug, Clone, Default)]
+pub struct EffectPreview {
+    pub damage: Hp,
+    pub heal: Hp,
+    /// The largest radius of the areas and zones.
+    pub radius: Option<Coord>,
+    /// Names of the statuses applied to the target, see [Status::name].
+    pub statuses: Vec<&'static str>,
+}
+
+impl EffectPreview {
+    /// Whether the preview found anything to show.
+    pub fn is_empty(&self) -> bool {
+        self.damage == Hp::ZERO
+            && self.heal == Hp::ZERO
+            && self.radius.is_none()
+            && self.statuses.is_empty()
+    }
+
+    /// Adds up the previews of the effects applied together.
+    pub fn merge(&mut self, other: Self) {
+        self.damage += other.damage;
+        self.heal += other.heal;
+        self.radius = self.radius.max(other.radius);
+        self.statuses.extend(other.statuses);
+    }
+
+    fn add_radius(&mut self, radius: Coord) {
+        self.radius = Some(self.radius.map_or(radius, |known| known.max(radius)));
+    }
+}
+
+impl Effect {
+    /// A dry run of the effect against a hypothetical target, assuming that every pellet,
+    /// area and zone tick hits it at full strength. The scripts are opaque to the preview.
+    pub fn describe(&self, caster: Option<&dyn ValueSource>, target: &Health) -> EffectPreview {
+        let mut preview = EffectPreview::default();
+        self.preview(caster, target, R32::ONE, &mut preview);
+        preview
+    }
+
+    /// Adds the effect to the preview, the damage and healing scaled by the number of hits.
+    fn preview(
+        &self,
+        caster: Option<&dyn ValueSource>,
+        target: &Health,
+        hits: R32,
+        preview: &mut EffectPreview,
+    ) {
+        let child = |id: EffectId| self.subtree(id);
+        match self.node() {
+            EffectNode::List(effect) => {
+                for &effect in &effect.effects {
+                    child(effect).preview(caster, target, hits, preview);
+                }
+            }
+            EffectNode::Projectile(effect) => {
+                let shots = effect.pellets * effect.burst.map_or(1, |burst| burst.shots);
+                let hits = hits * r32(shots as f32);
+                child(effect.on_hit).preview(caster, target, hits, preview);
+                child(effect.on_expire).preview(caster, target, hits, preview);
+            }
+            EffectNode::Damage(effect) => {
+                preview.damage += effect.value.evaluate(caster, target) * hits;
+            }
+            EffectNode::Heal(effect) => {
+                preview.heal += effect.value.evaluate(caster, target) * hits;
+            }
+            EffectNode::Area(effect) => {
+                preview.add_radius(effect.radius);
+                child(effect.effect).preview(caster, target, hits, preview);
+            }
+            EffectNode::SpawnZone(effect) => {
+                preview.add_radius(effect.area.radius);
+                let ticks = (effect.duration / effect.tick_time).as_f32().floor();
+                let hits = hits * r32(ticks.max(1.0));
+                child(effect.area.effect).preview(caster, target, hits, preview);
+            }
+            EffectNode::Dash(effect) => {
+                child(effect.on_contact).preview(caster, target, hits, preview)
+            }
+            EffectNode::MeleeSwing(effect) => {
+                child(effect.effect).preview(caster, target, hits, preview)
+            }
+            EffectNode::Grapple(effect) => {
+                child(effect.on_attach).preview(caster, target, hits, preview)
+            }
+            EffectNode::ApplyStatus(effect) => {
+                preview.statuses.push(effect.status.name());
+                if let Status::Burning {
+                    time,
+                    damage_per_second,
+                } = effect.status
+                {
+                    preview.damage += damage_per_second * time * hits;
+                }
+                child(effect.on_expire).preview(caster, target, hits, preview);
+            }
+            EffectNode::Noop
+            | EffectNode::Sound(_)
+            | EffectNode::FireWeapon
+            | EffectNode::GiveWeapon(_)
+            | EffectNode::RestoreAmmo(_)
+            | EffectNode::SlowFall(_)
+            | EffectNode::ModifyMaxHp(_)
+            | EffectNode::Script(_) => {}
+        }
+    }
+}
+
+impl UnitTemplate {
+    /// What the unit's action and death do to a target, for the codex.
+    pub fn describe_abilities(&self, animations: &Animations, target: &Health) -> EffectPreview {
+        let mut preview = EffectPreview::default();
+        let actions = std::iter::once(&self.action).chain(
+            self.boss
+                .iter()
+                .flat_map(|boss| boss.phases.iter().map(|phase| &phase.action)),
+        );
+        for action in actions {
+            for frame in &animations.get(action.animation).keyframes {
+                if let Some(effect) = &frame.start_effect {
+                    preview.merge(effect.describe(Some(self), target));
+                }
+            }
+        }
+        preview.merge(self.on_death.describe(Some(self), target));
+        preview
+    }
+}
@@ -0,0 +1,181 @@
+use super::*;
+use logic::Who;
+
+/// For how long the units stay wet after leaving the water or the rain.
+pub const WET_TIME: f32 = 4.0;
+
+/// A status that reacts with the damage of some type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    Burning,
+    Wet,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReactionKind {
+    /// The fire bursts into an explosion around the target.
+    Blast,
+    /// The current jumps from the target to the units around it.
+    Shock,
+}
+
+/// A bonus effect triggered when the damage lands on a unit with the element,
+/// the element's status is consumed by the reaction.
+#[derive(Debug, Clone)]
+pub struct Reaction {
+    pub element: Element,
+    pub damage_type: DamageType,
+    pub kind: ReactionKind,
+}
+
+/// The reaction table consulted when damage lands on a unit.
+pub static REACTIONS: [Reaction; 2] = [
+    Reaction {
+        element: Element::Burning,
+        damage_type: DamageType::Explosive,
+        kind: ReactionKind::Blast,
+    },
+    Reaction {
+        element: Element::Wet,
+        damage_type: DamageType::Energy,
+        kind: ReactionKind::Shock,
+    },
+];
+
+impl Status {
+    pub fn element(&self) -> Option<Element> {
+        match self {
+            Self::Burning { .. } => Some(Element::Burning),
+            Self::Wet { .. } => Some(Element::Wet),
+            _ => None,
+        }
+    }
+}
+
+impl Unit {
+    /// Makes the unit wet, a unit that is already wet has the time refreshed.
+    pub fn soak(&mut self) {
+        self.statuses
+            .retain(|status| !matches!(status.status, Status::Wet { .. }));
+        self.statuses.push(
+            Status::Wet {
+                time: Time::new(WET_TIME),
+            }
+            .into(),
+        );
+    }
+}
+
+impl Reaction {
+    /// The reaction of the damage with the first of the unit's statuses that reacts with it.
+    pub fn find(damage_type: &DamageType, unit: &Unit) -> Option<&'static Self> {
+        unit.statuses.iter().find_map(|status| {
+            let element = status.status.element()?;
+            REACTIONS.iter().find(|reaction| {
+                reaction.element == element && reaction.damage_type == *damage_type
+            })
+        })
+    }
+
+    /// The effect of the reaction, centered on the target.
+    pub fn effect(&self) -> Effect {
+        let (radius, damage_type, damage) = match self.kind {
+            ReactionKind::Blast => (3.0, DamageType::Explosive, 3.0),
+            ReactionKind::Shock => (3.0, DamageType::Energy, 1.5),
+        };
+        let sound = damage_type
+            .impact()
+            .sound
+            .map(|sound| Effect::new(EffectNode::Sound(sound)));
+        let area = Effect::new(EffectNode::Area(AreaEffect {
+            center: Who::Target,
+            radius: Coord::new(radius),
+            filter: FactionFilter::Enemies,
+            hits_caster: false,
+            falloff: None,
+            effect: Effect::new(EffectNode::Damage(DamageEffect {
+                damage_type,
+                value: Value::Flat(Hp::new(damage)),
+                hits_caster: false,
+                impact: None,
+            })),
+        }));
+        Effect::new(EffectNode::List(ListEffect {
+            effects: std::iter::once(area).chain(sound).collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bench::{test_effect, test_model, test_spawn};
+
+    fn burning() -> AppliedStatus {
+        Status::Burning {
+            time: Time::new(5.0),
+            damage_per_second: Hp::ZERO,
+        }
+        .into()
+    }
+
+    fn explosion() -> Effect {
+        Effect::new(EffectNode::Damage(DamageEffect {
+            damage_type: DamageType::Explosive,
+            value: Value::Flat(Hp::new(1.0)),
+            hits_caster: false,
+            impact: None,
+        }))
+    }
+
+    fn hp(model: &Model, id: Id) -> Hp {
+        model.world.bodies.healths.get(id).unwrap().hp
+    }
+
+    #[test]
+    fn the_table_matches_the_element_and_the_damage_type() {
+        let mut model = test_model();
+        let id = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        let unit = model.world.units.get_mut(&id).unwrap();
+        assert!(Reaction::find(&DamageType::Explosive, unit).is_none());
+        unit.statuses.push(burning());
+        assert!(matches!(
+            Reaction::find(&DamageType::Explosive, unit).map(|reaction| reaction.kind),
+            Some(ReactionKind::Blast)
+        ));
+        assert!(Reaction::find(&DamageType::Energy, unit).is_none());
+        unit.soak();
+        assert!(matches!(
+            Reaction::find(&DamageType::Energy, unit).map(|reaction| reaction.kind),
+            Some(ReactionKind::Shock)
+        ));
+    }
+
+    #[test]
+    fn a_reaction_consumes_the_element_and_hits_the_units_around() {
+        let mut model = test_model();
+        let target = test_spawn(&mut model, "tank", 0.0, Faction::Mech);
+        let other = test_spawn(&mut model, "tank", 1.0, Faction::Mech);
+        let full = hp(&model, other);
+
+        // No reaction without the element
+        test_effect(&mut model, explosion(), None, target);
+        assert_eq!(hp(&model, other), full);
+
+        model
+            .world
+            .units
+            .get_mut(&target)
+            .unwrap()
+            .statuses
+            .push(burning());
+        test_effect(&mut model, explosion(), None, target);
+        assert!(hp(&model, other) < full);
+        let unit = model.world.units.get(&target).unwrap();
+        assert!(unit
+            .statuses
+            .iter()
+            .all(|status| status.status.element() != Some(Element::Burning)));
+    }
+}
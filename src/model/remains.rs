@@ -0,0 +1,23 @@
+use super::*;
+
+/// How long the remains of a dead unit are kept for the effects still referring to it.
+/// Longer than the lifetime of a projectile, so that its `on_hit` can find its dead caster.
+pub const REMAINS_TIME: f32 = 15.0;
+
+/// What is known about a unit after it died.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remains {
+    pub faction: Faction,
+    pub position: Position,
+    pub time_left: Time,
+}
+
+impl Remains {
+    pub fn new(unit: &Unit, position: Position) -> Self {
+        Self {
+            faction: unit.faction,
+            position,
+            time_left: Time::new(REMAINS_TIME),
+        }
+    }
+}
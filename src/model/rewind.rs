@@ -0,0 +1,264 @@
+use super::*;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A snapshot of the simulation is taken every this many ticks.
+const SNAPSHOT_INTERVAL: u64 = 60;
+/// The oldest snapshots are dropped past this many, together they cover the input history.
+const MAX_SNAPSHOTS: usize = (MAX_INPUT_HISTORY as u64 / SNAPSHOT_INTERVAL) as usize;
+/// The rewind goes back at least this many ticks, if the snapshots reach that far.
+const REWIND_TICKS: u64 = 300;
+
+/// The state of the simulation at the start of a tick.
+#[derive(Clone)]
+pub struct Snapshot {
+    /// Hash of the units and the projectiles, to tell whether the re-simulation diverged.
+    digest: u64,
+    /// Shared with the crash reporter, see [crash::record_snapshot].
+    world: Rc<World>,
+}
+
+impl Snapshot {
+    pub fn tick(&self) -> u64 {
+        self.world.current_tick
+    }
+}
+
+/// Re-simulation of the ticks since a snapshot, with the inputs recorded the first time.
+/// Kept after the replay has caught up, to report whether it matched.
+#[derive(Debug, Clone)]
+pub struct Rewind {
+    /// The tick the simulation was rewound from.
+    pub end_tick: u64,
+    pub inputs: VecDeque<TickInput>,
+    /// Digests of the dropped snapshots, compared with the ones taken again.
+    digests: VecDeque<(u64, u64)>,
+    /// The first tick whose state differs from the one simulated the first time.
+    pub desync: Option<u64>,
+}
+
+impl Rewind {
+    pub fn is_replaying(&self) -> bool {
+        !self.inputs.is_empty()
+    }
+}
+
+impl Model {
+    /// Keeps a snapshot every [SNAPSHOT_INTERVAL] ticks, checking it against the first run when rewound.
+    pub(super) fn take_snapshot(&mut self) {
+        if self.world.current_tick % SNAPSHOT_INTERVAL != 0 {
+            return;
+        }
+        let snapshot = self.snapshot_state();
+        if let Some(rewind) = &mut self.rewind {
+            while let Some(&(tick, digest)) = rewind.digests.front() {
+                if tick > snapshot.tick() {
+                    break;
+                }
+                rewind.digests.pop_front();
+                if tick == snapshot.tick() && digest != snapshot.digest && rewind.desync.is_none() {
+                    rewind.desync = Some(tick);
+                }
+            }
+        }
+        if !self.preview {
+            crash::record_snapshot(&self.config, &snapshot.world);
+        }
+        self.snapshots.push_back(snapshot);
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Restores the latest snapshot at least [REWIND_TICKS] old, or the oldest one,
+    /// and queues the inputs recorded since then to be played again. Returns the restored tick.
+    /// A co-op run cannot be rewound.
+    pub fn rewind(&mut self) -> Option<u64> {
+        if self.lockstep.is_some() {
+            // The other player would go on without the rewind
+            return None;
+        }
+        let current_tick = self.world.current_tick;
+        let index = self
+            .snapshots
+            .iter()
+            .rposition(|snapshot| current_tick - snapshot.tick() >= REWIND_TICKS)
+            .unwrap_or(0);
+        // The rest are taken again during the re-simulation
+        let mut dropped = self.snapshots.drain(index..);
+        let snapshot = dropped.next()?;
+        let digests = dropped
+            .map(|snapshot| (snapshot.tick(), snapshot.digest))
+            .collect();
+        let tick = snapshot.tick();
+        let inputs = self
+            .input_history
+            .iter()
+            .filter(|input| input.tick >= tick)
+            .cloned()
+            .collect();
+        self.input_history.retain(|input| input.tick < tick);
+        let end_tick = self
+            .rewind
+            .as_ref()
+            .map_or(current_tick, |rewind| rewind.end_tick.max(current_tick));
+        self.rewind = Some(Rewind {
+            end_tick,
+            inputs,
+            digests,
+            desync: None,
+        });
+        self.restore_snapshot(snapshot);
+        Some(tick)
+    }
+
+    /// Continues a crashed run from the snapshot in its report, the recorded inputs are played
+    /// again up to the crash.
+    pub fn from_crash(assets: &Rc<Assets>, crash: crash::CrashFile) -> Self {
+        let mut model = Self::new(assets, crash.config);
+        model.world = crash.world;
+        let end_tick = crash
+            .inputs
+            .last()
+            .map_or(model.world.current_tick, |input| input.tick);
+        model.rewind = Some(Rewind {
+            end_tick,
+            inputs: crash.inputs.into(),
+            digests: default(),
+            desync: None,
+        });
+        model
+    }
+
+    /// Replaces the inputs of this tick with the recorded ones while re-simulating.
+    pub(super) fn replay_input(&mut self) {
+        let input = match self
+            .rewind
+            .as_mut()
+            .and_then(|rewind| rewind.inputs.pop_front())
+        {
+            Some(input) => input,
+            None => return,
+        };
+        // The live inputs are ignored until the replay catches up
+        self.queued_events = input.events;
+        self.aim = input.aim;
+        self.blocking = input.blocking;
+        self.gliding = input.gliding;
+    }
+
+    fn snapshot_state(&self) -> Snapshot {
+        Snapshot {
+            digest: self.digest(),
+            world: Rc::new(self.world.clone()),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.world = Rc::try_unwrap(snapshot.world).unwrap_or_else(|world| (*world).clone());
+        self.accumulated_time = Time::ZERO;
+        self.events.clear();
+        // The visuals of the future ticks would not match
+        self.sparks.clear();
+        self.damage_numbers.clear();
+        self.damage_indicators.clear();
+        self.clean_up_bodies();
+    }
+
+    /// A hash of the units and the projectiles, independent of the order they are stored in.
+    pub(super) fn digest(&self) -> u64 {
+        let world = &self.world;
+        let mut ids: Vec<Id> = world
+            .units
+            .iter()
+            .map(|unit| unit.id)
+            .chain(world.projectiles.iter().map(|projectile| projectile.id))
+            .collect();
+        ids.sort();
+        let mut hasher = DefaultHasher::new();
+        world.current_tick.hash(&mut hasher);
+        for id in ids {
+            id.hash(&mut hasher);
+            let bodies = &world.bodies;
+            let vectors = [bodies.positions.get(id), bodies.velocities.get(id)];
+            for vector in vectors.into_iter().flatten() {
+                vector.x.raw().to_bits().hash(&mut hasher);
+                vector.y.raw().to_bits().hash(&mut hasher);
+            }
+            if let Some(health) = bodies.healths.get(id) {
+                health.hp.raw().to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bench::{test_model, test_spawn};
+    use game::{HeldActions, PlayerEvent};
+
+    fn tick(model: &mut Model) {
+        model.update(Time::new(logic::TICK_TIME));
+    }
+
+    /// A mech firing at an enemy walking up to it.
+    fn fight() -> Model {
+        let mut model = test_model();
+        test_spawn(&mut model, "artillery", 0.0, Faction::Mech);
+        test_spawn(&mut model, "blighter", 15.0, Faction::Alien);
+        model
+    }
+
+    #[test]
+    fn the_digest_does_not_depend_on_the_storage_order() {
+        let mut model = fight();
+        let digest = model.digest();
+        let mut ids: Vec<Id> = model.world.units.ids().copied().collect();
+        ids.sort();
+        let units: Vec<Unit> = ids
+            .iter()
+            .rev()
+            .map(|id| model.world.units.remove(id).unwrap())
+            .collect();
+        for unit in units {
+            model.world.units.insert(unit);
+        }
+        assert_eq!(model.digest(), digest);
+
+        let id = model.world.units.iter().next().unwrap().id;
+        model.world.bodies.healths.get_mut(id).unwrap().hp -= Hp::ONE;
+        assert_ne!(model.digest(), digest);
+    }
+
+    #[test]
+    fn the_rewound_run_plays_out_the_same() {
+        let mut model = fight();
+        for index in 0..400 {
+            if index == 100 {
+                let aim = vec2(Coord::new(10.0), model.world.ground_level);
+                model.handle_event(PlayerEvent::Held(HeldActions {
+                    aim: Some(aim),
+                    ..default()
+                }));
+            }
+            tick(&mut model);
+        }
+        let digest = model.digest();
+        let end_tick = model.world.current_tick;
+
+        let tick_from = model.rewind().unwrap();
+        assert!(end_tick - tick_from >= REWIND_TICKS);
+        assert_ne!(model.digest(), digest);
+        while model.world.current_tick < end_tick {
+            tick(&mut model);
+        }
+        let rewind = model.rewind.as_ref().unwrap();
+        assert!(!rewind.is_replaying());
+        assert_eq!(rewind.desync, None);
+        assert_eq!(model.digest(), digest);
+    }
+}
@@ -0,0 +1,83 @@
+use super::*;
+
+/// Name of the template of the target dummies.
+pub const DUMMY_NAME: &str = "dummy";
+/// The meter of a dummy resets after it has not been hit for this many seconds.
+const DPS_IDLE_RESET: f32 = 3.0;
+/// The damage per second is averaged over at least this many seconds,
+/// so that the first hit does not read as a huge spike.
+const DPS_MIN_DURATION: f32 = 1.0;
+
+/// The damage dealt to a target dummy since it started being hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpsMeter {
+    pub total: Hp,
+    first_hit: Time,
+    last_hit: Time,
+}
+
+impl DpsMeter {
+    pub fn new(time: Time) -> Self {
+        Self {
+            total: Hp::ZERO,
+            first_hit: time,
+            last_hit: time,
+        }
+    }
+
+    pub fn record(&mut self, time: Time, amount: Hp) {
+        self.total += amount;
+        self.last_hit = time;
+    }
+
+    /// Whether the dummy has not been hit for long enough to start measuring anew.
+    pub fn is_idle(&self, time: Time) -> bool {
+        time - self.last_hit > Time::new(DPS_IDLE_RESET)
+    }
+
+    /// The damage per second from the first hit to the last one.
+    pub fn dps(&self) -> Hp {
+        let duration = (self.last_hit - self.first_hit).max(Time::new(DPS_MIN_DURATION));
+        self.total / duration
+    }
+}
+
+impl Level {
+    /// An empty level without the tutorial, for the sandbox.
+    pub fn sandbox() -> Self {
+        Self {
+            tutorial: vec![],
+            ..default()
+        }
+    }
+}
+
+impl UnitTemplates {
+    /// The templates that can be spawned in the sandbox with the factions they join.
+    pub fn sandbox_palette(&self) -> [(&UnitTemplate, Faction); 10] {
+        [
+            (&self.artillery, Faction::Mech),
+            (&self.tank, Faction::Mech),
+            (&self.healer, Faction::Mech),
+            (&self.engineer, Faction::Mech),
+            (&self.generator, Faction::Mech),
+            (&self.dummy, Faction::Alien),
+            (&self.blighter, Faction::Alien),
+            (&self.ravager, Faction::Alien),
+            (&self.stinger, Faction::Alien),
+            (&self.ravager_alpha, Faction::Alien),
+        ]
+    }
+}
+
+impl Unit {
+    pub fn is_dummy(&self) -> bool {
+        self.name == DUMMY_NAME
+    }
+
+    /// Whether the hits leave the health of the unit as it is,
+    /// the dummies never lose it and the mechs do not in the sandbox.
+    pub fn keeps_health(&self, mode: GameMode) -> bool {
+        self.is_dummy() || (mode.is_sandbox() && self.faction == Faction::Mech)
+    }
+}
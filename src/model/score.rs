@@ -0,0 +1,33 @@
+use super::*;
+
+use std::collections::HashSet;
+
+/// Statistics of the run that the score is calculated from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Score {
+    pub kills: usize,
+    pub bosses: usize,
+    pub damage_taken: Hp,
+    /// Points of the kills, multiplied by the combo they were made in.
+    pub style: R32,
+    pub best_combo: usize,
+    /// Names of the templates of the killed enemies, unlocking them in the codex.
+    #[serde(deserialize_with = "deserialize_static_str_set")]
+    pub killed_types: HashSet<&'static str>,
+}
+
+impl Model {
+    /// Time passed since the start of the run, without the pauses.
+    pub fn time(&self) -> Time {
+        let ticks = self.world.current_tick - self.world.paused_ticks;
+        Time::new(ticks as f32 * logic::TICK_TIME)
+    }
+
+    pub fn score(&self) -> u64 {
+        let score = self.world.score.style.as_f32()
+            + self.world.wave_number as f32 * 100.0
+            + self.time().as_f32()
+            - self.world.score.damage_taken.as_f32() * 2.0;
+        score.max(0.0) as u64
+    }
+}
@@ -0,0 +1,308 @@
+use super::*;
+use logic::*;
+
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::cell::RefCell;
+
+/// Operations a single script call may run before it fails, so that a runaway loop
+/// in a mod or a prototype cannot freeze the game.
+const MAX_OPERATIONS: u64 = 100_000;
+/// Depth of the nested function calls a script may make.
+const MAX_CALL_LEVELS: usize = 32;
+/// Depth of the nested expressions, at the top level and inside functions.
+const MAX_EXPR_DEPTH: usize = 64;
+
+/// Calls a function from the effect scripts, see `static/scripts/effects.rhai`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptEffect {
+    pub function: String,
+}
+
+/// The scripting engine with the compiled effect scripts.
+pub struct Scripts {
+    engine: Engine,
+    /// `None` if the scripts failed to compile.
+    ast: Option<AST>,
+}
+
+/// What a script asks the game to do, applied after the script finishes.
+#[derive(Debug, Clone)]
+enum ScriptCommand {
+    Damage(Hp),
+    Heal(Hp),
+    ModifyMaxHp { amount: Hp, duration: Option<Time> },
+    Projectile { speed: Coord, damage: Hp },
+}
+
+/// The part of the effect context visible to the scripts.
+#[derive(Debug, Default)]
+struct ScriptState {
+    caster_hp: Option<(Hp, Hp)>,
+    target_hp: Option<(Hp, Hp)>,
+    distance: Option<Coord>,
+    /// Distances from the target, or the caster if there is no target, to the caster's enemies
+    enemy_distances: Vec<Coord>,
+    commands: Vec<ScriptCommand>,
+    /// Actions called with numbers the game cannot use, they queue no command.
+    errors: Vec<String>,
+}
+
+/// The safe api passed to the script functions as their only argument.
+/// Queries read a snapshot of the model and actions are queued as effects.
+#[derive(Debug, Clone)]
+struct ScriptApi {
+    state: Rc<RefCell<ScriptState>>,
+}
+
+impl ScriptApi {
+    fn new(context: &EffectContext, logic: &Logic) -> Self {
+        let bodies = &logic.model.world.bodies;
+        let caster = context.get(Who::Caster, logic);
+        let target = context.get(Who::Target, logic);
+        let center = context
+            .position_of(Who::Target, logic)
+            .or_else(|| caster.map(|unit| bodies.positions[unit.id]));
+        let enemy_distances = match (caster, center) {
+            (Some(caster), Some(center)) => logic
+                .model
+                .world
+                .units
+                .iter()
+                .filter(|unit| unit.faction != caster.faction)
+                .map(|unit| (bodies.positions[unit.id] - center).len())
+                .collect(),
+            _ => vec![],
+        };
+        let state = ScriptState {
+            caster_hp: caster.map(|unit| {
+                let health = &bodies.healths[unit.id];
+                (health.hp, health.max_hp)
+            }),
+            target_hp: target.map(|unit| {
+                let health = &bodies.healths[unit.id];
+                (health.hp, health.max_hp)
+            }),
+            distance: caster.zip(target).map(|(caster, target)| {
+                (bodies.positions[target.id] - bodies.positions[caster.id]).len()
+            }),
+            enemy_distances,
+            commands: vec![],
+            errors: vec![],
+        };
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    fn has_target(&mut self) -> bool {
+        self.state.borrow().target_hp.is_some()
+    }
+
+    fn caster_hp(&mut self) -> f64 {
+        self.state
+            .borrow()
+            .caster_hp
+            .map_or(0.0, |(hp, _)| hp.as_f32() as f64)
+    }
+
+    fn caster_max_hp(&mut self) -> f64 {
+        self.state
+            .borrow()
+            .caster_hp
+            .map_or(0.0, |(_, max)| max.as_f32() as f64)
+    }
+
+    fn target_hp(&mut self) -> f64 {
+        self.state
+            .borrow()
+            .target_hp
+            .map_or(0.0, |(hp, _)| hp.as_f32() as f64)
+    }
+
+    fn target_max_hp(&mut self) -> f64 {
+        self.state
+            .borrow()
+            .target_hp
+            .map_or(0.0, |(_, max)| max.as_f32() as f64)
+    }
+
+    fn distance(&mut self) -> f64 {
+        self.state
+            .borrow()
+            .distance
+            .map_or(0.0, |d| d.as_f32() as f64)
+    }
+
+    fn count_enemies(&mut self, radius: f64) -> i64 {
+        self.state
+            .borrow()
+            .enemy_distances
+            .iter()
+            .filter(|distance| distance.as_f32() as f64 <= radius)
+            .count() as i64
+    }
+
+    fn push(&mut self, command: ScriptCommand) {
+        self.state.borrow_mut().commands.push(command);
+    }
+
+    /// Converts the numbers passed to the action, recording an error if any is not finite,
+    /// e.g. after a division by zero.
+    fn numbers<const N: usize>(&mut self, action: &str, values: [f64; N]) -> Option<[R32; N]> {
+        let numbers = values.map(|value| value as f32);
+        if numbers.iter().all(|number| number.is_finite()) {
+            return Some(numbers.map(r32));
+        }
+        self.state.borrow_mut().errors.push(format!(
+            "{}() expects finite numbers, got {:?}",
+            action, values
+        ));
+        None
+    }
+
+    fn damage(&mut self, value: f64) {
+        if let Some([value]) = self.numbers("damage", [value]) {
+            self.push(ScriptCommand::Damage(value));
+        }
+    }
+
+    fn heal(&mut self, value: f64) {
+        if let Some([value]) = self.numbers("heal", [value]) {
+            self.push(ScriptCommand::Heal(value));
+        }
+    }
+
+    /// A non-positive duration makes the change permanent.
+    fn modify_max_hp(&mut self, amount: f64, duration: f64) {
+        if let Some([amount, duration]) = self.numbers("modify_max_hp", [amount, duration]) {
+            self.push(ScriptCommand::ModifyMaxHp {
+                amount,
+                duration: (duration > Time::ZERO).then(|| duration),
+            });
+        }
+    }
+
+    fn projectile(&mut self, speed: f64, damage: f64) {
+        if let Some([speed, damage]) = self.numbers("projectile", [speed, damage]) {
+            self.push(ScriptCommand::Projectile { speed, damage });
+        }
+    }
+}
+
+impl Scripts {
+    pub fn new(source: &str) -> Self {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+            .register_type_with_name::<ScriptApi>("Context")
+            .register_fn("has_target", ScriptApi::has_target)
+            .register_fn("caster_hp", ScriptApi::caster_hp)
+            .register_fn("caster_max_hp", ScriptApi::caster_max_hp)
+            .register_fn("target_hp", ScriptApi::target_hp)
+            .register_fn("target_max_hp", ScriptApi::target_max_hp)
+            .register_fn("distance", ScriptApi::distance)
+            .register_fn("count_enemies", ScriptApi::count_enemies)
+            .register_fn("damage", ScriptApi::damage)
+            .register_fn("heal", ScriptApi::heal)
+            .register_fn("modify_max_hp", ScriptApi::modify_max_hp)
+            .register_fn("projectile", ScriptApi::projectile);
+        let ast = match engine.compile(source) {
+            Ok(ast) => Some(ast),
+            Err(err) => {
+                error!("Failed to compile the effect scripts: {}", err);
+                None
+            }
+        };
+        Self { engine, ast }
+    }
+}
+
+impl ScriptEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let scripts = logic.model.scripts.clone();
+        let ast = scripts.ast.as_ref()?;
+        let api = ScriptApi::new(&context, logic);
+        if let Err(err) = scripts.engine.call_fn::<Dynamic>(
+            &mut Scope::new(),
+            ast,
+            &self.function,
+            (api.clone(),),
+        ) {
+            // Also hit when a script runs into one of the limits of the engine
+            let error = format!("Effect script {:?} failed: {}", self.function, err);
+            error!("{}", error);
+            logic.model.script_errors.push(error);
+            return None;
+        }
+
+        for error in std::mem::take(&mut api.state.borrow_mut().errors) {
+            let error = format!("Effect script {:?}: {}", self.function, error);
+            error!("{}", error);
+            logic.model.script_errors.push(error);
+        }
+        let commands = std::mem::take(&mut api.state.borrow_mut().commands);
+        for command in commands {
+            let effect = match command {
+                ScriptCommand::Damage(value) => Effect::new(EffectNode::Damage(DamageEffect {
+                    damage_type: DamageType::Physical,
+                    value: Value::Flat(value),
+                    hits_caster: false,
+                    impact: None,
+                })),
+                ScriptCommand::Heal(value) => Effect::new(EffectNode::Heal(HealEffect {
+                    value: Value::Flat(value),
+                    overheal_shield: false,
+                })),
+                ScriptCommand::ModifyMaxHp { amount, duration } => {
+                    Effect::new(EffectNode::ModifyMaxHp(ModifyMaxHpEffect {
+                        amount,
+                        duration,
+                        change: MaxHpChange::Clamp,
+                    }))
+                }
+                ScriptCommand::Projectile { speed, damage } => {
+                    let animation = tank_projectile_animation(
+                        &logic.model.assets().clone(),
+                        &mut logic.model.world.animations,
+                        1.0,
+                    );
+                    Effect::new(EffectNode::Projectile(ProjectileEffect {
+                        offset: Position::ZERO,
+                        attachment: None,
+                        ai: ProjectileAI::Idle,
+                        collider: Collider::Aabb {
+                            size: vec2(0.5, 0.5).map(Coord::new),
+                        },
+                        speed,
+                        spread: Coord::ZERO,
+                        pellets: 1,
+                        fan: Coord::ZERO,
+                        burst: None,
+                        max_range: None,
+                        arming_distance: Coord::ZERO,
+                        falloff: None,
+                        prediction: AimPrediction::Linear,
+                        missing: MissingUnit::LastKnownPosition,
+                        on_expire: Effect::noop(),
+                        on_hit: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Physical,
+                            value: Value::Flat(damage),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                        animation,
+                        intercepts: false,
+                        lifetime: None,
+                    }))
+                }
+            };
+            logic.effects.push_back(QueuedEffect {
+                effect,
+                context: context.clone(),
+            });
+        }
+        Some(())
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+
+/// Serializes a collection of entities as the list of them, since the ids are not strings
+/// and cannot be the keys of a json object.
+pub mod collection {
+    use super::*;
+
+    pub fn serialize<T, S>(collection: &Collection<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: HasId + Serialize,
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(collection.iter())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Collection<T>, D::Error>
+    where
+        T: HasId + Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let items = Vec::<T>::deserialize(deserializer)?;
+        let mut collection = Collection::new();
+        for item in items {
+            collection.insert(item);
+        }
+        Ok(collection)
+    }
+}
+
+/// Serializes a map keyed by the entity ids as the list of the pairs, see [collection].
+pub mod id_map {
+    use super::*;
+
+    pub fn serialize<T, S>(map: &HashMap<Id, T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<HashMap<Id, T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let pairs = Vec::<(Id, T)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+thread_local! {
+    /// The names deserialized so far, each one is only leaked once.
+    static NAMES: RefCell<HashSet<&'static str>> = default();
+}
+
+/// The names of the templates are `&'static str`, the ones that are read back are leaked.
+fn leak(name: String) -> &'static str {
+    NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        if let Some(&known) = names.get(name.as_str()) {
+            return known;
+        }
+        let name: &'static str = Box::leak(name.into_boxed_str());
+        names.insert(name);
+        name
+    })
+}
+
+pub fn deserialize_static_str<'de, D>(deserializer: D) -> Result<&'static str, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    String::deserialize(deserializer).map(leak)
+}
+
+pub fn deserialize_static_str_map<'de, T, D>(
+    deserializer: D,
+) -> Result<BTreeMap<&'static str, T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let map = BTreeMap::<String, T>::deserialize(deserializer)?;
+    Ok(map
+        .into_iter()
+        .map(|(name, value)| (leak(name), value))
+        .collect())
+}
+
+pub fn deserialize_static_str_set<'de, D>(
+    deserializer: D,
+) -> Result<HashSet<&'static str>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let names = Vec::<String>::deserialize(deserializer)?;
+    Ok(names.into_iter().map(leak).collect())
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+/// How heavy the unit is, the heavier units resist the crowd control more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizeClass {
+    Small,
+    Medium,
+    Large,
+    Boss,
+}
+
+impl SizeClass {
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Small => "size.small",
+            Self::Medium => "size.medium",
+            Self::Large => "size.large",
+            Self::Boss => "size.boss",
+        }
+    }
+
+    /// Multiplier of the stun durations the unit receives.
+    pub fn stun_scale(&self) -> R32 {
+        r32(match self {
+            Self::Small => 1.25,
+            Self::Medium => 1.0,
+            Self::Large => 0.6,
+            Self::Boss => 0.3,
+        })
+    }
+
+    /// Multiplier of the speed the unit is pulled with,
+    /// `None` if it is too heavy to be pulled at all.
+    pub fn pull_scale(&self) -> Option<R32> {
+        match self {
+            Self::Small => Some(R32::ONE),
+            Self::Medium => Some(r32(0.7)),
+            Self::Large | Self::Boss => None,
+        }
+    }
+
+    /// Scales the crowd control of the status received by a unit of the size.
+    pub fn scale_status(&self, status: &mut Status) {
+        if let Status::Stunned { time } = status {
+            *time *= self.stun_scale();
+        }
+    }
+}
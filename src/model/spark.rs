@@ -0,0 +1,37 @@
+use super::*;
+
+const SPARK_LIFETIME: f32 = 0.3;
+
+/// A short-lived flash flying out of a collision, drawn as a streak along its velocity.
+#[derive(Debug, Clone)]
+pub struct Spark {
+    pub position: Position,
+    pub velocity: Velocity,
+    pub lifetime: Time,
+    pub color: Rgba<f32>,
+}
+
+impl Spark {
+    /// The share of the lifetime left, from 1 to 0.
+    pub fn fade(&self) -> f32 {
+        (self.lifetime / Time::new(SPARK_LIFETIME))
+            .as_f32()
+            .clamp(0.0, 1.0)
+    }
+}
+
+impl Model {
+    /// Throws the sparks of the impact out of the position in random directions.
+    pub fn spawn_sparks(&mut self, position: Position, impact: &Impact) {
+        for _ in 0..impact.sparks {
+            let angle = self.world.rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = impact.spark_speed * self.world.rng.gen_range(0.5..1.0);
+            self.sparks.push(Spark {
+                position,
+                velocity: vec2(speed, 0.0).rotate(angle).map(Coord::new),
+                lifetime: Time::new(SPARK_LIFETIME),
+                color: impact.color,
+            });
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+/// Buckets the entities by the grid cells their bounds overlap,
+/// so that only the entities sharing a cell are checked for collisions.
+pub struct SpatialHash {
+    cell_size: Coord,
+    cells: HashMap<(i64, i64), Vec<Id>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: Coord) -> Self {
+        Self {
+            cell_size,
+            cells: default(),
+        }
+    }
+
+    pub fn insert(&mut self, id: Id, bounds: AABB<Coord>) {
+        for cell in self.cells_of(bounds) {
+            self.cells.entry(cell).or_default().push(id);
+        }
+    }
+
+    /// The entities that may overlap the bounds, each listed once.
+    pub fn query(&self, bounds: AABB<Coord>) -> Vec<Id> {
+        let mut ids: Vec<Id> = self
+            .cells_of(bounds)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    fn cells_of(&self, bounds: AABB<Coord>) -> impl Iterator<Item = (i64, i64)> {
+        let cell = |value: Coord| (value / self.cell_size).as_f32().floor() as i64;
+        let (x_min, x_max) = (cell(bounds.x_min), cell(bounds.x_max));
+        let (y_min, y_max) = (cell(bounds.y_min), cell(bounds.y_max));
+        (x_min..=x_max).flat_map(move |x| (y_min..=y_max).map(move |y| (x, y)))
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+/// Width of the world in the widest view the camera shows, 20 units high at 21:9.
+const MAX_VIEW_WIDTH: f32 = 20.0 * 21.0 / 9.0;
+/// How far past the edge of the view the enemies appear.
+const VIEW_MARGIN: f32 = 3.0;
+/// The enemies do not appear closer than this to a mech.
+const MIN_MECH_DISTANCE: f32 = 15.0;
+/// Number of candidate positions tried before the rules are given up on.
+const SPAWN_ATTEMPTS: usize = 20;
+/// How much further the candidates may spread with each rejected attempt.
+const SPAWN_SPREAD: f32 = 2.0;
+
+/// How a unit enters the battle, so that the player sees it coming.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SpawnStyle {
+    pub telegraph: TelegraphKind,
+    /// For how long the telegraph is shown before the unit appears.
+    pub telegraph_time: Time,
+    /// For how long the unit is invulnerable and inactive after appearing.
+    pub animation_time: Time,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TelegraphKind {
+    /// Opens on the ground where the unit appears.
+    Portal,
+    /// Falls from the sky onto the spawn position.
+    DropPod,
+}
+
+/// A unit waiting for its telegraph to finish.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSpawn {
+    pub template: UnitTemplate,
+    pub position: Position,
+    pub faction: Faction,
+    pub style: SpawnStyle,
+    pub time_left: Time,
+}
+
+impl PendingSpawn {
+    /// How far the telegraph has played, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        1.0 - (self.time_left / self.style.telegraph_time)
+            .as_f32()
+            .clamp(0.0, 1.0)
+    }
+}
+
+impl Unit {
+    /// How far the spawn animation has played, from 0 to 1, if it is still playing.
+    pub fn spawn_progress(&self) -> Option<f32> {
+        self.statuses.iter().find_map(|status| match status.status {
+            Status::Spawning { time, duration } => {
+                Some(1.0 - (time / duration).as_f32().clamp(0.0, 1.0))
+            }
+            _ => None,
+        })
+    }
+}
+
+impl Model {
+    /// Spawns the unit after the telegraph of its template, or right away if it has none.
+    pub fn spawn_telegraphed(
+        &mut self,
+        template: UnitTemplate,
+        position: Position,
+        faction: Faction,
+    ) {
+        match template.spawn {
+            Some(style) => self.world.pending_spawns.push(PendingSpawn {
+                template,
+                position,
+                faction,
+                style,
+                time_left: style.telegraph_time,
+            }),
+            None => self.spawn_unit(template, position, faction),
+        }
+    }
+
+    /// Picks the position closest to the preferred one that is out of view,
+    /// away from the mechs and clear of the obstacles, hazards and water.
+    pub fn spawn_position(&mut self, preferred: Position, collider: &Collider) -> Position {
+        let min_x = self.world.left_border + Coord::new(MAX_VIEW_WIDTH + VIEW_MARGIN);
+        let start = vec2(preferred.x.max(min_x), preferred.y);
+        for attempt in 0..SPAWN_ATTEMPTS {
+            let spread = Coord::new(SPAWN_SPREAD * attempt as f32);
+            let candidate =
+                start + vec2(self.world.rng.gen_range(Coord::ZERO..=spread), Coord::ZERO);
+            if self.is_clear_spawn(candidate, collider) {
+                return candidate;
+            }
+        }
+        start
+    }
+
+    fn is_clear_spawn(&self, position: Position, collider: &Collider) -> bool {
+        let near_mech = self.world.units.iter().any(|unit| {
+            unit.faction == Faction::Mech
+                && (self.world.bodies.positions[unit.id] - position).len()
+                    < Coord::new(MIN_MECH_DISTANCE)
+        });
+        let blocked = self.world.obstacles.iter().any(|obstacle| {
+            obstacle
+                .collider
+                .check(collider, position - obstacle.position)
+        });
+        let hazardous = self.world.hazards.iter().any(|hazard| {
+            matches!(
+                hazard.kind,
+                HazardKind::Spikes { .. } | HazardKind::Lava { .. }
+            ) && collider.intersects_area(position, &hazard.area)
+        });
+        let submerged = self
+            .world
+            .water
+            .iter()
+            .any(|water| collider.intersects_area(position, &water.area));
+        !(near_mech || blocked || hazardous || submerged)
+    }
+}
@@ -1,24 +1,23 @@
 use super::*;
 
-#[derive(Clone)]
+use assets::{PixelTexture, TextureId};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sprite {
-    pub texture: Rc<ugli::Texture>,
+    pub texture: TextureId,
     pub size: Vec2<f32>,
 }
 
 impl Sprite {
-    pub fn new(texture: &Rc<ugli::Texture>, scale: f32) -> Self {
+    pub fn new(texture: &PixelTexture, scale: f32) -> Self {
         Self {
             size: texture.size().map(|x| x as f32) * scale,
-            texture: texture.clone(),
+            texture: texture.id(),
         }
     }
-}
 
-impl Debug for Sprite {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct(stringify!(Sprite))
-            .field("size", &self.size)
-            .finish()
+    /// The texture to draw, missing if the textures are not loaded, e.g. in the benchmarks.
+    pub fn texture(&self) -> Option<Rc<ugli::Texture>> {
+        self.texture.texture()
     }
 }
@@ -0,0 +1,150 @@
+use super::*;
+
+/// A unit stat, evaluated from its base value and the modifiers applied by perks and statuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stat {
+    /// Multiplies the damage dealt
+    Attack,
+    /// Divides the damage taken by `1 + defense`
+    Defense,
+    /// Multiplies the movement speed
+    MoveSpeed,
+    /// Multiplies how fast the action cooldown recovers
+    CooldownRate,
+    /// Chance for the damage dealt to be critical
+    Crit,
+    /// Multiplies the speed of the launched projectiles
+    ProjectileSpeed,
+}
+
+/// How a modifier combines with the others: `(base + flat) * (1 + increased) * more`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModifierKind {
+    /// Added to the base value
+    Flat,
+    /// Summed up with the other increases into a single multiplier
+    Increased,
+    /// Multiplies the value on its own
+    More,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub value: R32,
+}
+
+/// The modifier stack of a unit, statuses add their modifiers on top of it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// Damage multiplier of critical hits.
+pub const CRIT_MULTIPLIER: f32 = 2.0;
+
+impl Stat {
+    pub fn base(&self) -> R32 {
+        match self {
+            Self::Attack | Self::MoveSpeed | Self::CooldownRate | Self::ProjectileSpeed => R32::ONE,
+            Self::Defense | Self::Crit => R32::ZERO,
+        }
+    }
+}
+
+impl StatModifier {
+    pub fn new(stat: Stat, kind: ModifierKind, value: f32) -> Self {
+        Self {
+            stat,
+            kind,
+            value: r32(value),
+        }
+    }
+}
+
+impl Stats {
+    /// The value of the stat from the stack alone, without any statuses.
+    pub fn value(&self, stat: Stat) -> R32 {
+        evaluate(stat, self.modifiers.iter())
+    }
+
+    pub fn add(&mut self, stat: Stat, kind: ModifierKind, value: R32) {
+        self.modifiers.push(StatModifier { stat, kind, value });
+    }
+}
+
+/// Combines the modifiers of the stat with its base value.
+fn evaluate<'a>(stat: Stat, modifiers: impl Iterator<Item = &'a StatModifier>) -> R32 {
+    let mut flat = stat.base();
+    let mut increased = R32::ONE;
+    let mut more = R32::ONE;
+    for modifier in modifiers.filter(|modifier| modifier.stat == stat) {
+        match modifier.kind {
+            ModifierKind::Flat => flat += modifier.value,
+            ModifierKind::Increased => increased += modifier.value,
+            ModifierKind::More => more *= modifier.value,
+        }
+    }
+    flat * increased.max(R32::ZERO) * more
+}
+
+impl Unit {
+    /// The final value of the stat, including the modifiers of the statuses.
+    pub fn stat(&self, stat: Stat) -> R32 {
+        let statuses = self
+            .statuses
+            .iter()
+            .filter_map(|status| match &status.status {
+                Status::Modified { modifier, .. } => Some(modifier),
+                _ => None,
+            });
+        evaluate(stat, self.stats.modifiers.iter().chain(statuses))
+    }
+
+    pub fn move_speed(&self) -> Coord {
+        self.speed * self.stat(Stat::MoveSpeed)
+    }
+
+    /// Multiplier of the damage taken.
+    pub fn damage_taken(&self) -> R32 {
+        R32::ONE / (R32::ONE + self.stat(Stat::Defense).max(R32::ZERO))
+    }
+
+    pub fn crit_chance(&self) -> f64 {
+        self.stat(Stat::Crit).as_f32().clamp(0.0, 1.0) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_modifiers_give_the_base() {
+        let stats = Stats::default();
+        assert_eq!(stats.value(Stat::Attack), R32::ONE);
+        assert_eq!(stats.value(Stat::Crit), R32::ZERO);
+    }
+
+    #[test]
+    fn modifiers_combine_in_order() {
+        let mut stats = Stats::default();
+        stats.add(Stat::Attack, ModifierKind::Flat, r32(1.0));
+        stats.add(Stat::Attack, ModifierKind::Increased, r32(0.25));
+        stats.add(Stat::Attack, ModifierKind::Increased, r32(0.25));
+        stats.add(Stat::Attack, ModifierKind::More, r32(2.0));
+        stats.add(Stat::Attack, ModifierKind::More, r32(1.5));
+        // Other stats are left alone
+        stats.add(Stat::Defense, ModifierKind::Flat, r32(10.0));
+        // (1 + 1) * (1 + 0.25 + 0.25) * 2 * 1.5
+        assert_eq!(stats.value(Stat::Attack), r32(9.0));
+    }
+
+    #[test]
+    fn increases_never_go_negative() {
+        let mut stats = Stats::default();
+        stats.add(Stat::MoveSpeed, ModifierKind::Increased, r32(-3.0));
+        assert_eq!(stats.value(Stat::MoveSpeed), R32::ZERO);
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+/// A prompt shown once its trigger fires, the waves wait until the player performs the action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TutorialStep {
+    pub trigger: TutorialTrigger,
+    pub action: TutorialAction,
+    /// Locale key of the prompt, `{keys}` is replaced with the keys bound to the action.
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TutorialTrigger {
+    /// At the start of the run.
+    Start,
+    /// Once the left border reaches the position.
+    Reach(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TutorialAction {
+    /// Spawn a mech of any type.
+    Spawn,
+    Aim,
+    Reload,
+    Interact,
+    Block,
+    Glide,
+    Ping,
+}
+
+impl TutorialStep {
+    fn new(trigger: TutorialTrigger, action: TutorialAction, prompt: &str) -> Self {
+        Self {
+            trigger,
+            action,
+            prompt: prompt.to_owned(),
+        }
+    }
+
+    /// The steps teaching the basic controls, played in the default level.
+    pub fn basics() -> Vec<Self> {
+        vec![
+            Self::new(
+                TutorialTrigger::Start,
+                TutorialAction::Spawn,
+                "tutorial.spawn",
+            ),
+            Self::new(
+                TutorialTrigger::Reach(0.0),
+                TutorialAction::Aim,
+                "tutorial.aim",
+            ),
+            Self::new(
+                TutorialTrigger::Reach(10.0),
+                TutorialAction::Block,
+                "tutorial.block",
+            ),
+            Self::new(
+                TutorialTrigger::Reach(20.0),
+                TutorialAction::Reload,
+                "tutorial.reload",
+            ),
+        ]
+    }
+}
+
+impl TutorialAction {
+    /// The controls that perform the action.
+    pub fn controls(&self) -> &'static [ControlAction] {
+        match self {
+            Self::Spawn => &[
+                ControlAction::SpawnArtillery,
+                ControlAction::SpawnTank,
+                ControlAction::SpawnHealer,
+            ],
+            Self::Aim => &[ControlAction::Aim],
+            Self::Reload => &[ControlAction::Reload],
+            Self::Interact => &[ControlAction::Interact],
+            Self::Block => &[ControlAction::Block],
+            Self::Glide => &[ControlAction::Glide],
+            Self::Ping => &[ControlAction::Ping],
+        }
+    }
+}
+
+impl Model {
+    /// Hides the shown prompt if it asks for the action.
+    pub fn complete_tutorial(&mut self, action: TutorialAction) {
+        if self
+            .world
+            .tutorial_prompt
+            .as_ref()
+            .map_or(false, |step| step.action == action)
+        {
+            self.world.tutorial_prompt = None;
+        }
+    }
+}
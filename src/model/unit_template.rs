@@ -1,111 +1,363 @@
 use super::*;
+use logic::Who;
 
 impl UnitTemplates {
-    pub fn new(assets: &Rc<Assets>) -> Self {
-        Self {
-            artillery: artillery(assets),
-            tank: tank(assets),
-            healer: healer(assets),
-            blighter: blighter(assets),
-            ravager: ravager(assets),
-            stinger: stinger(assets),
+    pub fn new(assets: &Rc<Assets>, animations: &mut Animations) -> Self {
+        let mut templates = Self {
+            artillery: artillery(assets, animations),
+            tank: tank(assets, animations),
+            healer: healer(assets, animations),
+            blighter: blighter(assets, animations),
+            ravager: ravager(assets, animations),
+            stinger: stinger(assets, animations),
+            ravager_alpha: ravager_alpha(assets, animations),
+            generator: generator(assets, animations),
+            engineer: engineer(assets, animations),
+            dummy: dummy(assets, animations),
+            modded: vec![],
+        };
+        // The effects of the mods may use it before a tank weapon is first made
+        tank_projectile_animation(assets, animations, 1.0);
+        for &(name, ref template) in &assets.mods.templates {
+            // The base templates are checked when the mods are applied
+            if let Some(base) = templates.get(&template.base) {
+                let template = template.build(name, base, &assets.mods, animations);
+                templates.modded.push(template);
+            }
         }
+        templates
+    }
+
+    /// Names of the built-in templates [Self::get] finds.
+    pub const NAMES: [&'static str; 8] = [
+        "artillery",
+        "tank",
+        "healer",
+        "blighter",
+        "ravager",
+        "stinger",
+        "ravager_alpha",
+        DUMMY_NAME,
+    ];
+
+    /// Names of the enemy templates, in the order of [Self::named_enemies].
+    pub const ENEMY_NAMES: [&'static str; 4] = ["blighter", "ravager", "stinger", "ravager_alpha"];
+
+    /// All templates with their names.
+    pub fn named(&self) -> [(&'static str, &UnitTemplate); 7] {
+        let [blighter, ravager, stinger, ravager_alpha] = Self::ENEMY_NAMES;
+        [
+            ("artillery", &self.artillery),
+            ("tank", &self.tank),
+            ("healer", &self.healer),
+            (blighter, &self.blighter),
+            (ravager, &self.ravager),
+            (stinger, &self.stinger),
+            (ravager_alpha, &self.ravager_alpha),
+        ]
+    }
+
+    /// The named templates of the enemies.
+    pub fn named_enemies(&self) -> [(&'static str, &UnitTemplate); 4] {
+        // The mechs come first
+        let [_, _, _, enemies @ ..] = self.named();
+        enemies
+    }
+
+    /// The templates of the allies placed by the level triggers.
+    pub fn named_allies(&self) -> [(&'static str, &UnitTemplate); 2] {
+        [("generator", &self.generator), ("engineer", &self.engineer)]
+    }
+
+    /// The named template, the dummy or a template of the mods, e.g. for the console to spawn.
+    pub fn get(&self, name: &str) -> Option<&UnitTemplate> {
+        self.named()
+            .into_iter()
+            .chain([(DUMMY_NAME, &self.dummy)])
+            .chain(self.modded.iter().map(|template| (template.name, template)))
+            .find(|(template, _)| *template == name)
+            .map(|(_, template)| template)
     }
 }
 
 impl UnitTemplate {
-    pub fn instance(self, id_gen: &mut IdGen, position: Position, faction: Faction) -> Unit {
-        Unit {
+    pub fn instance(
+        self,
+        id_gen: &mut IdGen,
+        animations: &Animations,
+        position: Position,
+        faction: Faction,
+    ) -> (Unit, Body) {
+        let body = Body::new(position, Velocity::ZERO)
+            .with_collider(self.collider)
+            .with_health(self.health);
+        let unit = Unit {
             id: id_gen.gen(),
+            name: self.name,
             faction,
             ai: self.ai.clone(),
-            health: self.health,
             sanity: self.sanity,
-            collider: self.collider,
-            position,
-            velocity: Velocity::ZERO,
             speed: self.speed,
             acceleration: self.acceleration,
             target_velocity: Velocity::ZERO,
-            statuses: default(),
+            statuses: self.statuses.into_iter().map(AppliedStatus::from).collect(),
             action: self.action,
             action_state: self.start_action_state,
             flip_sprite: false,
-            animation_state: AnimationState::new(&self.idle_animation),
+            in_water: false,
+            animation_state: AnimationState::new(self.idle_animation, animations),
+            glide_state: None,
             idle_animation: self.idle_animation,
             move_animation: self.move_animation,
-            extra_render: self.extra_render,
+            glide_animation: self.glide_animation,
+            attachments: self.attachments,
+            weapon: self.weapon,
+            ammo: self.ammo,
+            block: self.block,
+            boss: self.boss,
+            stats: self.stats,
+            elites: self.elites,
             on_death: self.on_death,
-        }
+            point_defense: self.point_defense,
+            skill: self.skill,
+            size: self.size,
+            npc: self.npc,
+            pilot: None,
+            motion_history: VecDeque::new(),
+        };
+        (unit, body)
     }
 }
 
+/// Creates an animation from the textures and stores it.
 pub fn to_animation(
+    animations: &mut Animations,
     textures: &[assets::PixelTexture],
     sprite_scale: f32,
     cycle_time: Time,
     actions: Vec<(usize, Effect)>,
-) -> Rc<Animation> {
-    let time = cycle_time / Time::new(textures.len() as f32);
-    Rc::new(Animation {
-        keyframes: {
-            textures
-                .iter()
-                .enumerate()
-                .map(|(frame, texture)| AnimationFrame {
-                    sprite: Sprite::new(&texture.texture(), sprite_scale),
-                    time,
-                    start_effect: actions.iter().find_map(|(action_frame, action)| {
-                        (frame == *action_frame).then(|| action.clone())
-                    }),
-                })
-                .collect()
+) -> AnimationId {
+    animations.insert(Animation::from_textures(
+        textures,
+        sprite_scale,
+        cycle_time,
+        actions,
+    ))
+}
+
+fn generator(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
+    let idle_animation = to_animation(
+        animations,
+        &assets.effects.heal,
+        1.0 / 16.0,
+        Time::new(2.0),
+        vec![],
+    );
+    UnitTemplate {
+        name: "generator",
+        ai: UnitAI::Idle,
+        health: Health::new(Hp::new(40.0)),
+        sanity: None,
+        collider: Collider::Aabb {
+            size: vec2(2.0, 2.0).map(Coord::new),
+        },
+        speed: Coord::ZERO,
+        acceleration: Coord::ZERO,
+        statuses: vec![],
+        start_action_state: ActionState::Ready,
+        action: Action {
+            cooldown: Time::ONE,
+            engage_radius: Coord::ZERO,
+            animation: idle_animation,
         },
-    })
+        idle_animation,
+        move_animation: idle_animation,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Boss,
+        npc: true,
+        spawn: None,
+    }
 }
 
-fn tank(assets: &Rc<Assets>) -> UnitTemplate {
+/// A target for the sandbox that never moves nor attacks.
+fn dummy(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
     let idle_animation = to_animation(
-        &[assets.mech.tank.idle.clone()],
+        animations,
+        &[assets.enemies.blighter.idle.clone()],
         1.0 / 32.0,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.mech.tank.walk, 1.0 / 32.0, Time::ONE, vec![]);
+    UnitTemplate {
+        name: DUMMY_NAME,
+        ai: UnitAI::Idle,
+        health: Health::new(Hp::new(100.0)),
+        sanity: None,
+        collider: Collider::Aabb {
+            size: vec2(1.0, 1.0).map(Coord::new),
+        },
+        speed: Coord::ZERO,
+        acceleration: Coord::ZERO,
+        statuses: vec![],
+        start_action_state: ActionState::Ready,
+        action: Action {
+            cooldown: Time::ONE,
+            engage_radius: Coord::ZERO,
+            animation: idle_animation,
+        },
+        idle_animation,
+        move_animation: idle_animation,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Medium,
+        npc: false,
+        spawn: None,
+    }
+}
+
+/// An allied engineer that follows the mechs, repairing them and boosting their damage.
+fn engineer(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
+    let idle_animation = to_animation(
+        animations,
+        &[assets.mech.healer.idle.clone()],
+        1.0 / 40.0,
+        Time::ONE,
+        vec![],
+    );
+    let move_animation = to_animation(
+        animations,
+        &assets.mech.healer.walk,
+        1.0 / 40.0,
+        Time::ONE,
+        vec![],
+    );
     let animation = to_animation(
-        &assets.mech.tank.attack,
-        1.0 / 32.0,
+        animations,
+        &assets.mech.healer.heal,
+        1.0 / 40.0,
         Time::ONE,
         vec![(
-            2,
-            Effect::List(Box::new(ListEffect {
-                effects: vec![
-                    Effect::Projectile(Box::new(ProjectileEffect {
-                        offset: Position::ZERO,
-                        ai: ProjectileAI::Idle,
-                        collider: Collider::Aabb {
-                            size: vec2(0.5, 0.5).map(Coord::new),
-                        },
-                        speed: Coord::new(10.0),
-                        on_hit: Effect::Damage(Box::new(DamageEffect {
-                            damage_type: DamageType::Physical,
-                            value: Hp::new(1.0),
+            5,
+            Effect::new(EffectNode::Area(AreaEffect {
+                center: Who::Caster,
+                radius: Coord::new(4.0),
+                filter: FactionFilter::Allies,
+                hits_caster: false,
+                falloff: None,
+                effect: Effect::new(EffectNode::List(ListEffect {
+                    effects: vec![
+                        Effect::new(EffectNode::Heal(HealEffect {
+                            value: Value::Flat(Hp::new(2.0)),
+                            overheal_shield: false,
                         })),
-                        animation: to_animation(
-                            &[assets.mech.tank.projectile.clone()],
-                            1.0 / 16.0,
-                            Time::ONE,
-                            vec![],
-                        ),
-                    })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.mechs.tank.shoot.clone(),
-                    })),
-                ],
+                        Effect::new(EffectNode::ApplyStatus(ApplyStatusEffect {
+                            status: Status::Modified {
+                                time: Time::new(4.0),
+                                modifier: StatModifier::new(
+                                    Stat::Attack,
+                                    ModifierKind::Increased,
+                                    0.2,
+                                ),
+                            },
+                            on_expire: Effect::noop(),
+                        })),
+                    ],
+                })),
             })),
         )],
     );
     UnitTemplate {
+        name: "engineer",
+        ai: UnitAI::Engage {
+            target: TargetAI::LowestHp,
+            default: PositionAI::Follow,
+            switch: None,
+        },
+        health: Health::new(Hp::new(15.0)),
+        sanity: None,
+        collider: Collider::Aabb {
+            size: vec2(0.8, 1.6).map(Coord::new),
+        },
+        speed: Coord::new(2.0),
+        acceleration: Coord::new(10.0),
+        statuses: vec![],
+        start_action_state: ActionState::Cooldown {
+            time_left: Time::new(2.0),
+        },
+        action: Action {
+            cooldown: Time::new(4.0),
+            engage_radius: Coord::new(4.0),
+            animation,
+        },
+        idle_animation,
+        move_animation,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Medium,
+        npc: true,
+        spawn: None,
+    }
+}
+
+fn tank(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
+    let idle_animation = to_animation(
+        animations,
+        &[assets.mech.tank.idle.clone()],
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
+    let move_animation = to_animation(
+        animations,
+        &assets.mech.tank.walk,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
+    let glide_animation = to_animation(
+        animations,
+        &assets.mech.tank.walk,
+        1.0 / 32.0,
+        Time::new(3.0),
+        vec![],
+    );
+    let animation = to_animation(
+        animations,
+        &assets.mech.tank.attack,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![(2, Effect::new(EffectNode::FireWeapon))],
+    );
+    UnitTemplate {
+        name: "tank",
         ai: UnitAI::Engage {
             target: TargetAI::Closest,
             default: PositionAI::Advance,
@@ -118,6 +370,7 @@ fn tank(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(2.0),
         acceleration: Coord::new(10.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(3.0),
         },
@@ -128,34 +381,113 @@ fn tank(assets: &Rc<Assets>) -> UnitTemplate {
         },
         idle_animation,
         move_animation,
-        extra_render: Some(ExtraUnitRender::Tank {
-            hand_pos: vec2(-0.4, 0.5).map(Coord::new),
-            weapon_pos: vec2(1.2, 0.1).map(Coord::new),
-            shoot_pos: vec2(0.5, 0.0).map(Coord::new),
-            rotation: Coord::ZERO,
+        glide_animation: Some(glide_animation),
+        attachments: vec![
+            AttachmentPoint {
+                aim: true,
+                sprite: Some(Sprite {
+                    texture: assets.mech.tank.hand.id(),
+                    size: vec2(2.0, 2.0 * 9.0 / 62.0),
+                }),
+                ..AttachmentPoint::new("hand", None, vec2(-0.4, 0.5).map(Coord::new))
+            },
+            AttachmentPoint::new("weapon", Some("hand"), vec2(1.2, 0.1).map(Coord::new)),
+            AttachmentPoint::new("muzzle", Some("weapon"), vec2(0.5, 0.0).map(Coord::new)),
+        ],
+        weapon: Some(Weapon::tank_cannon(assets, animations)),
+        ammo: None,
+        block: Some(BlockStats {
+            reduction: r32(0.7),
+            parry_window: Time::new(0.25),
+            riposte: Effect::new(EffectNode::List(ListEffect {
+                effects: vec![
+                    Effect::new(EffectNode::MeleeSwing(MeleeSwingEffect {
+                        shape: MeleeShape::Arc {
+                            radius: Coord::new(2.5),
+                            half_angle: Coord::new(std::f32::consts::PI / 2.0),
+                        },
+                        attachment: Some("hand".to_owned()),
+                        duration: Time::new(0.3),
+                        effect: Effect::new(EffectNode::List(ListEffect {
+                            effects: vec![
+                                Effect::new(EffectNode::Damage(DamageEffect {
+                                    damage_type: DamageType::Physical,
+                                    value: Value::Flat(Hp::new(3.0)),
+                                    hits_caster: false,
+                                    impact: None,
+                                })),
+                                // Knocks a parried charge out of its stride
+                                Effect::new(EffectNode::ApplyStatus(ApplyStatusEffect {
+                                    status: Status::Stunned {
+                                        time: Time::new(0.75),
+                                    },
+                                    on_expire: Effect::noop(),
+                                })),
+                            ],
+                        })),
+                        deflect: Some(r32(1.5)),
+                    })),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::MechHit,
+                    })),
+                ],
+            })),
         }),
-        on_death: Effect::Noop,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: Some(PointDefense::flak(assets, animations)),
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Large,
+        npc: false,
+        spawn: None,
     }
 }
 
-fn artillery(assets: &Rc<Assets>) -> UnitTemplate {
+fn artillery(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
     let idle_animation = to_animation(
+        animations,
         &[assets.mech.artillery.idle.clone()],
         1.0 / 32.0,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.mech.artillery.walk, 1.0 / 32.0, Time::ONE, vec![]);
+    let move_animation = to_animation(
+        animations,
+        &assets.mech.artillery.walk,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
+    let glide_animation = to_animation(
+        animations,
+        &assets.mech.artillery.walk,
+        1.0 / 32.0,
+        Time::new(3.0),
+        vec![],
+    );
+    // Named for the effects of the mods to use
+    let projectile_animation = animations.get_or_insert_with("artillery projectile", || {
+        Animation::from_textures(
+            &assets.mech.artillery.projectile_anim,
+            1.0 / 24.0,
+            Time::ONE,
+            vec![],
+        )
+    });
     let animation = to_animation(
+        animations,
         &assets.mech.artillery.attack,
         1.0 / 32.0,
         Time::ONE,
         vec![(
             2,
-            Effect::List(Box::new(ListEffect {
+            Effect::new(EffectNode::List(ListEffect {
                 effects: vec![
-                    Effect::Projectile(Box::new(ProjectileEffect {
+                    Effect::new(EffectNode::Projectile(ProjectileEffect {
                         offset: vec2(-0.5, 0.5).map(Coord::new),
+                        attachment: None,
                         ai: ProjectileAI::Rocket {
                             speed: Coord::new(15.0),
                             acceleration: Coord::new(20.0),
@@ -165,37 +497,44 @@ fn artillery(assets: &Rc<Assets>) -> UnitTemplate {
                             size: vec2(0.5, 0.5).map(Coord::new),
                         },
                         speed: Coord::ZERO,
-                        on_hit: Effect::List(Box::new(ListEffect {
+                        spread: Coord::ZERO,
+                        pellets: 1,
+                        fan: Coord::ZERO,
+                        burst: None,
+                        max_range: None,
+                        // Do not blow up right in front of the launcher
+                        arming_distance: Coord::new(1.5),
+                        falloff: None,
+                        // Rockets home in on their target
+                        prediction: AimPrediction::None,
+                        missing: MissingUnit::Retarget,
+                        on_expire: Effect::noop(),
+                        on_hit: Effect::new(EffectNode::List(ListEffect {
                             effects: vec![
-                                Effect::Damage(Box::new(DamageEffect {
+                                Effect::new(EffectNode::Damage(DamageEffect {
                                     damage_type: DamageType::Explosive,
-                                    value: Hp::new(3.0),
+                                    value: Value::Flat(Hp::new(3.0)),
+                                    hits_caster: false,
+                                    impact: None,
                                 })),
-                                Effect::Sound(Box::new(SoundEffect {
-                                    sound: assets
-                                        .sound_design
-                                        .mechs
-                                        .artillery
-                                        .rocket_explode
-                                        .clone(),
+                                Effect::new(EffectNode::Sound(SoundEffect {
+                                    sound: SoundId::RocketExplode,
                                 })),
                             ],
                         })),
-                        animation: to_animation(
-                            &assets.mech.artillery.projectile_anim,
-                            1.0 / 24.0,
-                            Time::ONE,
-                            vec![],
-                        ),
+                        animation: projectile_animation,
+                        intercepts: false,
+                        lifetime: None,
                     })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.mechs.artillery.artillery_shoot.clone(),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::ArtilleryShoot,
                     })),
                 ],
             })),
         )],
     );
     UnitTemplate {
+        name: "artillery",
         ai: UnitAI::Engage {
             target: TargetAI::Closest,
             default: PositionAI::Advance,
@@ -208,6 +547,7 @@ fn artillery(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(2.0),
         acceleration: Coord::new(10.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(3.0),
         },
@@ -218,38 +558,72 @@ fn artillery(assets: &Rc<Assets>) -> UnitTemplate {
         },
         idle_animation,
         move_animation,
-        extra_render: None,
-        on_death: Effect::Noop,
+        glide_animation: Some(glide_animation),
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Medium,
+        npc: false,
+        spawn: None,
     }
 }
 
-fn healer(assets: &Rc<Assets>) -> UnitTemplate {
+fn healer(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
     let idle_animation = to_animation(
+        animations,
         &[assets.mech.healer.idle.clone()],
         1.0 / 32.0,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.mech.healer.walk, 1.0 / 32.0, Time::ONE, vec![]);
+    let move_animation = to_animation(
+        animations,
+        &assets.mech.healer.walk,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
+    let glide_animation = to_animation(
+        animations,
+        &assets.mech.healer.walk,
+        1.0 / 32.0,
+        Time::new(3.0),
+        vec![],
+    );
     let animation = to_animation(
+        animations,
         &assets.mech.healer.heal,
         1.0 / 32.0,
         Time::ONE,
         vec![(
             5,
-            Effect::List(Box::new(ListEffect {
+            Effect::new(EffectNode::List(ListEffect {
                 effects: vec![
-                    Effect::Heal(Box::new(HealEffect {
-                        value: Hp::new(5.0),
+                    Effect::new(EffectNode::Heal(HealEffect {
+                        // Heals more the sturdier the healer is made by perks
+                        value: Value::CasterMaxHp(r32(0.7)),
+                        overheal_shield: false,
+                    })),
+                    Effect::new(EffectNode::SlowFall(SlowFallEffect {
+                        duration: Time::new(2.0),
+                        max_fall_speed: Coord::new(GLIDE_FALL_SPEED),
                     })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.mechs.healer.heal_effect.clone(),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::HealEffect,
                     })),
                 ],
             })),
         )],
     );
     UnitTemplate {
+        name: "healer",
         ai: UnitAI::Engage {
             target: TargetAI::LowestHp,
             default: PositionAI::Follow,
@@ -262,6 +636,7 @@ fn healer(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(2.5),
         acceleration: Coord::new(10.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(3.0),
         },
@@ -272,53 +647,126 @@ fn healer(assets: &Rc<Assets>) -> UnitTemplate {
         },
         idle_animation,
         move_animation,
-        extra_render: None,
-        on_death: Effect::Noop,
+        glide_animation: Some(glide_animation),
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        // Leaves a healing zone behind for the other mechs
+        on_death: Effect::new(EffectNode::SpawnZone(SpawnZoneEffect {
+            kind: ZoneKind::Healing,
+            center: Who::Caster,
+            duration: Time::new(5.0),
+            tick_time: Time::new(0.5),
+            stacking: ZoneStacking::Refresh,
+            area: AreaEffect {
+                center: Who::Caster,
+                radius: Coord::new(2.0),
+                filter: FactionFilter::Allies,
+                hits_caster: false,
+                falloff: None,
+                effect: Effect::new(EffectNode::Heal(HealEffect {
+                    value: Value::Flat(Hp::new(0.5)),
+                    overheal_shield: false,
+                })),
+            },
+        })),
+        point_defense: None,
+        skill: AiSkill::PERFECT,
+        size: SizeClass::Medium,
+        npc: false,
+        spawn: None,
     }
 }
 
-fn blighter(assets: &Rc<Assets>) -> UnitTemplate {
+fn blighter(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
     let idle_animation = to_animation(
+        animations,
         &[assets.enemies.blighter.idle.clone()],
         1.0 / 32.0,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.enemies.blighter.walk, 1.0 / 32.0, Time::ONE, vec![]);
+    let move_animation = to_animation(
+        animations,
+        &assets.enemies.blighter.walk,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
+    let projectile_animation = animations.get_or_insert_with("blighter projectile", || {
+        Animation::from_textures(
+            &[assets.enemies.blighter.projectile.clone()],
+            1.0 / 32.0,
+            Time::ONE,
+            vec![],
+        )
+    });
     let animation = to_animation(
+        animations,
         &assets.enemies.blighter.attack,
         1.0 / 32.0,
         Time::ONE,
         vec![(
             10,
-            Effect::List(Box::new(ListEffect {
+            Effect::new(EffectNode::List(ListEffect {
                 effects: vec![
-                    Effect::Projectile(Box::new(ProjectileEffect {
+                    Effect::new(EffectNode::Projectile(ProjectileEffect {
                         offset: vec2(0.0, 0.5).map(Coord::new),
+                        attachment: None,
                         ai: ProjectileAI::Idle,
                         collider: Collider::Aabb {
                             size: vec2(0.5, 0.5).map(Coord::new),
                         },
                         speed: Coord::new(20.0),
-                        on_hit: Effect::Damage(Box::new(DamageEffect {
-                            damage_type: DamageType::Physical,
-                            value: Hp::new(2.0),
+                        spread: Coord::ZERO,
+                        pellets: 1,
+                        fan: Coord::ZERO,
+                        burst: None,
+                        max_range: None,
+                        arming_distance: Coord::ZERO,
+                        falloff: None,
+                        prediction: AimPrediction::Full,
+                        missing: MissingUnit::Skip,
+                        on_expire: Effect::noop(),
+                        on_hit: Effect::new(EffectNode::List(ListEffect {
+                            effects: vec![
+                                Effect::new(EffectNode::Damage(DamageEffect {
+                                    damage_type: DamageType::Physical,
+                                    value: Value::Flat(Hp::new(2.0)),
+                                    hits_caster: false,
+                                    impact: None,
+                                })),
+                                // The blight slows the target down
+                                Effect::new(EffectNode::ApplyStatus(ApplyStatusEffect {
+                                    status: Status::Modified {
+                                        time: Time::new(2.0),
+                                        modifier: StatModifier::new(
+                                            Stat::MoveSpeed,
+                                            ModifierKind::More,
+                                            0.75,
+                                        ),
+                                    },
+                                    on_expire: Effect::noop(),
+                                })),
+                            ],
                         })),
-                        animation: to_animation(
-                            &[assets.enemies.blighter.projectile.clone()],
-                            1.0 / 32.0,
-                            Time::ONE,
-                            vec![],
-                        ),
+                        animation: projectile_animation,
+                        intercepts: false,
+                        lifetime: None,
                     })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.enemies.blighter.shoot.clone(),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::BlighterShoot,
                     })),
                 ],
             })),
         )],
     );
     UnitTemplate {
+        name: "blighter",
         ai: UnitAI::Engage {
             target: TargetAI::Farthest,
             default: PositionAI::Advance,
@@ -331,6 +779,7 @@ fn blighter(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(2.0),
         acceleration: Coord::new(10.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(3.0),
         },
@@ -341,117 +790,214 @@ fn blighter(assets: &Rc<Assets>) -> UnitTemplate {
         },
         idle_animation,
         move_animation,
-        extra_render: None,
-        on_death: Effect::Noop,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        // Bursts into a pool of poison
+        on_death: Effect::new(EffectNode::SpawnZone(SpawnZoneEffect {
+            kind: ZoneKind::Poison,
+            center: Who::Caster,
+            duration: Time::new(4.0),
+            tick_time: Time::new(0.5),
+            stacking: ZoneStacking::Stack,
+            area: AreaEffect {
+                center: Who::Caster,
+                radius: Coord::new(1.5),
+                filter: FactionFilter::Enemies,
+                hits_caster: false,
+                falloff: None,
+                effect: Effect::new(EffectNode::Damage(DamageEffect {
+                    damage_type: DamageType::Physical,
+                    value: Value::Flat(Hp::new(0.5)),
+                    hits_caster: false,
+                    impact: None,
+                })),
+            },
+        })),
+        point_defense: None,
+        skill: AiSkill::new(0.3, 0.1),
+        size: SizeClass::Medium,
+        npc: false,
+        spawn: Some(SpawnStyle {
+            telegraph: TelegraphKind::Portal,
+            telegraph_time: Time::new(1.0),
+            animation_time: Time::new(0.5),
+        }),
     }
 }
 
-fn ravager(assets: &Rc<Assets>) -> UnitTemplate {
+/// Animations and the roar-charge-bite cycle of the ravager.
+struct RavagerKit {
+    idle_animation: AnimationId,
+    move_animation: AnimationId,
+    ai: UnitAI,
+    action: Action,
+}
+
+fn ravager_kit(
+    assets: &Rc<Assets>,
+    animations: &mut Animations,
+    sprite_scale: f32,
+    charge_speed: Coord,
+    charge_cooldown: Time,
+) -> RavagerKit {
     let idle_animation = to_animation(
+        animations,
         &[assets.enemies.ravager.idle.clone()],
-        1.0 / 32.0,
+        sprite_scale,
+        Time::ONE,
+        vec![],
+    );
+    let move_animation = to_animation(
+        animations,
+        &assets.enemies.ravager.walk,
+        sprite_scale,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.enemies.ravager.walk, 1.0 / 32.0, Time::ONE, vec![]);
     let roar = to_animation(
+        animations,
         &assets.enemies.ravager.roar,
-        1.0 / 32.0,
+        sprite_scale,
         Time::ONE,
         vec![(
             1,
-            Effect::Sound(Box::new(SoundEffect {
-                sound: assets.sound_design.enemies.ravager.roar.clone(),
+            Effect::new(EffectNode::Sound(SoundEffect {
+                sound: SoundId::RavagerRoar,
             })),
         )],
     );
     let anticipation = to_animation(
+        animations,
         &assets.enemies.ravager.anticipation,
-        1.0 / 32.0,
+        sprite_scale,
         Time::ONE,
         vec![],
     );
     let charge = to_animation(
+        animations,
         &assets.enemies.ravager.charge,
-        1.0 / 32.0,
+        sprite_scale,
         Time::ONE,
         vec![(
             1,
-            Effect::List(Box::new(ListEffect {
+            Effect::new(EffectNode::List(ListEffect {
                 effects: vec![
-                    Effect::Dash(Box::new(DashEffect {
-                        speed: Coord::new(15.0),
+                    Effect::new(EffectNode::Dash(DashEffect {
+                        speed: charge_speed,
                         duration: Time::new(0.5),
-                        on_contact: Effect::Damage(Box::new(DamageEffect {
+                        on_contact: Effect::new(EffectNode::Damage(DamageEffect {
                             damage_type: DamageType::Physical,
-                            value: Hp::new(5.0),
+                            value: Value::Flat(Hp::new(5.0)),
+                            hits_caster: false,
+                            impact: None,
                         })),
                     })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.enemies.ravager.charge.clone(),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::RavagerCharge,
                     })),
                 ],
             })),
         )],
     );
     let attack = to_animation(
+        animations,
         &assets.enemies.ravager.attack,
-        1.0 / 32.0,
+        sprite_scale,
         Time::ONE,
         vec![(
             3,
-            Effect::List(Box::new(ListEffect {
+            Effect::new(EffectNode::List(ListEffect {
                 effects: vec![
-                    Effect::Damage(Box::new(DamageEffect {
-                        damage_type: DamageType::Physical,
-                        value: Hp::new(1.0),
+                    Effect::new(EffectNode::MeleeSwing(MeleeSwingEffect {
+                        shape: MeleeShape::Arc {
+                            radius: Coord::new(2.5),
+                            half_angle: Coord::new(std::f32::consts::PI / 3.0),
+                        },
+                        attachment: None,
+                        duration: Time::new(0.2),
+                        effect: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Physical,
+                            value: Value::Flat(Hp::new(1.0)),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                        deflect: None,
                     })),
-                    Effect::Sound(Box::new(SoundEffect {
-                        sound: assets.sound_design.enemies.ravager.bite.clone(),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::RavagerBite,
                     })),
                 ],
             })),
         )],
     );
-    UnitTemplate {
-        ai: UnitAI::Engage {
-            target: TargetAI::Closest,
-            default: PositionAI::Advance,
-            switch: Some(SwitchAction {
-                next_action: Action {
-                    cooldown: Time::ZERO,
-                    engage_radius: Coord::new(10.0),
-                    animation: anticipation,
-                },
-                next_ai: Box::new(UnitAI::Engage {
-                    target: TargetAI::Closest,
-                    default: PositionAI::Advance,
-                    switch: Some(SwitchAction {
-                        next_action: Action {
-                            cooldown: Time::new(2.0),
-                            engage_radius: Coord::new(10.0),
-                            animation: charge,
-                        },
-                        next_ai: Box::new(UnitAI::Engage {
-                            target: TargetAI::Closest,
-                            default: PositionAI::Advance,
-                            switch: Some(SwitchAction {
-                                next_action: Action {
-                                    cooldown: Time::new(1.0),
-                                    engage_radius: Coord::new(3.0),
-                                    animation: attack,
-                                },
-                                next_ai: Box::new(UnitAI::Engage {
-                                    target: TargetAI::Closest,
-                                    default: PositionAI::Advance,
-                                    switch: None,
-                                }),
+    let ai = UnitAI::Engage {
+        target: TargetAI::Closest,
+        default: PositionAI::Advance,
+        switch: Some(SwitchAction {
+            next_action: Action {
+                cooldown: Time::ZERO,
+                engage_radius: Coord::new(10.0),
+                animation: anticipation,
+            },
+            next_ai: Box::new(UnitAI::Engage {
+                target: TargetAI::Closest,
+                default: PositionAI::Advance,
+                switch: Some(SwitchAction {
+                    next_action: Action {
+                        cooldown: charge_cooldown,
+                        engage_radius: Coord::new(10.0),
+                        animation: charge,
+                    },
+                    next_ai: Box::new(UnitAI::Engage {
+                        target: TargetAI::Closest,
+                        default: PositionAI::Advance,
+                        switch: Some(SwitchAction {
+                            next_action: Action {
+                                cooldown: Time::new(1.0),
+                                engage_radius: Coord::new(3.0),
+                                animation: attack,
+                            },
+                            next_ai: Box::new(UnitAI::Engage {
+                                target: TargetAI::Closest,
+                                default: PositionAI::Advance,
+                                switch: None,
                             }),
                         }),
                     }),
                 }),
             }),
-        },
+        }),
+    };
+    let action = Action {
+        cooldown: Time::ZERO,
+        engage_radius: Coord::new(10.0),
+        animation: roar,
+    };
+    RavagerKit {
+        idle_animation,
+        move_animation,
+        ai,
+        action,
+    }
+}
+
+fn ravager(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
+    let kit = ravager_kit(
+        assets,
+        animations,
+        1.0 / 32.0,
+        Coord::new(15.0),
+        Time::new(2.0),
+    );
+    UnitTemplate {
+        name: "ravager",
+        ai: kit.ai,
         health: Health::new(Hp::new(20.0)),
         sanity: None,
         collider: Collider::Aabb {
@@ -459,42 +1005,136 @@ fn ravager(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(2.0),
         acceleration: Coord::new(20.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(3.0),
         },
-        action: Action {
-            cooldown: Time::ZERO,
-            engage_radius: Coord::new(10.0),
-            animation: roar,
+        action: kit.action,
+        idle_animation: kit.idle_animation,
+        move_animation: kit.move_animation,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: Stats {
+            modifiers: vec![StatModifier::new(Stat::Defense, ModifierKind::Flat, 0.25)],
         },
-        idle_animation,
-        move_animation,
-        extra_render: None,
-        on_death: Effect::Noop,
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::new(0.2, 0.05),
+        size: SizeClass::Medium,
+        npc: false,
+        spawn: Some(SpawnStyle {
+            telegraph: TelegraphKind::DropPod,
+            telegraph_time: Time::new(1.2),
+            animation_time: Time::new(0.6),
+        }),
     }
 }
 
-fn stinger(assets: &Rc<Assets>) -> UnitTemplate {
+fn ravager_alpha(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
+    let sprite_scale = 1.0 / 16.0;
+    let kit = ravager_kit(
+        assets,
+        animations,
+        sprite_scale,
+        Coord::new(15.0),
+        Time::new(2.0),
+    );
+    let mut phase = |health_threshold: f32, charge_speed: f32, charge_cooldown: f32| {
+        let kit = ravager_kit(
+            assets,
+            animations,
+            sprite_scale,
+            Coord::new(charge_speed),
+            Time::new(charge_cooldown),
+        );
+        BossPhase {
+            health_threshold: Hp::new(health_threshold),
+            ai: kit.ai,
+            action: kit.action,
+            invulnerability: Time::new(2.0),
+        }
+    };
+    UnitTemplate {
+        name: "ravager_alpha",
+        ai: kit.ai,
+        health: Health::new(Hp::new(150.0)),
+        sanity: None,
+        collider: Collider::Aabb {
+            size: vec2(4.0, 2.0).map(Coord::new),
+        },
+        speed: Coord::new(2.0),
+        acceleration: Coord::new(20.0),
+        statuses: vec![],
+        start_action_state: ActionState::Cooldown {
+            time_left: Time::new(3.0),
+        },
+        action: kit.action,
+        idle_animation: kit.idle_animation,
+        move_animation: kit.move_animation,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: Some(Boss {
+            name: "boss.alpha_ravager".to_owned(),
+            phases: vec![phase(0.6, 20.0, 1.0), phase(0.25, 25.0, 0.5)],
+            phase: 0,
+        }),
+        stats: Stats {
+            modifiers: vec![StatModifier::new(Stat::Defense, ModifierKind::Flat, 0.5)],
+        },
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::new(0.15, 0.03),
+        size: SizeClass::Boss,
+        npc: false,
+        spawn: Some(SpawnStyle {
+            telegraph: TelegraphKind::DropPod,
+            telegraph_time: Time::new(2.5),
+            animation_time: Time::new(1.0),
+        }),
+    }
+}
+
+fn stinger(assets: &Rc<Assets>, animations: &mut Animations) -> UnitTemplate {
     let idle_animation = to_animation(
+        animations,
         &[assets.enemies.stinger.idle.clone()],
         1.0 / 32.0,
         Time::ONE,
         vec![],
     );
-    let move_animation = to_animation(&assets.enemies.stinger.walk, 1.0 / 32.0, Time::ONE, vec![]);
+    let move_animation = to_animation(
+        animations,
+        &assets.enemies.stinger.walk,
+        1.0 / 32.0,
+        Time::ONE,
+        vec![],
+    );
     let attack = to_animation(
+        animations,
         &assets.enemies.stinger.attack,
         1.0 / 32.0,
         Time::ONE,
         vec![(
             13,
-            Effect::Damage(Box::new(DamageEffect {
+            Effect::new(EffectNode::Damage(DamageEffect {
                 damage_type: DamageType::Physical,
-                value: Hp::new(5.0),
+                value: Value::Flat(Hp::new(5.0)),
+                hits_caster: false,
+                impact: None,
             })),
         )],
     );
     UnitTemplate {
+        name: "stinger",
         ai: UnitAI::Stinger {
             target: TargetAI::Closest,
             preferred_height: Coord::new(7.0),
@@ -508,6 +1148,7 @@ fn stinger(assets: &Rc<Assets>) -> UnitTemplate {
         },
         speed: Coord::new(15.0),
         acceleration: Coord::new(10.0),
+        statuses: vec![],
         start_action_state: ActionState::Cooldown {
             time_left: Time::new(5.0),
         },
@@ -518,7 +1159,23 @@ fn stinger(assets: &Rc<Assets>) -> UnitTemplate {
         },
         idle_animation,
         move_animation,
-        extra_render: None,
-        on_death: Effect::Noop,
+        glide_animation: None,
+        attachments: vec![],
+        weapon: None,
+        ammo: None,
+        block: None,
+        boss: None,
+        stats: default(),
+        elites: vec![],
+        on_death: Effect::noop(),
+        point_defense: None,
+        skill: AiSkill::new(0.25, 0.08),
+        size: SizeClass::Small,
+        npc: false,
+        spawn: Some(SpawnStyle {
+            telegraph: TelegraphKind::Portal,
+            telegraph_time: Time::new(0.8),
+            animation_time: Time::new(0.4),
+        }),
     }
 }
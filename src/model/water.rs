@@ -0,0 +1,11 @@
+use super::*;
+
+/// Depth of the water placed in the levels.
+pub const WATER_DEPTH: f32 = 1.5;
+
+/// A body of water that slows down everything inside it.
+#[derive(HasId, Debug, Clone, Serialize, Deserialize)]
+pub struct Water {
+    pub id: Id,
+    pub area: AABB<Coord>,
+}
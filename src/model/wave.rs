@@ -1,9 +1,19 @@
 use super::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wave {
     pub position: Coord,
-    pub units: Vec<UnitTemplate>,
+    pub units: Vec<WaveUnit>,
+}
+
+/// A unit of a wave and when and where it spawns once the wave starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveUnit {
+    pub template: UnitTemplate,
+    /// Random ahead of the left border if not set.
+    pub spawn_x: Option<Coord>,
+    /// Time after the start of the wave.
+    pub delay: Time,
 }
 
 impl Wave {
@@ -11,3 +21,13 @@ impl Wave {
         default()
     }
 }
+
+impl WaveUnit {
+    pub fn new(template: UnitTemplate) -> Self {
+        Self {
+            template,
+            spawn_x: None,
+            delay: Time::ZERO,
+        }
+    }
+}
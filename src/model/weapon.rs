@@ -0,0 +1,366 @@
+use super::*;
+use logic::*;
+
+/// A weapon that can be equipped on a unit with a matching attachment point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weapon {
+    pub name: String,
+    pub sprite: Option<Sprite>,
+    /// Name of the attachment point the weapon is mounted on
+    pub attachment: String,
+    /// Effect performed when the weapon is fired, usually a projectile
+    pub effect: Effect,
+    /// Time between the shots, replaces the unit's action cooldown
+    pub cooldown: Time,
+    /// Maximum deviation of the projectiles from the aimed direction, in radians
+    pub spread: Coord,
+    /// Ammo pool of the weapon, unlimited if `None`
+    pub ammo: Option<Ammo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiveWeaponEffect {
+    pub weapon: Weapon,
+}
+
+impl Weapon {
+    /// Weapons that can be mounted on the tank.
+    pub fn tank_weapons(assets: &Assets, animations: &mut Animations) -> Vec<Self> {
+        vec![
+            Self::tank_cannon(assets, animations),
+            Self::tank_weapon(
+                assets,
+                animations,
+                "Scattergun",
+                Time::new(0.6),
+                Coord::new(0.25),
+                Coord::new(12.0),
+                Hp::new(0.6),
+                1.0,
+                Some(Ammo::new(6, Time::new(2.0))),
+            )
+            .with_volley(5, Coord::new(0.5), None)
+            .with_falloff(Falloff {
+                start: Coord::new(3.0),
+                end: Coord::new(8.0),
+                min: r32(0.25),
+            }),
+            Self::tank_weapon(
+                assets,
+                animations,
+                "Minigun",
+                Time::new(1.2),
+                Coord::new(0.1),
+                Coord::new(13.0),
+                Hp::new(0.4),
+                0.8,
+                None,
+            )
+            .with_volley(
+                1,
+                Coord::ZERO,
+                Some(Burst {
+                    shots: 6,
+                    delay: Time::new(0.08),
+                }),
+            ),
+            Self::tank_weapon(
+                assets,
+                animations,
+                "Heavy Cannon",
+                Time::new(2.0),
+                Coord::ZERO,
+                Coord::new(14.0),
+                Hp::new(3.0),
+                1.4,
+                Some(Ammo::new(3, Time::new(4.0))),
+            ),
+            Self::tank_weapon(
+                assets,
+                animations,
+                "Flamethrower",
+                Time::new(0.3),
+                Coord::ZERO,
+                Coord::new(8.0),
+                Hp::new(0.3),
+                0.6,
+                Some(Ammo::new(20, Time::new(3.0))),
+            )
+            .with_volley(3, Coord::new(0.4), None)
+            .with_caster_motion(
+                ProjectileAI::Attached {
+                    offset: Position::ZERO,
+                },
+                Time::new(0.4),
+            )
+            .leaving_zone(SpawnZoneEffect::burning(
+                Time::new(2.0),
+                Coord::new(1.5),
+                Hp::new(0.3),
+            ))
+            .with_impact(Impact {
+                color: Rgba::new(1.0, 0.55, 0.1, 1.0),
+                sparks: 4,
+                spark_speed: 3.0,
+                sound: None,
+            }),
+            Self::tank_weapon(
+                assets,
+                animations,
+                "Orb Shield",
+                Time::new(5.0),
+                Coord::ZERO,
+                Coord::ZERO,
+                Hp::new(1.0),
+                0.8,
+                None,
+            )
+            .with_volley(3, Coord::ZERO, None)
+            .with_caster_motion(
+                ProjectileAI::Orbit {
+                    radius: Coord::new(2.0),
+                    angular_speed: Coord::new(3.0),
+                    angle: Coord::ZERO,
+                },
+                Time::new(4.0),
+            )
+            .intercepting()
+            .with_damage_type(DamageType::Energy),
+            Self::grappling_hook(assets),
+        ]
+    }
+
+    /// Pulls light enemies in and reels the tank towards heavy ones and the terrain.
+    pub fn grappling_hook(assets: &Assets) -> Self {
+        Self {
+            name: "Grappling Hook".to_owned(),
+            sprite: Some(Sprite {
+                texture: assets.mech.tank.weapon.id(),
+                size: vec2(1.0, 1.0 * 14.0 / 31.0) * 0.8,
+            }),
+            attachment: "weapon".to_owned(),
+            effect: Effect::new(EffectNode::List(ListEffect {
+                effects: vec![
+                    Effect::new(EffectNode::Grapple(GrappleEffect {
+                        attachment: Some("muzzle".to_owned()),
+                        speed: Coord::new(25.0),
+                        max_length: Coord::new(12.0),
+                        reel_speed: Coord::new(8.0),
+                        duration: Time::new(2.0),
+                        on_attach: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Physical,
+                            value: Value::Flat(Hp::new(0.5)),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                    })),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::TankShoot,
+                    })),
+                ],
+            })),
+            cooldown: Time::new(3.0),
+            spread: Coord::ZERO,
+            ammo: None,
+        }
+    }
+
+    pub fn tank_cannon(assets: &Assets, animations: &mut Animations) -> Self {
+        Self::tank_weapon(
+            assets,
+            animations,
+            "Cannon",
+            Time::new(1.0),
+            Coord::ZERO,
+            Coord::new(10.0),
+            Hp::new(1.0),
+            1.0,
+            None,
+        )
+    }
+
+    /// Makes every shot of the weapon launch several pellets and/or fire in bursts.
+    fn with_volley(mut self, pellets: usize, fan: Coord, burst: Option<Burst>) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Projectile(projectile) = effect {
+                projectile.pellets = pellets;
+                projectile.fan = fan;
+                projectile.burst = burst;
+            }
+        });
+        self
+    }
+
+    /// Makes the weapon's projectiles move relative to the caster and last for the lifetime.
+    fn with_caster_motion(mut self, ai: ProjectileAI, lifetime: Time) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Projectile(projectile) = effect {
+                projectile.ai = ai.clone();
+                projectile.lifetime = Some(lifetime);
+            }
+        });
+        self
+    }
+
+    /// Makes the weapon's projectiles leave the zone where they expire.
+    fn leaving_zone(mut self, zone: SpawnZoneEffect) -> Self {
+        let zone = Effect::new(EffectNode::SpawnZone(zone));
+        self.effect.walk_mut(&mut |effect, nodes| {
+            if let EffectNode::Projectile(projectile) = effect {
+                projectile.on_expire = zone.copy_into(nodes);
+            }
+        });
+        self
+    }
+
+    /// Makes the weapon's projectiles destroy the enemy projectiles they touch.
+    fn intercepting(mut self) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Projectile(projectile) = effect {
+                projectile.intercepts = true;
+            }
+        });
+        self
+    }
+
+    /// Makes the weapon's projectiles deal less damage the farther they fly.
+    fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Projectile(projectile) = effect {
+                projectile.falloff = Some(falloff);
+            }
+        });
+        self
+    }
+
+    /// Changes the type of all the damage the weapon deals.
+    fn with_damage_type(mut self, damage_type: DamageType) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Damage(damage) = effect {
+                damage.damage_type = damage_type.clone();
+            }
+        });
+        self
+    }
+
+    /// Overrides how the weapon's hits look and sound.
+    fn with_impact(mut self, impact: Impact) -> Self {
+        self.effect.walk_mut(&mut |effect, _| {
+            if let EffectNode::Damage(damage) = effect {
+                damage.impact = Some(impact.clone());
+            }
+        });
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tank_weapon(
+        assets: &Assets,
+        animations: &mut Animations,
+        name: &str,
+        cooldown: Time,
+        spread: Coord,
+        speed: Coord,
+        damage: Hp,
+        scale: f32,
+        ammo: Option<Ammo>,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            sprite: Some(Sprite {
+                texture: assets.mech.tank.weapon.id(),
+                size: vec2(1.0, 1.0 * 14.0 / 31.0) * scale,
+            }),
+            attachment: "weapon".to_owned(),
+            effect: Effect::new(EffectNode::List(ListEffect {
+                effects: vec![
+                    Effect::new(EffectNode::Projectile(ProjectileEffect {
+                        offset: Position::ZERO,
+                        attachment: Some("muzzle".to_owned()),
+                        ai: ProjectileAI::Idle,
+                        collider: Collider::Aabb {
+                            size: vec2(0.5, 0.5).map(|x| Coord::new(x * scale)),
+                        },
+                        speed,
+                        spread: Coord::ZERO,
+                        pellets: 1,
+                        fan: Coord::ZERO,
+                        burst: None,
+                        max_range: None,
+                        arming_distance: Coord::ZERO,
+                        falloff: None,
+                        prediction: AimPrediction::Full,
+                        missing: MissingUnit::Skip,
+                        on_expire: Effect::noop(),
+                        on_hit: Effect::new(EffectNode::Damage(DamageEffect {
+                            damage_type: DamageType::Physical,
+                            value: Value::Flat(damage),
+                            hits_caster: false,
+                            impact: None,
+                        })),
+                        animation: tank_projectile_animation(assets, animations, scale),
+                        intercepts: false,
+                        lifetime: None,
+                    })),
+                    Effect::new(EffectNode::Sound(SoundEffect {
+                        sound: SoundId::TankShoot,
+                    })),
+                ],
+            })),
+            cooldown,
+            spread,
+            ammo,
+        }
+    }
+}
+
+/// The animation of the tank's projectiles, shared by all weapons of the same scale.
+pub fn tank_projectile_animation(
+    assets: &Assets,
+    animations: &mut Animations,
+    scale: f32,
+) -> AnimationId {
+    animations.get_or_insert_with(format!("tank projectile {}", scale), || {
+        Animation::from_textures(
+            &[assets.mech.tank.projectile.clone()],
+            1.0 / 16.0 * scale,
+            Time::ONE,
+            vec![],
+        )
+    })
+}
+
+impl Unit {
+    pub fn can_equip(&self, weapon: &Weapon) -> bool {
+        self.attachments
+            .iter()
+            .any(|point| point.name == weapon.attachment)
+    }
+}
+
+impl GiveWeaponEffect {
+    pub fn process(self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let target = context.get_mut(Who::Target, logic)?;
+        if !target.can_equip(&self.weapon) {
+            return None;
+        }
+        target.action.cooldown = self.weapon.cooldown;
+        target.ammo = self.weapon.ammo.clone();
+        target.weapon = Some(self.weapon);
+        Some(())
+    }
+}
+
+/// Fires the caster's equipped weapon.
+pub fn fire_weapon(context: EffectContext, logic: &mut Logic) -> Option<()> {
+    let weapon = context.get(Who::Caster, logic)?.weapon.as_ref()?;
+    let spread = weapon.spread;
+    let mut effect = weapon.effect.clone();
+    effect.walk_mut(&mut |effect, _| {
+        if let EffectNode::Projectile(projectile) = effect {
+            projectile.spread = spread;
+        }
+    });
+    logic.effects.push_front(QueuedEffect { effect, context });
+    Some(())
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+/// Projectiles no larger than this drift with the wind.
+const LIGHT_PROJECTILE_SIZE: f32 = 0.5;
+
+/// The ambient weather of a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Weather {
+    Clear,
+    Rain,
+    Snow,
+    Ash,
+    Fog,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::Clear
+    }
+}
+
+impl Weather {
+    pub const ALL: [Self; 5] = [Self::Clear, Self::Rain, Self::Snow, Self::Ash, Self::Fog];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Clear => "Clear",
+            Self::Rain => "Rain",
+            Self::Snow => "Snow",
+            Self::Ash => "Falling ash",
+            Self::Fog => "Fog",
+        }
+    }
+
+    /// Horizontal acceleration of the light projectiles, and the drift of the weather particles.
+    pub fn wind(&self) -> Coord {
+        Coord::new(match self {
+            Self::Clear | Self::Fog => 0.0,
+            Self::Rain => 1.0,
+            Self::Snow => 1.5,
+            Self::Ash => 2.5,
+        })
+    }
+
+    /// Whether the weather puts out the fires.
+    pub fn extinguishes(&self) -> bool {
+        matches!(self, Self::Rain)
+    }
+}
+
+impl Projectile {
+    /// Whether the projectile is carried by the wind.
+    pub fn is_light(&self, bodies: &Bodies) -> bool {
+        let size = match bodies.colliders.get(self.id) {
+            Some(Collider::Aabb { size }) => size,
+            None => return false,
+        };
+        matches!(self.ai, ProjectileAI::Idle)
+            && size.x.max(size.y) <= Coord::new(LIGHT_PROJECTILE_SIZE)
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+use logic::*;
+
+/// Leaves a zone on the ground under the unit that applies the area effect around itself
+/// every tick until the duration runs out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnZoneEffect<E = Effect> {
+    pub kind: ZoneKind,
+    /// Whose position the zone is left under.
+    pub center: Who,
+    pub duration: Time,
+    /// Time between the applications of the area.
+    pub tick_time: Time,
+    pub stacking: ZoneStacking,
+    /// Applied around the zone, the center of the area is ignored.
+    pub area: AreaEffect<E>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZoneKind {
+    Burning,
+    Poison,
+    Healing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZoneStacking {
+    /// The overlapping zones of the kind all apply.
+    Stack,
+    /// A new zone refreshes the duration of an overlapping one of the kind instead.
+    Refresh,
+}
+
+/// A lingering patch on the ground, e.g. fire or poison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Zone {
+    pub kind: ZoneKind,
+    pub caster: Option<Id>,
+    pub position: Position,
+    pub duration: Time,
+    pub time_left: Time,
+    pub tick_time: Time,
+    pub next_tick: Time,
+    pub area: AreaEffect,
+}
+
+/// Time between the damage ticks of the burning zones.
+const BURNING_TICK_TIME: f32 = 0.5;
+
+impl SpawnZoneEffect {
+    /// A patch of fire under the target that damages the caster's enemies and refreshes
+    /// instead of stacking.
+    pub fn burning(duration: Time, radius: Coord, damage: Hp) -> Self {
+        Self {
+            kind: ZoneKind::Burning,
+            center: Who::Target,
+            duration,
+            tick_time: Time::new(BURNING_TICK_TIME),
+            stacking: ZoneStacking::Refresh,
+            area: AreaEffect {
+                center: Who::Target,
+                radius,
+                filter: FactionFilter::Enemies,
+                hits_caster: false,
+                falloff: None,
+                effect: Effect::new(EffectNode::Damage(DamageEffect {
+                    damage_type: DamageType::Physical,
+                    value: Value::Flat(damage),
+                    hits_caster: false,
+                    impact: None,
+                })),
+            },
+        }
+    }
+}
+
+impl Zone {
+    /// The share of the duration left, from 1 to 0.
+    pub fn fade(&self) -> f32 {
+        (self.time_left / self.duration).as_f32().clamp(0.0, 1.0)
+    }
+}
+
+impl SpawnZoneEffect {
+    pub fn process(mut self, context: EffectContext, logic: &mut Logic) -> Option<()> {
+        let center = context.position_of(self.center, logic)?;
+        let position = vec2(center.x, logic.model.world.ground_level);
+        if self.stacking == ZoneStacking::Refresh {
+            if let Some(zone) = logic.model.world.zones.iter_mut().find(|zone| {
+                zone.kind == self.kind
+                    && (zone.position - position).len() < zone.area.radius + self.area.radius
+            }) {
+                zone.time_left = zone.time_left.max(self.duration);
+                zone.duration = zone.time_left;
+                return Some(());
+            }
+        }
+        self.area.center = Who::Target;
+        logic.model.world.zones.push(Zone {
+            kind: self.kind,
+            caster: context.caster,
+            position,
+            duration: self.duration,
+            time_left: self.duration,
+            tick_time: self.tick_time,
+            next_tick: Time::ZERO,
+            area: self.area,
+        });
+        Some(())
+    }
+}
@@ -0,0 +1,571 @@
+use super::*;
+
+use assets::PixelTexture;
+use model::{
+    Animation, AnimationId, Animations, Coord, Effect, Health, Hp, Level, Time, UnitTemplate,
+    UnitTemplates,
+};
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// A mod pack found in the `mods` directory.
+///
+/// Packs are directories that may contain:
+/// - effect scripts in `scripts/*.rhai`,
+/// - cutscene scripts in `cutscenes/<name>.txt` replacing the built-in ones,
+/// - sprites in `sprites/<name>.png`,
+/// - effects in `effects/<name>.json`, see [ModEffect],
+/// - unit templates in `units/<name>.json`, see [ModTemplate],
+/// - levels in `levels/<name>.json`, see [Level].
+#[derive(Default)]
+pub struct ModPack {
+    pub name: String,
+    pub scripts: Vec<(String, String)>,
+    pub cutscenes: HashMap<String, String>,
+    pub sprites: Vec<(String, PixelTexture)>,
+    pub effects: Vec<(String, String)>,
+    pub units: Vec<(String, String)>,
+    pub levels: Vec<(String, String)>,
+    /// The files of the pack that failed to load.
+    pub problems: Vec<String>,
+}
+
+/// The contents of the mod packs merged by [apply], empty until then.
+#[derive(Default)]
+pub struct ModAssets {
+    pub sprites: HashMap<String, PixelTexture>,
+    pub effects: HashMap<String, ModEffect>,
+    /// The names are leaked once the mods are applied,
+    /// so that they are static like the names of the built-in templates.
+    pub templates: Vec<(&'static str, ModTemplate)>,
+    pub levels: Vec<(String, Level)>,
+    /// Everything that was skipped, shown in the main menu.
+    pub problems: Vec<String>,
+}
+
+/// An effect of a mod pack, in the same format as the serialized [Effect]s, except that
+/// the projectiles refer to their animations by name instead of an index into the store,
+/// e.g. `"animation": "artillery projectile"`. The built-in animations with a name are
+/// `artillery projectile`, `blighter projectile` and `tank projectile 1`.
+#[derive(Debug, Clone)]
+pub struct ModEffect(serde_json::Value);
+
+impl ModEffect {
+    /// Checks the format of the effect, the animation names are checked by [Self::resolve].
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let json: serde_json::Value =
+            serde_json::from_str(source).map_err(|err| err.to_string())?;
+        let mut check = json.clone();
+        resolve_animations(&mut check, &|_| Some(0.into()))?;
+        serde_json::from_value::<Effect>(check).map_err(|err| err.to_string())?;
+        Ok(Self(json))
+    }
+
+    /// Builds the effect with the animations of the run, fails on an unknown animation name.
+    pub fn resolve(&self, animations: &Animations) -> Result<Effect, String> {
+        let mut json = self.0.clone();
+        resolve_animations(&mut json, &|name| {
+            animations
+                .named(name)
+                .map(|id| serde_json::to_value(id).unwrap())
+        })?;
+        serde_json::from_value(json).map_err(|err| err.to_string())
+    }
+}
+
+/// Replaces the animation names of the projectiles with the ids `find` returns.
+fn resolve_animations(
+    json: &mut serde_json::Value,
+    find: &impl Fn(&str) -> Option<serde_json::Value>,
+) -> Result<(), String> {
+    use serde_json::Value;
+    match json {
+        Value::Object(object) => {
+            for value in object.values_mut() {
+                resolve_animations(value, find)?;
+            }
+            if let Some(Value::Object(projectile)) = object.get_mut("Projectile") {
+                let id = match projectile.get("animation") {
+                    Some(Value::String(name)) => {
+                        find(name).ok_or_else(|| format!("unknown animation {:?}", name))?
+                    }
+                    _ => return Err("projectile animation must be a name".to_owned()),
+                };
+                projectile.insert("animation".to_owned(), id);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                resolve_animations(value, find)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// A unit template of a mod pack, the fields left out are taken from the base template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModTemplate {
+    /// Name of the built-in template, see [UnitTemplates::get].
+    pub base: String,
+    #[serde(default)]
+    pub max_hp: Option<Hp>,
+    #[serde(default)]
+    pub speed: Option<Coord>,
+    #[serde(default)]
+    pub acceleration: Option<Coord>,
+    #[serde(default)]
+    pub collider: Option<model::Collider>,
+    #[serde(default)]
+    pub idle: Option<ModAnimation>,
+    #[serde(default)]
+    pub walk: Option<ModAnimation>,
+    /// Played by the action, the effects of its frames are the attack.
+    #[serde(default)]
+    pub attack: Option<ModAnimation>,
+    /// Name of the effect performed when the unit dies.
+    #[serde(default)]
+    pub on_death: Option<String>,
+}
+
+/// An animation made of the sprites of the mods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModAnimation {
+    /// Names of the sprites of the frames.
+    pub frames: Vec<String>,
+    /// Size of a pixel of the sprites in the world.
+    pub sprite_scale: f32,
+    /// Duration of the whole animation in seconds.
+    pub cycle_time: Time,
+    /// Names of the effects started at the frames, by the frame index.
+    #[serde(default)]
+    pub effects: HashMap<usize, String>,
+}
+
+impl ModTemplate {
+    /// The sprites and effects this template refers to.
+    fn references(&self) -> (Vec<&String>, Vec<&String>) {
+        let animations = self.animations();
+        let sprites = animations
+            .clone()
+            .flat_map(|animation| &animation.frames)
+            .collect();
+        let effects = animations
+            .flat_map(|animation| animation.effects.values())
+            .chain(&self.on_death)
+            .collect();
+        (sprites, effects)
+    }
+
+    fn animations(&self) -> impl Iterator<Item = &ModAnimation> + Clone {
+        [&self.idle, &self.walk, &self.attack].into_iter().flatten()
+    }
+
+    /// Builds the template on top of the base one, the references are checked by [apply].
+    pub fn build(
+        &self,
+        name: &'static str,
+        base: &UnitTemplate,
+        mods: &ModAssets,
+        animations: &mut Animations,
+    ) -> UnitTemplate {
+        let mut template = base.clone();
+        template.name = name;
+        if let Some(max_hp) = self.max_hp {
+            template.health = Health::new(max_hp);
+        }
+        if let Some(speed) = self.speed {
+            template.speed = speed;
+        }
+        if let Some(acceleration) = self.acceleration {
+            template.acceleration = acceleration;
+        }
+        if let Some(collider) = &self.collider {
+            template.collider = collider.clone();
+        }
+        if let Some(idle) = &self.idle {
+            template.idle_animation = idle.build(mods, animations);
+        }
+        if let Some(walk) = &self.walk {
+            template.move_animation = walk.build(mods, animations);
+        }
+        if let Some(attack) = &self.attack {
+            template.action.animation = attack.build(mods, animations);
+        }
+        // The unknown animations of the effects are reported by the validation
+        if let Some(Ok(effect)) = self
+            .on_death
+            .as_ref()
+            .and_then(|name| mods.effects.get(name))
+            .map(|effect| effect.resolve(animations))
+        {
+            template.on_death = effect;
+        }
+        template
+    }
+}
+
+impl ModAnimation {
+    fn build(&self, mods: &ModAssets, animations: &mut Animations) -> AnimationId {
+        let textures: Vec<PixelTexture> = self
+            .frames
+            .iter()
+            .filter_map(|name| mods.sprites.get(name).cloned())
+            .collect();
+        let effects = self
+            .effects
+            .iter()
+            .filter_map(|(&frame, name)| {
+                Some((frame, mods.effects.get(name)?.resolve(animations).ok()?))
+            })
+            .collect();
+        animations.insert(Animation::from_textures(
+            &textures,
+            self.sprite_scale,
+            self.cycle_time,
+            effects,
+        ))
+    }
+}
+
+/// Scans the `mods` directory next to the executable for mod packs.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(geng: &Geng) -> Vec<ModPack> {
+    let path = run_dir().join("mods");
+    let entries = match std::fs::read_dir(&path) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut packs: Vec<ModPack> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .map(|path| load_pack(geng, &path))
+        .collect();
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    packs
+}
+
+/// Mods are not supported on the web.
+#[cfg(target_arch = "wasm32")]
+pub fn load(_geng: &Geng) -> Vec<ModPack> {
+    vec![]
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_pack(geng: &Geng, path: &Path) -> ModPack {
+    let mut pack = ModPack {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        ..default()
+    };
+    let problems = &mut pack.problems;
+    pack.scripts = read_files(&path.join("scripts"), "rhai", problems);
+    pack.cutscenes = read_files(&path.join("cutscenes"), "txt", problems)
+        .into_iter()
+        .collect();
+    pack.effects = read_files(&path.join("effects"), "json", problems);
+    pack.units = read_files(&path.join("units"), "json", problems);
+    pack.levels = read_files(&path.join("levels"), "json", problems);
+    for (name, path) in list_files(&path.join("sprites"), "png") {
+        // The mods are loaded before the menu, the same as the rest of the assets
+        let texture = <PixelTexture as geng::LoadAsset>::load(geng, &path);
+        match futures::executor::block_on(texture) {
+            Ok(texture) => pack.sprites.push((name, texture)),
+            Err(err) => problems.push(format!("{:?}: failed to load: {}", path, err)),
+        }
+    }
+    pack
+}
+
+/// Lists the files with the extension in the directory, along with their names without it.
+#[cfg(not(target_arch = "wasm32"))]
+fn list_files(path: &Path, extension: &str) -> Vec<(String, std::path::PathBuf)> {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == extension))
+        .filter_map(|path| Some((path.file_stem()?.to_string_lossy().into_owned(), path)))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Reads all files with the extension in the directory, returns their names without the extension.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_files(path: &Path, extension: &str, problems: &mut Vec<String>) -> Vec<(String, String)> {
+    list_files(path, extension)
+        .into_iter()
+        .filter_map(|(name, path)| match std::fs::read_to_string(&path) {
+            Ok(source) => Some((name, source)),
+            Err(err) => {
+                problems.push(format!("{:?}: failed to read: {}", path, err));
+                None
+            }
+        })
+        .collect()
+}
+
+/// Claims the name for the pack, unless the base game or an earlier pack already has it.
+fn claim(
+    owners: &mut HashMap<String, String>,
+    kind: &str,
+    name: &str,
+    pack: &str,
+) -> Result<(), String> {
+    match owners.get(name) {
+        Some(owner) => Err(format!(
+            "{}: {} {:?} is already defined by {}",
+            pack, kind, name, owner
+        )),
+        None => {
+            owners.insert(name.to_owned(), pack.to_owned());
+            Ok(())
+        }
+    }
+}
+
+/// Merges the mod packs into the assets and stores the problems that were found in them.
+/// Broken or conflicting parts of a pack are skipped, the packs are applied in
+/// alphabetical order and may use the sprites and effects of the packs before them.
+pub fn apply(assets: &mut Assets, packs: Vec<ModPack>) {
+    let engine = rhai::Engine::new();
+    let mut problems = vec![];
+    let base_game = || "the base game".to_owned();
+    let mut functions: HashMap<String, String> = match engine.compile(&assets.scripts.effects) {
+        Ok(ast) => ast
+            .iter_functions()
+            .map(|function| (function.name.to_owned(), base_game()))
+            .collect(),
+        Err(_) => default(),
+    };
+    let mut cutscenes: HashMap<String, String> = HashMap::new();
+    let mut sprites = HashMap::new();
+    let mut effects = HashMap::new();
+    let mut templates: HashMap<String, String> = UnitTemplates::NAMES
+        .iter()
+        .map(|&name| (name.to_owned(), base_game()))
+        .collect();
+    let mut levels = HashMap::new();
+    let mods = &mut assets.mods;
+
+    for pack in packs {
+        info!("Loading mod {:?}", pack.name);
+        problems.extend(pack.problems);
+        for (file, source) in &pack.scripts {
+            let ast = match engine.compile(source) {
+                Ok(ast) => ast,
+                Err(err) => {
+                    problems.push(format!(
+                        "{}/{}: failed to compile: {}",
+                        pack.name, file, err
+                    ));
+                    continue;
+                }
+            };
+            let names: Vec<String> = ast
+                .iter_functions()
+                .map(|function| function.name.to_owned())
+                .collect();
+            if let Some((name, owner)) = names
+                .iter()
+                .find_map(|name| Some((name, functions.get(name)?)))
+            {
+                problems.push(format!(
+                    "{}/{}: function {:?} is already defined by {}",
+                    pack.name, file, name, owner
+                ));
+                continue;
+            }
+            for name in names {
+                functions.insert(name, pack.name.clone());
+            }
+            assets.scripts.effects.push('\n');
+            assets.scripts.effects.push_str(source);
+        }
+
+        for (name, source) in &pack.cutscenes {
+            let target = match name.as_str() {
+                "intro" => &mut assets.cutscenes.intro,
+                "boss" => &mut assets.cutscenes.boss,
+                _ => {
+                    problems.push(format!("{}: unknown cutscene {:?}", pack.name, name));
+                    continue;
+                }
+            };
+            if let Some(owner) = cutscenes.get(name) {
+                problems.push(format!(
+                    "{}: cutscene {:?} is already replaced by {}",
+                    pack.name, name, owner
+                ));
+                continue;
+            }
+            cutscenes.insert(name.clone(), pack.name.clone());
+            *target = source.clone();
+        }
+
+        for (name, texture) in pack.sprites {
+            match claim(&mut sprites, "sprite", &name, &pack.name) {
+                Ok(()) => {
+                    mods.sprites.insert(name, texture);
+                }
+                Err(problem) => problems.push(problem),
+            }
+        }
+
+        for (name, source) in &pack.effects {
+            let effect = match ModEffect::parse(source) {
+                Ok(effect) => effect,
+                Err(err) => {
+                    problems.push(format!("{}/effects/{}: {}", pack.name, name, err));
+                    continue;
+                }
+            };
+            match claim(&mut effects, "effect", name, &pack.name) {
+                Ok(()) => {
+                    mods.effects.insert(name.clone(), effect);
+                }
+                Err(problem) => problems.push(problem),
+            }
+        }
+
+        for (name, source) in &pack.units {
+            let template: ModTemplate = match serde_json::from_str(source) {
+                Ok(template) => template,
+                Err(err) => {
+                    problems.push(format!("{}/units/{}: {}", pack.name, name, err));
+                    continue;
+                }
+            };
+            let (used_sprites, used_effects) = template.references();
+            let missing = if !UnitTemplates::NAMES.contains(&template.base.as_str()) {
+                Some(format!("unknown base template {:?}", template.base))
+            } else if let Some(sprite) = used_sprites
+                .into_iter()
+                .find(|&name| !mods.sprites.contains_key(name))
+            {
+                Some(format!("unknown sprite {:?}", sprite))
+            } else if let Some(effect) = used_effects
+                .into_iter()
+                .find(|&name| !mods.effects.contains_key(name))
+            {
+                Some(format!("unknown effect {:?}", effect))
+            } else {
+                template
+                    .animations()
+                    .any(|animation| animation.frames.is_empty())
+                    .then(|| "animation has no frames".to_owned())
+            };
+            if let Some(missing) = missing {
+                problems.push(format!("{}/units/{}: {}", pack.name, name, missing));
+                continue;
+            }
+            match claim(&mut templates, "unit", name, &pack.name) {
+                Ok(()) => {
+                    let name: &'static str = Box::leak(name.clone().into_boxed_str());
+                    mods.templates.push((name, template));
+                }
+                Err(problem) => problems.push(problem),
+            }
+        }
+
+        for (name, source) in &pack.levels {
+            let level: Level = match serde_json::from_str(source) {
+                Ok(level) => level,
+                Err(err) => {
+                    problems.push(format!("{}/levels/{}: {}", pack.name, name, err));
+                    continue;
+                }
+            };
+            // The waves leave out the unknown units, the level is still playable
+            for spawn in level.waves.iter().flat_map(|wave| &wave.spawns) {
+                if !templates.contains_key(&spawn.template) {
+                    problems.push(format!(
+                        "{}/levels/{}: unknown unit {:?}",
+                        pack.name, name, spawn.template
+                    ));
+                }
+            }
+            match claim(&mut levels, "level", name, &pack.name) {
+                Ok(()) => mods.levels.push((name.clone(), level)),
+                Err(problem) => problems.push(problem),
+            }
+        }
+    }
+
+    for problem in &problems {
+        warn!("Mod problem: {}", problem);
+    }
+    mods.problems = problems;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A projectile like the one of the artillery, launched by the effect.
+    fn projectile(animation: serde_json::Value) -> String {
+        serde_json::json!({
+            "List": {
+                "effects": [{
+                    "Projectile": {
+                        "offset": { "x": 0.0, "y": 0.5 },
+                        "attachment": null,
+                        "ai": "Idle",
+                        "collider": { "Aabb": { "size": { "x": 0.5, "y": 0.5 } } },
+                        "speed": 20.0,
+                        "spread": 0.0,
+                        "pellets": 1,
+                        "fan": 0.0,
+                        "burst": null,
+                        "max_range": null,
+                        "arming_distance": 0.0,
+                        "falloff": null,
+                        "prediction": "Full",
+                        "missing": "Skip",
+                        "on_hit": "Noop",
+                        "on_expire": "Noop",
+                        "animation": animation,
+                        "intercepts": false,
+                        "lifetime": null
+                    }
+                }]
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn effects_refer_to_animations_by_name() {
+        assert!(ModEffect::parse(&projectile(2.into())).is_err());
+        let effect = ModEffect::parse(&projectile("artillery projectile".into())).unwrap();
+
+        let mut animations = Animations::new();
+        assert!(effect.resolve(&animations).is_err());
+        let id = animations
+            .get_or_insert_with("artillery projectile", || Animation { keyframes: vec![] });
+        let resolved = effect.resolve(&animations).unwrap();
+        assert_eq!(resolved.find_projectile().unwrap().animation, id);
+    }
+
+    #[test]
+    fn names_are_claimed_by_the_first_pack() {
+        let mut owners = HashMap::new();
+        assert!(claim(&mut owners, "unit", "brute", "first").is_ok());
+        assert!(claim(&mut owners, "unit", "other", "second").is_ok());
+        let problem = claim(&mut owners, "unit", "brute", "second").unwrap_err();
+        assert_eq!(
+            problem,
+            "second: unit \"brute\" is already defined by first"
+        );
+        assert_eq!(owners["brute"], "first");
+    }
+}
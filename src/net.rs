@@ -0,0 +1,191 @@
+use super::*;
+
+use game::PlayerEvent;
+use model::{Level, RunConfig};
+
+/// The port the host listens on.
+pub const DEFAULT_PORT: u16 = 46147;
+
+/// A message between the two players of a co-op run, sent as a line of JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetMessage {
+    /// Sent by the host once the guest connects, both start the same run.
+    Start { config: RunConfig, level: Level },
+    /// The events of the player to apply at the tick.
+    Input { tick: u64, events: Vec<PlayerEvent> },
+    /// Hash of the simulation at the start of the tick, to tell whether the runs diverged.
+    Digest { tick: u64, digest: u64 },
+}
+
+/// Waits for the other player to connect.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Host {
+    listener: std::net::TcpListener,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Host {
+    pub fn bind(port: u16) -> Result<Self, String> {
+        let listener = std::net::TcpListener::bind(("0.0.0.0", port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+            .map_err(|error| format!("Failed to listen on port {}: {}", port, error))?;
+        Ok(Self { listener })
+    }
+
+    /// Takes the connection of the other player, if they have connected since the last call.
+    pub fn accept(&mut self) -> Result<Option<Peer>, String> {
+        match self.listener.accept() {
+            Ok((stream, _)) => Peer::new(stream).map(Some),
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => Ok(None),
+            Err(error) => Err(format!("Failed to accept the connection: {}", error)),
+        }
+    }
+}
+
+/// The connection to the other player. Nothing blocks once connected,
+/// the messages are buffered until the socket takes them.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Peer {
+    stream: std::net::TcpStream,
+    /// Received bytes of the lines not complete yet.
+    incoming: Vec<u8>,
+    /// Bytes of the sent messages the socket did not take yet.
+    outgoing: Vec<u8>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Peer {
+    /// Connects to the host, `address` is a host name or an ip with an optional port.
+    /// Blocks for a few seconds at most.
+    pub fn connect(address: &str) -> Result<Peer, String> {
+        use std::net::ToSocketAddrs;
+        let address = if address.contains(':') {
+            address.to_owned()
+        } else {
+            format!("{}:{}", address, DEFAULT_PORT)
+        };
+        let failed = |error: std::io::Error| format!("Failed to connect to {}: {}", address, error);
+        let socket = address
+            .to_socket_addrs()
+            .map_err(failed)?
+            .next()
+            .ok_or_else(|| format!("Unknown address {}", address))?;
+        let stream =
+            std::net::TcpStream::connect_timeout(&socket, std::time::Duration::from_secs(3))
+                .map_err(failed)?;
+        Self::new(stream)
+    }
+
+    fn new(stream: std::net::TcpStream) -> Result<Self, String> {
+        stream
+            .set_nonblocking(true)
+            .and_then(|()| stream.set_nodelay(true))
+            .map_err(|error| format!("Failed to set up the connection: {}", error))?;
+        Ok(Self {
+            stream,
+            incoming: vec![],
+            outgoing: vec![],
+        })
+    }
+
+    pub fn send(&mut self, message: &NetMessage) -> Result<(), String> {
+        serde_json::to_writer(&mut self.outgoing, message).map_err(|error| error.to_string())?;
+        self.outgoing.push(b'\n');
+        self.flush()
+    }
+
+    /// The messages received since the last call, fails once the other player is gone.
+    pub fn receive(&mut self) -> Result<Vec<NetMessage>, String> {
+        self.read()?;
+        let mut messages = vec![];
+        while let Some(message) = self.parse()? {
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+
+    /// The first message received since the last call, the rest stay for the next calls.
+    pub fn receive_next(&mut self) -> Result<Option<NetMessage>, String> {
+        self.read()?;
+        self.parse()
+    }
+
+    fn read(&mut self) -> Result<(), String> {
+        use std::io::Read;
+        self.flush()?;
+        let mut chunk = [0; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err("The other player left".to_owned()),
+                Ok(read) => self.incoming.extend_from_slice(&chunk[..read]),
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+                Err(error) => return Err(format!("Lost the connection: {}", error)),
+            }
+        }
+    }
+
+    /// Takes the first complete line of the received bytes.
+    fn parse(&mut self) -> Result<Option<NetMessage>, String> {
+        let end = match self.incoming.iter().position(|&byte| byte == b'\n') {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+        let line: Vec<u8> = self.incoming.drain(..=end).collect();
+        serde_json::from_slice(&line)
+            .map(Some)
+            .map_err(|error| format!("Invalid message from the other player: {}", error))
+    }
+
+    /// Writes as much of the sent messages as the socket takes.
+    fn flush(&mut self) -> Result<(), String> {
+        use std::io::Write;
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err("The other player left".to_owned()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(format!("Lost the connection: {}", error)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// There are no sockets on the web.
+#[cfg(target_arch = "wasm32")]
+pub struct Host;
+
+#[cfg(target_arch = "wasm32")]
+impl Host {
+    pub fn bind(_port: u16) -> Result<Self, String> {
+        Err("Co-op is not supported on the web".to_owned())
+    }
+
+    pub fn accept(&mut self) -> Result<Option<Peer>, String> {
+        Ok(None)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct Peer;
+
+#[cfg(target_arch = "wasm32")]
+impl Peer {
+    pub fn connect(_address: &str) -> Result<Peer, String> {
+        Err("Co-op is not supported on the web".to_owned())
+    }
+
+    pub fn send(&mut self, _message: &NetMessage) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn receive(&mut self) -> Result<Vec<NetMessage>, String> {
+        Ok(vec![])
+    }
+
+    pub fn receive_next(&mut self) -> Result<Option<NetMessage>, String> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,137 @@
+use super::*;
+
+use model::PingKind;
+
+const PREFERENCES_KEY: &str = "colors";
+
+/// Which palette is used for the colors that carry meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorMode {
+    Normal,
+    /// Safe for deuteranopia and protanopia, uses blue and orange instead of green and red.
+    RedGreen,
+    /// Safe for tritanopia, uses red and cyan instead of blue and yellow.
+    BlueYellow,
+    /// The palette from the preferences.
+    Custom,
+}
+
+impl Default for ColorMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl ColorMode {
+    pub const ALL: [Self; 4] = [Self::Normal, Self::RedGreen, Self::BlueYellow, Self::Custom];
+
+    /// The locale key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Normal => "colors.normal",
+            Self::RedGreen => "colors.red_green",
+            Self::BlueYellow => "colors.blue_yellow",
+            Self::Custom => "colors.custom",
+        }
+    }
+}
+
+/// The colors that tell the teams, telegraphs and pickups apart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Palette {
+    /// Health bars of the mechs.
+    pub mech: Rgba<f32>,
+    /// Health bars of the enemies and bosses.
+    pub enemy: Rgba<f32>,
+    /// Aim lines, danger pings and enemy markers.
+    pub danger: Rgba<f32>,
+    pub go_here: Rgba<f32>,
+    pub pickup: Rgba<f32>,
+    pub lava: Rgba<f32>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
+impl Palette {
+    fn normal() -> Self {
+        Self {
+            mech: hex("#28f000"),
+            enemy: hex("#ac3232"),
+            danger: Rgba::new(1.0, 0.3, 0.3, 1.0),
+            go_here: Rgba::new(0.3, 1.0, 0.4, 1.0),
+            pickup: Rgba::new(1.0, 0.9, 0.3, 1.0),
+            lava: Rgba::new(1.0, 0.35, 0.1, 1.0),
+        }
+    }
+
+    fn red_green() -> Self {
+        Self {
+            mech: hex("#56b4e9"),
+            enemy: hex("#d55e00"),
+            danger: hex("#e69f00"),
+            go_here: hex("#56b4e9"),
+            pickup: hex("#f0e442"),
+            lava: hex("#d55e00"),
+        }
+    }
+
+    fn blue_yellow() -> Self {
+        Self {
+            mech: hex("#40d0d0"),
+            enemy: hex("#d62020"),
+            danger: hex("#ff4040"),
+            go_here: hex("#40d0d0"),
+            pickup: hex("#ff90c0"),
+            lava: hex("#e03030"),
+        }
+    }
+
+    pub fn ping(&self, kind: PingKind) -> Rgba<f32> {
+        match kind {
+            PingKind::Danger => self.danger,
+            PingKind::GoHere => self.go_here,
+            PingKind::PickUp => self.pickup,
+        }
+    }
+}
+
+/// The color mode chosen in the menu, saved between sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColorSettings {
+    pub mode: ColorMode,
+    /// Used in the custom mode, edited in the preferences file.
+    #[serde(default)]
+    pub custom: Palette,
+}
+
+impl ColorSettings {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    pub fn palette(&self) -> Palette {
+        match self.mode {
+            ColorMode::Normal => Palette::normal(),
+            ColorMode::RedGreen => Palette::red_green(),
+            ColorMode::BlueYellow => Palette::blue_yellow(),
+            ColorMode::Custom => self.custom.clone(),
+        }
+    }
+}
+
+fn hex(color: &str) -> Rgba<f32> {
+    Rgba::try_from(color).unwrap()
+}
+
+/// The color with its opacity replaced.
+pub fn with_alpha(color: Rgba<f32>, alpha: f32) -> Rgba<f32> {
+    Rgba { a: alpha, ..color }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+use std::collections::HashSet;
+
+const PREFERENCES_KEY: &str = "profile";
+/// Experience required for every profile level.
+const EXPERIENCE_PER_LEVEL: u64 = 1000;
+
+/// Something that is locked until its [UnlockCondition] is met.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unlock {
+    Difficulty(Difficulty),
+    /// A perk, referenced by its name.
+    Perk(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum UnlockCondition {
+    /// Kill that many enemies across all runs.
+    LifetimeKills(usize),
+    /// Reach the wave in a single run.
+    ReachWave(usize),
+    /// Kill that many bosses across all runs.
+    LifetimeBosses(usize),
+    /// Reach the profile level.
+    Level(usize),
+}
+
+impl Unlock {
+    /// Everything that can be unlocked and the condition for it.
+    pub fn all() -> Vec<(Self, UnlockCondition)> {
+        vec![
+            (
+                Self::Difficulty(Difficulty::Hard),
+                UnlockCondition::ReachWave(5),
+            ),
+            (
+                Self::Perk("Glass Cannon".to_owned()),
+                UnlockCondition::LifetimeKills(100),
+            ),
+            (
+                Self::Perk("Piercing Rounds".to_owned()),
+                UnlockCondition::LifetimeBosses(1),
+            ),
+            (Self::Perk("Shrapnel".to_owned()), UnlockCondition::Level(3)),
+        ]
+    }
+
+    pub fn name(&self, locale: &Locale) -> String {
+        match self {
+            Self::Difficulty(difficulty) => locale.format(
+                "unlock.difficulty",
+                &[("difficulty", &locale.get(difficulty.key()))],
+            ),
+            Self::Perk(name) => {
+                locale.format("unlock.perk", &[("perk", &locale.get(&perk_key(name)))])
+            }
+        }
+    }
+}
+
+/// Statistics accumulated over all runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    pub runs: usize,
+    pub kills: usize,
+    pub bosses: usize,
+    pub best_wave: usize,
+    /// Total time played in seconds.
+    pub time: f32,
+}
+
+/// Progression of the player saved across runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub experience: u64,
+    pub stats: LifetimeStats,
+    pub unlocks: HashSet<Unlock>,
+    /// Names of the enemy templates killed at least once, shown in the codex.
+    #[serde(default)]
+    pub codex: HashSet<String>,
+}
+
+impl Profile {
+    pub fn load() -> Self {
+        storage::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(PREFERENCES_KEY, self);
+    }
+
+    pub fn level(&self) -> usize {
+        (self.experience / EXPERIENCE_PER_LEVEL) as usize + 1
+    }
+
+    pub fn is_unlocked(&self, unlock: &Unlock) -> bool {
+        self.unlocks.contains(unlock) || !Unlock::all().iter().any(|(locked, _)| locked == unlock)
+    }
+
+    /// Names of the perks that can be offered in a run.
+    pub fn available_perks(&self) -> Vec<String> {
+        model::Perk::all()
+            .into_iter()
+            .map(|perk| perk.name)
+            .filter(|name| self.is_unlocked(&Unlock::Perk(name.clone())))
+            .collect()
+    }
+
+    /// Records the finished run and returns everything that got unlocked by it.
+    pub fn record_run(&mut self, model: &model::Model) -> Vec<Unlock> {
+        self.experience += model.score();
+        self.stats.runs += 1;
+        self.stats.kills += model.world.score.kills;
+        self.stats.bosses += model.world.score.bosses;
+        self.stats.best_wave = self.stats.best_wave.max(model.world.wave_number);
+        self.stats.time += model.time().as_f32();
+        self.codex.extend(
+            model
+                .world
+                .score
+                .killed_types
+                .iter()
+                .map(|name| name.to_string()),
+        );
+
+        let mut unlocked = Vec::new();
+        for (unlock, condition) in Unlock::all() {
+            if !self.unlocks.contains(&unlock) && self.check(&condition, model) {
+                self.unlocks.insert(unlock.clone());
+                unlocked.push(unlock);
+            }
+        }
+        unlocked
+    }
+
+    /// Checks the condition after the stats of the run have been recorded.
+    fn check(&self, condition: &UnlockCondition, model: &model::Model) -> bool {
+        match *condition {
+            UnlockCondition::LifetimeKills(kills) => self.stats.kills >= kills,
+            UnlockCondition::ReachWave(wave) => model.world.wave_number >= wave,
+            UnlockCondition::LifetimeBosses(bosses) => self.stats.bosses >= bosses,
+            UnlockCondition::Level(level) => self.level() >= level,
+        }
+    }
+}
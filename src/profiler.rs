@@ -0,0 +1,166 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+/// Number of frames kept for the overlay graph.
+const MAX_FRAMES: usize = 120;
+const TRACE_FILE: &str = "trace.json";
+
+/// A part of the frame timed by the profiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfilePhase {
+    Ai,
+    Movement,
+    Collision,
+    Effects,
+    Particles,
+    Render,
+}
+
+impl ProfilePhase {
+    pub const ALL: [Self; 6] = [
+        Self::Ai,
+        Self::Movement,
+        Self::Collision,
+        Self::Effects,
+        Self::Particles,
+        Self::Render,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ai => "AI",
+            Self::Movement => "Movement",
+            Self::Collision => "Collision",
+            Self::Effects => "Effects",
+            Self::Particles => "Particles",
+            Self::Render => "Render",
+        }
+    }
+}
+
+/// Time spent in every phase during a frame, in seconds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimings {
+    pub phases: [f64; ProfilePhase::ALL.len()],
+}
+
+impl FrameTimings {
+    pub fn get(&self, phase: ProfilePhase) -> f64 {
+        self.phases[phase as usize]
+    }
+}
+
+/// A timed span in the chrome tracing format.
+struct TraceEvent {
+    phase: ProfilePhase,
+    start: f64,
+    duration: f64,
+}
+
+/// Measures the time spent in the phases of the update loop and the rendering.
+pub struct Profiler {
+    timer: Timer,
+    /// Timings of the last frames, the latest last.
+    pub frames: VecDeque<FrameTimings>,
+    current: FrameTimings,
+    /// The spans recorded since the trace was started.
+    trace: Option<Vec<TraceEvent>>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(),
+            frames: VecDeque::new(),
+            current: default(),
+            trace: None,
+        }
+    }
+}
+
+impl Profiler {
+    /// Returns the start time of a span to pass to [Profiler::record].
+    pub fn start(&self) -> f64 {
+        self.timer.elapsed()
+    }
+
+    /// Adds the time passed since the start to the phase of the current frame.
+    pub fn record(&mut self, phase: ProfilePhase, start: f64) {
+        let duration = self.timer.elapsed() - start;
+        self.current.phases[phase as usize] += duration;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEvent {
+                phase,
+                start,
+                duration,
+            });
+        }
+    }
+
+    /// Finishes the current frame and starts the next one.
+    pub fn finish_frame(&mut self) {
+        self.frames.push_back(std::mem::take(&mut self.current));
+        if self.frames.len() > MAX_FRAMES {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Average timings over the kept frames.
+    pub fn average(&self) -> FrameTimings {
+        let mut average = FrameTimings::default();
+        if self.frames.is_empty() {
+            return average;
+        }
+        for frame in &self.frames {
+            for (total, time) in average.phases.iter_mut().zip(frame.phases) {
+                *total += time;
+            }
+        }
+        for total in &mut average.phases {
+            *total /= self.frames.len() as f64;
+        }
+        average
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    pub fn start_trace(&mut self) {
+        self.trace = Some(vec![]);
+    }
+
+    /// Stops recording the trace and saves it in the chrome tracing format,
+    /// returns the path it was saved to.
+    pub fn stop_trace(&mut self) -> Result<String, String> {
+        let trace = self.trace.take().ok_or("The trace is not recording")?;
+        let events: Vec<String> = trace
+            .iter()
+            .map(|event| {
+                format!(
+                    r#"{{"name":"{}","ph":"X","ts":{:.1},"dur":{:.1},"pid":0,"tid":0}}"#,
+                    event.phase.name(),
+                    event.start * 1e6,
+                    event.duration * 1e6
+                )
+            })
+            .collect();
+        let json = format!(r#"{{"traceEvents":[{}]}}"#, events.join(","));
+        save_trace(&json)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_trace(json: &str) -> Result<String, String> {
+    let path = run_dir().join(TRACE_FILE);
+    std::fs::write(&path, json).map_err(|error| format!("Failed to save the trace: {}", error))?;
+    Ok(path.display().to_string())
+}
+
+/// There is no file system on the web, so the trace is printed to the log instead.
+#[cfg(target_arch = "wasm32")]
+fn save_trace(json: &str) -> Result<String, String> {
+    info!("{}: {}", TRACE_FILE, json);
+    Ok("the log".to_owned())
+}
@@ -1,17 +1,40 @@
 use std::collections::VecDeque;
 
-use crate::game::{PlayerEvent, MechType};
+use crate::game::{MechType, PlayerEvent};
 
 use super::*;
 use geng::{Camera2d, Draw2d};
 
 use model::*;
 
+mod aim;
 mod background;
+mod co_op;
+mod combat_log;
+mod combo;
+mod console;
+mod debug;
+mod effect_tree;
+mod indicators;
+mod inspector;
+mod juice;
+mod layout;
+mod menu;
+mod outline;
+mod perks;
+mod profiler;
 mod repeating;
+mod sandbox;
+mod touch;
+mod tutorial;
+mod weather;
 
 use background::*;
+use juice::*;
+use layout::*;
 use repeating::*;
+use touch::*;
+use weather::*;
 
 const FOV: f32 = 20.0;
 const NORMAL_COLOR: Rgba<f32> = Rgba {
@@ -26,22 +49,130 @@ const HOVERED_COLOR: Rgba<f32> = Rgba {
     b: 0.5,
     a: 1.0,
 };
+const SHIELD_COLOR: Rgba<f32> = Rgba {
+    r: 0.17,
+    g: 0.85,
+    b: 1.0,
+    a: 0.8,
+};
+const ELITE_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.8,
+    b: 0.2,
+    a: 1.0,
+};
+/// Opacity of the aim previews and frame.
+const AIM_ALPHA: f32 = 0.6;
+const AMMO_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.9,
+    b: 0.6,
+    a: 1.0,
+};
+const ROPE_COLOR: Rgba<f32> = Rgba {
+    r: 0.6,
+    g: 0.5,
+    b: 0.4,
+    a: 1.0,
+};
+const PORTAL_COLOR: Rgba<f32> = Rgba {
+    r: 0.6,
+    g: 0.2,
+    b: 0.9,
+    a: 0.8,
+};
+const BURNING_ZONE_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.45,
+    b: 0.1,
+    a: 0.7,
+};
+const POISON_ZONE_COLOR: Rgba<f32> = Rgba {
+    r: 0.45,
+    g: 0.8,
+    b: 0.2,
+    a: 0.6,
+};
+const HEALING_ZONE_COLOR: Rgba<f32> = Rgba {
+    r: 0.3,
+    g: 1.0,
+    b: 0.6,
+    a: 0.5,
+};
+/// Darkens the world at midnight, weaker as the day comes.
+const NIGHT_TINT: Rgba<f32> = Rgba {
+    r: 0.05,
+    g: 0.05,
+    b: 0.2,
+    a: 0.55,
+};
+/// Thickness of the zone patches on the ground.
+const ZONE_HEIGHT: f32 = 0.3;
+/// Share of the duration over which the zones fade out at the end.
+const ZONE_FADE: f32 = 0.25;
+const DROP_POD_COLOR: Rgba<f32> = Rgba {
+    r: 0.4,
+    g: 0.4,
+    b: 0.45,
+    a: 1.0,
+};
+/// Height above the spawn position the drop pods fall from.
+const DROP_HEIGHT: f32 = 15.0;
+const CRACK_COLOR: Rgba<f32> = Rgba {
+    r: 0.15,
+    g: 0.1,
+    b: 0.08,
+    a: 0.85,
+};
+/// The cracks drawn over the broken armor, relative to the sprite's size.
+const CRACKS: [[(f32, f32); 4]; 3] = [
+    [(-0.3, 0.35), (-0.15, 0.1), (-0.25, -0.05), (-0.05, -0.3)],
+    [(0.1, 0.4), (0.2, 0.15), (0.05, 0.0), (0.25, -0.2)],
+    [(-0.15, 0.1), (0.05, 0.0), (0.0, -0.15), (0.15, -0.35)],
+];
+/// Length of the spark's streak as the time it takes the spark to fly it.
+const SPARK_STREAK: f32 = 0.03;
+/// Extra space around the buttons to make them easier to hit with a finger.
+const TOUCH_PADDING: f32 = 20.0;
+const PAUSE_BUTTON_SIZE: f32 = 64.0;
 
 #[allow(dead_code)]
 pub struct Render {
     geng: Geng,
     assets: Rc<Assets>,
     camera: Camera2d,
+    framebuffer_size: Vec2<f32>,
     background: Background,
+    palette: Palette,
+    locale: Locale,
+    juice: Juice,
+    juice_policy: JuicePolicy,
+    display: DisplaySettings,
+    /// Where the HUD is placed on the current screen.
+    layout: HudLayout,
+    /// The world is drawn here first when it is drawn at a lower resolution.
+    world_frame: Option<ugli::Texture>,
     last_cam_pos: Coord,
-    pub visualize_hitboxes: bool,
-    artillery_slot: AABB<f32>,
-    tank_slot: AABB<f32>,
-    healer_slot: AABB<f32>,
+    /// Whether to draw the debug overlay.
+    pub debug_overlay: bool,
+    pub show_minimap: bool,
+    pub show_profiler: bool,
+    pub show_combat_log: bool,
+    /// Set once a touch event is received, enables on-screen controls.
+    pub touch_mode: bool,
+    pub touch: TouchControls,
+    /// Slots of the mechs available to the commander.
+    mech_slots: Vec<(MechType, AABB<f32>)>,
+    pause_button: AABB<f32>,
+    /// Cards of the perks currently offered to the player.
+    perk_cards: Vec<AABB<f32>>,
+    weather: WeatherLayers,
+    outline_quad: ugli::VertexBuffer<outline::OutlineVertex>,
 }
 
 impl Render {
     pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let display = DisplaySettings::load();
         Self {
             geng: geng.clone(),
             assets: assets.clone(),
@@ -50,44 +181,161 @@ impl Render {
                 rotation: 0.0,
                 fov: FOV,
             },
+            framebuffer_size: vec2(1.0, 1.0),
             background: Background::new(assets),
+            palette: ColorSettings::load().palette(),
+            locale: Locale::new(&assets.menu, Language::load()),
+            juice: default(),
+            juice_policy: JuicePolicy::load(),
+            display,
+            layout: HudLayout::new(AABB::ZERO.extend_positive(vec2(1.0, 1.0)), &display),
+            world_frame: None,
             last_cam_pos: Coord::ZERO,
-            visualize_hitboxes: false,
-            artillery_slot: AABB::ZERO,
-            tank_slot: AABB::ZERO,
-            healer_slot: AABB::ZERO,
+            debug_overlay: false,
+            show_minimap: true,
+            show_profiler: false,
+            show_combat_log: false,
+            touch_mode: false,
+            touch: default(),
+            mech_slots: Vec::new(),
+            pause_button: AABB::ZERO,
+            perk_cards: Vec::new(),
+            weather: default(),
+            outline_quad: outline::outline_quad(geng),
         }
     }
 
     pub fn handle_event(&mut self, event: geng::Event) -> Vec<PlayerEvent> {
         let mut events = Vec::new();
         match event {
-            geng::Event::MouseDown { position, button: geng::MouseButton::Left } => {
-                let pos = position.map(|x| x as f32);
-                if self.artillery_slot.contains(pos) {
-                    events.push(PlayerEvent::SpawnMech(MechType::Artillery));
-                }
-                if self.tank_slot.contains(pos) {
-                    events.push(PlayerEvent::SpawnMech(MechType::Tank));
+            geng::Event::MouseDown {
+                position,
+                button: geng::MouseButton::Left,
+            } => {
+                // Browsers emulate mouse clicks after touches, so ignore them in touch mode
+                if !self.touch_mode {
+                    events.extend(self.press(position.map(|x| x as f32)));
                 }
-                if self.healer_slot.contains(pos) {
-                    events.push(PlayerEvent::SpawnMech(MechType::Healer));
+            }
+            geng::Event::TouchStart { touches } => {
+                self.touch_mode = true;
+                // The touches that start on the buttons or the stick are held down
+                self.touch.update(&touches);
+                for touch in touches {
+                    let position = touch.position.map(|x| x as f32);
+                    match self.touch.button(position) {
+                        Some(ControlAction::Reload) => events.push(PlayerEvent::Reload),
+                        Some(ControlAction::Interact) => events.push(PlayerEvent::Interact),
+                        Some(_) => {}
+                        None if self.touch.on_stick(position) => {}
+                        None => events.extend(self.press(position)),
+                    }
                 }
             }
+            geng::Event::TouchMove { touches } => self.touch.update(&touches),
+            geng::Event::TouchEnd { .. } => self.touch.release(),
             _ => {}
         }
         events
     }
 
+    /// Returns the event caused by pressing at the given screen position.
+    fn press(&self, pos: Vec2<f32>) -> Option<PlayerEvent> {
+        let padding = if self.touch_mode { TOUCH_PADDING } else { 0.0 };
+        let hit = |slot: AABB<f32>| slot.extend_uniform(padding).contains(pos);
+        if !self.perk_cards.is_empty() {
+            // Only the perk choice is available while it is shown
+            return self
+                .perk_cards
+                .iter()
+                .position(|&card| hit(card))
+                .map(PlayerEvent::ChoosePerk);
+        }
+        self.mech_slots
+            .iter()
+            .find(|&&(_, slot)| hit(slot))
+            .map(|&(mech, _)| PlayerEvent::SpawnMech(mech))
+    }
+
+    pub fn pause_button_contains(&self, pos: Vec2<f32>) -> bool {
+        self.touch_mode
+            && self
+                .pause_button
+                .extend_uniform(TOUCH_PADDING)
+                .contains(pos)
+    }
+
+    pub fn screen_to_world(&self, position: Vec2<f32>) -> Position {
+        self.camera
+            .screen_to_world(self.framebuffer_size, position)
+            .map(Coord::new)
+    }
+
     pub fn draw(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        self.framebuffer_size = framebuffer_size;
+        self.layout = HudLayout::new(AABB::ZERO.extend_positive(framebuffer_size), &self.display);
         let camera_width = self.camera.fov * framebuffer_size.x / framebuffer_size.y;
-        self.camera.center.x = model.left_border.as_f32() + camera_width / 2.0;
+        let cutscene_offset = model
+            .world
+            .cutscene
+            .as_ref()
+            .map_or(0.0, |cutscene| cutscene.camera_offset.as_f32());
+        self.camera.center.x =
+            model.world.left_border.as_f32() + camera_width / 2.0 + cutscene_offset;
 
         self.background.update(
             Coord::new(camera_width),
             Coord::new(self.camera.center.x) - self.last_cam_pos,
         );
+        self.last_cam_pos = Coord::new(self.camera.center.x);
+        self.juice.update(model);
+        // The shake is applied after the background has moved with the camera
+        self.camera.center =
+            vec2(self.camera.center.x, 0.0) + self.juice.shake_offset(&self.juice_policy);
+        // Never show the void outside the world
+        let world = model.world_area().map(|x| x.as_f32());
+        let half_view = vec2(camera_width, self.camera.fov) / 2.0;
+        // A world smaller than the view keeps it pinned to the left border and the kill plane
+        self.camera.center.x =
+            (self.camera.center.x.min(world.x_max - half_view.x)).max(world.x_min + half_view.x);
+        self.camera.center.y =
+            (self.camera.center.y.min(world.y_max - half_view.y)).max(world.y_min + half_view.y);
+
+        if self.display.render_scale < 1.0 {
+            let size = framebuffer
+                .size()
+                .map(|x| ((x as f32 * self.display.render_scale) as usize).max(1));
+            let mut frame = match self.world_frame.take() {
+                Some(frame) if frame.size() == size => frame,
+                _ => {
+                    let mut frame = ugli::Texture::new_uninitialized(self.geng.ugli(), size);
+                    // Keeps the pixel art crisp when scaled up
+                    frame.set_filter(ugli::Filter::Nearest);
+                    frame
+                }
+            };
+            {
+                let mut world = ugli::Framebuffer::new_color(
+                    self.geng.ugli(),
+                    ugli::ColorAttachment::Texture(&mut frame),
+                );
+                ugli::clear(&mut world, Some(Rgba::BLACK), None);
+                self.draw_scene(model, &mut world);
+            }
+            draw_2d::TexturedQuad::new(AABB::ZERO.extend_positive(framebuffer_size), &frame)
+                .draw_2d(&self.geng, framebuffer, &geng::PixelPerfectCamera);
+            self.world_frame = Some(frame);
+        } else {
+            self.draw_scene(model, framebuffer);
+        }
+        self.draw_weather(model, framebuffer);
+        self.draw_ui(model, framebuffer);
+        self.draw_debug_ui(model, framebuffer);
+    }
+
+    /// Draws the background and the world, everything that is drawn with the world camera.
+    fn draw_scene(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         for (pos, sprite) in self
             .background
             .background
@@ -117,7 +365,7 @@ impl Render {
         {
             draw_sprite(
                 sprite,
-                pos + vec2(model.left_border, model.ground_level),
+                pos + vec2(model.world.left_border, model.world.ground_level),
                 false,
                 0.0,
                 &self.geng,
@@ -127,79 +375,318 @@ impl Render {
         }
 
         self.draw_world(model, framebuffer);
-        self.draw_ui(model, framebuffer);
+        self.draw_lighting(model, framebuffer);
+        self.draw_debug_world(model, framebuffer);
+    }
 
-        self.last_cam_pos = Coord::new(self.camera.center.x);
+    /// Tints the world with the ambient light of the time of day.
+    fn draw_lighting(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let night = model.night().as_f32();
+        if night <= 0.0 {
+            return;
+        }
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let color = with_alpha(NIGHT_TINT, NIGHT_TINT.a * night);
+        draw_2d::Quad::new(screen, color).draw_2d(
+            &self.geng,
+            framebuffer,
+            &geng::PixelPerfectCamera,
+        );
     }
 
     fn draw_world(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         let geng = &self.geng;
         let camera = &self.camera;
 
-        // Draw units
-        for unit in &model.units {
+        // Draw decorations
+        for decoration in &model.world.decorations {
             draw_sprite(
-                unit.animation_state.get_sprite(),
-                unit.position,
-                unit.flip_sprite,
+                &decoration.sprite,
+                decoration.position,
+                false,
                 0.0,
                 geng,
                 framebuffer,
                 camera,
             );
-            if let Some(render) = &unit.extra_render {
-                match render {
-                    ExtraUnitRender::Tank {
-                        hand_pos,
-                        weapon_pos,
-                        rotation,
-                        ..
-                    } => {
-                        let mut rotation = *rotation;
-                        let mut hand = *hand_pos;
-                        if unit.flip_sprite {
-                            rotation = -rotation;
-                            hand.x = -hand.x;
-                        }
-                        draw_sprite(
-                            &Sprite {
-                                texture: self.assets.mech.tank.hand.texture(),
-                                size: vec2(2.0, 2.0 * 9.0 / 62.0),
-                            },
-                            unit.position + hand,
-                            unit.flip_sprite,
-                            rotation.as_f32(),
-                            geng,
-                            framebuffer,
-                            camera,
-                        );
-                        let mut weapon = *weapon_pos;
-                        if unit.flip_sprite {
-                            weapon.x = -weapon.x;
-                        }
-                        draw_sprite(
-                            &Sprite {
-                                texture: self.assets.mech.tank.weapon.texture(),
-                                size: vec2(1.0, 1.0 * 14.0 / 31.0),
-                            },
-                            unit.position + hand + weapon.rotate(rotation),
-                            unit.flip_sprite,
-                            rotation.as_f32(),
-                            geng,
-                            framebuffer,
-                            camera,
-                        );
-                    }
+        }
+
+        // Draw spawn telegraphs
+        for spawn in &model.world.pending_spawns {
+            let progress = spawn.progress();
+            let bounds = spawn
+                .template
+                .collider
+                .bounds(spawn.position)
+                .map(|x| x.as_f32());
+            let size = bounds.size();
+            match spawn.style.telegraph {
+                TelegraphKind::Portal => {
+                    // The rift opens up to the size of the unit
+                    let pulse = self.juice_policy.pulse(0.1, progress * 20.0);
+                    let half_size = vec2(0.1 + size.x / 2.0 * progress, size.y / 2.0 * progress)
+                        + vec2(pulse, pulse);
+                    draw_2d::Quad::new(
+                        AABB::point(bounds.center()).extend_symmetric(half_size),
+                        PORTAL_COLOR,
+                    )
+                    .draw_2d(geng, framebuffer, camera);
+                }
+                TelegraphKind::DropPod => {
+                    // The landing spot is marked while the pod is falling
+                    let marker = AABB::point(vec2(bounds.center().x, bounds.y_min))
+                        .extend_symmetric(vec2(size.x / 2.0 * progress, 0.1));
+                    draw_2d::Quad::new(marker, with_alpha(self.palette.danger, 0.8)).draw_2d(
+                        geng,
+                        framebuffer,
+                        camera,
+                    );
+                    let height = DROP_HEIGHT * (1.0 - progress * progress);
+                    let pod = AABB::point(bounds.center() + vec2(0.0, height))
+                        .extend_symmetric(size / 2.0);
+                    draw_2d::Quad::new(pod, DROP_POD_COLOR).draw_2d(geng, framebuffer, camera);
                 }
             }
         }
 
+        // Draw zones
+        for zone in &model.world.zones {
+            let color = match zone.kind {
+                ZoneKind::Burning => BURNING_ZONE_COLOR,
+                ZoneKind::Poison => POISON_ZONE_COLOR,
+                ZoneKind::Healing => HEALING_ZONE_COLOR,
+            };
+            // The fire flickers, and every zone fades out towards the end
+            let flicker = match zone.kind {
+                ZoneKind::Burning => {
+                    0.8 + self.juice_policy.pulse(0.2, zone.time_left.as_f32() * 15.0)
+                }
+                ZoneKind::Poison | ZoneKind::Healing => 1.0,
+            };
+            let alpha = flicker * zone.fade().min(ZONE_FADE) / ZONE_FADE;
+            let position = zone.position.map(|x| x.as_f32());
+            let patch = AABB::point(position)
+                .extend_symmetric(vec2(zone.area.radius.as_f32(), ZONE_HEIGHT / 2.0));
+            draw_2d::Quad::new(patch, with_alpha(color, color.a * alpha)).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+
+        // Draw units
+        for unit in &model.world.units {
+            let position = model.world.bodies.positions[unit.id];
+            let mut tint = self.juice.tint(unit.id, &self.juice_policy);
+            if let Some(progress) = unit.spawn_progress() {
+                // Fade in while spawning
+                tint.a *= progress;
+            }
+            let sprite = unit.sprite(&model.world.animations);
+            let mut outline = self.outline_color(unit, sprite, model);
+            outline.a *= tint.a;
+            self.draw_outline(sprite, position, unit.flip_sprite, outline, framebuffer);
+            draw_sprite_tinted(
+                sprite,
+                position,
+                unit.flip_sprite,
+                0.0,
+                tint,
+                geng,
+                framebuffer,
+                camera,
+            );
+            if unit.is_armor_broken() {
+                draw_cracks(
+                    unit.sprite(&model.world.animations),
+                    position,
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+            for point in &unit.attachments {
+                let (sprite, transform) =
+                    match (&point.sprite, unit.attachment_transform(&point.name)) {
+                        (Some(sprite), Some(transform)) => (sprite, transform),
+                        _ => continue,
+                    };
+                draw_sprite(
+                    sprite,
+                    position + transform.offset,
+                    unit.flip_sprite,
+                    transform.rotation.as_f32(),
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+            if let Some((sprite, transform)) = unit.weapon.as_ref().and_then(|weapon| {
+                Some((
+                    weapon.sprite.as_ref()?,
+                    unit.attachment_transform(&weapon.attachment)?,
+                ))
+            }) {
+                draw_sprite(
+                    sprite,
+                    position + transform.offset,
+                    unit.flip_sprite,
+                    transform.rotation.as_f32(),
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+        }
+
+        // Draw grappling hooks
+        for grapple in &model.world.grapples {
+            let (caster, hook) = match model
+                .world
+                .units
+                .get(&grapple.caster)
+                .zip(grapple.hook_position(model))
+            {
+                Some(value) => value,
+                None => continue,
+            };
+            let origin = grapple
+                .origin(caster, &model.world.bodies)
+                .map(|x| x.as_f32());
+            let hook = hook.map(|x| x.as_f32());
+            let chain = Chain::new(vec![origin, hook]);
+            draw_2d::Chain::new(chain, 0.05, ROPE_COLOR, 0).draw_2d(geng, framebuffer, camera);
+            draw_2d::Quad::new(AABB::point(hook).extend_uniform(0.15), ROPE_COLOR).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+
+        // Draw hazards
+        for hazard in &model.world.hazards {
+            let aabb = hazard.area.map(|x| x.as_f32());
+            let color = match hazard.kind {
+                HazardKind::Spikes { .. } => Rgba::new(0.6, 0.6, 0.6, 1.0),
+                HazardKind::Lava { .. } => with_alpha(self.palette.lava, 0.9),
+                HazardKind::Wind { .. } => Rgba::new(0.7, 0.9, 1.0, 0.15),
+            };
+            draw_2d::Quad::new(aabb, color).draw_2d(geng, framebuffer, camera);
+            if let HazardKind::Wind { force } = hazard.kind {
+                // Show the direction of the wind
+                let center = aabb.center();
+                let direction = force.map(|x| x.as_f32()).normalize_or_zero();
+                let chain = Chain::new(vec![center - direction, center + direction]);
+                draw_2d::Chain::new(chain, 0.1, Rgba::new(0.7, 0.9, 1.0, 0.6), 0).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+        }
+
+        // Draw water
+        for water in &model.world.water {
+            draw_2d::Quad::new(
+                water.area.map(|x| x.as_f32()),
+                Rgba::new(0.2, 0.4, 0.9, 0.5),
+            )
+            .draw_2d(geng, framebuffer, camera);
+        }
+
+        // Draw obstacles
+        for obstacle in &model.world.obstacles {
+            draw_sprite(
+                &obstacle.sprite,
+                obstacle.position,
+                false,
+                0.0,
+                geng,
+                framebuffer,
+                camera,
+            );
+            if obstacle.health.hp < obstacle.health.max_hp {
+                let size = obstacle.sprite.size;
+                let position = obstacle.position.map(|x| x.as_f32()) + vec2(0.0, size.y / 2.0);
+                let aabb = AABB::point(position).extend_symmetric(vec2(size.x / 2.0, 0.1));
+                draw_2d::Quad::new(aabb, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+                let mut bar = aabb;
+                bar.x_max = bar.x_min + bar.width() * obstacle.health.ratio().as_f32();
+                draw_2d::Quad::new(bar, Rgba::GRAY).draw_2d(geng, framebuffer, camera);
+            }
+        }
+
+        // Draw doors
+        for door in &model.world.doors {
+            let aabb = AABB::point(door.position)
+                .extend_symmetric(door.size / Coord::new(2.0))
+                .map(|x| x.as_f32());
+            draw_2d::Quad::new(aabb, Rgba::new(0.45, 0.35, 0.3, 1.0)).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+
+        // Draw interactables
+        for object in &model.world.interactables {
+            let color = match object.kind {
+                InteractableKind::Chest { .. } => Rgba::new(0.8, 0.6, 0.2, 1.0),
+                InteractableKind::Lever { .. } => Rgba::new(0.5, 0.5, 0.6, 1.0),
+                InteractableKind::Survivor { .. } => Rgba::new(0.3, 0.8, 0.4, 1.0),
+                InteractableKind::Vehicle { .. } => Rgba::new(0.35, 0.45, 0.3, 1.0),
+            };
+            let aabb = AABB::point(object.position).extend_symmetric(object.size / Coord::new(2.0));
+            draw_2d::Quad::new(aabb.map(|x| x.as_f32()), color).draw_2d(geng, framebuffer, camera);
+        }
+
+        // Draw pickups
+        for pickup in &model.world.pickups {
+            draw_sprite(
+                &pickup.sprite,
+                pickup.position,
+                false,
+                0.0,
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+
+        // Draw pings
+        for ping in &model.world.pings {
+            let mut color = self.palette.ping(ping.kind);
+            color.a *= ping.lifetime.as_f32().min(1.0);
+            let center = ping.position.map(|x| x.as_f32());
+            // Pulse to draw attention
+            let size = 0.5 + self.juice_policy.pulse(0.1, ping.lifetime.as_f32() * 8.0);
+            let chain = Chain::new(vec![
+                center + vec2(0.0, size),
+                center + vec2(size, 0.0),
+                center + vec2(0.0, -size),
+                center + vec2(-size, 0.0),
+                center + vec2(0.0, size),
+            ]);
+            draw_2d::Chain::new(chain, 0.1, color, 0).draw_2d(geng, framebuffer, camera);
+            let stem = Chain::new(vec![
+                center + vec2(0.0, size),
+                center + vec2(0.0, size + 1.0),
+            ]);
+            draw_2d::Chain::new(stem, 0.1, color, 0).draw_2d(geng, framebuffer, camera);
+        }
+
         // Draw projectiles
-        for projectile in &model.projectiles {
-            let rotation = projectile.velocity.arg();
+        for projectile in &model.world.projectiles {
+            let rotation = model.world.bodies.velocities[projectile.id].arg();
             draw_sprite(
-                projectile.animation_state.get_sprite(),
-                projectile.position,
+                projectile
+                    .animation_state
+                    .get_sprite(&model.world.animations),
+                model.world.bodies.positions[projectile.id],
                 false,
                 rotation.as_f32(),
                 geng,
@@ -208,11 +695,47 @@ impl Render {
             );
         }
 
+        // Draw sparks
+        for spark in &model.sparks {
+            let position = spark.position.map(|x| x.as_f32());
+            let tail = spark.velocity.map(|x| x.as_f32()) * SPARK_STREAK;
+            let chain = Chain::new(vec![position - tail, position]);
+            let color = with_alpha(spark.color, spark.fade());
+            draw_2d::Chain::new(chain, 0.08, color, 0).draw_2d(geng, framebuffer, camera);
+        }
+
+        // Draw damage numbers
+        for number in &model.damage_numbers {
+            self.locale.font(geng).draw(
+                framebuffer,
+                camera,
+                &format!("{:.1}", number.value),
+                number.position.map(|x| x.as_f32()),
+                geng::TextAlign::CENTER,
+                0.5,
+                with_alpha(number.color, number.fade()),
+            );
+        }
+
+        // Aim indicator
+        if let Some(aim) = model.aim {
+            let aim_color = with_alpha(self.palette.danger, AIM_ALPHA);
+            self.draw_aim_previews(model, aim, aim_color, framebuffer);
+            draw_aabb_frame(
+                AABB::point(aim).extend_uniform(Coord::new(0.5)),
+                Coord::new(0.1),
+                aim_color,
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+
         // Draw particles
-        for particle in &model.particles {
+        for particle in &model.world.particles {
             draw_sprite(
-                particle.animation_state.get_sprite(),
-                particle.position,
+                particle.animation_state.get_sprite(&model.world.animations),
+                model.world.bodies.positions[particle.id],
                 false,
                 0.0,
                 geng,
@@ -221,43 +744,22 @@ impl Render {
             );
         }
 
-        // Hitboxes
-        if self.visualize_hitboxes {
-            for (pos, collider) in model
-                .units
-                .iter()
-                .map(|unit| (unit.position, &unit.collider))
-                .chain(
-                    model
-                        .projectiles
-                        .iter()
-                        .map(|proj| (proj.position, &proj.collider)),
-                )
-            {
-                match collider {
-                    Collider::Aabb { size } => {
-                        draw_aabb_frame(
-                            AABB::point(pos).extend_symmetric(*size / Coord::new(2.0)),
-                            Coord::new(0.1),
-                            Rgba::GREEN,
-                            geng,
-                            framebuffer,
-                            camera,
-                        );
-                    }
-                }
-            }
-        }
-
         // Health
-        for unit in &model.units {
+        for unit in &model.world.units {
+            let health = &model.world.bodies.healths[unit.id];
             match unit.faction {
                 Faction::Mech => {
                     let sprite = Sprite::new(&self.assets.ui.mech_bar, 0.03);
-                    let position = unit.position
+                    let position = model.world.bodies.positions[unit.id]
                         + vec2(
                             0.0,
-                            (unit.animation_state.get_sprite().size.y + sprite.size.y) / 2.0,
+                            (unit
+                                .animation_state
+                                .get_sprite(&model.world.animations)
+                                .size
+                                .y
+                                + sprite.size.y)
+                                / 2.0,
                         )
                         .map(Coord::new);
                     // Health bar
@@ -270,9 +772,9 @@ impl Render {
                         },
                         sprite.size,
                         position.map(|x| x.as_f32()),
-                        unit.health.ratio().as_f32(),
+                        health.ratio().as_f32(),
                     );
-                    let color = Rgba::try_from("#28f000").unwrap();
+                    let color = self.palette.mech;
                     draw_2d::Quad::new(bar_aabb, color).draw_2d(geng, framebuffer, camera);
                     // Sanity bar
                     let bar_aabb = layout_bar(
@@ -292,13 +794,35 @@ impl Render {
                     let color = Rgba::try_from("#d77bba").unwrap();
                     draw_2d::Quad::new(bar_aabb, color).draw_2d(geng, framebuffer, camera);
                     draw_sprite(&sprite, position, false, 0.0, geng, framebuffer, camera);
+                    // Ammo
+                    if let Some(ammo) = &unit.ammo {
+                        let text = match ammo.reload_left {
+                            Some(_) => self.locale.get("hud.reloading").to_owned(),
+                            None => format!("{}/{}", ammo.loaded, ammo.capacity),
+                        };
+                        self.locale.font(geng).draw(
+                            framebuffer,
+                            camera,
+                            &text,
+                            position.map(|x| x.as_f32()) + vec2(0.0, sprite.size.y),
+                            geng::TextAlign::CENTER,
+                            0.4,
+                            AMMO_COLOR,
+                        );
+                    }
                 }
                 Faction::Alien => {
                     let sprite = Sprite::new(&self.assets.ui.enemy_health, 0.03);
-                    let position = unit.position
+                    let position = model.world.bodies.positions[unit.id]
                         + vec2(
                             0.0,
-                            (unit.animation_state.get_sprite().size.y + sprite.size.y) / 2.0,
+                            (unit
+                                .animation_state
+                                .get_sprite(&model.world.animations)
+                                .size
+                                .y
+                                + sprite.size.y)
+                                / 2.0,
                         )
                         .map(Coord::new);
                     let bar_aabb = layout_bar(
@@ -310,25 +834,95 @@ impl Render {
                         },
                         sprite.size,
                         position.map(|x| x.as_f32()),
-                        unit.health.ratio().as_f32(),
+                        health.ratio().as_f32(),
                     );
-                    let color = Rgba::try_from("#ac3232").unwrap();
+                    let color = self.palette.enemy;
                     draw_2d::Quad::new(bar_aabb, color).draw_2d(geng, framebuffer, camera);
+                    // Shield
+                    let shield = unit.shield();
+                    if shield > Hp::ZERO {
+                        let mut shield_aabb = bar_aabb;
+                        shield_aabb.x_max = shield_aabb.x_min
+                            + (shield_aabb.width() / health.ratio().as_f32().max(0.01))
+                                * (shield / health.max_hp).min(Hp::ONE).as_f32();
+                        shield_aabb.y_min = shield_aabb.center().y;
+                        draw_2d::Quad::new(shield_aabb, SHIELD_COLOR).draw_2d(
+                            geng,
+                            framebuffer,
+                            camera,
+                        );
+                    }
                     draw_sprite(&sprite, position, false, 0.0, geng, framebuffer, camera);
+                    // Elite modifiers
+                    if !unit.elites.is_empty() {
+                        let text = unit
+                            .elites
+                            .iter()
+                            .map(|modifier| self.locale.get(modifier.key()))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        self.locale.font(geng).draw(
+                            framebuffer,
+                            camera,
+                            &text,
+                            position.map(|x| x.as_f32()) + vec2(0.0, sprite.size.y),
+                            geng::TextAlign::CENTER,
+                            0.4,
+                            ELITE_COLOR,
+                        );
+                    }
                 }
             }
         }
     }
 
+    /// Shows what the nearby interactable does and the keys to use it.
+    pub fn draw_interact_prompt(
+        &self,
+        model: &Model,
+        keys: &[geng::Key],
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let object = match model.interactable_in_range() {
+            Some((object, _)) => object,
+            None => return,
+        };
+        let keys = keys
+            .iter()
+            .map(|key| format!("{:?}", key))
+            .collect::<Vec<_>>()
+            .join("/");
+        let position = object.position.map(|x| x.as_f32()) + vec2(0.0, object.size.y.as_f32());
+        self.locale.font(&self.geng).draw(
+            framebuffer,
+            &self.camera,
+            &self.locale.format(
+                "hud.interact",
+                &[
+                    ("keys", &keys),
+                    ("action", &self.locale.get(object.prompt())),
+                ],
+            ),
+            position,
+            geng::TextAlign::CENTER,
+            0.5,
+            Rgba::WHITE,
+        );
+    }
+
     fn draw_ui(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         let geng = &self.geng;
         let camera = &geng::PixelPerfectCamera;
-        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let layout = self.layout;
+        let area = layout.area;
+
+        // Under the rest of the interface, so that it stays readable
+        self.draw_low_health(model, framebuffer);
 
         // Energy
-        let energy_sprite = Sprite::new(&self.assets.ui.energy_bar, 5.0);
+        let energy_sprite = Sprite::new(&self.assets.ui.energy_bar, layout.size(5.0));
         let position = vec2(0.5 - 100.0 / 258.0, 110.0 / 158.0 - 0.5) * energy_sprite.size
-            + vec2(screen.center().x, screen.y_max);
+            + layout.at(Anchor::Top, Vec2::ZERO);
         let bar_aabb = layout_bar(
             AABB {
                 x_min: 84.0 / 258.0,
@@ -338,7 +932,7 @@ impl Render {
             },
             energy_sprite.size,
             position,
-            model.player_energy.ratio().as_f32(),
+            model.world.player_energy.ratio().as_f32(),
         );
         let color = Rgba::try_from("#2BD9FE").unwrap();
         draw_2d::Quad::new(bar_aabb, color).draw_2d(geng, framebuffer, camera);
@@ -357,47 +951,221 @@ impl Render {
         let energy_pos = position + vec2(-energy_sprite.size.x, energy_sprite.size.y) / 2.0;
         let mouse_pos = self.geng.window().mouse_pos().map(|x| x as f32);
 
-        // Artillery
-        let sprite = Sprite::new(&self.assets.ui.artillery_slot, 4.0);
-        let back = self.assets.ui.artillery_slot_bg.texture();
-        let position = vec2(150.0, 130.0) * energy_size + energy_pos;
-        let aabb = AABB::point(position).extend_symmetric(sprite.size / 2.0);
-        let color = if aabb.contains(mouse_pos) {
-            HOVERED_COLOR
-        } else {
-            NORMAL_COLOR
-        };
-        draw_2d::TexturedQuad::colored(aabb, back, color).draw_2d(geng, framebuffer, camera);
-        draw_2d::TexturedQuad::new(aabb, sprite.texture).draw_2d(geng, framebuffer, camera);
-        self.artillery_slot = aabb;
-
-        // Tank
-        let sprite = Sprite::new(&self.assets.ui.tank_slot, 4.0);
-        let back = self.assets.ui.tank_slot_bg.texture();
-        let position = vec2(175.0, 130.0) * energy_size + energy_pos;
-        let aabb = AABB::point(position).extend_symmetric(sprite.size / 2.0);
-        let color = if aabb.contains(mouse_pos) {
-            HOVERED_COLOR
-        } else {
-            NORMAL_COLOR
-        };
-        draw_2d::TexturedQuad::colored(aabb, back, color).draw_2d(geng, framebuffer, camera);
-        draw_2d::TexturedQuad::new(aabb, sprite.texture).draw_2d(geng, framebuffer, camera);
-        self.tank_slot = aabb;
-
-        // Healer
-        let sprite = Sprite::new(&self.assets.ui.healer_slot, 4.0);
-        let back = self.assets.ui.healer_slot_bg.texture();
-        let position = vec2(200.0, 130.0) * energy_size + energy_pos;
-        let aabb = AABB::point(position).extend_symmetric(sprite.size / 2.0);
-        let color = if aabb.contains(mouse_pos) {
-            HOVERED_COLOR
-        } else {
-            NORMAL_COLOR
-        };
-        draw_2d::TexturedQuad::colored(aabb, back, color).draw_2d(geng, framebuffer, camera);
-        draw_2d::TexturedQuad::new(aabb, sprite.texture).draw_2d(geng, framebuffer, camera);
-        self.healer_slot = aabb;
+        self.mech_slots.clear();
+        for (index, mech) in model.config.commander.roster().into_iter().enumerate() {
+            let (slot, back) = match mech {
+                MechType::Artillery => (
+                    &self.assets.ui.artillery_slot,
+                    &self.assets.ui.artillery_slot_bg,
+                ),
+                MechType::Tank => (&self.assets.ui.tank_slot, &self.assets.ui.tank_slot_bg),
+                MechType::Healer => (&self.assets.ui.healer_slot, &self.assets.ui.healer_slot_bg),
+            };
+            let sprite = Sprite::new(slot, layout.size(4.0));
+            let position = vec2(150.0 + index as f32 * 25.0, 130.0) * energy_size + energy_pos;
+            let aabb = AABB::point(position).extend_symmetric(sprite.size / 2.0);
+            let color = if aabb.contains(mouse_pos) {
+                HOVERED_COLOR
+            } else {
+                NORMAL_COLOR
+            };
+            draw_2d::TexturedQuad::colored(aabb, back.texture(), color).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+            draw_2d::TexturedQuad::new(aabb, slot.texture()).draw_2d(geng, framebuffer, camera);
+            self.mech_slots.push((mech, aabb));
+        }
+
+        // Pause button
+        if self.touch_mode {
+            let size = layout.size(PAUSE_BUTTON_SIZE);
+            let aabb = AABB::point(layout.at(
+                Anchor::TopRight,
+                vec2(-PAUSE_BUTTON_SIZE, -PAUSE_BUTTON_SIZE),
+            ))
+            .extend_uniform(size / 2.0);
+            draw_2d::Quad::new(aabb, Rgba::new(0.0, 0.0, 0.0, 0.5)).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+            for x in [-0.2, 0.2] {
+                let bar = AABB::point(aabb.center() + vec2(x * size, 0.0))
+                    .extend_symmetric(vec2(0.08, 0.3) * size);
+                draw_2d::Quad::new(bar, Rgba::WHITE).draw_2d(geng, framebuffer, camera);
+            }
+            self.pause_button = aabb;
+        }
+
+        // Difficulty
+        self.locale.font(geng).draw(
+            framebuffer,
+            camera,
+            &self.locale.format(
+                "hud.run",
+                &[
+                    ("mode", &self.locale.get(model.config.mode.key())),
+                    (
+                        "difficulty",
+                        &self.locale.get(model.config.difficulty.key()),
+                    ),
+                ],
+            ),
+            layout.at(Anchor::TopLeft, vec2(10.0, -30.0)),
+            geng::TextAlign::LEFT,
+            layout.size(24.0),
+            Rgba::WHITE,
+        );
+
+        // Respawns
+        if let Some(checkpoint) = &model.world.checkpoint {
+            self.locale.font(geng).draw(
+                framebuffer,
+                camera,
+                &self.locale.format(
+                    "hud.checkpoint",
+                    &[
+                        ("wave", &checkpoint.wave_number),
+                        ("respawns", &(MAX_RESPAWNS - model.world.respawns)),
+                    ],
+                ),
+                layout.at(Anchor::TopLeft, vec2(10.0, -60.0)),
+                geng::TextAlign::LEFT,
+                layout.size(20.0),
+                Rgba::WHITE,
+            );
+        }
+
+        self.draw_combo(model, framebuffer);
+
+        // Objectives
+        for (index, objective) in model.world.objectives.iter().enumerate() {
+            let unit = match model.world.units.get(&objective.unit) {
+                Some(unit) => unit,
+                None => continue,
+            };
+            let health = (model.world.bodies.healths[unit.id].ratio().as_f32() * 100.0).round();
+            let text = match objective.goal {
+                ObjectiveGoal::Survive { time_left } => self.locale.format(
+                    "hud.protect",
+                    &[("time", &time_left.as_f32().ceil()), ("health", &health)],
+                ),
+                ObjectiveGoal::Reach { x } => self.locale.format(
+                    "hud.escort",
+                    &[
+                        (
+                            "distance",
+                            &(x - model.world.bodies.positions[unit.id].x)
+                                .as_f32()
+                                .max(0.0)
+                                .ceil(),
+                        ),
+                        ("health", &health),
+                    ],
+                ),
+            };
+            self.locale.font(geng).draw(
+                framebuffer,
+                camera,
+                &text,
+                layout.at(Anchor::TopLeft, vec2(10.0, -90.0 - index as f32 * 30.0)),
+                geng::TextAlign::LEFT,
+                layout.size(20.0),
+                Rgba::new(1.0, 0.85, 0.3, 1.0),
+            );
+        }
+
+        // Boss health
+        if let Some((unit, boss)) = model
+            .world
+            .units
+            .iter()
+            .find_map(|unit| unit.boss.as_ref().map(|boss| (unit, boss)))
+        {
+            let aabb = AABB::point(vec2(area.center().x, area.y_max - area.height() * 0.2))
+                .extend_symmetric(vec2(area.width() * 0.3, layout.size(10.0)));
+            let frame = aabb.extend_uniform(layout.size(3.0));
+            draw_2d::Quad::new(frame, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+            let mut bar = aabb;
+            bar.x_max =
+                bar.x_min + bar.width() * model.world.bodies.healths[unit.id].ratio().as_f32();
+            let color = if unit.is_invulnerable() {
+                Rgba::GRAY
+            } else {
+                self.palette.enemy
+            };
+            draw_2d::Quad::new(bar, color).draw_2d(geng, framebuffer, camera);
+            self.locale.font(geng).draw(
+                framebuffer,
+                camera,
+                self.locale.get(&boss.name),
+                vec2(aabb.center().x, aabb.y_max + layout.size(10.0)),
+                geng::TextAlign::CENTER,
+                layout.size(24.0),
+                Rgba::WHITE,
+            );
+        }
+
+        // Dialogue
+        if let Some((speaker, text)) = model
+            .world
+            .cutscene
+            .as_ref()
+            .and_then(|cutscene| cutscene.dialogue())
+        {
+            let aabb = AABB {
+                x_min: area.x_min + area.width() * 0.1,
+                x_max: area.x_max - area.width() * 0.1,
+                y_min: area.y_min + layout.size(20.0),
+                y_max: area.y_min + layout.size(160.0),
+            };
+            draw_2d::Quad::new(aabb, Rgba::new(0.0, 0.0, 0.0, 0.8)).draw_2d(
+                geng,
+                framebuffer,
+                camera,
+            );
+            let font = self.locale.font(geng);
+            font.draw(
+                framebuffer,
+                camera,
+                speaker,
+                vec2(aabb.x_min, aabb.y_max) + vec2(20.0, -40.0) * layout.scale,
+                geng::TextAlign::LEFT,
+                layout.size(28.0),
+                SHIELD_COLOR,
+            );
+            font.draw(
+                framebuffer,
+                camera,
+                &text,
+                vec2(aabb.x_min, aabb.y_max) + vec2(20.0, -85.0) * layout.scale,
+                geng::TextAlign::LEFT,
+                layout.size(28.0),
+                Rgba::WHITE,
+            );
+            font.draw(
+                framebuffer,
+                camera,
+                self.locale.get("hud.dialogue_help"),
+                vec2(aabb.x_max, aabb.y_min) + vec2(-20.0, 15.0) * layout.scale,
+                geng::TextAlign::RIGHT,
+                layout.size(18.0),
+                Rgba::GRAY,
+            );
+        }
+
+        self.draw_dps_meters(model, framebuffer);
+        self.draw_offscreen_indicators(model, framebuffer);
+        self.draw_damage_indicators(model, framebuffer);
+        self.draw_minimap(model, framebuffer);
+        self.draw_perk_choice(model, framebuffer);
+        self.draw_touch_controls(framebuffer);
     }
 }
 
@@ -443,11 +1211,38 @@ fn draw_sprite(
     framebuffer: &mut ugli::Framebuffer,
     camera: &impl geng::AbstractCamera2d,
 ) {
+    draw_sprite_tinted(
+        sprite,
+        position,
+        flip,
+        rotation,
+        Rgba::WHITE,
+        geng,
+        framebuffer,
+        camera,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_sprite_tinted(
+    sprite: &Sprite,
+    position: Position,
+    flip: bool,
+    rotation: f32,
+    color: Rgba<f32>,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+    camera: &impl geng::AbstractCamera2d,
+) {
+    let texture = match sprite.texture() {
+        Some(texture) => texture,
+        None => return,
+    };
     let mut aabb = AABB::ZERO.extend_symmetric(sprite.size / 2.0);
     if flip {
         aabb = flip_aabb(aabb);
     }
-    draw_2d::TexturedQuad::new(aabb, &*sprite.texture.clone()).draw_2d_transformed(
+    draw_2d::TexturedQuad::colored(aabb, &*texture, color).draw_2d_transformed(
         geng,
         framebuffer,
         camera,
@@ -455,6 +1250,29 @@ fn draw_sprite(
     );
 }
 
+/// Draws the armor cracks over the sprite.
+fn draw_cracks(
+    sprite: &Sprite,
+    position: Position,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+    camera: &impl geng::AbstractCamera2d,
+) {
+    let position = position.map(|x| x.as_f32());
+    let width = sprite.size.x.min(sprite.size.y) * 0.04;
+    for crack in CRACKS {
+        let points = crack
+            .iter()
+            .map(|&(x, y)| position + vec2(x * sprite.size.x, y * sprite.size.y))
+            .collect();
+        draw_2d::Chain::new(Chain::new(points), width, CRACK_COLOR, 0).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+    }
+}
+
 fn flip_aabb<T: Num>(aabb: AABB<T>) -> AABB<T> {
     AABB::point(aabb.bottom_right()).extend_positive(vec2(-aabb.size().x, aabb.size().y))
 }
@@ -0,0 +1,78 @@
+use super::*;
+
+use logic::TICK_TIME;
+
+/// Maximum time ahead the arcs are previewed for.
+const PREVIEW_TIME: f32 = 3.0;
+/// Number of ticks between the dots of an arc.
+const DOT_STEP: usize = 3;
+const DOT_SIZE: f32 = 0.08;
+
+impl Render {
+    /// Draws the path the projectile of each aimable mech would take towards the aim:
+    /// a dotted arc for the lobbed projectiles, and a ray for the ones flying straight.
+    pub(super) fn draw_aim_previews(
+        &self,
+        model: &Model,
+        aim: Position,
+        color: Rgba<f32>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &self.camera;
+        for unit in model
+            .world
+            .units
+            .iter()
+            .filter(|unit| unit.is_aimable(&model.world.animations))
+        {
+            let effect = match unit.action.projectile(&model.world.animations) {
+                Some(effect) => effect,
+                None => continue,
+            };
+            let start = effect.launch_position(unit, &model.world.bodies);
+            let delta = aim - start;
+            match effect.ai {
+                ProjectileAI::Idle => {}
+                // Rockets compensate for the gravity
+                ProjectileAI::Rocket { .. } => {
+                    let chain =
+                        Chain::new(vec![start.map(|x| x.as_f32()), aim.map(|x| x.as_f32())]);
+                    draw_2d::Chain::new(chain, 0.05, color, 0).draw_2d(geng, framebuffer, camera);
+                    continue;
+                }
+                // Move with the caster, not towards the aim
+                ProjectileAI::Orbit { .. } | ProjectileAI::Attached { .. } => continue,
+            }
+
+            // The same solution the projectile is launched with
+            let speed = effect.speed * unit.stat(Stat::ProjectileSpeed);
+            let mut velocity = aim_parabollically(delta, model.world.gravity.y, speed)
+                .map(|(velocity, _)| velocity)
+                .unwrap_or_else(|| max_range_velocity(delta, speed));
+            let mut position = start;
+            let mut distance = Coord::ZERO;
+            let delta_time = Time::new(TICK_TIME);
+            let ticks = (PREVIEW_TIME / TICK_TIME) as usize;
+            for tick in 1..=ticks {
+                integrate(
+                    &mut position,
+                    &mut velocity,
+                    model.world.gravity,
+                    delta_time,
+                );
+                distance += velocity.len() * delta_time;
+                let landed = position.y <= model.world.ground_level;
+                let passed = (position.x - start.x).abs() >= delta.x.abs();
+                let out_of_range = effect.max_range.map_or(false, |range| distance >= range);
+                if tick % DOT_STEP == 0 || landed || passed || out_of_range {
+                    let dot = AABB::point(position.map(|x| x.as_f32())).extend_uniform(DOT_SIZE);
+                    draw_2d::Quad::new(dot, color).draw_2d(geng, framebuffer, camera);
+                }
+                if landed || passed || out_of_range {
+                    break;
+                }
+            }
+        }
+    }
+}
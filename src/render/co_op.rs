@@ -0,0 +1,40 @@
+use super::*;
+
+const STATUS_SIZE: f32 = 24.0;
+
+impl Render {
+    /// Draws the state of the connection of a co-op run, if anything is off with it.
+    pub fn draw_co_op_status(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let lockstep = match &model.lockstep {
+            Some(lockstep) => lockstep,
+            None => return,
+        };
+        let (text, color) = if let Some(error) = &lockstep.error {
+            (
+                self.locale.format("hud.co_op_left", &[("error", error)]),
+                Rgba::RED,
+            )
+        } else if let Some(tick) = lockstep.desync {
+            (
+                self.locale.format("hud.co_op_desync", &[("tick", &tick)]),
+                Rgba::RED,
+            )
+        } else if lockstep.waiting {
+            (self.locale.get("hud.co_op_waiting").to_owned(), Rgba::WHITE)
+        } else {
+            return;
+        };
+        let layout = self.layout;
+        let area = layout.area;
+        let size = layout.size(STATUS_SIZE);
+        self.locale.font(&self.geng).draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            &text,
+            vec2(area.center().x, area.y_min + size * 3.0),
+            geng::TextAlign::CENTER,
+            size,
+            color,
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+const PANEL_WIDTH: f32 = 460.0;
+const LINE_HEIGHT: f32 = 20.0;
+
+impl Render {
+    /// Draws the latest entries of the combat log on the right side of the screen.
+    pub fn draw_combat_log(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        if !self.show_combat_log {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let panel = AABB {
+            x_min: screen.x_max - PANEL_WIDTH,
+            y_min: screen.y_min + 120.0,
+            y_max: screen.y_max - 100.0,
+            ..screen
+        };
+        draw_2d::Quad::new(panel, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(geng, framebuffer, camera);
+
+        // The newest entry is at the top, the older ones scroll down and out of the panel
+        let visible = (panel.height() / LINE_HEIGHT) as usize;
+        for (row, entry) in model
+            .world
+            .combat_log
+            .entries
+            .iter()
+            .rev()
+            .take(visible)
+            .enumerate()
+        {
+            let (text, color) = match entry.kind {
+                CombatLogKind::Damage { amount, killed } => {
+                    let killed = if killed { ", killed" } else { "" };
+                    let text = format!("{:.1} damage{}", amount, killed);
+                    (text, Rgba::new(1.0, 0.5, 0.5, 1.0))
+                }
+                CombatLogKind::Heal { amount } => (
+                    format!("{:.1} healed", amount),
+                    Rgba::new(0.5, 1.0, 0.5, 1.0),
+                ),
+                CombatLogKind::Status { name } => (name.to_owned(), Rgba::new(0.6, 0.8, 1.0, 1.0)),
+            };
+            geng.default_font().draw(
+                framebuffer,
+                camera,
+                &format!(
+                    "[{:6.1}] {} -> {}: {}",
+                    entry.time, entry.source, entry.target, text
+                ),
+                vec2(
+                    panel.x_min + 10.0,
+                    panel.y_max - LINE_HEIGHT * (row + 1) as f32,
+                ),
+                geng::TextAlign::LEFT,
+                LINE_HEIGHT * 0.8,
+                color,
+            );
+        }
+    }
+
+    /// Draws the totals of the run by source and by enemy, starting at the position.
+    pub fn draw_run_stats(
+        &self,
+        log: &CombatLog,
+        mut position: Vec2<f32>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let font = self.locale.font(geng);
+        let locale = &self.locale;
+        let mut line = |text: &str, color: Rgba<f32>| {
+            font.draw(
+                framebuffer,
+                camera,
+                text,
+                position,
+                geng::TextAlign::LEFT,
+                LINE_HEIGHT,
+                color,
+            );
+            position.y -= LINE_HEIGHT * 1.2;
+        };
+        let unit_name = |name: &str| locale.get(&format!("unit.{}", name)).to_owned();
+
+        line(locale.get("game_over.by_source"), Rgba::WHITE);
+        for (&name, stats) in &log.sources {
+            line(
+                &locale.format(
+                    "game_over.source",
+                    &[
+                        ("name", &unit_name(name)),
+                        ("damage", &format!("{:.0}", stats.damage)),
+                        ("healing", &format!("{:.0}", stats.healing)),
+                        ("kills", &stats.kills),
+                    ],
+                ),
+                Rgba::GRAY,
+            );
+        }
+        line("", Rgba::WHITE);
+        line(locale.get("game_over.by_enemy"), Rgba::WHITE);
+        for (&name, stats) in &log.enemies {
+            line(
+                &locale.format(
+                    "game_over.enemy",
+                    &[
+                        ("name", &unit_name(name)),
+                        ("killed", &stats.killed),
+                        ("taken", &format!("{:.0}", stats.damage_taken)),
+                        ("dealt", &format!("{:.0}", stats.damage_dealt)),
+                    ],
+                ),
+                Rgba::GRAY,
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+/// Kills needed for the combo to show up.
+const MIN_SHOWN_KILLS: usize = 2;
+const COMBO_TEXT_SIZE: f32 = 28.0;
+/// Extra text size with every tier of the combo.
+const TIER_GROWTH: f32 = 6.0;
+/// The colors of the tiers, the last one is used for the higher ones.
+const TIER_COLORS: [Rgba<f32>; 4] = [
+    Rgba::WHITE,
+    Rgba {
+        r: 1.0,
+        g: 0.9,
+        b: 0.3,
+        a: 1.0,
+    },
+    Rgba {
+        r: 1.0,
+        g: 0.55,
+        b: 0.1,
+        a: 1.0,
+    },
+    Rgba {
+        r: 1.0,
+        g: 0.2,
+        b: 0.35,
+        a: 1.0,
+    },
+];
+/// Part of the combo window during which the text pops after a kill.
+const POP_TIME: f32 = 0.1;
+const POP_SCALE: f32 = 0.3;
+
+impl Render {
+    /// Draws the combo counter, growing louder as the combo goes on, with the time left to continue it.
+    pub fn draw_combo(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let combo = &model.world.combo;
+        if combo.kills < MIN_SHOWN_KILLS {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let layout = self.layout;
+        let tier = combo.tier().min(TIER_COLORS.len() - 1);
+        let window = (combo.time_left / Time::new(COMBO_WINDOW))
+            .as_f32()
+            .clamp(0.0, 1.0);
+        let pop = ((window - (1.0 - POP_TIME)) / POP_TIME).max(0.0);
+        let size =
+            layout.size(COMBO_TEXT_SIZE + TIER_GROWTH * tier as f32) * (1.0 + POP_SCALE * pop);
+        let color = TIER_COLORS[tier];
+
+        let position = layout.at(Anchor::TopRight, vec2(-20.0, -120.0));
+        self.locale.font(geng).draw(
+            framebuffer,
+            camera,
+            &self.locale.format(
+                "hud.combo",
+                &[
+                    ("kills", &combo.kills),
+                    ("multiplier", &format!("{:.1}", combo.multiplier().as_f32())),
+                ],
+            ),
+            position,
+            geng::TextAlign::RIGHT,
+            size,
+            color,
+        );
+
+        // The time left to continue the combo
+        let width = layout.size(160.0);
+        let top = position.y - layout.size(12.0);
+        let bar = AABB {
+            x_min: position.x - width,
+            x_max: position.x,
+            y_min: top - layout.size(6.0),
+            y_max: top,
+        };
+        draw_2d::Quad::new(bar, Rgba::new(0.0, 0.0, 0.0, 0.6)).draw_2d(geng, framebuffer, camera);
+        let filled = AABB {
+            x_min: bar.x_max - bar.width() * window,
+            ..bar
+        };
+        draw_2d::Quad::new(filled, color).draw_2d(geng, framebuffer, camera);
+    }
+}
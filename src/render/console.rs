@@ -0,0 +1,49 @@
+use super::*;
+
+const CONSOLE_HEIGHT: f32 = 0.4;
+const CONSOLE_LINE_HEIGHT: f32 = 22.0;
+
+impl Render {
+    /// Draws the debug console dropped down from the top of the screen.
+    pub fn draw_console(&self, console: &Console, framebuffer: &mut ugli::Framebuffer) {
+        if !console.open {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = geng.default_font();
+
+        let panel = AABB {
+            y_min: screen.y_max - screen.height() * CONSOLE_HEIGHT,
+            ..screen
+        };
+        draw_2d::Quad::new(panel, Rgba::new(0.0, 0.0, 0.0, 0.8)).draw_2d(geng, framebuffer, camera);
+
+        let mut position = vec2(panel.x_min + 10.0, panel.y_min + 10.0);
+        font.draw(
+            framebuffer,
+            camera,
+            &format!("> {}_", console.input),
+            position,
+            geng::TextAlign::LEFT,
+            CONSOLE_LINE_HEIGHT,
+            Rgba::WHITE,
+        );
+        for line in console.history.iter().rev() {
+            position.y += CONSOLE_LINE_HEIGHT;
+            if position.y + CONSOLE_LINE_HEIGHT > panel.y_max {
+                break;
+            }
+            font.draw(
+                framebuffer,
+                camera,
+                line,
+                position,
+                geng::TextAlign::LEFT,
+                CONSOLE_LINE_HEIGHT,
+                Rgba::GRAY,
+            );
+        }
+    }
+}
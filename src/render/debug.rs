@@ -0,0 +1,215 @@
+use super::*;
+
+use logic::TICK_TIME;
+
+const COLLIDER_COLOR: Rgba<f32> = Rgba {
+    r: 0.0,
+    g: 1.0,
+    b: 0.0,
+    a: 1.0,
+};
+const VELOCITY_COLOR: Rgba<f32> = Rgba {
+    r: 0.3,
+    g: 0.6,
+    b: 1.0,
+    a: 1.0,
+};
+const TRAJECTORY_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+    a: 0.4,
+};
+/// Maximum time ahead the projectile trajectories are predicted for.
+const TRAJECTORY_TIME: f32 = 2.0;
+/// Number of ticks between the points of a predicted trajectory.
+const TRAJECTORY_STEP: usize = 4;
+
+impl Render {
+    /// Draws the colliders, velocities, AI states and predicted trajectories in the world.
+    pub(super) fn draw_debug_world(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        if !self.debug_overlay {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &self.camera;
+
+        // Colliders
+        let colliders = model
+            .world
+            .bodies
+            .colliders
+            .iter()
+            .filter_map(|(id, collider)| Some((*model.world.bodies.positions.get(id)?, collider)))
+            .chain(
+                model
+                    .world
+                    .obstacles
+                    .iter()
+                    .map(|obstacle| (obstacle.position, &obstacle.collider)),
+            )
+            .chain(
+                model
+                    .world
+                    .pickups
+                    .iter()
+                    .map(|pickup| (pickup.position, &pickup.collider)),
+            );
+        for (pos, collider) in colliders {
+            draw_aabb_frame(
+                collider.bounds(pos),
+                Coord::new(0.1),
+                COLLIDER_COLOR,
+                geng,
+                framebuffer,
+                camera,
+            );
+        }
+        for swing in &model.world.melee_swings {
+            let caster = match model.world.units.get(&swing.caster) {
+                Some(caster) => caster,
+                None => continue,
+            };
+            let origin = swing.origin(caster, &model.world.bodies);
+            let direction = if caster.flip_sprite { -1.0 } else { 1.0 };
+            let aabb = match &swing.shape {
+                MeleeShape::Arc { radius, .. } => {
+                    AABB::point(origin + vec2(*radius * Coord::new(direction / 2.0), Coord::ZERO))
+                        .extend_symmetric(vec2(*radius / Coord::new(2.0), *radius))
+                }
+                MeleeShape::Box { offset, size } => {
+                    AABB::point(origin + vec2(offset.x * Coord::new(direction), offset.y))
+                        .extend_symmetric(*size / Coord::new(2.0))
+                }
+            };
+            draw_aabb_frame(aabb, Coord::new(0.1), Rgba::RED, geng, framebuffer, camera);
+        }
+
+        // Velocities and AI states
+        for unit in &model.world.units {
+            let position = model.world.bodies.positions[unit.id].map(|x| x.as_f32());
+            let velocity = model.world.bodies.velocities[unit.id].map(|x| x.as_f32());
+            if velocity.len() > 1e-3 {
+                // Scaled to the distance travelled in a quarter of a second
+                let chain = Chain::new(vec![position, position + velocity * 0.25]);
+                draw_2d::Chain::new(chain, 0.05, VELOCITY_COLOR, 0).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+            let size = match model.world.bodies.bounds(unit.id) {
+                Some(bounds) => bounds.map(|x| x.as_f32()),
+                None => continue,
+            };
+            geng.default_font().draw(
+                framebuffer,
+                camera,
+                &ai_label(unit),
+                vec2(position.x, size.y_min - 0.4),
+                geng::TextAlign::CENTER,
+                0.3,
+                Rgba::WHITE,
+            );
+        }
+
+        // Predicted trajectories
+        for projectile in &model.world.projectiles {
+            let acceleration = match projectile.ai {
+                ProjectileAI::Idle => model.world.gravity,
+                // Rockets compensate for the gravity
+                ProjectileAI::Rocket { .. } => Vec2::ZERO,
+                // Move with the caster, not along a trajectory
+                ProjectileAI::Orbit { .. } | ProjectileAI::Attached { .. } => continue,
+            };
+            let mut position = model.world.bodies.positions[projectile.id];
+            let mut velocity = model.world.bodies.velocities[projectile.id];
+            let mut points = vec![position.map(|x| x.as_f32())];
+            let delta_time = Time::new(TICK_TIME);
+            let ticks = (TRAJECTORY_TIME.min(projectile.lifetime.as_f32()) / TICK_TIME) as usize;
+            for tick in 1..=ticks {
+                integrate(&mut position, &mut velocity, acceleration, delta_time);
+                if position.y <= model.world.ground_level {
+                    points.push(position.map(|x| x.as_f32()));
+                    break;
+                }
+                if tick % TRAJECTORY_STEP == 0 {
+                    points.push(position.map(|x| x.as_f32()));
+                }
+            }
+            if points.len() > 1 {
+                draw_2d::Chain::new(Chain::new(points), 0.05, TRAJECTORY_COLOR, 0).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+            }
+        }
+    }
+
+    /// Draws the counters of the simulation in the corner of the screen.
+    pub(super) fn draw_debug_ui(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        if !self.debug_overlay {
+            return;
+        }
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let mut lines = vec![
+            format!("tick: {}", model.world.current_tick),
+            format!("effects processed: {}", model.effects_processed),
+            format!("effects delayed: {}", model.world.delayed_effects.len()),
+            format!("units: {}", model.world.units.len()),
+            format!("projectiles: {}", model.world.projectiles.len()),
+            format!("particles: {}", model.world.particles.len()),
+            format!("snapshots: {}", model.snapshots.len()),
+        ];
+        if let Some(rewind) = &model.rewind {
+            lines.push(if rewind.is_replaying() {
+                format!("replaying until tick {}", rewind.end_tick)
+            } else {
+                format!("replayed until tick {}", rewind.end_tick)
+            });
+            lines.push(match rewind.desync {
+                Some(tick) => format!("desynced at tick {}", tick),
+                None => "no desync".to_owned(),
+            });
+        }
+        for (index, line) in lines.iter().enumerate() {
+            self.geng.default_font().draw(
+                framebuffer,
+                camera,
+                line,
+                vec2(
+                    screen.x_min + 10.0,
+                    screen.y_max - 100.0 - index as f32 * 20.0,
+                ),
+                geng::TextAlign::LEFT,
+                18.0,
+                Rgba::WHITE,
+            );
+        }
+    }
+}
+
+/// A short description of what the unit's AI is doing.
+fn ai_label(unit: &Unit) -> String {
+    let ai = match &unit.ai {
+        UnitAI::Idle => "idle",
+        UnitAI::Stinger { .. } => "stinger",
+        UnitAI::Engage { .. } => "engage",
+    };
+    let action = match &unit.action_state {
+        ActionState::Ready => "ready".to_owned(),
+        ActionState::InProgress { target, aim } => match (target, aim) {
+            (_, Some(_)) => "acting at aim".to_owned(),
+            (Some(_), None) => "acting at target".to_owned(),
+            (None, None) => "acting".to_owned(),
+        },
+        ActionState::Cooldown { time_left } => format!("cooldown {:.1}", time_left.as_f32()),
+    };
+    let mut label = format!("{}: {}", ai, action);
+    if unit.is_stunned() {
+        label += ", stunned";
+    }
+    label
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+const PANEL_WIDTH: f32 = 420.0;
+const LINE_HEIGHT: f32 = 20.0;
+const INDENT: f32 = 16.0;
+
+impl Render {
+    /// Draws the effect trees of the inspected unit on the left side of the screen.
+    pub fn draw_effect_tree(&self, editor: &EffectTreeEditor, framebuffer: &mut ugli::Framebuffer) {
+        if !editor.open {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let panel = AABB {
+            x_max: screen.x_min + PANEL_WIDTH,
+            y_min: screen.y_min + 120.0,
+            ..screen
+        };
+        draw_2d::Quad::new(panel, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(geng, framebuffer, camera);
+
+        let font = geng.default_font();
+        let hint = if editor.lines.is_empty() {
+            "Select a unit in the debug overlay"
+        } else {
+            "Up/Down - select, Enter - expand, Left/Right - change"
+        };
+        font.draw(
+            framebuffer,
+            camera,
+            hint,
+            vec2(panel.x_min + 10.0, panel.y_max - LINE_HEIGHT),
+            geng::TextAlign::LEFT,
+            LINE_HEIGHT * 0.8,
+            Rgba::GRAY,
+        );
+        // Keep the selected line in view
+        let visible = ((panel.height() / LINE_HEIGHT) as usize).saturating_sub(2);
+        let first = (editor.selected + 1).saturating_sub(visible);
+        for (row, (index, line)) in editor
+            .lines
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+            .enumerate()
+        {
+            let color = if index == editor.selected {
+                Rgba::WHITE
+            } else {
+                Rgba::GRAY
+            };
+            font.draw(
+                framebuffer,
+                camera,
+                &line.text,
+                vec2(
+                    panel.x_min + 10.0 + line.depth as f32 * INDENT,
+                    panel.y_max - LINE_HEIGHT * (row + 2) as f32,
+                ),
+                geng::TextAlign::LEFT,
+                LINE_HEIGHT * 0.8,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use super::*;
+
+const INDICATOR_MARGIN: f32 = 30.0;
+const INDICATOR_SIZE: f32 = 14.0;
+const INDICATOR_ALPHA: f32 = 0.8;
+/// Size of the wedges pointing towards where the mechs are hit from.
+const DAMAGE_INDICATOR_SIZE: f32 = 36.0;
+/// Width of the tint along the screen edges while the health is low.
+const LOW_HEALTH_EDGE: f32 = 120.0;
+const LOW_HEALTH_ALPHA: f32 = 0.35;
+
+const MINIMAP_SIZE: Vec2<f32> = Vec2 { x: 300.0, y: 80.0 };
+const MINIMAP_MARGIN: f32 = 20.0;
+/// World distance shown on the minimap behind the left border.
+const MINIMAP_BEHIND: f32 = 5.0;
+/// World distance shown on the minimap ahead of the left border.
+const MINIMAP_AHEAD: f32 = 75.0;
+const MINIMAP_HEIGHT: f32 = 20.0;
+const MINIMAP_DOT_SIZE: f32 = 3.0;
+const MINIMAP_BACKGROUND: Rgba<f32> = Rgba {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    a: 0.5,
+};
+const MINIMAP_LEVEL_COLOR: Rgba<f32> = Rgba {
+    r: 0.5,
+    g: 0.5,
+    b: 0.5,
+    a: 0.8,
+};
+const MECH_DOT_COLOR: Rgba<f32> = Rgba {
+    r: 0.17,
+    g: 0.85,
+    b: 1.0,
+    a: 1.0,
+};
+
+impl Render {
+    /// Draws arrows at the screen edges pointing towards the enemies, pickups and pings out of view.
+    pub(super) fn draw_offscreen_indicators(
+        &self,
+        model: &Model,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let layout = self.layout;
+        let inner = layout.area.extend_uniform(-layout.size(INDICATOR_MARGIN));
+        let size = layout.size(INDICATOR_SIZE);
+        let enemy_color = with_alpha(self.palette.danger, INDICATOR_ALPHA);
+        let pickup_color = with_alpha(self.palette.pickup, INDICATOR_ALPHA);
+
+        let enemies = model
+            .world
+            .units
+            .iter()
+            .filter(|unit| unit.faction != Faction::Mech)
+            .map(|unit| (model.world.bodies.positions[unit.id], enemy_color));
+        let pickups = model
+            .world
+            .pickups
+            .iter()
+            .map(|pickup| (pickup.position, pickup_color));
+        let pings = model
+            .world
+            .pings
+            .iter()
+            .map(|ping| (ping.position, self.palette.ping(ping.kind)));
+        for (position, color) in enemies.chain(pickups).chain(pings) {
+            let target = self.world_to_screen(position);
+            if screen.contains(target) {
+                continue;
+            }
+            let (tip, direction) = match edge_point(inner, target) {
+                Some(point) => point,
+                None => continue,
+            };
+            let normal = direction.rotate_90();
+            let base = tip - direction * size;
+            draw_2d::Polygon::new(
+                vec![tip, base + normal * size / 2.0, base - normal * size / 2.0],
+                color,
+            )
+            .draw_2d(geng, framebuffer, camera);
+        }
+    }
+
+    /// Flashes wedges at the screen edges pointing towards where the mechs were hit from.
+    pub(super) fn draw_damage_indicators(
+        &self,
+        model: &Model,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let layout = self.layout;
+        let inner = layout.area.extend_uniform(-layout.size(INDICATOR_MARGIN));
+        let size = layout.size(DAMAGE_INDICATOR_SIZE);
+        for indicator in &model.damage_indicators {
+            let source = self.world_to_screen(indicator.source);
+            let (tip, direction) = match edge_point(inner, source) {
+                Some(point) => point,
+                None => continue,
+            };
+            let normal = direction.rotate_90();
+            let base = tip - direction * size / 2.0;
+            let color = with_alpha(self.palette.danger, INDICATOR_ALPHA * indicator.fade());
+            draw_2d::Polygon::new(vec![tip, base + normal * size, base - normal * size], color)
+                .draw_2d(geng, framebuffer, camera);
+        }
+    }
+
+    /// Tints the screen edges while a mech is low on health, pulsing like a heartbeat.
+    pub(super) fn draw_low_health(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        if !model.world.low_health {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let outer = self.layout.area;
+        let inner = outer.extend_uniform(-self.layout.size(LOW_HEALTH_EDGE));
+        let alpha = LOW_HEALTH_ALPHA + self.juice_policy.pulse(0.1, model.time().as_f32() * 5.0);
+        let edge = with_alpha(self.palette.danger, alpha);
+        let clear = with_alpha(self.palette.danger, 0.0);
+        let outer = outer.corners();
+        let inner = inner.corners();
+        for i in 0..4 {
+            let j = (i + 1) % 4;
+            let vertex = |a_pos, a_color| draw_2d::ColoredVertex { a_pos, a_color };
+            draw_2d::Polygon::new_gradient(vec![
+                vertex(outer[i], edge),
+                vertex(outer[j], edge),
+                vertex(inner[j], clear),
+                vertex(inner[i], clear),
+            ])
+            .draw_2d(geng, framebuffer, camera);
+        }
+    }
+
+    /// Draws a map of the level around the left border in the corner of the screen.
+    pub(super) fn draw_minimap(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        if !self.show_minimap {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let layout = self.layout;
+        // Bottom right corner, away from the energy bar and the pause button
+        let panel = AABB::point(layout.at(
+            Anchor::BottomRight,
+            vec2(-MINIMAP_MARGIN - MINIMAP_SIZE.x, MINIMAP_MARGIN),
+        ))
+        .extend_positive(MINIMAP_SIZE * layout.scale);
+        let world = AABB {
+            x_min: model.world.left_border.as_f32() - MINIMAP_BEHIND,
+            x_max: model.world.left_border.as_f32() + MINIMAP_AHEAD,
+            y_min: model.world.ground_level.as_f32() - 1.0,
+            y_max: model.world.ground_level.as_f32() + MINIMAP_HEIGHT,
+        };
+        let to_minimap = |pos: Vec2<f32>| {
+            let t = (pos - world.bottom_left()) / world.size();
+            let t = t.map(|x| x.clamp(0.0, 1.0));
+            panel.bottom_left() + t * panel.size()
+        };
+        let to_minimap_aabb = |aabb: AABB<f32>| {
+            AABB::from_corners(to_minimap(aabb.bottom_left()), to_minimap(aabb.top_right()))
+        };
+
+        draw_2d::Quad::new(panel, MINIMAP_BACKGROUND).draw_2d(geng, framebuffer, camera);
+
+        // Level silhouette
+        let ground = to_minimap(vec2(world.x_min, model.world.ground_level.as_f32())).y;
+        draw_2d::Quad::new(
+            AABB {
+                y_max: ground,
+                ..panel
+            },
+            MINIMAP_LEVEL_COLOR,
+        )
+        .draw_2d(geng, framebuffer, camera);
+        for obstacle in &model.world.obstacles {
+            let aabb = to_minimap_aabb(
+                obstacle
+                    .collider
+                    .bounds(obstacle.position)
+                    .map(|x| x.as_f32()),
+            );
+            draw_2d::Quad::new(aabb, MINIMAP_LEVEL_COLOR).draw_2d(geng, framebuffer, camera);
+        }
+
+        // Camera view
+        let view = AABB::point(self.camera.center).extend_symmetric(
+            vec2(
+                self.camera.fov * screen.width() / screen.height(),
+                self.camera.fov,
+            ) / 2.0,
+        );
+        draw_aabb_frame(
+            to_minimap_aabb(view).map(Coord::new),
+            Coord::ONE,
+            Rgba::WHITE,
+            geng,
+            framebuffer,
+            camera,
+        );
+
+        // Units and pickups
+        let dot = |pos: Position, color: Rgba<f32>, framebuffer: &mut ugli::Framebuffer| {
+            let pos = to_minimap(pos.map(|x| x.as_f32()));
+            draw_2d::Quad::new(
+                AABB::point(pos).extend_uniform(layout.size(MINIMAP_DOT_SIZE) / 2.0),
+                color,
+            )
+            .draw_2d(geng, framebuffer, camera);
+        };
+        for pickup in &model.world.pickups {
+            dot(
+                pickup.position,
+                with_alpha(self.palette.pickup, INDICATOR_ALPHA),
+                framebuffer,
+            );
+        }
+        for unit in &model.world.units {
+            let color = match unit.faction {
+                Faction::Mech => MECH_DOT_COLOR,
+                Faction::Alien => with_alpha(self.palette.danger, INDICATOR_ALPHA),
+            };
+            dot(model.world.bodies.positions[unit.id], color, framebuffer);
+        }
+    }
+
+    pub(super) fn world_to_screen(&self, position: Position) -> Vec2<f32> {
+        let view_size = vec2(
+            self.camera.fov * self.framebuffer_size.x / self.framebuffer_size.y,
+            self.camera.fov,
+        );
+        let delta = position.map(|x| x.as_f32()) - self.camera.center;
+        (delta / view_size + vec2(0.5, 0.5)) * self.framebuffer_size
+    }
+}
+
+/// Where the line from the center of the area towards the target crosses its edge,
+/// and the direction of the line.
+fn edge_point(area: AABB<f32>, target: Vec2<f32>) -> Option<(Vec2<f32>, Vec2<f32>)> {
+    let center = area.center();
+    let delta = target - center;
+    if delta.len() < 1e-3 {
+        return None;
+    }
+    let half = area.size() / 2.0;
+    let scale = (half.x / delta.x.abs()).min(half.y / delta.y.abs());
+    Some((center + delta * scale, delta.normalize()))
+}
@@ -0,0 +1,129 @@
+use super::*;
+
+const PANEL_WIDTH: f32 = 320.0;
+const LINE_HEIGHT: f32 = 20.0;
+const INSPECTED_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.9,
+    b: 0.3,
+    a: 1.0,
+};
+
+impl Render {
+    /// Draws the state of the unit selected in the debug overlay.
+    pub fn draw_inspector(
+        &self,
+        model: &Model,
+        inspected: Option<Id>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let unit = match inspected.and_then(|id| model.world.units.get(&id)) {
+            Some(unit) if self.debug_overlay => unit,
+            _ => return,
+        };
+        let geng = &self.geng;
+        let (position, velocity) = (
+            model.world.bodies.positions[unit.id],
+            model.world.bodies.velocities[unit.id],
+        );
+        let health = &model.world.bodies.healths[unit.id];
+        if let Some(bounds) = model.world.bodies.bounds(unit.id) {
+            draw_aabb_frame(
+                bounds,
+                Coord::new(0.15),
+                INSPECTED_COLOR,
+                geng,
+                framebuffer,
+                &self.camera,
+            );
+        }
+
+        let mut lines = vec![
+            format!("{:?} {:?}", unit.faction, unit.id),
+            format!(
+                "hp: {:.1}/{:.1}, shield: {:.1}",
+                health.hp,
+                health.max_hp,
+                unit.shield()
+            ),
+            format!("position: {:.2}, {:.2}", position.x, position.y),
+            format!("velocity: {:.2}, {:.2}", velocity.x, velocity.y),
+            format!(
+                "speed: {:.2}, acceleration: {:.2}",
+                unit.speed, unit.acceleration
+            ),
+            format!(
+                "cooldown: {:.2}, engage radius: {:.2}",
+                unit.action.cooldown, unit.action.engage_radius
+            ),
+            format!("stat modifiers: {}", unit.stats.modifiers.len()),
+        ];
+        if let Some(boss) = &unit.boss {
+            lines.push(format!("boss: {}", boss.name));
+        }
+        if !unit.elites.is_empty() {
+            lines.push(format!("elites: {:?}", unit.elites));
+        }
+        lines.push("statuses:".to_owned());
+        lines.extend(
+            unit.statuses
+                .iter()
+                .map(|status| format!("  {}", status_label(&status.status))),
+        );
+        lines.push("edit <field> <value> in the console".to_owned());
+
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let panel = AABB {
+            x_min: screen.x_max - PANEL_WIDTH,
+            x_max: screen.x_max,
+            y_min: screen.y_max - 20.0 - LINE_HEIGHT * lines.len() as f32,
+            y_max: screen.y_max - 10.0,
+        };
+        draw_2d::Quad::new(panel, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(geng, framebuffer, camera);
+        for (index, line) in lines.iter().enumerate() {
+            geng.default_font().draw(
+                framebuffer,
+                camera,
+                line,
+                vec2(
+                    panel.x_min + 10.0,
+                    panel.y_max - LINE_HEIGHT * (index + 1) as f32,
+                ),
+                geng::TextAlign::LEFT,
+                LINE_HEIGHT * 0.8,
+                Rgba::WHITE,
+            );
+        }
+    }
+}
+
+fn status_label(status: &Status) -> String {
+    match status {
+        Status::Charge { time, .. } => format!("charge {:.1}", time),
+        Status::Stunned { time } => format!("stunned {:.1}", time),
+        Status::Invulnerable { time } => format!("invulnerable {:.1}", time),
+        Status::Spawning { time, .. } => format!("spawning {:.1}", time),
+        Status::Shield { hp, time } => match time {
+            Some(time) => format!("shield {:.1} hp {:.1}", hp, time),
+            None => format!("shield {:.1} hp", hp),
+        },
+        Status::Block => "block".to_owned(),
+        Status::Trail { .. } => "trail".to_owned(),
+        Status::Parry { time } => format!("parry {:.1}", time),
+        Status::Burning {
+            time,
+            damage_per_second,
+        } => format!("burning {:.1}/s {:.1}", damage_per_second, time),
+        Status::Modified { time, modifier } => format!(
+            "{:?} {:?} {:.2} {:.1}",
+            modifier.stat, modifier.kind, modifier.value, time
+        ),
+        Status::ArmorBroken { time, multiplier } => {
+            format!("armor broken x{:.1} {:.1}", multiplier, time)
+        }
+        Status::Wet { time } => format!("wet {:.1}", time),
+        Status::SlowFall { time, .. } => format!("slow fall {:.1}", time),
+        Status::MaxHp { time, amount, .. } => format!("max hp {:+.1} {:.1}", amount, time),
+    }
+}
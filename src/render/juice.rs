@@ -0,0 +1,69 @@
+use super::*;
+
+use std::collections::HashMap;
+
+/// How long a unit flashes after taking damage, in seconds.
+const FLASH_TIME: f32 = 0.15;
+/// Screen shake strength lost per second.
+const SHAKE_DECAY: f32 = 2.0;
+/// Share of a mech's max health lost in a single frame that shakes the screen at full strength.
+const FULL_SHAKE_DAMAGE: f32 = 0.3;
+
+/// The state of the screen shake and hit flashes, noticed from the changes in the model.
+#[derive(Debug, Default)]
+pub struct Juice {
+    /// Health of the units in the previous frame.
+    health: HashMap<Id, Hp>,
+    /// Flash time left for the units hit recently.
+    flashes: HashMap<Id, f32>,
+    /// Shake strength from 0 to 1.
+    shake: f32,
+    last_time: f32,
+}
+
+impl Juice {
+    /// Starts the flashes and shakes for the units that lost health since the last frame.
+    pub fn update(&mut self, model: &Model) {
+        let time = model.time().as_f32();
+        let delta_time = (time - self.last_time).max(0.0);
+        self.last_time = time;
+
+        self.shake = (self.shake - SHAKE_DECAY * delta_time).max(0.0);
+        for left in self.flashes.values_mut() {
+            *left -= delta_time;
+        }
+        self.flashes.retain(|_, left| *left > 0.0);
+
+        for unit in &model.world.units {
+            let health = &model.world.bodies.healths[unit.id];
+            let hp = health.hp;
+            let lost = match self.health.get(&unit.id) {
+                Some(&last) if last > hp => last - hp,
+                _ => continue,
+            };
+            self.flashes.insert(unit.id, FLASH_TIME);
+            if unit.faction == Faction::Mech {
+                let ratio = (lost / health.max_hp).as_f32();
+                self.shake = (self.shake + ratio / FULL_SHAKE_DAMAGE).min(1.0);
+            }
+        }
+        self.health = model
+            .world
+            .units
+            .iter()
+            .map(|unit| (unit.id, model.world.bodies.healths[unit.id].hp))
+            .collect();
+    }
+
+    pub fn shake_offset(&self, policy: &JuicePolicy) -> Vec2<f32> {
+        policy.shake_offset(self.shake, self.last_time)
+    }
+
+    /// Tint of the unit, white if it was not hit recently.
+    pub fn tint(&self, unit: Id, policy: &JuicePolicy) -> Rgba<f32> {
+        match self.flashes.get(&unit) {
+            Some(left) => policy.flash_tint(left / FLASH_TIME),
+            None => Rgba::WHITE,
+        }
+    }
+}
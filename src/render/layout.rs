@@ -0,0 +1,68 @@
+use super::*;
+
+/// Screen height the HUD is designed for, taller screens scale it up.
+const REFERENCE_HEIGHT: f32 = 1080.0;
+/// On wider screens the HUD stays in the middle, so it does not end up at the edges of the view.
+const MAX_HUD_ASPECT: f32 = 21.0 / 9.0;
+
+/// The point of the safe area a HUD element is attached to.
+#[derive(Debug, Clone, Copy)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Center,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Places the HUD relative to the edges of the safe area and scales its sizes,
+/// so that it is readable on large screens and not cut off by the notches.
+#[derive(Debug, Clone, Copy)]
+pub struct HudLayout {
+    /// The part of the screen where the HUD is placed.
+    pub area: AABB<f32>,
+    /// Multiplier of the sizes in pixels.
+    pub scale: f32,
+}
+
+impl HudLayout {
+    pub fn new(screen: AABB<f32>, settings: &DisplaySettings) -> Self {
+        let margin = screen.size() * settings.safe_area;
+        let mut area = AABB {
+            x_min: screen.x_min + margin.x,
+            x_max: screen.x_max - margin.x,
+            y_min: screen.y_min + margin.y,
+            y_max: screen.y_max - margin.y,
+        };
+        let excess = area.width() - area.height() * MAX_HUD_ASPECT;
+        if excess > 0.0 {
+            area.x_min += excess / 2.0;
+            area.x_max -= excess / 2.0;
+        }
+        Self {
+            area,
+            scale: settings.ui_scale * (screen.height() / REFERENCE_HEIGHT).max(1.0),
+        }
+    }
+
+    /// The anchor point moved by the offset, the offset is given in unscaled pixels.
+    pub fn at(&self, anchor: Anchor, offset: Vec2<f32>) -> Vec2<f32> {
+        let area = self.area;
+        let center = area.center();
+        let point = match anchor {
+            Anchor::TopLeft => vec2(area.x_min, area.y_max),
+            Anchor::Top => vec2(center.x, area.y_max),
+            Anchor::TopRight => vec2(area.x_max, area.y_max),
+            Anchor::Center => center,
+            Anchor::BottomLeft => vec2(area.x_min, area.y_min),
+            Anchor::BottomRight => vec2(area.x_max, area.y_min),
+        };
+        point + offset * self.scale
+    }
+
+    /// The size in unscaled pixels, scaled for the screen.
+    pub fn size(&self, pixels: f32) -> f32 {
+        pixels * self.scale
+    }
+}
@@ -0,0 +1,249 @@
+use super::*;
+
+use crate::crash::CrashReport;
+use crate::game::{GameOver, PauseMenu, CONTROLS_TAB, PAUSE_OPTIONS, PAUSE_TABS};
+
+impl Render {
+    /// The widgets of the open tab of the pause menu.
+    pub fn pause_widgets(&self, controls: &Controls, menu: &PauseMenu) -> Vec<ui::Widget> {
+        let locale = &self.locale;
+        if menu.menu.tab != CONTROLS_TAB {
+            return PAUSE_OPTIONS
+                .iter()
+                .map(|key| ui::Widget::button(locale.get(key)))
+                .collect();
+        }
+        ControlAction::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(index, action)| {
+                let keys = if index == menu.menu.selected && menu.rebinding {
+                    locale.get("pause.press_key").to_owned()
+                } else {
+                    controls
+                        .keys(action)
+                        .iter()
+                        .map(|key| format!("{:?}", key))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                ui::Widget::button(locale.format(
+                    "pause.binding",
+                    &[("action", &locale.get(action.key())), ("keys", &keys)],
+                ))
+            })
+            .collect()
+    }
+
+    pub fn draw_pause_menu(
+        &self,
+        controls: &Controls,
+        menu: &mut PauseMenu,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(geng);
+
+        draw_2d::Quad::new(screen, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+
+        let line_height = 40.0;
+        let mut position = vec2(screen.center().x, screen.y_max - screen.height() / 6.0);
+        font.draw(
+            framebuffer,
+            camera,
+            self.locale.get("pause.title"),
+            position,
+            geng::TextAlign::CENTER,
+            line_height * 1.5,
+            Rgba::WHITE,
+        );
+        position.y -= line_height * 1.5;
+
+        let tabs = PAUSE_TABS.map(|key| self.locale.get(key).to_owned());
+        let widgets = self.pause_widgets(controls, menu);
+        let style = ui::MenuStyle {
+            top: position,
+            width: 700.0,
+            spacing: line_height * 0.9,
+            text_size: line_height * 0.75,
+        };
+        menu.menu
+            .draw(geng, font, &tabs, &widgets, style, framebuffer);
+
+        font.draw(
+            framebuffer,
+            camera,
+            self.locale.get("pause.help"),
+            vec2(screen.center().x, screen.y_min + line_height),
+            geng::TextAlign::CENTER,
+            line_height * 0.6,
+            Rgba::GRAY,
+        );
+    }
+}
+
+impl Render {
+    pub fn draw_crash(
+        &self,
+        crash: &CrashReport,
+        controls: &Controls,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(geng);
+
+        draw_2d::Quad::new(screen, Rgba::new(0.0, 0.0, 0.0, 0.85)).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+
+        let mut position = vec2(screen.center().x, screen.y_max - screen.height() / 4.0);
+        let mut line = |text: &str, size: f32, color: Rgba<f32>| {
+            font.draw(
+                framebuffer,
+                camera,
+                text,
+                position,
+                geng::TextAlign::CENTER,
+                size,
+                color,
+            );
+            position.y -= size * 1.2;
+        };
+
+        let locale = &self.locale;
+        line(locale.get("crash.title"), 60.0, Rgba::WHITE);
+        line(&crash.message, 20.0, Rgba::GRAY);
+        match &crash.location {
+            Ok(location) => line(
+                &locale.format("crash.saved", &[("location", location)]),
+                28.0,
+                Rgba::WHITE,
+            ),
+            Err(error) => line(error, 28.0, Rgba::RED),
+        }
+        line(locale.get("crash.attach"), 28.0, Rgba::WHITE);
+        let keys = controls
+            .keys(ControlAction::Rewind)
+            .iter()
+            .map(|key| format!("{:?}", key))
+            .collect::<Vec<_>>()
+            .join("/");
+        line(
+            &locale.format("crash.rewind", &[("keys", &keys)]),
+            24.0,
+            Rgba::GRAY,
+        );
+        line(locale.get("menu.return"), 24.0, Rgba::GRAY);
+    }
+
+    pub fn draw_game_over(
+        &self,
+        model: &Model,
+        game_over: &GameOver,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.locale.font(geng);
+
+        draw_2d::Quad::new(screen, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+
+        let line_height = 40.0;
+        let mut position = vec2(screen.center().x, screen.y_max - screen.height() / 5.0);
+        let mut line = |text: &str, size: f32, color: Rgba<f32>| {
+            font.draw(
+                framebuffer,
+                camera,
+                text,
+                position,
+                geng::TextAlign::CENTER,
+                size,
+                color,
+            );
+            position.y -= size;
+        };
+
+        let locale = &self.locale;
+        line(
+            locale.get("game_over.title"),
+            line_height * 1.5,
+            Rgba::WHITE,
+        );
+        line(
+            &locale.format(
+                "game_over.stats",
+                &[
+                    ("score", &game_over.score),
+                    ("wave", &model.world.wave_number),
+                    ("kills", &model.world.score.kills),
+                    ("combo", &model.world.score.best_combo),
+                    ("time", &format!("{:.0}", model.time().as_f32())),
+                ],
+            ),
+            line_height,
+            Rgba::WHITE,
+        );
+        line(
+            &locale.format("game_over.seed", &[("seed", &model.config.seed)]),
+            line_height * 0.6,
+            Rgba::GRAY,
+        );
+        if let Some(high_scores) = &game_over.high_scores {
+            line("", line_height * 0.5, Rgba::WHITE);
+            line(
+                locale.get("game_over.high_scores"),
+                line_height,
+                Rgba::WHITE,
+            );
+            for (index, score) in high_scores.scores.iter().enumerate() {
+                let color = if game_over.place == Some(index) {
+                    ui::SELECTED_COLOR
+                } else {
+                    Rgba::WHITE
+                };
+                line(
+                    &locale.format(
+                        "game_over.high_score",
+                        &[
+                            ("place", &(index + 1)),
+                            ("score", &score.score),
+                            ("wave", &score.wave),
+                            ("difficulty", &locale.get(score.difficulty.key())),
+                            ("mode", &locale.get(score.mode.key())),
+                            ("date", &score.date),
+                        ],
+                    ),
+                    line_height * 0.7,
+                    color,
+                );
+            }
+        }
+        for unlock in &game_over.unlocked {
+            line(
+                &locale.format("game_over.unlocked", &[("unlock", &unlock.name(locale))]),
+                line_height * 0.7,
+                ui::SELECTED_COLOR,
+            );
+        }
+        line("", line_height * 0.5, Rgba::WHITE);
+        line(locale.get("menu.return"), line_height * 0.6, Rgba::GRAY);
+
+        let stats_position = vec2(screen.x_min + 40.0, screen.y_max - screen.height() / 5.0);
+        self.draw_run_stats(&model.world.combat_log, stats_position, framebuffer);
+    }
+}
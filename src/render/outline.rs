@@ -0,0 +1,99 @@
+use super::*;
+
+/// Width of the outline in the sprite's texels.
+const OUTLINE_WIDTH: f32 = 2.0;
+const OUTLINE_ALPHA: f32 = 0.6;
+/// How far the outline of a hovered or aimed at unit is brightened towards white.
+const HIGHLIGHT: f32 = 0.5;
+
+#[derive(ugli::Vertex)]
+pub(super) struct OutlineVertex {
+    a_pos: Vec2<f32>,
+    a_vt: Vec2<f32>,
+}
+
+/// The unit quad the outlines are drawn with.
+pub(super) fn outline_quad(geng: &Geng) -> ugli::VertexBuffer<OutlineVertex> {
+    let vertex = |x: f32, y: f32| OutlineVertex {
+        a_pos: vec2(x - 0.5, y - 0.5),
+        a_vt: vec2(x, y),
+    };
+    ugli::VertexBuffer::new_static(
+        geng.ugli(),
+        vec![
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(1.0, 1.0),
+            vertex(0.0, 1.0),
+        ],
+    )
+}
+
+impl Render {
+    /// The team color of the unit, brighter while it is hovered or aimed at.
+    pub(super) fn outline_color(&self, unit: &Unit, sprite: &Sprite, model: &Model) -> Rgba<f32> {
+        let color = if unit.npc {
+            self.palette.go_here
+        } else {
+            match unit.faction {
+                Faction::Mech => self.palette.mech,
+                Faction::Alien => self.palette.enemy,
+            }
+        };
+        let bounds = AABB::point(model.world.bodies.positions[unit.id].map(|x| x.as_f32()))
+            .extend_symmetric(sprite.size / 2.0);
+        let mouse = self
+            .screen_to_world(self.geng.window().mouse_pos().map(|x| x as f32))
+            .map(|x| x.as_f32());
+        let aimed = model
+            .aim
+            .map_or(false, |aim| bounds.contains(aim.map(|x| x.as_f32())));
+        if aimed || (!self.touch_mode && bounds.contains(mouse)) {
+            let light = |value: f32| value + (1.0 - value) * HIGHLIGHT;
+            Rgba::new(light(color.r), light(color.g), light(color.b), 1.0)
+        } else {
+            with_alpha(color, OUTLINE_ALPHA)
+        }
+    }
+
+    /// Draws the outline around the opaque texels of the sprite.
+    pub(super) fn draw_outline(
+        &self,
+        sprite: &Sprite,
+        position: Position,
+        flip: bool,
+        color: Rgba<f32>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let texture = match sprite.texture() {
+            Some(texture) => texture,
+            None => return,
+        };
+        let texture_size = texture.size().map(|x| x as f32);
+        let border = sprite.size * OUTLINE_WIDTH / texture_size;
+        let size = sprite.size + border * 2.0;
+        let flip = if flip { -1.0 } else { 1.0 };
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        ugli::draw(
+            framebuffer,
+            &self.assets.shaders.outline,
+            ugli::DrawMode::TriangleFan,
+            &self.outline_quad,
+            (
+                ugli::uniforms! {
+                    u_texture: &*texture,
+                    u_color: color,
+                    u_texel: vec2(OUTLINE_WIDTH, OUTLINE_WIDTH) / texture_size,
+                    u_uv_scale: size / sprite.size,
+                    u_model_matrix: Mat3::translate(position.map(|x| x.as_f32()))
+                        * Mat3::scale(vec2(size.x * flip, size.y)),
+                },
+                geng::camera2d_uniforms(&self.camera, framebuffer_size),
+            ),
+            ugli::DrawParameters {
+                blend_mode: Some(default()),
+                ..default()
+            },
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+const CARD_SIZE: Vec2<f32> = Vec2 { x: 300.0, y: 160.0 };
+const CARD_SPACING: f32 = 40.0;
+const PREVIEW_COLOR: Rgba<f32> = Rgba {
+    r: 0.7,
+    g: 0.8,
+    b: 1.0,
+    a: 1.0,
+};
+
+impl Render {
+    /// Draws the perks offered to the player and remembers their cards for input.
+    pub(super) fn draw_perk_choice(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        self.perk_cards.clear();
+        let perks = match &model.world.perk_choice {
+            Some(perks) => perks,
+            None => return,
+        };
+
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let layout = self.layout;
+        let middle = layout.at(Anchor::Center, Vec2::ZERO);
+        let card_size = CARD_SIZE * layout.scale;
+        let spacing = layout.size(CARD_SPACING);
+        let font = self.locale.font(geng);
+        let mouse_pos = geng.window().mouse_pos().map(|x| x as f32);
+
+        draw_2d::Quad::new(screen, Rgba::new(0.0, 0.0, 0.0, 0.5)).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+        font.draw(
+            framebuffer,
+            camera,
+            self.locale.get("perks.title"),
+            middle + vec2(0.0, card_size.y),
+            geng::TextAlign::CENTER,
+            layout.size(48.0),
+            Rgba::WHITE,
+        );
+
+        let total_width = perks.len() as f32 * (card_size.x + spacing) - spacing;
+        for (index, perk) in perks.iter().enumerate() {
+            let center = vec2(
+                middle.x - total_width / 2.0
+                    + card_size.x / 2.0
+                    + index as f32 * (card_size.x + spacing),
+                middle.y,
+            );
+            let aabb = AABB::point(center).extend_symmetric(card_size / 2.0);
+            let key = perk_key(&perk.name);
+            let color = if aabb.contains(mouse_pos) {
+                HOVERED_COLOR
+            } else {
+                Rgba::new(0.2, 0.2, 0.25, 0.9)
+            };
+            draw_2d::Quad::new(aabb, color).draw_2d(geng, framebuffer, camera);
+            font.draw(
+                framebuffer,
+                camera,
+                &self.locale.format(
+                    "perks.card",
+                    &[("index", &(index + 1)), ("name", &self.locale.get(&key))],
+                ),
+                vec2(center.x, aabb.y_max - layout.size(40.0)),
+                geng::TextAlign::CENTER,
+                layout.size(28.0),
+                Rgba::WHITE,
+            );
+            font.draw(
+                framebuffer,
+                camera,
+                self.locale.get(&format!("{}.description", key)),
+                vec2(center.x, center.y - layout.size(20.0)),
+                geng::TextAlign::CENTER,
+                layout.size(18.0),
+                Rgba::WHITE,
+            );
+            if let Some(preview) = perk.preview() {
+                font.draw(
+                    framebuffer,
+                    camera,
+                    &self.locale.preview(&preview),
+                    vec2(center.x, aabb.y_min + layout.size(20.0)),
+                    geng::TextAlign::CENTER,
+                    layout.size(16.0),
+                    PREVIEW_COLOR,
+                );
+            }
+            self.perk_cards.push(aabb);
+        }
+    }
+}
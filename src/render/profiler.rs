@@ -0,0 +1,80 @@
+use super::*;
+
+/// Height of the graph bars per millisecond.
+const PIXELS_PER_MS: f32 = 4.0;
+const BAR_WIDTH: f32 = 3.0;
+/// Frame budget marked on the graph, in milliseconds.
+const BUDGET_MS: f32 = 1000.0 / 60.0;
+
+impl Render {
+    /// Draws a graph of the time spent in each phase over the last frames.
+    pub fn draw_profiler(&self, profiler: &Profiler, framebuffer: &mut ugli::Framebuffer) {
+        if !self.show_profiler {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let origin = vec2(screen.x_min + 10.0, screen.y_min + 10.0);
+        let graph = AABB::point(origin).extend_positive(vec2(
+            BAR_WIDTH * profiler.frames.len().max(1) as f32,
+            BUDGET_MS * PIXELS_PER_MS * 1.5,
+        ));
+        draw_2d::Quad::new(graph, Rgba::new(0.0, 0.0, 0.0, 0.6)).draw_2d(geng, framebuffer, camera);
+
+        for (index, frame) in profiler.frames.iter().enumerate() {
+            let x = origin.x + index as f32 * BAR_WIDTH;
+            let mut y = origin.y;
+            for phase in ProfilePhase::ALL {
+                let height = frame.get(phase) as f32 * 1000.0 * PIXELS_PER_MS;
+                let bar = AABB {
+                    x_min: x,
+                    x_max: x + BAR_WIDTH,
+                    y_min: y,
+                    y_max: (y + height).min(graph.y_max),
+                };
+                draw_2d::Quad::new(bar, phase_color(phase)).draw_2d(geng, framebuffer, camera);
+                y += height;
+            }
+        }
+        let budget = origin.y + BUDGET_MS * PIXELS_PER_MS;
+        let line = Chain::new(vec![vec2(graph.x_min, budget), vec2(graph.x_max, budget)]);
+        draw_2d::Chain::new(line, 1.0, Rgba::WHITE, 0).draw_2d(geng, framebuffer, camera);
+
+        // Legend
+        let average = profiler.average();
+        for (index, phase) in ProfilePhase::ALL.into_iter().enumerate() {
+            geng.default_font().draw(
+                framebuffer,
+                camera,
+                &format!("{}: {:.2} ms", phase.name(), average.get(phase) * 1000.0),
+                vec2(graph.x_max + 10.0, graph.y_min + index as f32 * 18.0),
+                geng::TextAlign::LEFT,
+                16.0,
+                phase_color(phase),
+            );
+        }
+        if profiler.is_tracing() {
+            geng.default_font().draw(
+                framebuffer,
+                camera,
+                "Recording trace",
+                vec2(graph.x_min, graph.y_max + 5.0),
+                geng::TextAlign::LEFT,
+                16.0,
+                Rgba::RED,
+            );
+        }
+    }
+}
+
+fn phase_color(phase: ProfilePhase) -> Rgba<f32> {
+    match phase {
+        ProfilePhase::Ai => Rgba::new(0.9, 0.4, 0.9, 1.0),
+        ProfilePhase::Movement => Rgba::new(0.3, 0.6, 1.0, 1.0),
+        ProfilePhase::Collision => Rgba::new(1.0, 0.4, 0.3, 1.0),
+        ProfilePhase::Effects => Rgba::new(1.0, 0.9, 0.3, 1.0),
+        ProfilePhase::Particles => Rgba::new(0.5, 0.9, 0.5, 1.0),
+        ProfilePhase::Render => Rgba::new(0.7, 0.7, 0.7, 1.0),
+    }
+}
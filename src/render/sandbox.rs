@@ -0,0 +1,125 @@
+use super::*;
+
+/// Distance of the palette from the top of the HUD, below the score and the objectives.
+const PALETTE_TOP: f32 = 200.0;
+const LINE_HEIGHT: f32 = 24.0;
+const SELECTED_COLOR: Rgba<f32> = Rgba {
+    r: 1.0,
+    g: 0.85,
+    b: 0.3,
+    a: 1.0,
+};
+/// Height of the damage meter above the center of a dummy, in world units.
+const METER_OFFSET: f32 = 1.5;
+
+impl Render {
+    /// Draws the palette of the sandbox and its time scale.
+    pub fn draw_sandbox(
+        &self,
+        model: &Model,
+        controls: &Controls,
+        selected: usize,
+        time_scale: f64,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        if !model.config.mode.is_sandbox() {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let layout = self.layout;
+        let font = self.locale.font(geng);
+        let keys = |action: ControlAction| {
+            controls
+                .keys(action)
+                .iter()
+                .map(|key| format!("{:?}", key))
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+
+        let mut lines = vec![
+            (
+                self.locale.format(
+                    "sandbox.time_scale",
+                    &[
+                        ("scale", &time_scale),
+                        ("slower", &keys(ControlAction::SlowDown)),
+                        ("faster", &keys(ControlAction::SpeedUp)),
+                    ],
+                ),
+                Rgba::WHITE,
+            ),
+            (
+                self.locale.format(
+                    "sandbox.help",
+                    &[
+                        ("next", &keys(ControlAction::NextSandboxUnit)),
+                        ("spawn", &keys(ControlAction::SpawnSandboxUnit)),
+                    ],
+                ),
+                Rgba::GRAY,
+            ),
+        ];
+        for (index, (template, _)) in model
+            .world
+            .templates
+            .sandbox_palette()
+            .into_iter()
+            .enumerate()
+        {
+            let name = self.locale.get(&format!("unit.{}", template.name));
+            let color = if index == selected {
+                SELECTED_COLOR
+            } else {
+                Rgba::WHITE
+            };
+            lines.push((name.to_owned(), color));
+        }
+        for (row, (text, color)) in lines.iter().enumerate() {
+            font.draw(
+                framebuffer,
+                camera,
+                text,
+                layout.at(
+                    Anchor::TopLeft,
+                    vec2(10.0, -PALETTE_TOP - row as f32 * LINE_HEIGHT),
+                ),
+                geng::TextAlign::LEFT,
+                layout.size(20.0),
+                *color,
+            );
+        }
+    }
+
+    /// Draws the damage per second and the total damage above the dummies being hit.
+    pub(super) fn draw_dps_meters(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let font = self.locale.font(geng);
+        for (id, meter) in &model.world.dps_meters {
+            let dummy = match model.world.units.get(id) {
+                Some(unit) => unit,
+                None => continue,
+            };
+            let position = model.world.bodies.positions[dummy.id]
+                + vec2(Coord::ZERO, Coord::new(METER_OFFSET));
+            let text = self.locale.format(
+                "sandbox.dps",
+                &[
+                    ("dps", &format!("{:.1}", meter.dps())),
+                    ("total", &format!("{:.0}", meter.total)),
+                ],
+            );
+            font.draw(
+                framebuffer,
+                camera,
+                &text,
+                self.world_to_screen(position),
+                geng::TextAlign::CENTER,
+                self.layout.size(20.0),
+                Rgba::WHITE,
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use super::*;
+
+/// Half the size of the stick's base, in unscaled pixels.
+const STICK_RADIUS: f32 = 100.0;
+/// Distance of the stick's center from the bottom left corner of the HUD.
+const STICK_OFFSET: f32 = 160.0;
+const BUTTON_SIZE: f32 = 110.0;
+/// How far from the mechs the full deflection of the stick aims, in world units.
+const AIM_RANGE: f32 = 30.0;
+/// The actions of the buttons, from the bottom right corner up.
+const BUTTONS: [ControlAction; 4] = [
+    ControlAction::Block,
+    ControlAction::Glide,
+    ControlAction::Reload,
+    ControlAction::Interact,
+];
+
+/// The on-screen stick and buttons of the touch mode.
+#[derive(Debug, Default)]
+pub struct TouchControls {
+    /// The fingers currently on the screen.
+    touches: Vec<Vec2<f32>>,
+    stick: AABB<f32>,
+    buttons: Vec<(ControlAction, AABB<f32>)>,
+}
+
+impl TouchControls {
+    /// Remembers the fingers from a touch event.
+    pub fn update(&mut self, touches: &[geng::TouchPoint]) {
+        self.touches = touches
+            .iter()
+            .map(|touch| touch.position.map(|x| x as f32))
+            .collect();
+    }
+
+    pub fn release(&mut self) {
+        self.touches.clear();
+    }
+
+    /// Whether the screen position is close enough to the stick to move it.
+    pub fn on_stick(&self, position: Vec2<f32>) -> bool {
+        self.stick
+            .extend_uniform(self.stick.width() / 2.0)
+            .contains(position)
+    }
+
+    /// The deflection of the stick, up to 1 in length, while a finger holds it.
+    pub fn stick(&self) -> Option<Vec2<f32>> {
+        let center = self.stick.center();
+        let radius = self.stick.width() / 2.0;
+        self.touches
+            .iter()
+            .find(|&&touch| self.on_stick(touch))
+            .map(|&touch| {
+                let delta = (touch - center) / radius;
+                if delta.len() > 1.0 {
+                    delta.normalize()
+                } else {
+                    delta
+                }
+            })
+    }
+
+    /// Whether a finger holds the button of the action.
+    pub fn is_held(&self, action: ControlAction) -> bool {
+        self.touches
+            .iter()
+            .any(|&touch| self.button(touch) == Some(action))
+    }
+
+    /// The action of the button at the screen position.
+    pub fn button(&self, position: Vec2<f32>) -> Option<ControlAction> {
+        self.buttons
+            .iter()
+            .find(|(_, aabb)| aabb.extend_uniform(TOUCH_PADDING).contains(position))
+            .map(|&(action, _)| action)
+    }
+}
+
+impl Render {
+    /// The world position the stick aims at, relative to the middle of the mechs.
+    pub fn touch_aim(&self, model: &Model) -> Option<Position> {
+        let stick = self.touch.stick()?;
+        let mechs: Vec<Position> = model
+            .world
+            .units
+            .iter()
+            .filter(|unit| unit.faction == Faction::Mech)
+            .map(|unit| model.world.bodies.positions[unit.id])
+            .collect();
+        let center = if mechs.is_empty() {
+            vec2(model.world.left_border, model.world.ground_level)
+        } else {
+            mechs.iter().copied().fold(Position::ZERO, Position::add)
+                / Coord::new(mechs.len() as f32)
+        };
+        Some(center + stick.map(|x| Coord::new(x * AIM_RANGE)))
+    }
+
+    pub fn draw_touch_controls(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        if !self.touch_mode {
+            return;
+        }
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let layout = self.layout;
+        let font = self.locale.font(geng);
+        let back = Rgba::new(0.0, 0.0, 0.0, 0.5);
+
+        let radius = layout.size(STICK_RADIUS);
+        let stick = AABB::point(layout.at(Anchor::BottomLeft, vec2(STICK_OFFSET, STICK_OFFSET)))
+            .extend_uniform(radius);
+        self.touch.stick = stick;
+        draw_2d::Quad::new(stick, back).draw_2d(geng, framebuffer, camera);
+        let knob = stick.center() + self.touch.stick().unwrap_or(Vec2::ZERO) * radius;
+        draw_2d::Quad::new(AABB::point(knob).extend_uniform(radius * 0.4), Rgba::WHITE).draw_2d(
+            geng,
+            framebuffer,
+            camera,
+        );
+
+        let size = layout.size(BUTTON_SIZE);
+        self.touch.buttons.clear();
+        for (index, action) in BUTTONS.into_iter().enumerate() {
+            let aabb = AABB::point(layout.at(
+                Anchor::BottomRight,
+                vec2(-BUTTON_SIZE, BUTTON_SIZE * (1.0 + 1.2 * index as f32)),
+            ))
+            .extend_uniform(size / 2.0);
+            let color = if self.touch.is_held(action) {
+                HOVERED_COLOR
+            } else {
+                back
+            };
+            draw_2d::Quad::new(aabb, color).draw_2d(geng, framebuffer, camera);
+            font.draw(
+                framebuffer,
+                camera,
+                self.locale.get(action.key()),
+                aabb.center(),
+                geng::TextAlign::CENTER,
+                layout.size(20.0),
+                Rgba::WHITE,
+            );
+            self.touch.buttons.push((action, aabb));
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+const PROMPT_SIZE: f32 = 32.0;
+
+impl Render {
+    /// Draws the tutorial prompt with the keys bound to its action.
+    pub fn draw_tutorial_prompt(
+        &self,
+        model: &Model,
+        controls: &Controls,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let step = match &model.world.tutorial_prompt {
+            Some(step) => step,
+            None => return,
+        };
+        let geng = &self.geng;
+        let camera = &geng::PixelPerfectCamera;
+        let layout = self.layout;
+        let area = layout.area;
+        let keys = step
+            .action
+            .controls()
+            .iter()
+            .flat_map(|&action| controls.keys(action))
+            .map(|key| format!("{:?}", key))
+            .collect::<Vec<_>>()
+            .join("/");
+        let text = self.locale.format(&step.prompt, &[("keys", &keys)]);
+
+        let size = layout.size(PROMPT_SIZE);
+        let position = vec2(area.center().x, area.y_max - area.height() * 0.3);
+        let aabb = AABB::point(position).extend_symmetric(vec2(area.width() * 0.3, size));
+        draw_2d::Quad::new(aabb, Rgba::new(0.0, 0.0, 0.0, 0.7)).draw_2d(geng, framebuffer, camera);
+        self.locale.font(geng).draw(
+            framebuffer,
+            camera,
+            &text,
+            position - vec2(0.0, size / 3.0),
+            geng::TextAlign::CENTER,
+            size,
+            Rgba::WHITE,
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+use logic::TICK_TIME;
+
+/// Layers of the weather particles, from the nearest to the farthest.
+const WEATHER_LAYERS: usize = 3;
+const PARTICLES_PER_LAYER: usize = 50;
+/// How much smaller, slower and fainter each next layer is.
+const LAYER_DEPTH: f32 = 0.3;
+/// Screen widths the wind carries the particles per unit of wind and second.
+const WIND_DRIFT: f32 = 0.05;
+const FOG_BANDS: usize = 4;
+const FOG_COLOR: Rgba<f32> = Rgba {
+    r: 0.7,
+    g: 0.75,
+    b: 0.8,
+    a: 0.15,
+};
+
+/// How the particles of the weather look and move.
+struct ParticleStyle {
+    /// Screen heights per second.
+    fall_speed: f32,
+    /// Size in pixels on the nearest layer.
+    size: Vec2<f32>,
+    color: Rgba<f32>,
+    /// Screen widths of the side to side motion.
+    sway: f32,
+}
+
+/// Random starting points of the weather particles on the screen, from 0 to 1.
+/// The particles move as a function of the simulation time, so they stop with the game.
+pub struct WeatherLayers {
+    seeds: Vec<Vec2<f32>>,
+}
+
+impl Default for WeatherLayers {
+    fn default() -> Self {
+        let mut rng = global_rng();
+        Self {
+            seeds: (0..WEATHER_LAYERS * PARTICLES_PER_LAYER)
+                .map(|_| vec2(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)))
+                .collect(),
+        }
+    }
+}
+
+impl Render {
+    /// Draws the weather of the level over the world, in the screen space.
+    pub(super) fn draw_weather(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let style = match model.world.weather {
+            Weather::Clear => return,
+            Weather::Fog => {
+                self.draw_fog(model, framebuffer);
+                return;
+            }
+            Weather::Rain => ParticleStyle {
+                fall_speed: 1.5,
+                size: vec2(2.0, 16.0),
+                color: Rgba::new(0.6, 0.7, 1.0, 0.5),
+                sway: 0.0,
+            },
+            Weather::Snow => ParticleStyle {
+                fall_speed: 0.12,
+                size: vec2(5.0, 5.0),
+                color: Rgba::new(1.0, 1.0, 1.0, 0.8),
+                sway: 0.01,
+            },
+            Weather::Ash => ParticleStyle {
+                fall_speed: 0.08,
+                size: vec2(4.0, 3.0),
+                color: Rgba::new(0.3, 0.28, 0.27, 0.8),
+                sway: 0.02,
+            },
+        };
+        let screen = framebuffer.size().map(|x| x as f32);
+        let time = model.world.current_tick as f32 * TICK_TIME;
+        let wind = model.world.weather.wind().as_f32() * WIND_DRIFT;
+        let camera_width = self.camera.fov * screen.x / screen.y;
+        let scroll = self.camera.center.x / camera_width;
+        for (index, seed) in self.weather.seeds.iter().enumerate() {
+            let depth = 1.0 - LAYER_DEPTH * (index % WEATHER_LAYERS) as f32;
+            let sway = style.sway * (time * 2.0 + seed.y * 10.0).sin();
+            // The nearer layers move faster with the camera
+            let x = (seed.x + wind * time * depth + sway - scroll * depth).rem_euclid(1.0);
+            let y = (seed.y - style.fall_speed * time * depth).rem_euclid(1.0);
+            let quad = AABB::point(vec2(x, y) * screen).extend_symmetric(style.size * depth / 2.0);
+            let color = with_alpha(style.color, style.color.a * depth);
+            draw_2d::Quad::new(quad, color).draw_2d(
+                &self.geng,
+                framebuffer,
+                &geng::PixelPerfectCamera,
+            );
+        }
+    }
+
+    /// Slowly drifting bands of haze.
+    fn draw_fog(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let screen = framebuffer.size().map(|x| x as f32);
+        let time = model.world.current_tick as f32 * TICK_TIME;
+        for band in 0..FOG_BANDS {
+            let phase = band as f32 / FOG_BANDS as f32;
+            let height = screen.y * (0.15 + 0.2 * phase);
+            let y = screen.y * (0.1 + 0.5 * phase) + 20.0 * (time * 0.3 + phase * 5.0).sin();
+            let quad = AABB::point(vec2(screen.x / 2.0, y))
+                .extend_symmetric(vec2(screen.x / 2.0, height / 2.0));
+            draw_2d::Quad::new(quad, FOG_COLOR).draw_2d(
+                &self.geng,
+                framebuffer,
+                &geng::PixelPerfectCamera,
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+use serde::de::DeserializeOwned;
+
+/// Prefix of the keys, so that other games on the same site are not affected.
+#[cfg(target_arch = "wasm32")]
+const KEY_PREFIX: &str = "wowie-jam-4.";
+
+/// Keeps the settings, the profile and the high scores between sessions.
+pub trait Storage {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&self, key: &str, value: &str) -> Result<(), String>;
+}
+
+/// Keeps every key in its own json file in the `save` directory next to the executable.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(format!("{}.json", key))).ok()
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|error| error.to_string())?;
+        std::fs::write(self.dir.join(format!("{}.json", key)), value)
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// Keeps the keys in the browser's local storage, which lasts between visits of the page.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    storage: Option<web_sys::Storage>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        let storage = self.storage.as_ref()?;
+        storage
+            .get_item(&format!("{}{}", KEY_PREFIX, key))
+            .ok()
+            .flatten()
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), String> {
+        let storage = self
+            .storage
+            .as_ref()
+            .ok_or_else(|| "local storage is not available".to_owned())?;
+        storage
+            .set_item(&format!("{}{}", KEY_PREFIX, key), value)
+            .map_err(|error| format!("{:?}", error))
+    }
+}
+
+/// The storage of the current platform.
+#[cfg(not(target_arch = "wasm32"))]
+fn storage() -> impl Storage {
+    FileStorage {
+        dir: run_dir().join("save"),
+    }
+}
+
+/// The storage of the current platform.
+#[cfg(target_arch = "wasm32")]
+fn storage() -> impl Storage {
+    LocalStorage {
+        // Not available when the browser blocks the site data, e.g. in private windows
+        storage: web_sys::window().and_then(|window| window.local_storage().ok().flatten()),
+    }
+}
+
+/// Loads the value saved under the key, falling back to the preferences of the older versions.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let saved = storage()
+        .read(key)
+        .and_then(|json| match serde_json::from_str(&json) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                warn!("Failed to parse the saved {}: {}", key, error);
+                None
+            }
+        });
+    saved.or_else(|| batbox::preferences::load(key))
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(|error| error.to_string())
+        .and_then(|json| storage().write(key, &json));
+    if let Err(error) = result {
+        error!("Failed to save the {}: {}", key, error);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_keep_the_latest_value_of_every_key() {
+        let dir = std::env::temp_dir().join(format!("wowie-jam-4-storage-{}", std::process::id()));
+        // The directory is created by the first write
+        let storage = FileStorage {
+            dir: dir.join("save"),
+        };
+        assert_eq!(storage.read("profile"), None);
+        storage.write("profile", "{}").unwrap();
+        storage.write("high_scores", "[1]").unwrap();
+        storage.write("profile", r#"{"level":2}"#).unwrap();
+        assert_eq!(storage.read("profile").as_deref(), Some(r#"{"level":2}"#));
+        assert_eq!(storage.read("high_scores").as_deref(), Some("[1]"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
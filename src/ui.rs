@@ -0,0 +1,320 @@
+use super::*;
+
+use geng::Draw2d;
+
+pub const SELECTED_COLOR: Rgba<f32> = Rgba {
+    r: 0.17,
+    g: 0.85,
+    b: 1.0,
+    a: 1.0,
+};
+/// Width of the slider bar relative to the row.
+const SLIDER_WIDTH: f32 = 0.4;
+/// Height of the slider bar relative to the text.
+const SLIDER_HEIGHT: f32 = 0.12;
+
+/// An input the widgets react to, the devices are translated into these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    NextTab,
+}
+
+impl UiInput {
+    pub fn from_key(key: geng::Key) -> Option<Self> {
+        match key {
+            geng::Key::Up | geng::Key::W => Some(Self::Up),
+            geng::Key::Down | geng::Key::S => Some(Self::Down),
+            geng::Key::Left | geng::Key::A => Some(Self::Left),
+            geng::Key::Right | geng::Key::D => Some(Self::Right),
+            geng::Key::Enter | geng::Key::Space => Some(Self::Confirm),
+            geng::Key::Tab => Some(Self::NextTab),
+            _ => None,
+        }
+    }
+}
+
+/// A row of the menu, rebuilt from the state it shows every frame.
+#[derive(Debug, Clone)]
+pub struct Widget {
+    pub label: String,
+    pub kind: WidgetKind,
+    /// Shown under the menu while the widget is selected.
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum WidgetKind {
+    Button,
+    Toggle {
+        on: bool,
+    },
+    /// Cycles through the options, showing the current one.
+    Choice {
+        value: String,
+    },
+    /// Steps through the values, `fill` is the position of the value from 0 to 1.
+    Slider {
+        value: String,
+        fill: f32,
+    },
+}
+
+impl Widget {
+    pub fn button(label: impl Into<String>) -> Self {
+        Self::new(label, WidgetKind::Button)
+    }
+
+    pub fn toggle(label: impl Into<String>, on: bool) -> Self {
+        Self::new(label, WidgetKind::Toggle { on })
+    }
+
+    pub fn choice(label: impl Into<String>, value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self::new(label, WidgetKind::Choice { value })
+    }
+
+    /// A slider over the values, with the current one at `index`.
+    pub fn slider(
+        label: impl Into<String>,
+        value: impl Into<String>,
+        index: usize,
+        count: usize,
+    ) -> Self {
+        let fill = index as f32 / (count.max(2) - 1) as f32;
+        let value = value.into();
+        Self::new(label, WidgetKind::Slider { value, fill })
+    }
+
+    pub fn with_hint(self, hint: impl Into<String>) -> Self {
+        Self {
+            hint: Some(hint.into()),
+            ..self
+        }
+    }
+
+    fn new(label: impl Into<String>, kind: WidgetKind) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            hint: None,
+        }
+    }
+
+    fn text(&self, selected: bool) -> String {
+        let text = match &self.kind {
+            WidgetKind::Button => return self.label.clone(),
+            WidgetKind::Toggle { on } => {
+                format!("{}: [{}]", self.label, if *on { "x" } else { " " })
+            }
+            WidgetKind::Choice { value } | WidgetKind::Slider { value, .. } => {
+                format!("{}: {}", self.label, value)
+            }
+        };
+        if selected {
+            format!("< {} >", text)
+        } else {
+            text
+        }
+    }
+}
+
+/// What the player did with the menu, the owner of the menu applies it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiEvent {
+    /// The widget was confirmed.
+    Pressed(usize),
+    /// The value of the widget was stepped, a toggle is flipped with any change.
+    Changed { widget: usize, delta: isize },
+    /// Another tab was opened.
+    TabChanged,
+}
+
+/// Where and how large the menu is drawn.
+#[derive(Debug, Clone, Copy)]
+pub struct MenuStyle {
+    /// Center of the first row.
+    pub top: Vec2<f32>,
+    pub width: f32,
+    pub spacing: f32,
+    pub text_size: f32,
+}
+
+/// The selection and the drawn positions of a list of widgets split into tabs.
+#[derive(Debug, Default)]
+pub struct Menu {
+    pub tab: usize,
+    pub selected: usize,
+    /// Drawn positions of the rows, for the clicks.
+    rows: Vec<AABB<f32>>,
+    tabs: Vec<AABB<f32>>,
+}
+
+impl Menu {
+    pub fn handle_input(
+        &mut self,
+        input: UiInput,
+        tabs: usize,
+        widgets: &[Widget],
+    ) -> Option<UiEvent> {
+        if widgets.is_empty() && input != UiInput::NextTab {
+            return None;
+        }
+        let count = widgets.len();
+        match input {
+            UiInput::Up => self.selected = (self.selected + count - 1) % count,
+            UiInput::Down => self.selected = (self.selected + 1) % count,
+            UiInput::Left | UiInput::Right => {
+                let delta = if input == UiInput::Left { -1 } else { 1 };
+                return self.change(widgets, self.selected, delta);
+            }
+            UiInput::Confirm => {
+                if let WidgetKind::Toggle { .. } = widgets.get(self.selected)?.kind {
+                    return self.change(widgets, self.selected, 1);
+                }
+                return Some(UiEvent::Pressed(self.selected));
+            }
+            UiInput::NextTab => {
+                if tabs > 1 {
+                    self.open_tab((self.tab + 1) % tabs);
+                    return Some(UiEvent::TabChanged);
+                }
+            }
+        }
+        None
+    }
+
+    /// Handles a click or a touch at the screen position, using the rows of the last drawn frame.
+    pub fn handle_click(&mut self, position: Vec2<f32>, widgets: &[Widget]) -> Option<UiEvent> {
+        if let Some(tab) = self.tabs.iter().position(|aabb| aabb.contains(position)) {
+            self.open_tab(tab);
+            return Some(UiEvent::TabChanged);
+        }
+        let index = self.rows.iter().position(|row| row.contains(position))?;
+        self.selected = index;
+        match widgets.get(index)?.kind {
+            WidgetKind::Button => Some(UiEvent::Pressed(index)),
+            WidgetKind::Toggle { .. } => self.change(widgets, index, 1),
+            _ => {
+                // The left half of the row steps back
+                let delta = if position.x < self.rows[index].center().x {
+                    -1
+                } else {
+                    1
+                };
+                self.change(widgets, index, delta)
+            }
+        }
+    }
+
+    fn change(&self, widgets: &[Widget], widget: usize, delta: isize) -> Option<UiEvent> {
+        match widgets.get(widget)?.kind {
+            WidgetKind::Button => None,
+            _ => Some(UiEvent::Changed { widget, delta }),
+        }
+    }
+
+    fn open_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        self.selected = 0;
+    }
+
+    /// Draws the tab titles above the widgets of the open tab, and the hint of the selected one.
+    pub fn draw(
+        &mut self,
+        geng: &Geng,
+        font: &geng::Font,
+        tabs: &[String],
+        widgets: &[Widget],
+        style: MenuStyle,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let camera = &geng::PixelPerfectCamera;
+        let mut position = style.top;
+        let row_size = vec2(style.width, style.spacing) / 2.0;
+
+        self.tabs.clear();
+        if tabs.len() > 1 {
+            let tab_width = style.width / tabs.len() as f32;
+            for (index, title) in tabs.iter().enumerate() {
+                let center = vec2(
+                    position.x - style.width / 2.0 + tab_width * (index as f32 + 0.5),
+                    position.y,
+                );
+                let aabb =
+                    AABB::point(center).extend_symmetric(vec2(tab_width, style.spacing) / 2.0);
+                let color = if index == self.tab {
+                    SELECTED_COLOR
+                } else {
+                    Rgba::GRAY
+                };
+                font.draw(
+                    framebuffer,
+                    camera,
+                    title,
+                    center - vec2(0.0, style.text_size / 3.0),
+                    geng::TextAlign::CENTER,
+                    style.text_size,
+                    color,
+                );
+                self.tabs.push(aabb);
+            }
+            position.y -= style.spacing * 1.5;
+        }
+
+        self.selected = self.selected.min(widgets.len().saturating_sub(1));
+        self.rows.clear();
+        for (index, widget) in widgets.iter().enumerate() {
+            let selected = index == self.selected;
+            let color = if selected { Rgba::WHITE } else { Rgba::GRAY };
+            font.draw(
+                framebuffer,
+                camera,
+                &widget.text(selected),
+                position - vec2(0.0, style.text_size / 3.0),
+                geng::TextAlign::CENTER,
+                style.text_size,
+                color,
+            );
+            if let WidgetKind::Slider { fill, .. } = widget.kind {
+                let bar = AABB::point(position - vec2(0.0, style.text_size * 0.6))
+                    .extend_symmetric(
+                        vec2(style.width * SLIDER_WIDTH, style.text_size * SLIDER_HEIGHT) / 2.0,
+                    );
+                draw_2d::Quad::new(bar, Rgba::new(0.3, 0.3, 0.3, 1.0)).draw_2d(
+                    geng,
+                    framebuffer,
+                    camera,
+                );
+                let filled = AABB {
+                    x_max: bar.x_min + bar.width() * fill,
+                    ..bar
+                };
+                let color = if selected { SELECTED_COLOR } else { color };
+                draw_2d::Quad::new(filled, color).draw_2d(geng, framebuffer, camera);
+            }
+            self.rows
+                .push(AABB::point(position).extend_symmetric(row_size));
+            position.y -= style.spacing;
+        }
+
+        if let Some(hint) = widgets
+            .get(self.selected)
+            .and_then(|widget| widget.hint.as_ref())
+        {
+            font.draw(
+                framebuffer,
+                camera,
+                hint,
+                position - vec2(0.0, style.text_size / 3.0),
+                geng::TextAlign::CENTER,
+                style.text_size * 0.7,
+                Rgba::WHITE,
+            );
+        }
+    }
+}
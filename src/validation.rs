@@ -0,0 +1,268 @@
+use super::*;
+
+use assets::PixelTexture;
+use model::{
+    local_transform, Animation, AnimationId, Animations, Effect, EffectNode, Falloff, UnitAI,
+    UnitTemplate, UnitTemplates,
+};
+
+/// Shown instead of the main menu when the validation fails, lists the problems.
+pub struct InvalidAssets {
+    geng: Geng,
+    problems: Vec<String>,
+}
+
+impl InvalidAssets {
+    pub fn new(geng: &Geng, problems: Vec<String>) -> Self {
+        Self {
+            geng: geng.clone(),
+            problems,
+        }
+    }
+}
+
+impl geng::State for InvalidAssets {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None);
+        let camera = &geng::PixelPerfectCamera;
+        let screen = AABB::ZERO.extend_positive(framebuffer.size().map(|x| x as f32));
+        let font = self.geng.default_font();
+
+        let mut position = vec2(screen.center().x, screen.y_max - 80.0);
+        font.draw(
+            framebuffer,
+            camera,
+            "The assets are invalid",
+            position,
+            geng::TextAlign::CENTER,
+            40.0,
+            Rgba::RED,
+        );
+        position.y -= 60.0;
+        for problem in &self.problems {
+            if position.y < screen.y_min {
+                break;
+            }
+            font.draw(
+                framebuffer,
+                camera,
+                problem,
+                position,
+                geng::TextAlign::CENTER,
+                20.0,
+                Rgba::WHITE,
+            );
+            position.y -= 24.0;
+        }
+    }
+}
+
+/// Checks the loaded assets and the unit templates built from them,
+/// returns every problem found instead of stopping at the first one.
+pub fn validate(assets: &Rc<Assets>) -> Vec<String> {
+    let mut problems = vec![];
+    validate_textures(assets, &mut problems);
+    if !problems.is_empty() {
+        // The templates are built from the textures and would fail to build as well
+        return problems;
+    }
+    let mut animations = Animations::new();
+    let templates = UnitTemplates::new(assets, &mut animations);
+    for (name, template) in templates
+        .named()
+        .into_iter()
+        .chain(templates.named_allies())
+        .chain(
+            templates
+                .modded
+                .iter()
+                .map(|template| (template.name, template)),
+        )
+    {
+        validate_template(name, template, &animations, &mut problems);
+    }
+    let mut effects: Vec<_> = assets.mods.effects.iter().collect();
+    effects.sort_by_key(|&(name, _)| name);
+    for (name, effect) in effects {
+        if let Err(error) = effect.resolve(&animations) {
+            problems.push(format!("mod effect {}: {}", name, error));
+        }
+    }
+    problems
+}
+
+fn validate_textures(assets: &Assets, problems: &mut Vec<String>) {
+    let frames: [(&str, &[PixelTexture]); 17] = [
+        ("effects/heal", &assets.effects.heal),
+        ("enemies/blighter/attack", &assets.enemies.blighter.attack),
+        ("enemies/blighter/walk", &assets.enemies.blighter.walk),
+        (
+            "enemies/ravager/anticipation",
+            &assets.enemies.ravager.anticipation,
+        ),
+        ("enemies/ravager/attack", &assets.enemies.ravager.attack),
+        ("enemies/ravager/charge", &assets.enemies.ravager.charge),
+        ("enemies/ravager/roar", &assets.enemies.ravager.roar),
+        ("enemies/ravager/walk", &assets.enemies.ravager.walk),
+        ("enemies/stinger/attack", &assets.enemies.stinger.attack),
+        ("enemies/stinger/walk", &assets.enemies.stinger.walk),
+        ("mech/artillery/attack", &assets.mech.artillery.attack),
+        ("mech/artillery/walk", &assets.mech.artillery.walk),
+        (
+            "mech/artillery/projectile_anim",
+            &assets.mech.artillery.projectile_anim,
+        ),
+        ("mech/healer/heal", &assets.mech.healer.heal),
+        ("mech/healer/walk", &assets.mech.healer.walk),
+        ("mech/tank/attack", &assets.mech.tank.attack),
+        ("mech/tank/walk", &assets.mech.tank.walk),
+    ];
+    for (path, textures) in frames {
+        let first = match textures.first() {
+            Some(first) => first.size(),
+            None => {
+                problems.push(format!("static/{}: animation has no frames", path));
+                continue;
+            }
+        };
+        for (index, texture) in textures.iter().enumerate() {
+            let file = format!("static/{}/{}.png", path, index + 1);
+            let size = texture.size();
+            if size.x == 0 || size.y == 0 {
+                problems.push(format!("{}: texture is empty", file));
+            } else if size != first {
+                problems.push(format!(
+                    "{}: frame size {}x{} differs from the first frame's {}x{}",
+                    file, size.x, size.y, first.x, first.y
+                ));
+            }
+        }
+    }
+}
+
+fn validate_template(
+    name: &str,
+    template: &UnitTemplate,
+    store: &Animations,
+    problems: &mut Vec<String>,
+) {
+    for point in &template.attachments {
+        if local_transform(&template.attachments, &point.name).is_none() {
+            problems.push(format!(
+                "{} attachment {}: attached to a missing point or to itself",
+                name, point.name
+            ));
+        }
+    }
+
+    let mut animations = vec![
+        ("idle".to_owned(), template.idle_animation),
+        ("move".to_owned(), template.move_animation),
+        ("action".to_owned(), template.action.animation),
+    ];
+    if let Some(glide) = template.glide_animation {
+        animations.push(("glide".to_owned(), glide));
+    }
+    let mut ai = Some(&template.ai);
+    let mut index = 0;
+    while let Some(UnitAI::Engage {
+        switch: Some(switch),
+        ..
+    }) = ai
+    {
+        index += 1;
+        animations.push((
+            format!("switch action {}", index),
+            switch.next_action.animation,
+        ));
+        ai = Some(&switch.next_ai);
+    }
+    if let Some(boss) = &template.boss {
+        for (index, phase) in boss.phases.iter().enumerate() {
+            animations.push((
+                format!("boss phase {} action", index + 1),
+                phase.action.animation,
+            ));
+        }
+    }
+    if let Some(weapon) = &template.weapon {
+        let mut effect = weapon.effect.clone();
+        validate_effect("weapon", &mut effect, &mut animations, problems);
+    }
+    let mut on_death = template.on_death.clone();
+    validate_effect("on death", &mut on_death, &mut animations, problems);
+    for (label, animation) in animations.clone() {
+        let frames = store
+            .try_get(animation)
+            .map_or(&[][..], |animation| &animation.keyframes);
+        for frame in frames {
+            if let Some(effect) = &frame.start_effect {
+                let mut effect = effect.clone();
+                validate_effect(&label, &mut effect, &mut animations, problems);
+            }
+        }
+    }
+
+    for (label, animation) in animations {
+        let label = format!("{} {}", name, label);
+        match store.try_get(animation) {
+            Some(animation) => validate_animation(&label, animation, problems),
+            None => problems.push(format!("{} animation: unknown animation", label)),
+        }
+    }
+}
+
+/// Adds the animations of the projectiles launched by the effect and checks its falloffs.
+fn validate_effect(
+    label: &str,
+    effect: &mut Effect,
+    animations: &mut Vec<(String, AnimationId)>,
+    problems: &mut Vec<String>,
+) {
+    effect.walk_mut(&mut |effect, _| {
+        let falloff = match effect {
+            EffectNode::Projectile(projectile) => {
+                animations.push((format!("{} projectile", label), projectile.animation));
+                projectile.falloff
+            }
+            EffectNode::Area(area) => area.falloff,
+            _ => None,
+        };
+        if let Some(problem) = falloff.as_ref().and_then(falloff_problem) {
+            problems.push(format!("{} effect: {}", label, problem));
+        }
+    });
+}
+
+fn falloff_problem(falloff: &Falloff) -> Option<&'static str> {
+    if falloff.end <= falloff.start {
+        Some("damage falloff ends before it starts")
+    } else if falloff.min < R32::ZERO || falloff.min > R32::ONE {
+        Some("damage falloff minimum is outside of 0 to 1")
+    } else {
+        None
+    }
+}
+
+fn validate_animation(label: &str, animation: &Animation, problems: &mut Vec<String>) {
+    if animation.keyframes.is_empty() {
+        problems.push(format!("{} animation: has no frames", label));
+        return;
+    }
+    for (index, frame) in animation.keyframes.iter().enumerate() {
+        if frame.time <= model::Time::ZERO {
+            problems.push(format!(
+                "{} animation: frame {} has zero duration",
+                label,
+                index + 1
+            ));
+        }
+        if frame.sprite.size.x <= 0.0 || frame.sprite.size.y <= 0.0 {
+            problems.push(format!(
+                "{} animation: frame {} has an empty sprite",
+                label,
+                index + 1
+            ));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+varying vec2 v_vt;
+
+#ifdef VERTEX_SHADER
+attribute vec2 a_pos;
+attribute vec2 a_vt;
+uniform mat3 u_projection_matrix;
+uniform mat3 u_view_matrix;
+uniform mat3 u_model_matrix;
+// Size of the quad relative to the sprite, the outline is drawn in the margin
+uniform vec2 u_uv_scale;
+void main() {
+    v_vt = (a_vt - 0.5) * u_uv_scale + 0.5;
+    vec3 pos = u_projection_matrix * u_view_matrix * u_model_matrix * vec3(a_pos, 1.0);
+    gl_Position = vec4(pos.xy, 0.0, pos.z);
+}
+#endif
+
+#ifdef FRAGMENT_SHADER
+uniform sampler2D u_texture;
+uniform vec4 u_color;
+// Width of the outline in texture coordinates
+uniform vec2 u_texel;
+
+float alpha_at(vec2 uv) {
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        return 0.0;
+    }
+    return texture2D(u_texture, uv).a;
+}
+
+void main() {
+    if (alpha_at(v_vt) > 0.5) {
+        discard;
+    }
+    float neighbours = max(
+        max(alpha_at(v_vt + vec2(u_texel.x, 0.0)), alpha_at(v_vt - vec2(u_texel.x, 0.0))),
+        max(alpha_at(v_vt + vec2(0.0, u_texel.y)), alpha_at(v_vt - vec2(0.0, u_texel.y)))
+    );
+    if (neighbours < 0.5) {
+        discard;
+    }
+    gl_FragColor = u_color;
+}
+#endif